@@ -0,0 +1,42 @@
+//! Anchoring of attestations in external timestamping systems.
+//!
+//! The time of the connection in an attestation is asserted by the Notary. To
+//! strengthen this claim, the Prover can anchor the attestation in an external
+//! timestamping system such as [OpenTimestamps](https://opentimestamps.org),
+//! which proves that the attestation existed no later than the time it was
+//! anchored.
+//!
+//! The [`anchor_payload`](crate::Attestation::anchor_payload) of an
+//! attestation commits to both its header and the Notary's signature. A
+//! Verifier checks the inclusion proof of a payload with an [`AnchorVerifier`]
+//! via [`PresentationOutput::verify_anchor`](crate::presentation::PresentationOutput::verify_anchor).
+
+use tlsn_core::hash::Hash;
+
+/// Domain separator of the anchor payload.
+pub(crate) const ANCHOR_DOMAIN: &[u8] = b"tlsn_attestation_anchor";
+
+/// Verifier of inclusion proofs of an external timestamping system.
+pub trait AnchorVerifier {
+    /// Verifies that `payload` is included in the timestamping system,
+    /// returning the UNIX time in seconds at which it was anchored.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The anchor payload.
+    /// * `proof` - The inclusion proof, in the format of the timestamping
+    ///   system.
+    fn verify(&self, payload: &Hash, proof: &[u8]) -> Result<u64, AnchorError>;
+}
+
+/// Error for [`AnchorVerifier`].
+#[derive(Debug, thiserror::Error)]
+#[error("anchor error: {0}")]
+pub struct AnchorError(String);
+
+impl AnchorError {
+    /// Creates a new error with the given message.
+    pub fn new(msg: impl Into<String>) -> Self {
+        Self(msg.into())
+    }
+}
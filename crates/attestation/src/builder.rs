@@ -1,17 +1,19 @@
 use std::error::Error;
 
 use rand::{Rng, rng};
+use web_time::{SystemTime, UNIX_EPOCH};
 
 use tlsn_core::{
     connection::{ConnectionInfo, ServerEphemKey},
     hash::HashAlgId,
-    transcript::TranscriptCommitment,
+    transcript::{Direction, TranscriptCommitment},
 };
 
 use crate::{
     Attestation, AttestationConfig, Body, CryptoProvider, Extension, FieldId, Header,
-    ServerCertCommitment, VERSION, request::Request, serialize::CanonicalSerialize,
-    signing::SignatureAlgId,
+    ServerCertCommitment, SessionEvidence, UnsignedAttestation, VERSION,
+    request::Request,
+    signing::{AsyncSigner, SignatureAlgId, VerifyingKey},
 };
 
 /// Attestation builder state for accepting a request.
@@ -24,6 +26,7 @@ pub struct Sign {
     hash_alg: HashAlgId,
     connection_info: Option<ConnectionInfo>,
     server_ephemeral_key: Option<ServerEphemKey>,
+    time: Option<u64>,
     cert_commitment: ServerCertCommitment,
     extensions: Vec<Extension>,
     transcript_commitments: Vec<TranscriptCommitment>,
@@ -73,10 +76,9 @@ impl<'a> AttestationBuilder<'a, Accept> {
             ));
         }
 
-        if let Some(validator) = config.extension_validator() {
-            validator(&extensions)
-                .map_err(|err| AttestationBuilderError::new(ErrorKind::Extension, err))?;
-        }
+        config
+            .validate_extensions(&extensions)
+            .map_err(|err| AttestationBuilderError::new(ErrorKind::Extension, err))?;
 
         Ok(AttestationBuilder {
             config: self.config,
@@ -85,6 +87,7 @@ impl<'a> AttestationBuilder<'a, Accept> {
                 hash_alg,
                 connection_info: None,
                 server_ephemeral_key: None,
+                time: None,
                 cert_commitment,
                 transcript_commitments: Vec::new(),
                 extensions,
@@ -106,6 +109,15 @@ impl AttestationBuilder<'_, Sign> {
         self
     }
 
+    /// Sets the time at which the attestation is signed, as a UNIX timestamp in
+    /// seconds.
+    ///
+    /// Defaults to the current system time.
+    pub fn time(&mut self, time: u64) -> &mut Self {
+        self.state.time = Some(time);
+        self
+    }
+
     /// Adds an extension to the attestation.
     pub fn extension(&mut self, extension: Extension) -> &mut Self {
         self.state.extensions.push(extension);
@@ -123,11 +135,107 @@ impl AttestationBuilder<'_, Sign> {
 
     /// Builds the attestation.
     pub fn build(self, provider: &CryptoProvider) -> Result<Attestation, AttestationBuilderError> {
-        let Sign {
+        let signature_alg = self.state.signature_alg;
+        let signer = provider.signer.get(&signature_alg).map_err(|_| {
+            AttestationBuilderError::new(
+                ErrorKind::Config,
+                format!(
+                    "accepted signature algorithm {signature_alg} but it's missing in the provider"
+                ),
+            )
+        })?;
+
+        let (header, body) = self.into_parts(provider, signer.verifying_key())?;
+
+        let signature = signer
+            .sign(&header.to_bytes())
+            .map_err(|err| AttestationBuilderError::new(ErrorKind::Signature, err))?;
+
+        Ok(Attestation {
+            signature,
+            header,
+            body,
+        })
+    }
+
+    /// Builds the attestation, signing it with the provided asynchronous
+    /// signer instead of the signer in the provider.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - The crypto provider.
+    /// * `signer` - The signer for the accepted signature algorithm.
+    pub async fn build_async(
+        self,
+        provider: &CryptoProvider,
+        signer: &(dyn AsyncSigner + Sync),
+    ) -> Result<Attestation, AttestationBuilderError> {
+        let signature_alg = self.state.signature_alg;
+        if signer.alg_id() != signature_alg {
+            return Err(AttestationBuilderError::new(
+                ErrorKind::Config,
+                format!(
+                    "accepted signature algorithm {signature_alg} but the signer uses {}",
+                    signer.alg_id()
+                ),
+            ));
+        }
+
+        let (header, body) = self.into_parts(provider, signer.verifying_key())?;
+
+        let signature = signer
+            .sign(&header.to_bytes())
+            .await
+            .map_err(|err| AttestationBuilderError::new(ErrorKind::Signature, err))?;
+
+        Ok(Attestation {
+            signature,
+            header,
+            body,
+        })
+    }
+
+    /// Builds the attestation without signing it.
+    ///
+    /// The unsigned attestation can be handed off to a separate service which
+    /// holds the signing key, see [`UnsignedAttestation`].
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - The crypto provider.
+    /// * `verifying_key` - The verifying key of the signer which will sign the
+    ///   attestation.
+    /// * `evidence` - The evidence of the session, which the signer checks the
+    ///   body against.
+    pub fn build_unsigned(
+        self,
+        provider: &CryptoProvider,
+        verifying_key: VerifyingKey,
+        evidence: SessionEvidence,
+    ) -> Result<UnsignedAttestation, AttestationBuilderError> {
+        let signature_alg = self.state.signature_alg;
+        let (header, body) = self.into_parts(provider, verifying_key)?;
+
+        Ok(UnsignedAttestation {
             signature_alg,
+            header,
+            body,
+            evidence,
+        })
+    }
+
+    /// Returns the unsigned header and the body of the attestation.
+    fn into_parts(
+        self,
+        provider: &CryptoProvider,
+        verifying_key: VerifyingKey,
+    ) -> Result<(Header, Body), AttestationBuilderError> {
+        let Sign {
+            signature_alg: _,
             hash_alg,
             connection_info,
             server_ephemeral_key,
+            time,
             cert_commitment,
             extensions,
             transcript_commitments,
@@ -139,22 +247,24 @@ impl AttestationBuilder<'_, Sign> {
                 format!("accepted hash algorithm {hash_alg} but it's missing in the provider"),
             )
         })?;
-        let signer = provider.signer.get(&signature_alg).map_err(|_| {
-            AttestationBuilderError::new(
-                ErrorKind::Config,
-                format!(
-                    "accepted signature algorithm {signature_alg} but it's missing in the provider"
-                ),
-            )
+
+        let connection_info = connection_info.ok_or_else(|| {
+            AttestationBuilderError::new(ErrorKind::Field, "connection info was not set")
         })?;
 
+        let time = match time {
+            Some(time) => time,
+            None => now()?,
+        };
+
+        check_session_duration(self.config, &connection_info, time)?;
+        check_transcript_commitments(&connection_info, &transcript_commitments)?;
+
         let mut field_id = FieldId::default();
 
         let body = Body {
-            verifying_key: field_id.next(signer.verifying_key()),
-            connection_info: field_id.next(connection_info.ok_or_else(|| {
-                AttestationBuilderError::new(ErrorKind::Field, "connection info was not set")
-            })?),
+            verifying_key: field_id.next(verifying_key),
+            connection_info: field_id.next(connection_info),
             server_ephemeral_key: field_id.next(server_ephemeral_key.ok_or_else(|| {
                 AttestationBuilderError::new(ErrorKind::Field, "handshake data was not set")
             })?),
@@ -173,18 +283,70 @@ impl AttestationBuilder<'_, Sign> {
             id: rng().random(),
             version: VERSION,
             root: body.root(hasher),
+            time,
         };
 
-        let signature = signer
-            .sign(&CanonicalSerialize::serialize(&header))
-            .map_err(|err| AttestationBuilderError::new(ErrorKind::Signature, err))?;
+        Ok((header, body))
+    }
+}
 
-        Ok(Attestation {
-            signature,
-            header,
-            body,
-        })
+/// Returns the current system time as a UNIX timestamp in seconds.
+pub(crate) fn now() -> Result<u64, AttestationBuilderError> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_secs())
+        .map_err(|_| AttestationBuilderError::new(ErrorKind::Config, "system time is before 1970"))
+}
+
+/// Checks that the session did not last longer than allowed, measured from the
+/// handshake until signing.
+pub(crate) fn check_session_duration(
+    config: &AttestationConfig,
+    connection_info: &ConnectionInfo,
+    time: u64,
+) -> Result<(), AttestationBuilderError> {
+    if let Some(max_duration) = config.max_session_duration() {
+        let duration = time.saturating_sub(connection_info.time);
+        if duration > max_duration {
+            return Err(AttestationBuilderError::new(
+                ErrorKind::Expired,
+                format!("session lasted {duration} seconds, the maximum is {max_duration} seconds"),
+            ));
+        }
     }
+
+    Ok(())
+}
+
+/// Checks that the commitments are consistent with the transcript length which
+/// is attested to.
+pub(crate) fn check_transcript_commitments<'a>(
+    connection_info: &ConnectionInfo,
+    transcript_commitments: impl IntoIterator<Item = &'a TranscriptCommitment>,
+) -> Result<(), AttestationBuilderError> {
+    let length = &connection_info.transcript_length;
+    for commitment in transcript_commitments {
+        let (direction, end) = match commitment {
+            TranscriptCommitment::Hash(hash) => (hash.direction, hash.idx.end().unwrap_or(0)),
+            _ => continue,
+        };
+
+        let len = match direction {
+            Direction::Sent => length.sent,
+            Direction::Received => length.received,
+        } as usize;
+
+        if end > len {
+            return Err(AttestationBuilderError::new(
+                ErrorKind::Field,
+                format!(
+                    "transcript commitment is out of bounds of the {direction} transcript: {end} > {len}"
+                ),
+            ));
+        }
+    }
+
+    Ok(())
 }
 
 /// Error for [`AttestationBuilder`].
@@ -195,16 +357,17 @@ pub struct AttestationBuilderError {
 }
 
 #[derive(Debug)]
-enum ErrorKind {
+pub(crate) enum ErrorKind {
     Request,
     Config,
     Field,
     Signature,
     Extension,
+    Expired,
 }
 
 impl AttestationBuilderError {
-    fn new<E>(kind: ErrorKind, error: E) -> Self
+    pub(crate) fn new<E>(kind: ErrorKind, error: E) -> Self
     where
         E: Into<Box<dyn Error + Send + Sync + 'static>>,
     {
@@ -218,6 +381,11 @@ impl AttestationBuilderError {
     pub fn is_request(&self) -> bool {
         matches!(self.kind, ErrorKind::Request)
     }
+
+    /// Returns whether the session lasted longer than the configured maximum.
+    pub fn is_expired(&self) -> bool {
+        matches!(self.kind, ErrorKind::Expired)
+    }
 }
 
 impl std::fmt::Display for AttestationBuilderError {
@@ -228,6 +396,7 @@ impl std::fmt::Display for AttestationBuilderError {
             ErrorKind::Field => f.write_str("field error")?,
             ErrorKind::Signature => f.write_str("signature error")?,
             ErrorKind::Extension => f.write_str("extension error")?,
+            ErrorKind::Expired => f.write_str("session expired")?,
         }
 
         if let Some(source) = &self.source {
@@ -240,15 +409,21 @@ impl std::fmt::Display for AttestationBuilderError {
 
 #[cfg(test)]
 mod test {
+    use rangeset::set::RangeSet;
     use rstest::{fixture, rstest};
     use tlsn_core::{
         connection::{CertBinding, CertBindingV1_2},
         fixtures::ConnectionFixture,
-        transcript::Transcript,
+        hash::{Hash, TypedHash},
+        transcript::{Transcript, hash::PlaintextHash},
     };
     use tlsn_data_fixtures::http::{request::GET_WITH_HEADER, response::OK_JSON};
 
-    use crate::fixtures::{RequestFixture, request_fixture};
+    use crate::{
+        InvalidExtension, UnsignedAttestation,
+        fixtures::{RequestFixture, request_fixture},
+        signing::{BlockingSigner, Secp256k1Signer, Secp256r1Signer},
+    };
 
     use super::*;
 
@@ -453,4 +628,327 @@ mod test {
 
         assert_eq!(attestation.body.extensions().count(), 1);
     }
+
+    #[rstest]
+    fn test_attestation_builder_registered_extension() {
+        let attestation_config = AttestationConfig::builder()
+            .supported_signature_algs([SignatureAlgId::SECP256K1])
+            .register_extension("nonce", |value| {
+                if value.len() != 4 {
+                    return Err(InvalidExtension::new("nonce must be 4 bytes"));
+                }
+                Ok(())
+            })
+            .build()
+            .unwrap();
+
+        let accept = |extensions: Vec<Extension>| {
+            let transcript = Transcript::new(GET_WITH_HEADER, OK_JSON);
+            let connection = ConnectionFixture::tlsnotary(transcript.length());
+            let RequestFixture { request, .. } =
+                request_fixture(transcript, connection, extensions);
+
+            Attestation::builder(&attestation_config)
+                .accept_request(request)
+                .map(|_| ())
+        };
+
+        let nonce = |value: &[u8]| Extension {
+            id: b"nonce".to_vec(),
+            value: value.to_vec(),
+        };
+
+        assert!(accept(vec![nonce(&[1, 2, 3, 4])]).is_ok());
+
+        let err = accept(vec![nonce(&[1, 2, 3])]).unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::Extension));
+
+        // Unregistered extensions are still rejected by default.
+        let err = accept(vec![
+            nonce(&[1, 2, 3, 4]),
+            Extension {
+                id: b"foo".to_vec(),
+                value: b"bar".to_vec(),
+            },
+        ])
+        .unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::Extension));
+    }
+
+    #[rstest]
+    fn test_attestation_builder_extension_limits() {
+        let attestation_config = AttestationConfig::builder()
+            .supported_signature_algs([SignatureAlgId::SECP256K1])
+            .extension_validator(|_| Ok(()))
+            .max_extensions(2)
+            .max_extension_size(8)
+            .build()
+            .unwrap();
+
+        let accept = |extensions: Vec<Extension>| {
+            let transcript = Transcript::new(GET_WITH_HEADER, OK_JSON);
+            let connection = ConnectionFixture::tlsnotary(transcript.length());
+            let RequestFixture { request, .. } =
+                request_fixture(transcript, connection, extensions);
+
+            Attestation::builder(&attestation_config)
+                .accept_request(request)
+                .map(|_| ())
+        };
+
+        let extension = |value: &[u8]| Extension {
+            id: b"foo".to_vec(),
+            value: value.to_vec(),
+        };
+
+        assert!(accept(vec![extension(b"12345"), extension(b"")]).is_ok());
+        assert!(accept(vec![extension(b"123456")]).is_err());
+        assert!(accept(vec![extension(b""), extension(b""), extension(b"")]).is_err());
+    }
+
+    #[rstest]
+    fn test_attestation_builder_commitment_out_of_bounds(
+        attestation_config: &AttestationConfig,
+        crypto_provider: &CryptoProvider,
+    ) {
+        let transcript = Transcript::new(GET_WITH_HEADER, OK_JSON);
+        let (_, recv_len) = transcript.len();
+
+        let mut attestation_builder = sign_builder(attestation_config);
+        attestation_builder.transcript_commitments(vec![TranscriptCommitment::Hash(
+            PlaintextHash {
+                direction: Direction::Received,
+                idx: RangeSet::from(0..recv_len + 1),
+                hash: TypedHash {
+                    alg: HashAlgId::SHA256,
+                    value: Hash::default(),
+                },
+            },
+        )]);
+
+        let err = attestation_builder.build(crypto_provider).err().unwrap();
+        assert!(matches!(err.kind, ErrorKind::Field));
+    }
+
+    #[rstest]
+    fn test_attestation_builder_max_session_duration(crypto_provider: &CryptoProvider) {
+        let attestation_config = AttestationConfig::builder()
+            .supported_signature_algs([SignatureAlgId::SECP256K1])
+            .max_session_duration(300)
+            .build()
+            .unwrap();
+
+        let transcript = Transcript::new(GET_WITH_HEADER, OK_JSON);
+        let time = ConnectionFixture::tlsnotary(transcript.length())
+            .connection_info
+            .time;
+
+        // The fixture connection took place long ago.
+        let err = sign_builder(&attestation_config)
+            .build(crypto_provider)
+            .err()
+            .unwrap();
+        assert!(err.is_expired());
+
+        let mut attestation_builder = sign_builder(&attestation_config);
+        attestation_builder.time(time + 300);
+
+        let attestation = attestation_builder.build(crypto_provider).unwrap();
+        assert_eq!(attestation.header.time, time + 300);
+
+        let mut attestation_builder = sign_builder(&attestation_config);
+        attestation_builder.time(time + 301);
+
+        let err = attestation_builder.build(crypto_provider).err().unwrap();
+        assert!(err.is_expired());
+    }
+
+    /// Returns the evidence of the session of [`sign_builder`].
+    fn evidence() -> SessionEvidence {
+        let ConnectionFixture {
+            server_cert_data,
+            connection_info,
+            ..
+        } = ConnectionFixture::tlsnotary(Transcript::new(GET_WITH_HEADER, OK_JSON).length());
+
+        SessionEvidence {
+            connection_info,
+            binding: server_cert_data.binding,
+        }
+    }
+
+    fn sign_builder(attestation_config: &AttestationConfig) -> AttestationBuilder<'_, Sign> {
+        let transcript = Transcript::new(GET_WITH_HEADER, OK_JSON);
+        let connection = ConnectionFixture::tlsnotary(transcript.length());
+
+        let RequestFixture { request, .. } =
+            request_fixture(transcript, connection.clone(), Vec::new());
+
+        let mut attestation_builder = Attestation::builder(attestation_config)
+            .accept_request(request)
+            .unwrap();
+
+        let ConnectionFixture {
+            server_cert_data,
+            connection_info,
+            ..
+        } = connection;
+
+        let CertBinding::V1_2(CertBindingV1_2 {
+            server_ephemeral_key,
+            ..
+        }) = server_cert_data.binding
+        else {
+            panic!("expected v1.2 handshake data");
+        };
+
+        attestation_builder
+            .connection_info(connection_info)
+            .server_ephemeral_key(server_ephemeral_key);
+
+        attestation_builder
+    }
+
+    #[rstest]
+    fn test_attestation_builder_build_async(
+        attestation_config: &AttestationConfig,
+        crypto_provider: &CryptoProvider,
+    ) {
+        let signer = BlockingSigner(Secp256k1Signer::new(&[42u8; 32]).unwrap());
+
+        let attestation = futures::executor::block_on(
+            sign_builder(attestation_config).build_async(crypto_provider, &signer),
+        )
+        .unwrap();
+
+        assert_eq!(attestation.body.verifying_key(), &signer.verifying_key());
+        crypto_provider
+            .signature
+            .get(&attestation.signature.alg)
+            .unwrap()
+            .verify(
+                &signer.verifying_key(),
+                &attestation.header.to_bytes(),
+                &attestation.signature.data,
+            )
+            .unwrap();
+    }
+
+    #[rstest]
+    fn test_attestation_builder_build_async_wrong_alg(
+        attestation_config: &AttestationConfig,
+        crypto_provider: &CryptoProvider,
+    ) {
+        let signer = BlockingSigner(Secp256r1Signer::new(&[42u8; 32]).unwrap());
+
+        let err = futures::executor::block_on(
+            sign_builder(attestation_config).build_async(crypto_provider, &signer),
+        )
+        .err()
+        .unwrap();
+
+        assert!(matches!(err.kind, ErrorKind::Config));
+    }
+
+    #[rstest]
+    fn test_unsigned_attestation(
+        attestation_config: &AttestationConfig,
+        crypto_provider: &CryptoProvider,
+    ) {
+        let verifying_key = crypto_provider
+            .signer
+            .get(&SignatureAlgId::SECP256K1)
+            .unwrap()
+            .verifying_key();
+
+        let unsigned = sign_builder(attestation_config)
+            .build_unsigned(crypto_provider, verifying_key.clone(), evidence())
+            .unwrap();
+
+        // The handoff survives serialization.
+        let unsigned: UnsignedAttestation =
+            bcs::from_bytes(&bcs::to_bytes(&unsigned).unwrap()).unwrap();
+        let root = unsigned.header().root.clone();
+
+        let attestation = unsigned.sign(attestation_config, crypto_provider).unwrap();
+
+        assert_eq!(attestation.header.root, root);
+        crypto_provider
+            .signature
+            .get(&attestation.signature.alg)
+            .unwrap()
+            .verify(
+                &verifying_key,
+                &attestation.header.to_bytes(),
+                &attestation.signature.data,
+            )
+            .unwrap();
+    }
+
+    #[rstest]
+    fn test_unsigned_attestation_invalid(
+        attestation_config: &AttestationConfig,
+        crypto_provider: &CryptoProvider,
+    ) {
+        let verifying_key = crypto_provider
+            .signer
+            .get(&SignatureAlgId::SECP256K1)
+            .unwrap()
+            .verifying_key();
+        let unsigned = || {
+            sign_builder(attestation_config)
+                .build_unsigned(crypto_provider, verifying_key.clone(), evidence())
+                .unwrap()
+        };
+
+        // Signed by a different key than the one in the body.
+        let mut other_provider = CryptoProvider::default();
+        other_provider.signer.set_secp256k1(&[1u8; 32]).unwrap();
+        let err = unsigned()
+            .sign(attestation_config, &other_provider)
+            .err()
+            .unwrap();
+        assert!(matches!(err.kind, ErrorKind::Field));
+
+        // The body does not match the root.
+        let mut tampered = unsigned();
+        tampered.body.connection_info.data.time += 1;
+        let err = tampered
+            .sign(attestation_config, crypto_provider)
+            .err()
+            .unwrap();
+        assert!(matches!(err.kind, ErrorKind::Field));
+
+        // The body is not of the session witnessed by the Notary.
+        let mut tampered = unsigned();
+        tampered.evidence.connection_info.transcript_length.received += 1;
+        let err = tampered
+            .sign(attestation_config, crypto_provider)
+            .err()
+            .unwrap();
+        assert!(matches!(err.kind, ErrorKind::Field));
+
+        let mut tampered = unsigned();
+        tampered.evidence.binding =
+            ConnectionFixture::appliedzkp(Transcript::new(GET_WITH_HEADER, OK_JSON).length())
+                .server_cert_data
+                .binding;
+        let err = tampered
+            .sign(attestation_config, crypto_provider)
+            .err()
+            .unwrap();
+        assert!(matches!(err.kind, ErrorKind::Field));
+
+        // The signer applies its own limits.
+        let strict_config = AttestationConfig::builder()
+            .supported_signature_algs([SignatureAlgId::SECP256K1])
+            .max_session_duration(300)
+            .build()
+            .unwrap();
+        let err = unsigned()
+            .sign(&strict_config, crypto_provider)
+            .err()
+            .unwrap();
+        assert!(err.is_expired());
+    }
 }
@@ -1,12 +1,21 @@
-use std::{fmt::Debug, sync::Arc};
+use std::{collections::HashMap, fmt::Debug, sync::Arc};
 
 use tlsn_core::hash::HashAlgId;
 
 use crate::{
-    Extension, InvalidExtension, hash::DEFAULT_SUPPORTED_HASH_ALGS, signing::SignatureAlgId,
+    Extension, InvalidExtension,
+    hash::DEFAULT_SUPPORTED_HASH_ALGS,
+    nonce::{NONCE_EXTENSION_ID, Nonce},
+    signing::SignatureAlgId,
 };
 
 type ExtensionValidator = Arc<dyn Fn(&[Extension]) -> Result<(), InvalidExtension> + Send + Sync>;
+type ExtensionTypeValidator = Arc<dyn Fn(&[u8]) -> Result<(), InvalidExtension> + Send + Sync>;
+
+/// Default maximum number of extensions in a request.
+const DEFAULT_MAX_EXTENSIONS: usize = 16;
+/// Default maximum size of an extension in bytes, including its identifier.
+const DEFAULT_MAX_EXTENSION_SIZE: usize = 1024;
 
 #[derive(Debug)]
 #[allow(dead_code)]
@@ -46,6 +55,10 @@ pub struct AttestationConfig {
     supported_signature_algs: Vec<SignatureAlgId>,
     supported_hash_algs: Vec<HashAlgId>,
     extension_validator: Option<ExtensionValidator>,
+    extension_types: HashMap<Vec<u8>, ExtensionTypeValidator>,
+    max_extensions: usize,
+    max_extension_size: usize,
+    max_session_duration: Option<u64>,
 }
 
 impl AttestationConfig {
@@ -62,8 +75,48 @@ impl AttestationConfig {
         &self.supported_hash_algs
     }
 
-    pub(crate) fn extension_validator(&self) -> Option<&ExtensionValidator> {
-        self.extension_validator.as_ref()
+    pub(crate) fn max_session_duration(&self) -> Option<u64> {
+        self.max_session_duration
+    }
+
+    /// Validates the extensions of a request.
+    ///
+    /// Extensions of a registered type are validated by the validator of
+    /// their type, all other extensions are validated by the extension
+    /// validator.
+    pub(crate) fn validate_extensions(
+        &self,
+        extensions: &[Extension],
+    ) -> Result<(), InvalidExtension> {
+        if extensions.len() > self.max_extensions {
+            return Err(InvalidExtension::new(format!(
+                "too many extensions: {} > {}",
+                extensions.len(),
+                self.max_extensions
+            )));
+        }
+
+        let mut unregistered = Vec::new();
+        for extension in extensions {
+            let size = extension.id.len() + extension.value.len();
+            if size > self.max_extension_size {
+                return Err(InvalidExtension::new(format!(
+                    "extension is too large: {size} > {}",
+                    self.max_extension_size
+                )));
+            }
+
+            match self.extension_types.get(&extension.id) {
+                Some(validator) => validator(&extension.value)?,
+                None => unregistered.push(extension.clone()),
+            }
+        }
+
+        if let Some(validator) = &self.extension_validator {
+            validator(&unregistered)?;
+        }
+
+        Ok(())
     }
 }
 
@@ -72,6 +125,9 @@ impl Debug for AttestationConfig {
         f.debug_struct("AttestationConfig")
             .field("supported_signature_algs", &self.supported_signature_algs)
             .field("supported_hash_algs", &self.supported_hash_algs)
+            .field("max_extensions", &self.max_extensions)
+            .field("max_extension_size", &self.max_extension_size)
+            .field("max_session_duration", &self.max_session_duration)
             .finish_non_exhaustive()
     }
 }
@@ -81,6 +137,10 @@ pub struct AttestationConfigBuilder {
     supported_signature_algs: Vec<SignatureAlgId>,
     supported_hash_algs: Vec<HashAlgId>,
     extension_validator: Option<ExtensionValidator>,
+    extension_types: HashMap<Vec<u8>, ExtensionTypeValidator>,
+    max_extensions: usize,
+    max_extension_size: usize,
+    max_session_duration: Option<u64>,
 }
 
 impl Default for AttestationConfigBuilder {
@@ -97,6 +157,10 @@ impl Default for AttestationConfigBuilder {
                     Ok(())
                 }
             })),
+            extension_types: HashMap::new(),
+            max_extensions: DEFAULT_MAX_EXTENSIONS,
+            max_extension_size: DEFAULT_MAX_EXTENSION_SIZE,
+            max_session_duration: None,
         }
     }
 }
@@ -143,12 +207,72 @@ impl AttestationConfigBuilder {
         self
     }
 
+    /// Registers an extension type.
+    ///
+    /// Extensions with the given identifier are accepted if `f` accepts their
+    /// value, and are not passed to the [extension
+    /// validator](Self::extension_validator).
+    ///
+    /// # Example
+    /// ```
+    /// # use tlsn_attestation::{AttestationConfig, InvalidExtension};
+    /// # let mut builder = AttestationConfig::builder();
+    /// builder.register_extension("example.nonce", |value| {
+    ///     if value.len() != 32 {
+    ///         return Err(InvalidExtension::new("nonce must be 32 bytes"));
+    ///     }
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn register_extension<F>(&mut self, id: impl Into<Vec<u8>>, f: F) -> &mut Self
+    where
+        F: Fn(&[u8]) -> Result<(), InvalidExtension> + Send + Sync + 'static,
+    {
+        self.extension_types.insert(id.into(), Arc::new(f));
+        self
+    }
+
+    /// Accepts [nonce](crate::nonce) extensions of relying parties.
+    pub fn accept_nonces(&mut self) -> &mut Self {
+        self.register_extension(NONCE_EXTENSION_ID, Nonce::validate_extension)
+    }
+
+    /// Sets the maximum number of extensions a request may contain.
+    ///
+    /// Defaults to 16.
+    pub fn max_extensions(&mut self, max_extensions: usize) -> &mut Self {
+        self.max_extensions = max_extensions;
+        self
+    }
+
+    /// Sets the maximum size in bytes of an extension in a request, including
+    /// its identifier.
+    ///
+    /// Defaults to 1KiB.
+    pub fn max_extension_size(&mut self, max_extension_size: usize) -> &mut Self {
+        self.max_extension_size = max_extension_size;
+        self
+    }
+
+    /// Sets the maximum duration of a session in seconds, from the completion
+    /// of the TLS handshake until the attestation is signed.
+    ///
+    /// Attestations of longer sessions are refused. Unlimited by default.
+    pub fn max_session_duration(&mut self, seconds: u64) -> &mut Self {
+        self.max_session_duration = Some(seconds);
+        self
+    }
+
     /// Builds the configuration.
     pub fn build(&self) -> Result<AttestationConfig, AttestationConfigError> {
         Ok(AttestationConfig {
             supported_signature_algs: self.supported_signature_algs.clone(),
             supported_hash_algs: self.supported_hash_algs.clone(),
             extension_validator: self.extension_validator.clone(),
+            extension_types: self.extension_types.clone(),
+            max_extensions: self.max_extensions,
+            max_extension_size: self.max_extension_size,
+            max_session_duration: self.max_session_duration,
         })
     }
 }
@@ -158,6 +282,9 @@ impl Debug for AttestationConfigBuilder {
         f.debug_struct("AttestationConfigBuilder")
             .field("supported_signature_algs", &self.supported_signature_algs)
             .field("supported_hash_algs", &self.supported_hash_algs)
+            .field("max_extensions", &self.max_extensions)
+            .field("max_extension_size", &self.max_extension_size)
+            .field("max_session_duration", &self.max_session_duration)
             .finish_non_exhaustive()
     }
 }
@@ -72,6 +72,11 @@ impl ServerIdentityProof {
         Self { name, opening }
     }
 
+    /// Returns the server name, without verifying it.
+    pub(crate) fn name(&self) -> &ServerName {
+        &self.name
+    }
+
     /// Verifies the server identity proof.
     ///
     /// # Arguments
@@ -0,0 +1,328 @@
+//! EVM compatible attestation verification.
+//!
+//! Attestations signed with [`SECP256K1ETH`](crate::signing::SignatureAlgId::SECP256K1ETH)
+//! can be verified in a smart contract using `ecrecover`. This module provides
+//! an encoder for the data needed by such a contract, and a reference Solidity
+//! implementation in [`VERIFIER_CONTRACT`].
+//!
+//! # Header layout
+//!
+//! The notary signs the keccak256 hash of the canonically serialized
+//! [`Header`](crate::Header), see [`Header::to_bytes`](crate::Header::to_bytes),
+//! which for a 32 byte Merkle root is laid out as follows:
+//!
+//! | Offset | Length | Field                             |
+//! |--------|--------|-----------------------------------|
+//! | 0      | 1      | Layout, always `0x01`             |
+//! | 1      | 16     | Attestation id                    |
+//! | 17     | 4      | Version, little-endian            |
+//! | 21     | 1      | Hash algorithm id of the root     |
+//! | 22     | 1      | Length of the root, always 32     |
+//! | 23     | 32     | Merkle root of the body           |
+//! | 55     | 8      | Signing time, little-endian       |
+
+use k256::ecdsa::VerifyingKey as K256VerifyingKey;
+use tiny_keccak::{Hasher, Keccak};
+
+use crate::{
+    Attestation,
+    signing::{KeyAlgId, SignatureAlgId},
+};
+
+/// Length of a serialized header with a 32 byte Merkle root.
+pub const HEADER_LEN: usize = 63;
+/// Offset of the layout byte in a serialized header.
+pub const LAYOUT_OFFSET: usize = 0;
+/// Offset of the attestation id in a serialized header.
+pub const ID_OFFSET: usize = 1;
+/// Offset of the version in a serialized header.
+pub const VERSION_OFFSET: usize = 17;
+/// Offset of the hash algorithm id of the root in a serialized header.
+pub const ROOT_ALG_OFFSET: usize = 21;
+/// Offset of the Merkle root in a serialized header.
+pub const ROOT_OFFSET: usize = 23;
+/// Offset of the signing time in a serialized header.
+pub const TIME_OFFSET: usize = 55;
+
+/// Reference Solidity verifier for attestation headers.
+///
+/// The contract expects the arguments encoded by [`EvmAttestation::abi_encode`].
+pub const VERIFIER_CONTRACT: &str = r#"// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity ^0.8.20;
+
+/// @title TLSNotary attestation header verifier.
+/// @notice Verifies attestation headers signed with the secp256k1eth algorithm.
+contract AttestationVerifier {
+    uint256 internal constant HEADER_LEN = 63;
+    uint8 internal constant HEADER_LAYOUT = 1;
+    uint256 internal constant LAYOUT_OFFSET = 0;
+    uint256 internal constant VERSION_OFFSET = 17;
+    uint256 internal constant ROOT_ALG_OFFSET = 21;
+    uint256 internal constant ROOT_LEN_OFFSET = 22;
+    uint256 internal constant ROOT_OFFSET = 23;
+    uint256 internal constant TIME_OFFSET = 55;
+
+    /// @notice Returns whether the header was signed by the notary.
+    function verify(
+        bytes calldata header,
+        bytes32 r,
+        bytes32 s,
+        uint8 v,
+        address notary
+    ) public pure returns (bool) {
+        if (header.length != HEADER_LEN || notary == address(0)) {
+            return false;
+        }
+        if (uint8(header[LAYOUT_OFFSET]) != HEADER_LAYOUT) {
+            return false;
+        }
+
+        return ecrecover(keccak256(header), v, r, s) == notary;
+    }
+
+    /// @notice Returns the version of the attestation.
+    function version(bytes calldata header) public pure returns (uint32 v) {
+        require(header.length == HEADER_LEN, "invalid header length");
+
+        for (uint256 i = 0; i < 4; i++) {
+            v |= uint32(uint8(header[VERSION_OFFSET + i])) << uint32(8 * i);
+        }
+    }
+
+    /// @notice Returns the hash algorithm id and the Merkle root of the body.
+    function root(bytes calldata header) public pure returns (uint8 alg, bytes32 value) {
+        require(header.length == HEADER_LEN, "invalid header length");
+        require(uint8(header[ROOT_LEN_OFFSET]) == 32, "invalid root length");
+
+        alg = uint8(header[ROOT_ALG_OFFSET]);
+        value = bytes32(header[ROOT_OFFSET:ROOT_OFFSET + 32]);
+    }
+
+    /// @notice Returns the time at which the attestation was signed, as a UNIX timestamp.
+    function time(bytes calldata header) public pure returns (uint64 t) {
+        require(header.length == HEADER_LEN, "invalid header length");
+
+        for (uint256 i = 0; i < 8; i++) {
+            t |= uint64(uint8(header[TIME_OFFSET + i])) << uint64(8 * i);
+        }
+    }
+}
+"#;
+
+/// Error for [`EvmAttestation`].
+#[derive(Debug, thiserror::Error)]
+#[error("evm encoding error: {0}")]
+pub struct EvmError(String);
+
+/// Attestation data needed for verification on the EVM.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvmAttestation {
+    /// The serialized header which was signed by the notary.
+    pub header: Vec<u8>,
+    /// The `r` component of the signature.
+    pub r: [u8; 32],
+    /// The `s` component of the signature.
+    pub s: [u8; 32],
+    /// The recovery id of the signature, either 27 or 28.
+    pub v: u8,
+    /// The Ethereum address of the notary.
+    pub notary: [u8; 20],
+}
+
+impl EvmAttestation {
+    /// Creates the EVM encoding of an attestation.
+    ///
+    /// The attestation must be signed with the `secp256k1eth` algorithm and
+    /// have a 32 byte Merkle root.
+    pub fn new(attestation: &Attestation) -> Result<Self, EvmError> {
+        let signature = &attestation.signature;
+        if signature.alg != SignatureAlgId::SECP256K1ETH {
+            return Err(EvmError(format!(
+                "expected a secp256k1eth signature, got {}",
+                signature.alg
+            )));
+        }
+
+        if signature.data.len() != 65 {
+            return Err(EvmError(format!(
+                "expected a 65 byte signature, got {} bytes",
+                signature.data.len()
+            )));
+        }
+
+        let header = attestation.header.to_bytes();
+        if header.len() != HEADER_LEN {
+            return Err(EvmError(format!(
+                "expected a header of {HEADER_LEN} bytes, got {} bytes",
+                header.len()
+            )));
+        }
+
+        let key = attestation.body.verifying_key();
+        if key.alg != KeyAlgId::K256 {
+            return Err(EvmError(format!(
+                "expected a k256 verifying key, got {}",
+                key.alg
+            )));
+        }
+
+        let key = K256VerifyingKey::from_sec1_bytes(&key.data)
+            .map_err(|_| EvmError("invalid k256 verifying key".to_string()))?;
+
+        Ok(Self {
+            header,
+            r: signature.data[..32].try_into().unwrap(),
+            s: signature.data[32..64].try_into().unwrap(),
+            v: signature.data[64],
+            notary: address(&key),
+        })
+    }
+
+    /// Returns the Merkle root of the attestation body.
+    pub fn root(&self) -> [u8; 32] {
+        self.header[ROOT_OFFSET..ROOT_OFFSET + 32]
+            .try_into()
+            .unwrap()
+    }
+
+    /// Returns the time at which the attestation was signed.
+    pub fn time(&self) -> u64 {
+        u64::from_le_bytes(
+            self.header[TIME_OFFSET..TIME_OFFSET + 8]
+                .try_into()
+                .unwrap(),
+        )
+    }
+
+    /// Returns the ABI encoding of `(bytes header, bytes32 r, bytes32 s,
+    /// uint8 v)`, the leading arguments of `AttestationVerifier.verify`.
+    pub fn abi_encode(&self) -> Vec<u8> {
+        let padded_len = self.header.len().div_ceil(32) * 32;
+        let mut encoded = Vec::with_capacity(5 * 32 + padded_len);
+
+        // Head, with the offset of the dynamic `header` argument.
+        encoded.extend_from_slice(&word(4 * 32));
+        encoded.extend_from_slice(&self.r);
+        encoded.extend_from_slice(&self.s);
+        encoded.extend_from_slice(&word(self.v as usize));
+
+        // Tail.
+        encoded.extend_from_slice(&word(self.header.len()));
+        encoded.extend_from_slice(&self.header);
+        encoded.resize(5 * 32 + padded_len, 0);
+
+        encoded
+    }
+}
+
+/// Returns the Ethereum address of a verifying key.
+fn address(key: &K256VerifyingKey) -> [u8; 20] {
+    let point = key.to_encoded_point(false);
+
+    let mut hasher = Keccak::v256();
+    // Skip the SEC1 tag byte.
+    hasher.update(&point.as_bytes()[1..]);
+    let mut hash = [0u8; 32];
+    hasher.finalize(&mut hash);
+
+    hash[12..].try_into().unwrap()
+}
+
+/// Returns a big-endian ABI word.
+fn word(value: usize) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&(value as u64).to_be_bytes());
+    word
+}
+
+#[cfg(test)]
+mod test {
+    use k256::ecdsa::{RecoveryId, Signature};
+    use tlsn_core::{fixtures::ConnectionFixture, transcript::Transcript};
+    use tlsn_data_fixtures::http::{request::GET_WITH_HEADER, response::OK_JSON};
+
+    use crate::{
+        HEADER_LAYOUT_V1,
+        fixtures::{RequestFixture, attestation_fixture, request_fixture},
+    };
+
+    use super::*;
+
+    fn evm_attestation(alg: SignatureAlgId) -> (Attestation, Result<EvmAttestation, EvmError>) {
+        let transcript = Transcript::new(GET_WITH_HEADER, OK_JSON);
+        let connection = ConnectionFixture::tlsnotary(transcript.length());
+
+        let RequestFixture { request, .. } =
+            request_fixture(transcript, connection.clone(), Vec::new());
+        let attestation = attestation_fixture(request, connection, alg, &[]);
+        let evm = EvmAttestation::new(&attestation);
+
+        (attestation, evm)
+    }
+
+    #[test]
+    fn test_evm_attestation_layout() {
+        let (attestation, evm) = evm_attestation(SignatureAlgId::SECP256K1ETH);
+        let evm = evm.unwrap();
+
+        assert_eq!(evm.header[LAYOUT_OFFSET], HEADER_LAYOUT_V1);
+        assert_eq!(
+            &evm.header[ID_OFFSET..VERSION_OFFSET],
+            &attestation.header.id.0
+        );
+        assert_eq!(
+            evm.header[ROOT_ALG_OFFSET],
+            attestation.header.root.alg.as_u8()
+        );
+        assert_eq!(evm.header[ROOT_OFFSET - 1], 32);
+        assert_eq!(&evm.root(), attestation.header.root.value.as_bytes());
+        assert_eq!(evm.time(), attestation.header.time);
+    }
+
+    #[test]
+    fn test_evm_attestation_ecrecover() {
+        let (_, evm) = evm_attestation(SignatureAlgId::SECP256K1ETH);
+        let evm = evm.unwrap();
+
+        // Mirrors `ecrecover(keccak256(header), v, r, s)` in the contract.
+        let mut hasher = Keccak::v256();
+        hasher.update(&evm.header);
+        let mut prehash = [0u8; 32];
+        hasher.finalize(&mut prehash);
+
+        let signature = Signature::from_scalars(evm.r, evm.s).unwrap();
+        let recid = RecoveryId::from_byte(evm.v - 27).unwrap();
+        let key = K256VerifyingKey::recover_from_prehash(&prehash, &signature, recid).unwrap();
+
+        assert_eq!(address(&key), evm.notary);
+    }
+
+    #[test]
+    fn test_evm_attestation_abi_encode() {
+        let evm = EvmAttestation {
+            header: (0..HEADER_LEN as u8).collect(),
+            r: [1u8; 32],
+            s: [2u8; 32],
+            v: 27,
+            notary: [3u8; 20],
+        };
+
+        let encoded = evm.abi_encode();
+
+        assert_eq!(encoded.len(), 7 * 32);
+        assert_eq!(encoded[31], 0x80);
+        assert_eq!(&encoded[32..64], &[1u8; 32]);
+        assert_eq!(&encoded[64..96], &[2u8; 32]);
+        assert_eq!(encoded[127], 27);
+        assert_eq!(encoded[159], HEADER_LEN as u8);
+        assert_eq!(&encoded[160..160 + HEADER_LEN], evm.header.as_slice());
+        assert!(encoded[160 + HEADER_LEN..].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn test_evm_attestation_wrong_alg() {
+        let (_, evm) = evm_attestation(SignatureAlgId::SECP256K1);
+
+        assert!(evm.is_err());
+    }
+}
@@ -0,0 +1,459 @@
+//! Interchange format for explorer and viewer tools.
+//!
+//! A verified [`PresentationOutput`] can be exported to JSON with
+//! [`ExplorerSession::from_output`] and [`ExplorerSession::to_json`], so that
+//! it can be displayed by tools which do not depend on this crate. Binary
+//! values are encoded as lowercase hex strings and transcript ranges as
+//! half-open `[start, end)` pairs.
+//!
+//! ```json
+//! {
+//!   "format_version": 2,
+//!   "header": {
+//!     "id": "…",
+//!     "version": 1,
+//!     "root": { "alg": 1, "value": "…" },
+//!     "time": 1700000060
+//!   },
+//!   "verifying_key": { "alg": 1, "data": "…" },
+//!   "server_name": "example.com",
+//!   "connection_time": 1700000000,
+//!   "commitments": [
+//!     { "direction": "received", "ranges": [[0, 10]], "alg": 2, "hash": "…" }
+//!   ],
+//!   "transcript": {
+//!     "sent": { "length": 100, "reveals": [{ "start": 0, "data": "…" }] },
+//!     "received": { "length": 200, "reveals": [] }
+//!   }
+//! }
+//! ```
+//!
+//! The importer, [`ExplorerSession::from_json`], is strict: unknown fields,
+//! malformed hex and ranges outside of the transcript are rejected.
+//!
+//! Importing a session does not verify it. The data is only as trustworthy as
+//! the presentation it was exported from.
+
+use serde::{Deserialize, Serialize};
+use tlsn_core::{
+    hash::TypedHash,
+    transcript::{Direction, PartialTranscript, TranscriptCommitment},
+};
+
+use crate::presentation::PresentationOutput;
+
+/// Version of the interchange format.
+pub const FORMAT_VERSION: u32 = 2;
+
+/// Maximum length of a hash value in bytes.
+const MAX_HASH_LEN: usize = 64;
+
+/// A session in the explorer interchange format.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExplorerSession {
+    /// Version of the interchange format.
+    pub format_version: u32,
+    /// Attestation header.
+    pub header: ExplorerHeader,
+    /// Key the attestation was signed with.
+    pub verifying_key: ExplorerKey,
+    /// Authenticated server name, if disclosed.
+    pub server_name: Option<String>,
+    /// Time of the TLS connection as a UNIX timestamp in seconds.
+    pub connection_time: u64,
+    /// Transcript commitments in the attestation.
+    pub commitments: Vec<ExplorerCommitment>,
+    /// Revealed transcript data, if any.
+    pub transcript: Option<ExplorerTranscript>,
+}
+
+/// Attestation header.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExplorerHeader {
+    /// Attestation identifier, hex encoded.
+    pub id: String,
+    /// Attestation version.
+    pub version: u32,
+    /// Merkle root of the attestation fields.
+    pub root: ExplorerHash,
+    /// Time at which the attestation was signed, as a UNIX timestamp.
+    pub time: u64,
+}
+
+/// A typed hash value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExplorerHash {
+    /// Hash algorithm id.
+    pub alg: u8,
+    /// Hash value, hex encoded.
+    pub value: String,
+}
+
+/// A verifying key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExplorerKey {
+    /// Key algorithm id.
+    pub alg: u8,
+    /// Key data, hex encoded.
+    pub data: String,
+}
+
+/// Direction of transcript data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExplorerDirection {
+    /// Sent from the prover to the server.
+    Sent,
+    /// Received by the prover from the server.
+    Received,
+}
+
+impl From<Direction> for ExplorerDirection {
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::Sent => Self::Sent,
+            Direction::Received => Self::Received,
+        }
+    }
+}
+
+/// A transcript commitment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExplorerCommitment {
+    /// Direction of the committed data.
+    pub direction: ExplorerDirection,
+    /// Committed ranges.
+    pub ranges: Vec<[usize; 2]>,
+    /// Hash algorithm id.
+    pub alg: u8,
+    /// Hash value, hex encoded.
+    pub hash: String,
+}
+
+/// Revealed transcript data.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExplorerTranscript {
+    /// Data sent by the prover.
+    pub sent: ExplorerTranscriptData,
+    /// Data received by the prover.
+    pub received: ExplorerTranscriptData,
+}
+
+/// Revealed transcript data in one direction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExplorerTranscriptData {
+    /// Length of the transcript, including data which is not revealed.
+    pub length: usize,
+    /// Revealed data, in ascending order.
+    pub reveals: Vec<ExplorerReveal>,
+}
+
+/// Revealed data at an offset in the transcript.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExplorerReveal {
+    /// Offset of the data in the transcript.
+    pub start: usize,
+    /// Revealed data, hex encoded.
+    pub data: String,
+}
+
+impl ExplorerSession {
+    /// Creates a session from the output of a verified presentation.
+    pub fn from_output(output: &PresentationOutput) -> Self {
+        let header = &output.attestation.header;
+        let verifying_key = output.attestation.body.verifying_key();
+
+        let commitments = output
+            .attestation
+            .body
+            .transcript_commitments
+            .iter()
+            .filter_map(|field| match &field.data {
+                TranscriptCommitment::Hash(hash) => Some(ExplorerCommitment {
+                    direction: hash.direction.into(),
+                    ranges: hash
+                        .idx
+                        .iter()
+                        .map(|range| [range.start, range.end])
+                        .collect(),
+                    alg: hash.hash.alg.as_u8(),
+                    hash: hex::encode(hash.hash.value.as_bytes()),
+                }),
+                _ => None,
+            })
+            .collect();
+
+        Self {
+            format_version: FORMAT_VERSION,
+            header: ExplorerHeader {
+                id: hex::encode(header.id.0),
+                version: header.version.0,
+                root: ExplorerHash::from(&header.root),
+                time: header.time,
+            },
+            verifying_key: ExplorerKey {
+                alg: verifying_key.alg.as_u8(),
+                data: hex::encode(&verifying_key.data),
+            },
+            server_name: output
+                .server_name
+                .as_ref()
+                .map(|server_name| server_name.to_string()),
+            connection_time: output.connection_info.time,
+            commitments,
+            transcript: output.transcript.as_ref().map(ExplorerTranscript::from),
+        }
+    }
+
+    /// Serializes the session to JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("session should serialize to JSON")
+    }
+
+    /// Deserializes and validates a session from JSON.
+    pub fn from_json(json: &str) -> Result<Self, ExplorerError> {
+        let session: Self =
+            serde_json::from_str(json).map_err(|e| ExplorerError(format!("invalid JSON: {e}")))?;
+        session.validate()?;
+
+        Ok(session)
+    }
+
+    /// Validates the session.
+    pub fn validate(&self) -> Result<(), ExplorerError> {
+        if self.format_version != FORMAT_VERSION {
+            return Err(ExplorerError(format!(
+                "unsupported format version: {} != {FORMAT_VERSION}",
+                self.format_version
+            )));
+        }
+
+        if decode_hex("header id", &self.header.id)?.len() != 16 {
+            return Err(ExplorerError("header id must be 16 bytes".to_string()));
+        }
+        validate_hash("header root", &self.header.root.value)?;
+        decode_hex("verifying key", &self.verifying_key.data)?;
+
+        for commitment in &self.commitments {
+            validate_hash("commitment hash", &commitment.hash)?;
+            validate_ranges(
+                commitment.ranges.iter().map(|&[start, end]| (start, end)),
+                self.transcript
+                    .as_ref()
+                    .map(|transcript| match commitment.direction {
+                        ExplorerDirection::Sent => transcript.sent.length,
+                        ExplorerDirection::Received => transcript.received.length,
+                    }),
+            )?;
+        }
+
+        if let Some(transcript) = &self.transcript {
+            for data in [&transcript.sent, &transcript.received] {
+                let mut reveals = Vec::with_capacity(data.reveals.len());
+                for reveal in &data.reveals {
+                    let len = decode_hex("revealed data", &reveal.data)?.len();
+                    let end = reveal
+                        .start
+                        .checked_add(len)
+                        .ok_or_else(|| ExplorerError("revealed range overflows".to_string()))?;
+                    reveals.push((reveal.start, end));
+                }
+
+                validate_ranges(reveals.into_iter(), Some(data.length))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl From<&TypedHash> for ExplorerHash {
+    fn from(hash: &TypedHash) -> Self {
+        Self {
+            alg: hash.alg.as_u8(),
+            value: hex::encode(hash.value.as_bytes()),
+        }
+    }
+}
+
+impl From<&PartialTranscript> for ExplorerTranscript {
+    fn from(transcript: &PartialTranscript) -> Self {
+        let data = |direction: Direction, length: usize| ExplorerTranscriptData {
+            length,
+            reveals: transcript
+                .authed_segments(direction)
+                .map(|(range, data)| ExplorerReveal {
+                    start: range.start,
+                    data: hex::encode(data),
+                })
+                .collect(),
+        };
+
+        Self {
+            sent: data(Direction::Sent, transcript.len_sent()),
+            received: data(Direction::Received, transcript.len_received()),
+        }
+    }
+}
+
+impl PresentationOutput {
+    /// Exports the output to the explorer interchange format as JSON.
+    pub fn to_explorer_json(&self) -> String {
+        ExplorerSession::from_output(self).to_json()
+    }
+}
+
+fn decode_hex(field: &str, value: &str) -> Result<Vec<u8>, ExplorerError> {
+    if value.bytes().any(|b| b.is_ascii_uppercase()) {
+        return Err(ExplorerError(format!("{field} must be lowercase hex")));
+    }
+
+    hex::decode(value).map_err(|e| ExplorerError(format!("{field} is not valid hex: {e}")))
+}
+
+fn validate_hash(field: &str, value: &str) -> Result<(), ExplorerError> {
+    let len = decode_hex(field, value)?.len();
+    if len == 0 || len > MAX_HASH_LEN {
+        return Err(ExplorerError(format!(
+            "{field} must be between 1 and {MAX_HASH_LEN} bytes"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Checks that the ranges are non-empty, in ascending order, disjoint and
+/// within the transcript length, if known.
+fn validate_ranges(
+    ranges: impl Iterator<Item = (usize, usize)>,
+    len: Option<usize>,
+) -> Result<(), ExplorerError> {
+    let mut prev_end = None;
+    for (start, end) in ranges {
+        if start >= end {
+            return Err(ExplorerError(format!("empty range: {start}..{end}")));
+        }
+
+        if prev_end.is_some_and(|prev_end| start <= prev_end) {
+            return Err(ExplorerError(format!(
+                "ranges must be disjoint and in ascending order: {start}..{end}"
+            )));
+        }
+
+        if len.is_some_and(|len| end > len) {
+            return Err(ExplorerError(format!(
+                "range is out of bounds of the transcript: {start}..{end}"
+            )));
+        }
+
+        prev_end = Some(end);
+    }
+
+    Ok(())
+}
+
+/// Error for [`ExplorerSession`].
+#[derive(Debug, thiserror::Error)]
+#[error("explorer session error: {0}")]
+pub struct ExplorerError(String);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_session() -> ExplorerSession {
+        ExplorerSession {
+            format_version: FORMAT_VERSION,
+            header: ExplorerHeader {
+                id: hex::encode([1u8; 16]),
+                version: 1,
+                root: ExplorerHash {
+                    alg: 1,
+                    value: hex::encode([2u8; 32]),
+                },
+                time: 43,
+            },
+            verifying_key: ExplorerKey {
+                alg: 1,
+                data: hex::encode([3u8; 33]),
+            },
+            server_name: Some("example.com".to_string()),
+            connection_time: 42,
+            commitments: vec![ExplorerCommitment {
+                direction: ExplorerDirection::Received,
+                ranges: vec![[0, 4], [6, 8]],
+                alg: 2,
+                hash: hex::encode([4u8; 32]),
+            }],
+            transcript: Some(ExplorerTranscript {
+                sent: ExplorerTranscriptData {
+                    length: 4,
+                    reveals: vec![ExplorerReveal {
+                        start: 0,
+                        data: hex::encode(b"GET "),
+                    }],
+                },
+                received: ExplorerTranscriptData {
+                    length: 8,
+                    reveals: vec![ExplorerReveal {
+                        start: 6,
+                        data: hex::encode(b"ok"),
+                    }],
+                },
+            }),
+        }
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let session = test_session();
+
+        assert_eq!(
+            ExplorerSession::from_json(&session.to_json()).unwrap(),
+            session
+        );
+    }
+
+    #[test]
+    fn test_unknown_field() {
+        let mut json: serde_json::Value = serde_json::from_str(&test_session().to_json()).unwrap();
+        json["extra"] = serde_json::Value::Bool(true);
+
+        assert!(ExplorerSession::from_json(&json.to_string()).is_err());
+    }
+
+    #[test]
+    fn test_invalid_session() {
+        let mut session = test_session();
+        session.format_version = FORMAT_VERSION + 1;
+        assert!(session.validate().is_err());
+
+        let mut session = test_session();
+        session.header.id = "zz".repeat(16);
+        assert!(session.validate().is_err());
+
+        let mut session = test_session();
+        session.header.root.value = String::new();
+        assert!(session.validate().is_err());
+
+        let mut session = test_session();
+        session.commitments[0].ranges = vec![[6, 8], [0, 4]];
+        assert!(session.validate().is_err());
+
+        let mut session = test_session();
+        session.commitments[0].ranges = vec![[0, 9]];
+        assert!(session.validate().is_err());
+
+        let mut session = test_session();
+        session.transcript.as_mut().unwrap().received.reveals[0].start = 7;
+        assert!(session.validate().is_err());
+    }
+}
@@ -0,0 +1,218 @@
+//! Canonical serialization of the attestation [`Header`].
+//!
+//! The Notary signs the serialized header, so the layout of a header must
+//! never change once attestations have been signed with it. Instead of
+//! deriving it from the type, the layout is written out explicitly and pinned
+//! by golden files, and new layouts are distinguished by a leading layout
+//! byte.
+//!
+//! The layout depends on the [`Version`](crate::Version) of the attestation.
+//!
+//! # Version 1
+//!
+//! | Offset | Length | Field                             |
+//! |--------|--------|-----------------------------------|
+//! | 0      | 1      | Layout, always `0x01`             |
+//! | 1      | 16     | Attestation id                    |
+//! | 17     | 4      | Version, little-endian            |
+//! | 21     | 1      | Hash algorithm id of the root     |
+//! | 22     | 1      | Length of the root                |
+//! | 23     | n      | Merkle root of the body           |
+//! | 23 + n | 8      | Signing time, little-endian       |
+//!
+//! # Version 0
+//!
+//! Attestations of version 0 were signed over the BCS encoding of the header,
+//! which did not include the signing time:
+//!
+//! | Offset | Length | Field                             |
+//! |--------|--------|-----------------------------------|
+//! | 0      | 16     | Attestation id                    |
+//! | 16     | 4      | Version, little-endian            |
+//! | 20     | 1      | Hash algorithm id of the root     |
+//! | 21     | 1      | Length of the root, ULEB128       |
+//! | 22     | n      | Merkle root of the body           |
+//!
+//! The time of a version 0 header is not signed and is ignored.
+
+use tlsn_core::hash::{Hash, HashAlgorithm};
+
+use crate::{
+    Header,
+    serialize::{CanonicalSerialize, DomainSeparator},
+    signing::{SignatureError, SignatureVerifier, VerifyingKey},
+};
+
+/// Layout byte of the serialized [`Header`].
+pub const HEADER_LAYOUT_V1: u8 = 0x01;
+
+impl Header {
+    /// Returns the canonical serialization of the header, which is signed by
+    /// the Notary.
+    ///
+    /// The layout is chosen by the version of the header, see the
+    /// [module](self) documentation.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self.version.0 {
+            0 => self.to_bytes_v0(),
+            _ => self.to_bytes_v1(),
+        }
+    }
+
+    /// Returns the BCS encoding of the header without the time, which is how
+    /// version 0 headers were signed.
+    fn to_bytes_v0(&self) -> Vec<u8> {
+        CanonicalSerialize::serialize(&(&self.id, &self.version, &self.root))
+    }
+
+    /// Returns the pinned layout of the header.
+    fn to_bytes_v1(&self) -> Vec<u8> {
+        let root = self.root.value.as_bytes();
+        // The root is at most 64 bytes, so its length fits into a single byte.
+        let root_len = u8::try_from(root.len()).expect("root length fits into a byte");
+
+        let mut bytes = Vec::with_capacity(1 + 16 + 4 + 2 + root.len() + 8);
+        bytes.push(HEADER_LAYOUT_V1);
+        bytes.extend_from_slice(&self.id.0);
+        bytes.extend_from_slice(&self.version.0.to_le_bytes());
+        bytes.push(self.root.alg.as_u8());
+        bytes.push(root_len);
+        bytes.extend_from_slice(root);
+        bytes.extend_from_slice(&self.time.to_le_bytes());
+
+        bytes
+    }
+
+    /// Returns the digest of the header.
+    ///
+    /// The digest is computed over the canonical serialization, so it is as
+    /// stable as the signed bytes. For version 0 headers this is the digest
+    /// of earlier releases, which hashed the BCS encoding of the header.
+    pub fn digest(&self, hasher: &dyn HashAlgorithm) -> Hash {
+        hasher.hash_prefixed(self.domain(), &self.to_bytes())
+    }
+
+    /// Verifies a signature over the header.
+    ///
+    /// The signature is checked against the layout of the header's version.
+    ///
+    /// # Arguments
+    ///
+    /// * `verifier` - The signature verifier.
+    /// * `key` - The verifying key of the Notary.
+    /// * `sig` - The signature.
+    pub(crate) fn verify_signature(
+        &self,
+        verifier: &dyn SignatureVerifier,
+        key: &VerifyingKey,
+        sig: &[u8],
+    ) -> Result<(), SignatureError> {
+        verifier.verify(key, &self.to_bytes(), sig)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tlsn_core::hash::{Blake3, HashAlgId, TypedHash};
+
+    use super::*;
+    use crate::{
+        Uid, VERSION, Version,
+        signing::{Secp256k1Signer, Secp256k1Verifier, Signer},
+    };
+
+    const GOLDEN_V0: &str = include_str!("../tests/golden/header_v0.hex");
+    const GOLDEN_V1: &str = include_str!("../tests/golden/header_v1.hex");
+
+    fn header() -> Header {
+        Header {
+            id: Uid(core::array::from_fn(|i| i as u8)),
+            version: VERSION,
+            root: TypedHash {
+                alg: HashAlgId::BLAKE3,
+                value: Hash::try_from(vec![0xab; 32]).unwrap(),
+            },
+            time: 1_700_000_000,
+        }
+    }
+
+    fn header_v0() -> Header {
+        Header {
+            version: Version(0),
+            ..header()
+        }
+    }
+
+    fn golden(hex: &str) -> Vec<u8> {
+        hex::decode(hex.trim()).unwrap()
+    }
+
+    #[test]
+    fn test_header_golden() {
+        assert_eq!(header().to_bytes(), golden(GOLDEN_V1));
+    }
+
+    #[test]
+    fn test_header_v0_golden() {
+        assert_eq!(header_v0().to_bytes(), golden(GOLDEN_V0));
+
+        // The time is not part of version 0 headers.
+        let header = Header {
+            time: 0,
+            ..header_v0()
+        };
+        assert_eq!(header.to_bytes(), golden(GOLDEN_V0));
+    }
+
+    #[test]
+    fn test_header_digest() {
+        let header = header();
+
+        assert_eq!(
+            header.digest(&Blake3::default()),
+            Blake3::default().hash_prefixed(header.domain(), &golden(GOLDEN_V1))
+        );
+
+        let header = header_v0();
+
+        assert_eq!(
+            header.digest(&Blake3::default()),
+            Blake3::default().hash_prefixed(header.domain(), &golden(GOLDEN_V0))
+        );
+    }
+
+    #[test]
+    fn test_header_verify_signature() {
+        let header = header();
+        let signer = Secp256k1Signer::new(&[1u8; 32]).unwrap();
+        let key = signer.verifying_key();
+
+        let signature = signer.sign(&header.to_bytes()).unwrap();
+        assert!(
+            header
+                .verify_signature(&Secp256k1Verifier, &key, &signature.data)
+                .is_ok()
+        );
+
+        // A version 0 signature is over the BCS encoding.
+        let header_v0 = header_v0();
+        let signature = signer.sign(&golden(GOLDEN_V0)).unwrap();
+        assert!(
+            header_v0
+                .verify_signature(&Secp256k1Verifier, &key, &signature.data)
+                .is_ok()
+        );
+        assert!(
+            header
+                .verify_signature(&Secp256k1Verifier, &key, &signature.data)
+                .is_err()
+        );
+
+        let signature = signer.sign(b"not a header").unwrap();
+        assert!(
+            header
+                .verify_signature(&Secp256k1Verifier, &key, &signature.data)
+                .is_err()
+        );
+    }
+}
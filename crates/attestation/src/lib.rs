@@ -31,8 +31,8 @@
 //! Attestations are comprised of two parts: a [`Header`] and a [`Body`].
 //!
 //! The header is the data structure which is signed by a Notary. It
-//! contains a unique identifier, the protocol version, and a Merkle root
-//! of the body fields.
+//! contains a unique identifier, the protocol version, a Merkle root of the
+//! body fields, and the time at which it was signed.
 //!
 //! The body contains the fields of the attestation. These fields include data
 //! which can be used to verify aspects of a TLS connection, such as the
@@ -49,7 +49,11 @@
 //! to their attestation request, provided that the Notary supports them
 //! (disallowed by default). A Notary may also be configured to
 //! [validate](crate::AttestationConfigBuilder::extension_validator)
-//! any extensions requested by a Prover using custom application logic.
+//! any extensions requested by a Prover using custom application logic, or to
+//! [register](crate::AttestationConfigBuilder::register_extension) extension
+//! types it accepts, e.g. a [nonce](crate::nonce) from a relying party which
+//! binds the attestation to a context. The number and size of requested extensions are
+//! [limited](crate::AttestationConfigBuilder::max_extensions).
 //! Additionally, a Notary may
 //! [include](crate::AttestationBuilder::extension)
 //! their own extensions.
@@ -120,6 +124,10 @@
 //! signing key for attestations. See
 //! [`SignerProvider`](crate::signing::SignerProvider) for more information.
 //!
+//! The signing key may also be kept by a separate service, which signs an
+//! [`UnsignedAttestation`] handed off by the Notary after validating it
+//! against the Notary's [`SessionEvidence`].
+//!
 //! # Constructing a presentation
 //!
 //! A Prover can use an [`Attestation`] and the corresponding [`Secrets`] to
@@ -191,13 +199,18 @@
 #![deny(clippy::all)]
 #![forbid(unsafe_code)]
 
+pub mod anchor;
 mod builder;
 mod config;
 pub mod connection;
+pub mod evm;
+pub mod explorer;
 mod extension;
 #[cfg(any(test, feature = "fixtures"))]
 pub mod fixtures;
 pub(crate) mod hash;
+mod header;
+pub mod nonce;
 pub mod presentation;
 mod proof;
 mod provider;
@@ -205,6 +218,7 @@ pub mod request;
 mod secrets;
 pub(crate) mod serialize;
 pub mod signing;
+mod unsigned;
 
 use std::fmt;
 
@@ -229,11 +243,13 @@ use crate::{
 pub use builder::{AttestationBuilder, AttestationBuilderError};
 pub use config::{AttestationConfig, AttestationConfigBuilder, AttestationConfigError};
 pub use extension::{Extension, InvalidExtension};
+pub use header::HEADER_LAYOUT_V1;
 pub use proof::{AttestationError, AttestationProof};
 pub use provider::CryptoProvider;
-pub use secrets::Secrets;
+pub use secrets::{SealError, SealedSecrets, Secrets};
+pub use unsigned::{SessionEvidence, UnsignedAttestation};
 /// Current version of attestations.
-pub const VERSION: Version = Version(0);
+pub const VERSION: Version = Version(1);
 
 /// Unique identifier for an attestation.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
@@ -310,6 +326,9 @@ pub struct Header {
     pub version: Version,
     /// Merkle root of the attestation fields.
     pub root: TypedHash,
+    /// Time at which the attestation was signed, as a UNIX timestamp in
+    /// seconds.
+    pub time: u64,
 }
 
 impl_domain_separator!(Header);
@@ -440,4 +459,15 @@ impl Attestation {
     ) -> PresentationBuilder<'a> {
         PresentationBuilder::new(provider, self)
     }
+
+    /// Returns the payload to anchor in an external timestamping system.
+    ///
+    /// The payload commits to the header and the Notary's signature. See the
+    /// [`anchor`] module for more information.
+    pub fn anchor_payload(&self, hasher: &dyn HashAlgorithm) -> Hash {
+        let mut data = self.header.digest(hasher).as_bytes().to_vec();
+        data.extend_from_slice(&self.signature.data);
+
+        hasher.hash_prefixed(anchor::ANCHOR_DOMAIN, &data)
+    }
 }
@@ -0,0 +1,141 @@
+//! Nonce challenges of relying parties.
+//!
+//! An attestation on its own can be presented to any Verifier, any number of
+//! times. A relying party which wants to be sure that a presentation was
+//! produced for it can issue a fresh [`Nonce`] to the Prover, which binds it
+//! to both the attestation and the TLS connection:
+//!
+//! 1. The Prover sends the nonce to the server, e.g. in a request header named
+//!    [`NONCE_HEADER`] with the [hex encoded](Nonce::to_hex) nonce as value.
+//! 2. The Prover requests the nonce [extension](Nonce::extension) to be
+//!    included in the attestation. The Notary must
+//!    [accept](crate::AttestationConfigBuilder::accept_nonces) it.
+//! 3. The Prover reveals the bytes of the request which contain the nonce in
+//!    its presentation.
+//!
+//! The relying party then checks the verified presentation with
+//! [`PresentationOutput::verify_nonce`](crate::presentation::PresentationOutput::verify_nonce).
+//! A presentation of an earlier connection can not be replayed, as neither
+//! the attestation nor the transcript can be changed to contain the new nonce.
+
+use std::fmt;
+
+use rand::distr::{Distribution, StandardUniform};
+use serde::{Deserialize, Serialize};
+
+use crate::{Extension, InvalidExtension};
+
+/// Identifier of the nonce extension.
+pub const NONCE_EXTENSION_ID: &[u8] = b"tlsn.nonce";
+
+/// Name of the request header which is recommended to carry the nonce.
+pub const NONCE_HEADER: &str = "X-TLSN-Nonce";
+
+/// Length of a nonce in bytes.
+pub const NONCE_LEN: usize = 32;
+
+/// A nonce issued by a relying party.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Nonce([u8; NONCE_LEN]);
+
+impl Nonce {
+    /// Creates a new nonce from its bytes.
+    pub fn new(bytes: [u8; NONCE_LEN]) -> Self {
+        Self(bytes)
+    }
+
+    /// Generates a random nonce.
+    pub fn random() -> Self {
+        rand::random()
+    }
+
+    /// Parses a hex encoded nonce.
+    pub fn from_hex(value: &str) -> Result<Self, NonceError> {
+        let bytes = hex::decode(value).map_err(|e| NonceError(format!("invalid hex: {e}")))?;
+
+        bytes.try_into().map(Self).map_err(|bytes: Vec<u8>| {
+            NonceError(format!(
+                "nonce must be {NONCE_LEN} bytes, got {}",
+                bytes.len()
+            ))
+        })
+    }
+
+    /// Returns the bytes of the nonce.
+    pub fn as_bytes(&self) -> &[u8; NONCE_LEN] {
+        &self.0
+    }
+
+    /// Returns the hex encoding of the nonce, as it is sent to the server.
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.0)
+    }
+
+    /// Returns the attestation extension containing the nonce.
+    pub fn extension(&self) -> Extension {
+        Extension {
+            id: NONCE_EXTENSION_ID.to_vec(),
+            value: self.0.to_vec(),
+        }
+    }
+
+    /// Validates the value of a nonce extension.
+    pub(crate) fn validate_extension(value: &[u8]) -> Result<(), InvalidExtension> {
+        if value.len() != NONCE_LEN {
+            return Err(InvalidExtension::new(format!(
+                "nonce must be {NONCE_LEN} bytes, got {}",
+                value.len()
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl From<[u8; NONCE_LEN]> for Nonce {
+    fn from(bytes: [u8; NONCE_LEN]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl Distribution<Nonce> for StandardUniform {
+    fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> Nonce {
+        Nonce(self.sample(rng))
+    }
+}
+
+impl fmt::Display for Nonce {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+/// Error for [`Nonce`].
+#[derive(Debug, thiserror::Error)]
+#[error("invalid nonce: {0}")]
+pub struct NonceError(String);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nonce_hex() {
+        let nonce = Nonce::random();
+        assert_eq!(Nonce::from_hex(&nonce.to_hex()).unwrap(), nonce);
+        assert_eq!(nonce.to_string(), nonce.to_hex());
+
+        assert!(Nonce::from_hex("zz").is_err());
+        assert!(Nonce::from_hex(&hex::encode([0u8; 16])).is_err());
+    }
+
+    #[test]
+    fn test_nonce_extension() {
+        let nonce = Nonce::random();
+        let extension = nonce.extension();
+
+        assert_eq!(extension.id, NONCE_EXTENSION_ID);
+        assert!(Nonce::validate_extension(&extension.value).is_ok());
+        assert!(Nonce::validate_extension(&[0u8; 16]).is_err());
+    }
+}
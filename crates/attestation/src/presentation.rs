@@ -28,12 +28,17 @@ use serde::{Deserialize, Serialize};
 
 use tlsn_core::{
     connection::{ConnectionInfo, ServerName},
-    transcript::{PartialTranscript, TranscriptProof, TranscriptProofError},
+    transcript::{
+        CommittedLength, Direction, PartialTranscript, TranscriptProof, TranscriptProofError,
+        TranscriptProofV0,
+    },
 };
 
 use crate::{
     Attestation, AttestationError, AttestationProof, CryptoProvider, Extension,
+    anchor::AnchorVerifier,
     connection::{ServerIdentityProof, ServerIdentityProofError},
+    nonce::{NONCE_EXTENSION_ID, Nonce},
     signing::VerifyingKey,
 };
 
@@ -48,6 +53,27 @@ pub struct Presentation {
     transcript: Option<TranscriptProof>,
 }
 
+/// A [`Presentation`] in the layout of earlier releases, whose transcript
+/// proof did not start with a version.
+///
+/// See [`TranscriptProofV0`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PresentationV0 {
+    attestation: AttestationProof,
+    identity: Option<ServerIdentityProof>,
+    transcript: Option<TranscriptProofV0>,
+}
+
+impl From<PresentationV0> for Presentation {
+    fn from(presentation: PresentationV0) -> Self {
+        Self {
+            attestation: presentation.attestation,
+            identity: presentation.identity,
+            transcript: presentation.transcript.map(TranscriptProof::from),
+        }
+    }
+}
+
 impl Presentation {
     /// Creates a new builder.
     pub fn builder<'a>(
@@ -62,6 +88,17 @@ impl Presentation {
         self.attestation.verifying_key()
     }
 
+    /// Returns a human-readable summary of the presentation.
+    ///
+    /// The summary includes a fingerprint of the Notary's key, the server
+    /// name, the connection time and the revealed parts of the transcript.
+    ///
+    /// The presentation is not verified, so the summary must not be relied
+    /// upon for anything other than inspection.
+    pub fn describe(&self) -> String {
+        self.to_string()
+    }
+
     /// Verifies the presentation.
     pub fn verify(
         self,
@@ -86,6 +123,11 @@ impl Presentation {
             })
             .transpose()?;
 
+        let transcript_lengths = transcript
+            .as_ref()
+            .map(|transcript| transcript.lengths().to_vec())
+            .unwrap_or_default();
+
         let transcript = transcript
             .map(|transcript| {
                 transcript.verify_with_provider(
@@ -101,15 +143,187 @@ impl Presentation {
         let extensions = attestation.body.extensions().cloned().collect();
 
         Ok(PresentationOutput {
+            attestation,
+            server_name,
+            connection_info,
+            transcript,
+            transcript_lengths,
+            extensions,
+        })
+    }
+
+    /// Verifies the presentation against a policy.
+    ///
+    /// In addition to [`verify`](Self::verify), this checks that the
+    /// attestation is signed by a trusted key, and any other requirements of
+    /// the policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - The crypto provider.
+    /// * `policy` - The verification policy.
+    /// * `now` - The current time as a UNIX timestamp in seconds.
+    pub fn verify_with_policy(
+        self,
+        provider: &CryptoProvider,
+        policy: &VerificationPolicy,
+        now: u64,
+    ) -> Result<VerifiedSession, PresentationError> {
+        let verifying_key = self.verifying_key().clone();
+        if !policy.trusted_keys.contains(&verifying_key) {
+            return Err(PresentationError::policy(
+                "attestation is not signed by a trusted key",
+            ));
+        }
+
+        let PresentationOutput {
             attestation,
             server_name,
             connection_info,
             transcript,
             extensions,
+            ..
+        } = self.verify(provider)?;
+
+        if let Some(max_age) = policy.max_age {
+            let age = now.saturating_sub(connection_info.time);
+            if age > max_age {
+                return Err(PresentationError::policy(format!(
+                    "attestation is {age} seconds old, the maximum age is {max_age} seconds"
+                )));
+            }
+        }
+
+        if let Some(max_duration) = policy.max_session_duration {
+            let duration = attestation.header.time.saturating_sub(connection_info.time);
+            if duration > max_duration {
+                return Err(PresentationError::policy(format!(
+                    "session lasted {duration} seconds, the maximum is {max_duration} seconds"
+                )));
+            }
+        }
+
+        if let Some(expected) = &policy.server_name {
+            match &server_name {
+                Some(server_name) if server_name == expected => {}
+                Some(server_name) => {
+                    return Err(PresentationError::policy(format!(
+                        "expected server {expected}, got {server_name}"
+                    )));
+                }
+                None => {
+                    return Err(PresentationError::policy(format!(
+                        "expected server {expected}, but the server identity was not disclosed"
+                    )));
+                }
+            }
+        }
+
+        Ok(VerifiedSession {
+            verifying_key,
+            server_name,
+            time: connection_info.time,
+            signed_at: attestation.header.time,
+            transcript,
+            extensions,
         })
     }
 }
 
+impl fmt::Display for Presentation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verifying_key = self.verifying_key();
+        let fingerprint = blake3::hash(&verifying_key.data);
+
+        writeln!(f, "unverified presentation")?;
+        writeln!(
+            f,
+            "notary key: {} {}",
+            verifying_key.alg,
+            hex::encode(&fingerprint.as_bytes()[..8])
+        )?;
+
+        match &self.identity {
+            Some(identity) => writeln!(f, "server: {}", identity.name())?,
+            None => writeln!(f, "server: undisclosed")?,
+        }
+
+        writeln!(f, "time: {}", self.attestation.connection_info().time)?;
+        if self.attestation.connection_info().truncated {
+            writeln!(f, "truncated: server did not send close_notify")?;
+        }
+
+        match &self.transcript {
+            Some(transcript) => write!(f, "{transcript}"),
+            None => writeln!(f, "transcript: undisclosed"),
+        }
+    }
+}
+
+/// Policy for verifying a [`Presentation`].
+///
+/// See [`Presentation::verify_with_policy`].
+#[derive(Debug, Clone, Default)]
+pub struct VerificationPolicy {
+    trusted_keys: Vec<VerifyingKey>,
+    max_age: Option<u64>,
+    max_session_duration: Option<u64>,
+    server_name: Option<ServerName>,
+}
+
+impl VerificationPolicy {
+    /// Creates a new policy which does not trust any key.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trusts attestations signed with the given key.
+    pub fn trust_key(&mut self, key: VerifyingKey) -> &mut Self {
+        if !self.trusted_keys.contains(&key) {
+            self.trusted_keys.push(key);
+        }
+        self
+    }
+
+    /// Sets the maximum age of the TLS connection in seconds.
+    pub fn max_age(&mut self, seconds: u64) -> &mut Self {
+        self.max_age = Some(seconds);
+        self
+    }
+
+    /// Sets the maximum duration of the session in seconds, from the TLS
+    /// handshake until the attestation was signed.
+    pub fn max_session_duration(&mut self, seconds: u64) -> &mut Self {
+        self.max_session_duration = Some(seconds);
+        self
+    }
+
+    /// Requires the presentation to disclose the given server name.
+    pub fn server_name(&mut self, server_name: ServerName) -> &mut Self {
+        self.server_name = Some(server_name);
+        self
+    }
+}
+
+/// A session verified with [`Presentation::verify_with_policy`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct VerifiedSession {
+    /// Key the attestation was signed with.
+    pub verifying_key: VerifyingKey,
+    /// Authenticated server name.
+    pub server_name: Option<ServerName>,
+    /// Time of the TLS connection as a UNIX timestamp in seconds.
+    pub time: u64,
+    /// Time at which the attestation was signed as a UNIX timestamp in
+    /// seconds.
+    pub signed_at: u64,
+    /// Authenticated transcript data.
+    pub transcript: Option<PartialTranscript>,
+    /// Extensions.
+    pub extensions: Vec<Extension>,
+}
+
 /// Output of a verified [`Presentation`].
 #[derive(Debug)]
 #[non_exhaustive]
@@ -122,10 +336,106 @@ pub struct PresentationOutput {
     pub connection_info: ConnectionInfo,
     /// Authenticated transcript data.
     pub transcript: Option<PartialTranscript>,
+    /// Committed ranges of the transcript whose lengths were disclosed without
+    /// their contents.
+    pub transcript_lengths: Vec<CommittedLength>,
     /// Extensions.
     pub extensions: Vec<Extension>,
 }
 
+impl PresentationOutput {
+    /// Verifies an anchor of the attestation in an external timestamping
+    /// system, returning the UNIX time in seconds at which it was anchored.
+    ///
+    /// This proves that the connection took place no later than the returned
+    /// time, independently of the Notary.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - The crypto provider.
+    /// * `verifier` - Verifier of the timestamping system.
+    /// * `proof` - Inclusion proof of the
+    ///   [anchor payload](Attestation::anchor_payload).
+    pub fn verify_anchor(
+        &self,
+        provider: &CryptoProvider,
+        verifier: &dyn AnchorVerifier,
+        proof: &[u8],
+    ) -> Result<u64, PresentationError> {
+        let hasher = provider
+            .hash
+            .get(&self.attestation.header.root.alg)
+            .map_err(PresentationError::anchor)?;
+
+        let time = verifier
+            .verify(&self.attestation.anchor_payload(hasher), proof)
+            .map_err(PresentationError::anchor)?;
+
+        if time < self.connection_info.time {
+            return Err(PresentationError::anchor(format!(
+                "attestation was anchored at {time}, before the connection at {}",
+                self.connection_info.time
+            )));
+        }
+
+        Ok(time)
+    }
+
+    /// Verifies that the presentation answers the [nonce](crate::nonce)
+    /// challenge of a relying party.
+    ///
+    /// The attestation must contain exactly one nonce extension, which is
+    /// equal to `nonce`, and the hex encoded nonce must appear in the
+    /// authenticated data sent to the server.
+    ///
+    /// # Arguments
+    ///
+    /// * `nonce` - The nonce issued by the relying party.
+    pub fn verify_nonce(&self, nonce: &Nonce) -> Result<(), PresentationError> {
+        let mut extensions = self
+            .extensions
+            .iter()
+            .filter(|extension| extension.id == NONCE_EXTENSION_ID);
+
+        match (extensions.next(), extensions.next()) {
+            (Some(extension), None) if extension.value == nonce.as_bytes() => {}
+            (Some(_), None) => {
+                return Err(PresentationError::nonce(
+                    "attestation contains a different nonce",
+                ));
+            }
+            (None, _) => {
+                return Err(PresentationError::nonce(
+                    "attestation does not contain a nonce",
+                ));
+            }
+            (Some(_), Some(_)) => {
+                return Err(PresentationError::nonce(
+                    "attestation contains more than one nonce",
+                ));
+            }
+        }
+
+        let transcript = self.transcript.as_ref().ok_or_else(|| {
+            PresentationError::nonce("presentation does not reveal the transcript")
+        })?;
+
+        let hex = nonce.to_hex();
+        let sent = transcript.sent_unsafe();
+        let revealed = (0..(sent.len() + 1).saturating_sub(hex.len())).any(|start| {
+            transcript.get(Direction::Sent, start..start + hex.len()) == Some(hex.as_bytes())
+        });
+
+        if !revealed {
+            return Err(PresentationError::nonce(
+                "nonce does not appear in the revealed sent data",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
 /// Builder for [`Presentation`].
 pub struct PresentationBuilder<'a> {
     provider: &'a CryptoProvider,
@@ -217,6 +527,46 @@ enum ErrorKind {
     Attestation,
     Identity,
     Transcript,
+    Policy,
+    Anchor,
+    Nonce,
+}
+
+impl PresentationError {
+    fn anchor<E>(source: E) -> Self
+    where
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        Self {
+            kind: ErrorKind::Anchor,
+            source: Some(source.into()),
+        }
+    }
+
+    fn nonce<E>(source: E) -> Self
+    where
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        Self {
+            kind: ErrorKind::Nonce,
+            source: Some(source.into()),
+        }
+    }
+
+    fn policy<E>(source: E) -> Self
+    where
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        Self {
+            kind: ErrorKind::Policy,
+            source: Some(source.into()),
+        }
+    }
+
+    /// Returns whether the presentation is valid but violates the policy.
+    pub fn is_policy(&self) -> bool {
+        matches!(self.kind, ErrorKind::Policy)
+    }
 }
 
 impl fmt::Display for PresentationError {
@@ -227,6 +577,9 @@ impl fmt::Display for PresentationError {
             ErrorKind::Attestation => f.write_str("attestation error")?,
             ErrorKind::Identity => f.write_str("server identity error")?,
             ErrorKind::Transcript => f.write_str("transcript error")?,
+            ErrorKind::Policy => f.write_str("policy error")?,
+            ErrorKind::Anchor => f.write_str("anchor error")?,
+            ErrorKind::Nonce => f.write_str("nonce error")?,
         }
 
         if let Some(source) = &self.source {
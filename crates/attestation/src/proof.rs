@@ -3,13 +3,13 @@ use std::fmt;
 use serde::{Deserialize, Serialize};
 
 use tlsn_core::{
+    connection::ConnectionInfo,
     hash::HashAlgorithm,
     merkle::{MerkleProof, MerkleTree},
 };
 
 use crate::{
-    Attestation, Body, CryptoProvider, Header,
-    serialize::CanonicalSerialize,
+    Attestation, Body, CryptoProvider, Header, VERSION,
     signing::{Signature, VerifyingKey},
 };
 
@@ -45,6 +45,11 @@ impl AttestationProof {
         self.body.verifying_key()
     }
 
+    /// Returns the connection information, without verifying it.
+    pub(crate) fn connection_info(&self) -> &ConnectionInfo {
+        self.body.body.connection_info()
+    }
+
     /// Verifies the attestation proof.
     ///
     /// # Arguments
@@ -52,6 +57,18 @@ impl AttestationProof {
     /// * `provider` - Cryptography provider.
     /// * `verifying_key` - Verifying key for the Notary signature.
     pub fn verify(self, provider: &CryptoProvider) -> Result<Attestation, AttestationError> {
+        // The version is signed, so attestations of other versions can not be
+        // misread as this one.
+        if self.header.version != VERSION {
+            return Err(AttestationError::new(
+                ErrorKind::Version,
+                format!(
+                    "unsupported attestation version {}, this release supports version {}",
+                    self.header.version.0, VERSION.0
+                ),
+            ));
+        }
+
         let signature_verifier = provider
             .signature
             .get(&self.signature.alg)
@@ -61,10 +78,10 @@ impl AttestationProof {
         let body = self.body.verify_with_provider(provider, &self.header)?;
 
         // Verify signature of the header.
-        signature_verifier
-            .verify(
+        self.header
+            .verify_signature(
+                signature_verifier,
                 &body.verifying_key.data,
-                &CanonicalSerialize::serialize(&self.header),
                 &self.signature.data,
             )
             .map_err(|e| AttestationError::new(ErrorKind::Signature, e))?;
@@ -162,6 +179,7 @@ impl fmt::Display for AttestationError {
 
         match self.kind {
             ErrorKind::Provider => f.write_str("provider error")?,
+            ErrorKind::Version => f.write_str("version error")?,
             ErrorKind::Signature => f.write_str("signature error")?,
             ErrorKind::Body => f.write_str("body proof error")?,
         }
@@ -177,6 +195,7 @@ impl fmt::Display for AttestationError {
 #[derive(Debug)]
 enum ErrorKind {
     Provider,
+    Version,
     Signature,
     Body,
 }
@@ -22,7 +22,7 @@ use tlsn_core::hash::HashAlgId;
 
 use crate::{
     Attestation, CryptoProvider, Extension, connection::ServerCertCommitment,
-    serialize::CanonicalSerialize, signing::SignatureAlgId,
+    signing::SignatureAlgId,
 };
 
 pub use builder::{RequestBuilder, RequestBuilderError};
@@ -63,6 +63,22 @@ impl Request {
             )));
         }
 
+        let hasher = provider
+            .hash
+            .get(&attestation.header.root.alg)
+            .map_err(|_| {
+                AttestationValidationError::provider(format!(
+                    "provider not configured for hash algorithm id {:?}",
+                    attestation.header.root.alg,
+                ))
+            })?;
+
+        if attestation.body.root(hasher) != attestation.header.root {
+            return Err(AttestationValidationError::inconsistent(
+                "merkle root does not match the attestation body",
+            ));
+        }
+
         if attestation.body.cert_commitment() != &self.server_cert_commitment {
             return Err(AttestationValidationError::inconsistent(
                 "server certificate commitment does not match",
@@ -88,10 +104,11 @@ impl Request {
                 ))
             })?;
 
-        verifier
-            .verify(
+        attestation
+            .header
+            .verify_signature(
+                verifier,
                 &attestation.body.verifying_key.data,
-                &CanonicalSerialize::serialize(&attestation.header),
                 &attestation.signature.data,
             )
             .map_err(|_| {
@@ -144,7 +161,9 @@ impl std::fmt::Display for ErrorKind {
 #[cfg(test)]
 mod test {
     use tlsn_core::{
-        connection::TranscriptLength, fixtures::ConnectionFixture, hash::HashAlgId,
+        connection::TranscriptLength,
+        fixtures::ConnectionFixture,
+        hash::{Hash, HashAlgId},
         transcript::Transcript,
     };
     use tlsn_data_fixtures::http::{request::GET_WITH_HEADER, response::OK_JSON};
@@ -257,6 +276,27 @@ mod test {
         assert!(request.validate(&attestation, &provider).is_err())
     }
 
+    #[test]
+    fn test_wrong_root() {
+        let transcript = Transcript::new(GET_WITH_HEADER, OK_JSON);
+        let connection = ConnectionFixture::tlsnotary(transcript.length());
+
+        let RequestFixture { request, .. } =
+            request_fixture(transcript, connection.clone(), Vec::new());
+
+        let mut attestation =
+            attestation_fixture(request.clone(), connection, SignatureAlgId::SECP256K1, &[]);
+
+        // A malicious Notary signs a root which does not commit to the body.
+        attestation.header.root.value = Hash::default();
+
+        let provider = CryptoProvider::default();
+
+        let err = request.validate(&attestation, &provider).unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::Inconsistent));
+        assert!(err.message.contains("merkle root"));
+    }
+
     #[test]
     fn test_wrong_provider() {
         let transcript = Transcript::new(GET_WITH_HEADER, OK_JSON);
@@ -1,3 +1,8 @@
+use aes_gcm::{
+    Aes256Gcm, Key, Nonce,
+    aead::{Aead, NewAead, Payload},
+};
+use rand::{Rng, rng};
 use serde::{Deserialize, Serialize};
 
 use tlsn_core::{
@@ -5,7 +10,10 @@ use tlsn_core::{
     transcript::{Transcript, TranscriptCommitment, TranscriptProofBuilder, TranscriptSecret},
 };
 
-use crate::connection::{ServerCertOpening, ServerIdentityProof};
+use crate::{
+    Attestation,
+    connection::{ServerCertOpening, ServerIdentityProof},
+};
 
 /// Secret data of an [`Attestation`](crate::Attestation).
 #[derive(Clone, Serialize, Deserialize)]
@@ -35,8 +43,195 @@ impl Secrets {
         ServerIdentityProof::new(self.server_name.clone(), self.server_cert_opening.clone())
     }
 
+    /// Removes the openings of all transcript commitments for which `keep`
+    /// returns `false`.
+    ///
+    /// Use this once it is known which commitments will ever be revealed, to
+    /// reduce the amount of secret material which is stored. Pruned
+    /// commitments can no longer be opened in a transcript proof, this can not
+    /// be undone.
+    ///
+    /// # Arguments
+    ///
+    /// * `keep` - Returns whether the opening of a commitment should be kept.
+    pub fn prune(&mut self, keep: impl FnMut(&TranscriptSecret) -> bool) {
+        self.transcript_commitment_secrets.retain(keep);
+    }
+
     /// Returns a transcript proof builder.
     pub fn transcript_proof_builder(&self) -> TranscriptProofBuilder<'_> {
         TranscriptProofBuilder::new(&self.transcript, &self.transcript_commitment_secrets)
     }
+
+    /// Encrypts the secrets for storage at rest.
+    ///
+    /// The secrets are encrypted with AES-256-GCM and bound to the header of
+    /// the provided attestation, so they can only be unsealed alongside the
+    /// attestation they were issued with.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The encryption key.
+    /// * `attestation` - The attestation corresponding to these secrets.
+    pub fn seal(
+        &self,
+        key: &[u8; 32],
+        attestation: &Attestation,
+    ) -> Result<SealedSecrets, SealError> {
+        let nonce: [u8; 12] = rng().random();
+        let plaintext = bcs::to_bytes(self)
+            .map_err(|_| SealError("failed to serialize secrets".to_string()))?;
+
+        let ciphertext = Aes256Gcm::new(Key::from_slice(key))
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &plaintext,
+                    aad: &attestation.header.to_bytes(),
+                },
+            )
+            .map_err(|_| SealError("failed to encrypt secrets".to_string()))?;
+
+        Ok(SealedSecrets { nonce, ciphertext })
+    }
+}
+
+/// Encrypted [`Secrets`], see [`Secrets::seal`].
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SealedSecrets {
+    nonce: [u8; 12],
+    ciphertext: Vec<u8>,
+}
+
+opaque_debug::implement!(SealedSecrets);
+
+impl SealedSecrets {
+    /// Decrypts the secrets.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The encryption key.
+    /// * `attestation` - The attestation the secrets were sealed with.
+    pub fn unseal(&self, key: &[u8; 32], attestation: &Attestation) -> Result<Secrets, SealError> {
+        let plaintext = Aes256Gcm::new(Key::from_slice(key))
+            .decrypt(
+                Nonce::from_slice(&self.nonce),
+                Payload {
+                    msg: &self.ciphertext,
+                    aad: &attestation.header.to_bytes(),
+                },
+            )
+            .map_err(|_| {
+                SealError("failed to decrypt secrets, wrong key or attestation".to_string())
+            })?;
+
+        bcs::from_bytes(&plaintext)
+            .map_err(|_| SealError("failed to deserialize secrets".to_string()))
+    }
+}
+
+/// Error for [`Secrets::seal`] and [`SealedSecrets::unseal`].
+#[derive(Debug, thiserror::Error)]
+#[error("seal error: {0}")]
+pub struct SealError(String);
+
+#[cfg(test)]
+mod test {
+    use tlsn_core::{
+        fixtures::ConnectionFixture,
+        transcript::{Direction, TranscriptCommitConfigBuilder},
+    };
+    use tlsn_data_fixtures::http::{request::GET_WITH_HEADER, response::OK_JSON};
+
+    use crate::{
+        CryptoProvider,
+        fixtures::attestation_fixture,
+        request::{Request, RequestConfig},
+        signing::SignatureAlgId,
+    };
+
+    use super::*;
+
+    fn secrets_fixture(transcript: Transcript) -> (Attestation, Secrets) {
+        let connection = ConnectionFixture::tlsnotary(transcript.length());
+        let (sent_len, recv_len) = transcript.len();
+
+        let mut commit_builder = TranscriptCommitConfigBuilder::new(&transcript);
+        commit_builder
+            .commit_sent(&(0..sent_len))
+            .unwrap()
+            .commit_recv(&(0..recv_len))
+            .unwrap();
+
+        let mut builder = RequestConfig::builder();
+        builder.transcript_commit(commit_builder.build().unwrap());
+        let request_config = builder.build().unwrap();
+
+        let mut request_builder = Request::builder(&request_config);
+        request_builder
+            .server_name(connection.server_name.clone())
+            .handshake_data(connection.server_cert_data.clone())
+            .transcript(transcript);
+
+        let (request, secrets) = request_builder.build(&CryptoProvider::default()).unwrap();
+        let attestation = attestation_fixture(
+            request,
+            connection,
+            SignatureAlgId::SECP256K1,
+            &secrets.transcript_commitments,
+        );
+
+        (attestation, secrets)
+    }
+
+    #[test]
+    fn test_seal_unseal() {
+        let (attestation, secrets) = secrets_fixture(Transcript::new(GET_WITH_HEADER, OK_JSON));
+        let key = [1u8; 32];
+
+        let sealed = secrets.seal(&key, &attestation).unwrap();
+        let unsealed = sealed.unseal(&key, &attestation).unwrap();
+
+        assert_eq!(unsealed.transcript().sent(), secrets.transcript().sent());
+        assert_eq!(
+            unsealed.transcript().received(),
+            secrets.transcript().received()
+        );
+        assert_eq!(unsealed.server_name(), secrets.server_name());
+    }
+
+    #[test]
+    fn test_unseal_wrong_key() {
+        let (attestation, secrets) = secrets_fixture(Transcript::new(GET_WITH_HEADER, OK_JSON));
+
+        let sealed = secrets.seal(&[1u8; 32], &attestation).unwrap();
+
+        assert!(sealed.unseal(&[2u8; 32], &attestation).is_err());
+    }
+
+    #[test]
+    fn test_unseal_wrong_attestation() {
+        let (attestation, secrets) = secrets_fixture(Transcript::new(GET_WITH_HEADER, OK_JSON));
+        let (other_attestation, _) = secrets_fixture(Transcript::new(GET_WITH_HEADER, OK_JSON));
+        let key = [1u8; 32];
+
+        let sealed = secrets.seal(&key, &attestation).unwrap();
+
+        assert!(sealed.unseal(&key, &other_attestation).is_err());
+    }
+
+    #[test]
+    fn test_prune() {
+        let (_, mut secrets) = secrets_fixture(Transcript::new(GET_WITH_HEADER, OK_JSON));
+        let (sent_len, recv_len) = secrets.transcript().len();
+
+        secrets.prune(|secret| match secret {
+            TranscriptSecret::Hash(hash) => hash.direction == Direction::Sent,
+            _ => true,
+        });
+
+        let mut builder = secrets.transcript_proof_builder();
+        builder.reveal_sent(&(0..sent_len)).unwrap();
+        assert!(builder.reveal_recv(&(0..recv_len)).is_err());
+    }
 }
@@ -1,6 +1,9 @@
 //! Cryptographic signatures.
 
-use std::collections::HashMap;
+use std::{collections::HashMap, future::Future, pin::Pin, time::Duration};
+
+use futures::future::{self, Either};
+use futures_timer::Delay;
 
 use serde::{Deserialize, Serialize};
 
@@ -166,6 +169,137 @@ pub trait Signer {
     fn verifying_key(&self) -> VerifyingKey;
 }
 
+/// Future returned by [`AsyncSigner::sign`].
+pub type SignFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<Signature, SignatureError>> + Send + 'a>>;
+
+/// Cryptographic signer which signs asynchronously.
+///
+/// This is intended for keys which are kept in a remote KMS or an HSM, where
+/// signing requires a network call. Wrap the signer in a [`RetrySigner`] to
+/// bound the time spent signing.
+pub trait AsyncSigner {
+    /// Returns the algorithm used by this signer.
+    fn alg_id(&self) -> SignatureAlgId;
+
+    /// Signs the message.
+    fn sign<'a>(&'a self, msg: &'a [u8]) -> SignFuture<'a>;
+
+    /// Returns the verifying key for this signer.
+    fn verifying_key(&self) -> VerifyingKey;
+}
+
+/// Adapter which implements [`AsyncSigner`] for a [`Signer`].
+///
+/// The signer is called in place, blocking the current task.
+#[derive(Debug)]
+pub struct BlockingSigner<S>(pub S);
+
+impl<S> AsyncSigner for BlockingSigner<S>
+where
+    S: Signer + Sync,
+{
+    fn alg_id(&self) -> SignatureAlgId {
+        self.0.alg_id()
+    }
+
+    fn sign<'a>(&'a self, msg: &'a [u8]) -> SignFuture<'a> {
+        let result = self.0.sign(msg);
+        Box::pin(async move { result })
+    }
+
+    fn verifying_key(&self) -> VerifyingKey {
+        self.0.verifying_key()
+    }
+}
+
+/// Retry policy of a [`RetrySigner`].
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Maximum number of signing attempts, at least 1.
+    pub max_attempts: usize,
+    /// Delay before the first retry, doubled before each further retry.
+    pub backoff: Duration,
+    /// Maximum delay between two attempts.
+    pub max_backoff: Duration,
+    /// Timeout of a single signing attempt.
+    pub timeout: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(2),
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// [`AsyncSigner`] which retries failed signing attempts.
+///
+/// Each attempt is cancelled after the timeout of the [`RetryPolicy`], and
+/// failed attempts are retried with exponential backoff.
+#[derive(Debug)]
+pub struct RetrySigner<S> {
+    signer: S,
+    policy: RetryPolicy,
+}
+
+impl<S> RetrySigner<S> {
+    /// Creates a new retrying signer.
+    ///
+    /// # Arguments
+    ///
+    /// * `signer` - The signer to wrap.
+    /// * `policy` - The retry policy.
+    pub fn new(signer: S, policy: RetryPolicy) -> Self {
+        Self { signer, policy }
+    }
+}
+
+impl<S> AsyncSigner for RetrySigner<S>
+where
+    S: AsyncSigner + Sync,
+{
+    fn alg_id(&self) -> SignatureAlgId {
+        self.signer.alg_id()
+    }
+
+    fn sign<'a>(&'a self, msg: &'a [u8]) -> SignFuture<'a> {
+        Box::pin(async move {
+            let mut backoff = self.policy.backoff.min(self.policy.max_backoff);
+            let mut attempt = 1;
+            loop {
+                let result =
+                    match future::select(self.signer.sign(msg), Delay::new(self.policy.timeout))
+                        .await
+                    {
+                        Either::Left((result, _)) => result,
+                        Either::Right(_) => {
+                            Err(SignatureError::from_str("signing attempt timed out"))
+                        }
+                    };
+
+                match result {
+                    Ok(signature) => return Ok(signature),
+                    Err(err) if attempt >= self.policy.max_attempts => return Err(err),
+                    Err(_) => {
+                        Delay::new(backoff).await;
+                        backoff = backoff.saturating_mul(2).min(self.policy.max_backoff);
+                        attempt += 1;
+                    }
+                }
+            }
+        })
+    }
+
+    fn verifying_key(&self) -> VerifyingKey {
+        self.signer.verifying_key()
+    }
+}
+
 /// Provider of signature verifiers.
 pub struct SignatureVerifierProvider {
     verifiers: HashMap<SignatureAlgId, Box<dyn SignatureVerifier + Send + Sync>>,
@@ -521,9 +655,12 @@ pub use secp256k1eth::{Secp256k1EthSigner, Secp256k1EthVerifier};
 
 #[cfg(test)]
 mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
     use alloy_primitives::utils::eip191_message;
     use alloy_signer::SignerSync;
     use alloy_signer_local::PrivateKeySigner;
+    use futures::executor::block_on;
     use rand06_compat::Rand0_6CompatExt;
     use rstest::{fixture, rstest};
 
@@ -648,4 +785,82 @@ mod test {
         let signer = PrivateKeySigner::from_slice(sk).unwrap();
         signer.sign_message_sync(msg).unwrap().as_bytes().to_vec()
     }
+
+    /// Signer which fails a number of times before signing, or never
+    /// completes.
+    struct FlakySigner {
+        inner: Secp256k1Signer,
+        failures: AtomicUsize,
+        hang: bool,
+    }
+
+    impl FlakySigner {
+        fn new(failures: usize, hang: bool) -> Self {
+            Self {
+                inner: Secp256k1Signer::new(&[1u8; 32]).unwrap(),
+                failures: failures.into(),
+                hang,
+            }
+        }
+    }
+
+    impl AsyncSigner for FlakySigner {
+        fn alg_id(&self) -> SignatureAlgId {
+            self.inner.alg_id()
+        }
+
+        fn sign<'a>(&'a self, msg: &'a [u8]) -> SignFuture<'a> {
+            if self.hang {
+                return Box::pin(future::pending());
+            }
+
+            let failures = &self.failures;
+            let result = match failures.load(Ordering::Relaxed) {
+                0 => self.inner.sign(msg),
+                n => {
+                    failures.store(n - 1, Ordering::Relaxed);
+                    Err(SignatureError::from_str("unavailable"))
+                }
+            };
+            Box::pin(async move { result })
+        }
+
+        fn verifying_key(&self) -> VerifyingKey {
+            self.inner.verifying_key()
+        }
+    }
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(4),
+            timeout: Duration::from_millis(50),
+        }
+    }
+
+    #[test]
+    fn test_retry_signer() {
+        let signer = RetrySigner::new(FlakySigner::new(2, false), policy());
+        let signature = block_on(signer.sign(b"msg")).unwrap();
+
+        assert!(
+            Secp256k1Verifier
+                .verify(&signer.verifying_key(), b"msg", &signature.data)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_retry_signer_gives_up() {
+        let signer = RetrySigner::new(FlakySigner::new(3, false), policy());
+        assert!(block_on(signer.sign(b"msg")).is_err());
+    }
+
+    #[test]
+    fn test_retry_signer_timeout() {
+        let signer = RetrySigner::new(FlakySigner::new(0, true), policy());
+        let err = block_on(signer.sign(b"msg")).unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+    }
 }
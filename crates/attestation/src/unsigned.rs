@@ -0,0 +1,272 @@
+use serde::{Deserialize, Serialize};
+use tlsn_core::{
+    connection::{CertBinding, ConnectionInfo, TranscriptLength},
+    transcript::{Direction, TlsTranscript},
+};
+
+use crate::{
+    Attestation, AttestationConfig, Body, CryptoProvider, Header, VERSION,
+    builder::{
+        AttestationBuilderError, ErrorKind, check_session_duration, check_transcript_commitments,
+        now,
+    },
+    signing::{AsyncSigner, SignatureAlgId, VerifyingKey},
+};
+
+/// An attestation which is yet to be signed.
+///
+/// This allows splitting a Notary into a service which runs the TLS
+/// commitment protocol with Provers, and a service which holds the signing
+/// key, e.g. to run them in different trust zones. The former builds the
+/// unsigned attestation with
+/// [`build_unsigned`](crate::AttestationBuilder::build_unsigned) and hands it
+/// off to the latter, which validates and [signs](Self::sign) it.
+///
+/// Along with the attestation, the service hands off its
+/// [`SessionEvidence`], and the signer checks that the body matches the
+/// session. The signer can not check the MPC itself, so it must authenticate
+/// the service and trust it to have witnessed the session. The time of the
+/// header is set by the signer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnsignedAttestation {
+    pub(crate) signature_alg: SignatureAlgId,
+    pub(crate) header: Header,
+    pub(crate) body: Body,
+    pub(crate) evidence: SessionEvidence,
+}
+
+/// The record of an MPC-TLS session kept by the Notary which took part in it.
+///
+/// This is the connection information and the certificate binding, i.e. the
+/// randoms and the server's ephemeral key, as witnessed in the MPC. It is
+/// handed off along with an [`UnsignedAttestation`] so that the signer only
+/// signs bodies which match a session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionEvidence {
+    pub(crate) connection_info: ConnectionInfo,
+    pub(crate) binding: CertBinding,
+}
+
+impl SessionEvidence {
+    /// Creates the evidence from the TLS transcript of the session.
+    pub fn new(transcript: &TlsTranscript) -> Self {
+        let len = |direction| {
+            transcript
+                .record_boundaries(direction)
+                .last()
+                .map_or(0, |boundary| boundary.range.end) as u32
+        };
+
+        Self {
+            connection_info: ConnectionInfo {
+                time: transcript.time(),
+                version: *transcript.version(),
+                transcript_length: TranscriptLength {
+                    sent: len(Direction::Sent),
+                    received: len(Direction::Received),
+                },
+                truncated: transcript.is_truncated(),
+            },
+            binding: transcript.certificate_binding().clone(),
+        }
+    }
+
+    /// Returns the connection information of the session.
+    pub fn connection_info(&self) -> &ConnectionInfo {
+        &self.connection_info
+    }
+
+    /// Returns the certificate binding of the session.
+    pub fn binding(&self) -> &CertBinding {
+        &self.binding
+    }
+}
+
+impl UnsignedAttestation {
+    /// Returns the signature algorithm requested by the Prover.
+    pub fn signature_alg(&self) -> SignatureAlgId {
+        self.signature_alg
+    }
+
+    /// Returns the unsigned header.
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Returns the body.
+    pub fn body(&self) -> &Body {
+        &self.body
+    }
+
+    /// Returns the evidence of the session.
+    pub fn evidence(&self) -> &SessionEvidence {
+        &self.evidence
+    }
+
+    /// Validates and signs the attestation.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The attestation configuration of the signer.
+    /// * `provider` - The crypto provider.
+    pub fn sign(
+        mut self,
+        config: &AttestationConfig,
+        provider: &CryptoProvider,
+    ) -> Result<Attestation, AttestationBuilderError> {
+        let signature_alg = self.signature_alg;
+        let signer = provider.signer.get(&signature_alg).map_err(|_| {
+            AttestationBuilderError::new(
+                ErrorKind::Config,
+                format!("signature algorithm {signature_alg} is missing in the provider"),
+            )
+        })?;
+
+        self.validate(config, provider, &signer.verifying_key())?;
+
+        let signature = signer
+            .sign(&self.header.to_bytes())
+            .map_err(|err| AttestationBuilderError::new(ErrorKind::Signature, err))?;
+
+        Ok(Attestation {
+            signature,
+            header: self.header,
+            body: self.body,
+        })
+    }
+
+    /// Validates the attestation and signs it with the provided asynchronous
+    /// signer.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The attestation configuration of the signer.
+    /// * `provider` - The crypto provider.
+    /// * `signer` - The signer for the requested signature algorithm.
+    pub async fn sign_async(
+        mut self,
+        config: &AttestationConfig,
+        provider: &CryptoProvider,
+        signer: &(dyn AsyncSigner + Sync),
+    ) -> Result<Attestation, AttestationBuilderError> {
+        if signer.alg_id() != self.signature_alg {
+            return Err(AttestationBuilderError::new(
+                ErrorKind::Config,
+                format!(
+                    "requested signature algorithm {} but the signer uses {}",
+                    self.signature_alg,
+                    signer.alg_id()
+                ),
+            ));
+        }
+
+        self.validate(config, provider, &signer.verifying_key())?;
+
+        let signature = signer
+            .sign(&self.header.to_bytes())
+            .await
+            .map_err(|err| AttestationBuilderError::new(ErrorKind::Signature, err))?;
+
+        Ok(Attestation {
+            signature,
+            header: self.header,
+            body: self.body,
+        })
+    }
+
+    /// Validates the attestation against the configuration of the signer and
+    /// sets the time of the header.
+    fn validate(
+        &mut self,
+        config: &AttestationConfig,
+        provider: &CryptoProvider,
+        verifying_key: &VerifyingKey,
+    ) -> Result<(), AttestationBuilderError> {
+        let signature_alg = self.signature_alg;
+        if !config.supported_signature_algs().contains(&signature_alg) {
+            return Err(AttestationBuilderError::new(
+                ErrorKind::Request,
+                format!("unsupported signature algorithm: {signature_alg}"),
+            ));
+        }
+
+        let hash_alg = self.header.root.alg;
+        if !config.supported_hash_algs().contains(&hash_alg) {
+            return Err(AttestationBuilderError::new(
+                ErrorKind::Request,
+                format!("unsupported hash algorithm: {hash_alg}"),
+            ));
+        }
+
+        let hasher = provider.hash.get(&hash_alg).map_err(|_| {
+            AttestationBuilderError::new(
+                ErrorKind::Config,
+                format!("hash algorithm {hash_alg} is missing in the provider"),
+            )
+        })?;
+
+        if self.header.version != VERSION {
+            return Err(AttestationBuilderError::new(
+                ErrorKind::Field,
+                format!("unsupported attestation version: {:?}", self.header.version),
+            ));
+        }
+
+        if self.body.verifying_key() != verifying_key {
+            return Err(AttestationBuilderError::new(
+                ErrorKind::Field,
+                "verifying key does not match the signer",
+            ));
+        }
+
+        if self.body.root(hasher) != self.header.root {
+            return Err(AttestationBuilderError::new(
+                ErrorKind::Field,
+                "root of the header does not match the body",
+            ));
+        }
+
+        config
+            .validate_extensions(&self.body.extensions().cloned().collect::<Vec<_>>())
+            .map_err(|err| AttestationBuilderError::new(ErrorKind::Extension, err))?;
+
+        let connection_info = self.body.connection_info();
+        check_transcript_commitments(connection_info, self.body.transcript_commitments())?;
+        self.check_evidence()?;
+
+        let time = now()?;
+        check_session_duration(config, connection_info, time)?;
+        self.header.time = time;
+
+        Ok(())
+    }
+
+    /// Checks that the body matches the evidence of the session.
+    fn check_evidence(&self) -> Result<(), AttestationBuilderError> {
+        if self.body.connection_info() != &self.evidence.connection_info {
+            return Err(AttestationBuilderError::new(
+                ErrorKind::Field,
+                "connection info does not match the session",
+            ));
+        }
+
+        let server_ephemeral_key = match &self.evidence.binding {
+            CertBinding::V1_2(binding) => &binding.server_ephemeral_key,
+            _ => {
+                return Err(AttestationBuilderError::new(
+                    ErrorKind::Field,
+                    "unsupported certificate binding",
+                ));
+            }
+        };
+
+        if self.body.server_ephemeral_key() != server_ephemeral_key {
+            return Err(AttestationBuilderError::new(
+                ErrorKind::Field,
+                "server ephemeral key does not match the session",
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -2,14 +2,16 @@ use rand::{Rng, SeedableRng, rngs::StdRng};
 use rangeset::set::RangeSet;
 use tlsn_attestation::{
     Attestation, AttestationConfig, CryptoProvider,
-    presentation::PresentationOutput,
+    anchor::{AnchorError, AnchorVerifier},
+    nonce::{NONCE_HEADER, Nonce},
+    presentation::{PresentationOutput, VerificationPolicy},
     request::{Request, RequestConfig},
     signing::SignatureAlgId,
 };
 use tlsn_core::{
     connection::{CertBinding, CertBindingV1_2},
     fixtures::ConnectionFixture,
-    hash::{Blake3, Blinder, HashAlgId},
+    hash::{Blake3, Blinder, Hash, HashAlgId},
     transcript::{
         Direction, Transcript, TranscriptCommitment, TranscriptSecret,
         hash::{PlaintextHash, PlaintextHashSecret, hash_plaintext},
@@ -113,6 +115,8 @@ fn test_api() {
         .connection_info(connection_info.clone())
         // Server key Notary received during handshake
         .server_ephemeral_key(server_ephemeral_key)
+        // Notary signs 30 seconds after the handshake
+        .time(connection_info.time + 30)
         .transcript_commitments(vec![
             TranscriptCommitment::Hash(sent_hash_commitment),
             TranscriptCommitment::Hash(recv_hash_commitment),
@@ -141,13 +145,38 @@ fn test_api() {
 
     let presentation = builder.build().unwrap();
 
+    // The presentation can be inspected before it is verified.
+    let summary = presentation.describe();
+    assert!(summary.contains(&format!("server: {server_name}")));
+    assert!(summary.contains(&format!("time: {}", connection_info.time)));
+    assert!(summary.contains(&format!(
+        "sent: 1 range(s) revealed, {sent_len}/{sent_len} bytes"
+    )));
+    assert!(summary.contains(&format!(
+        "received: 1 range(s) revealed, {recv_len}/{recv_len} bytes"
+    )));
+
     // Verifier verifies the presentation.
+    let output = presentation.clone().verify(&provider).unwrap();
+
+    // Prover anchored the attestation after the connection.
+    let anchor = TestAnchor {
+        payload: attestation
+            .anchor_payload(provider.hash.get(&attestation.header.root.alg).unwrap()),
+        time: connection_info.time + 10,
+    };
+    assert_eq!(
+        output.verify_anchor(&provider, &anchor, b"proof").unwrap(),
+        connection_info.time + 10
+    );
+    assert!(output.verify_anchor(&provider, &anchor, b"other").is_err());
+
     let PresentationOutput {
         server_name: presented_server_name,
         connection_info: presented_connection_info,
         transcript: presented_transcript,
         ..
-    } = presentation.verify(&provider).unwrap();
+    } = output;
 
     assert_eq!(presented_server_name.unwrap(), server_name);
     assert_eq!(presented_connection_info, connection_info);
@@ -162,4 +191,169 @@ fn test_api() {
         presented_transcript.received_unsafe(),
         secrets.transcript().received()
     );
+
+    // Verifier verifies the presentation against a policy.
+    let mut policy = VerificationPolicy::new();
+    policy
+        .max_age(60)
+        .max_session_duration(30)
+        .server_name(server_name.clone());
+
+    let err = presentation
+        .clone()
+        .verify_with_policy(&provider, &policy, connection_info.time)
+        .unwrap_err();
+    assert!(err.is_policy());
+
+    policy.trust_key(presentation.verifying_key().clone());
+
+    let session = presentation
+        .clone()
+        .verify_with_policy(&provider, &policy, connection_info.time + 60)
+        .unwrap();
+    assert_eq!(session.server_name, Some(server_name));
+    assert_eq!(session.time, connection_info.time);
+    assert_eq!(session.signed_at, connection_info.time + 30);
+
+    let err = presentation
+        .clone()
+        .verify_with_policy(&provider, &policy, connection_info.time + 61)
+        .unwrap_err();
+    assert!(err.is_policy());
+
+    // Verifier requires a shorter session.
+    policy.max_session_duration(29);
+
+    let err = presentation
+        .verify_with_policy(&provider, &policy, connection_info.time + 60)
+        .unwrap_err();
+    assert!(err.is_policy());
+}
+
+/// Timestamping system which accepts a single payload.
+struct TestAnchor {
+    payload: Hash,
+    time: u64,
+}
+
+impl AnchorVerifier for TestAnchor {
+    fn verify(&self, payload: &Hash, proof: &[u8]) -> Result<u64, AnchorError> {
+        if payload == &self.payload && proof == b"proof" {
+            Ok(self.time)
+        } else {
+            Err(AnchorError::new("payload is not anchored"))
+        }
+    }
+}
+
+/// Tests the nonce challenge flow of a relying party.
+#[test]
+fn test_nonce_challenge() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut provider = CryptoProvider::default();
+    provider.signer.set_secp256k1(&[42u8; 32]).unwrap();
+
+    // Relying party issues a nonce, which the Prover sends to the server.
+    let nonce = Nonce::random();
+    let http_request =
+        format!("GET / HTTP/1.1\r\nHost: test-server.io\r\n{NONCE_HEADER}: {nonce}\r\n\r\n");
+    let transcript = Transcript::new(http_request.as_bytes(), OK_JSON);
+    let sent_len = transcript.sent().len();
+
+    let ConnectionFixture {
+        server_name,
+        connection_info,
+        server_cert_data,
+    } = ConnectionFixture::tlsnotary(transcript.length());
+
+    let CertBinding::V1_2(CertBindingV1_2 {
+        server_ephemeral_key,
+        ..
+    }) = server_cert_data.binding.clone()
+    else {
+        unreachable!()
+    };
+
+    let hasher = Blake3::default();
+    let blinder: Blinder = rng.random();
+    let sent_idx = RangeSet::from(0..sent_len);
+
+    let commitment = PlaintextHash {
+        direction: Direction::Sent,
+        idx: sent_idx.clone(),
+        hash: hash_plaintext(&hasher, transcript.sent(), &blinder),
+    };
+
+    let secret = PlaintextHashSecret {
+        direction: Direction::Sent,
+        idx: sent_idx,
+        alg: HashAlgId::BLAKE3,
+        blinder,
+    };
+
+    // Prover requests the nonce extension.
+    let mut request_config = RequestConfig::builder();
+    request_config.extension(nonce.extension());
+    let request_config = request_config.build().unwrap();
+
+    let mut request_builder = Request::builder(&request_config);
+    request_builder
+        .server_name(server_name)
+        .handshake_data(server_cert_data)
+        .transcript(transcript)
+        .transcript_commitments(
+            vec![TranscriptSecret::Hash(secret)],
+            vec![TranscriptCommitment::Hash(commitment.clone())],
+        );
+
+    let (request, secrets) = request_builder.build(&provider).unwrap();
+
+    // Notary rejects nonces unless it accepts them.
+    let attestation_config = AttestationConfig::builder()
+        .supported_signature_algs([SignatureAlgId::SECP256K1])
+        .build()
+        .unwrap();
+    assert!(
+        Attestation::builder(&attestation_config)
+            .accept_request(request.clone())
+            .is_err()
+    );
+
+    let attestation_config = AttestationConfig::builder()
+        .supported_signature_algs([SignatureAlgId::SECP256K1])
+        .accept_nonces()
+        .build()
+        .unwrap();
+
+    let mut attestation_builder = Attestation::builder(&attestation_config)
+        .accept_request(request)
+        .unwrap();
+    attestation_builder
+        .connection_info(connection_info)
+        .server_ephemeral_key(server_ephemeral_key)
+        .transcript_commitments(vec![TranscriptCommitment::Hash(commitment)]);
+
+    let attestation = attestation_builder.build(&provider).unwrap();
+
+    let mut transcript_proof_builder = secrets.transcript_proof_builder();
+    transcript_proof_builder
+        .reveal(&(0..sent_len), Direction::Sent)
+        .unwrap();
+
+    let mut builder = attestation.presentation_builder(&provider);
+    builder
+        .identity_proof(secrets.identity_proof())
+        .transcript_proof(transcript_proof_builder.build().unwrap());
+
+    // Relying party accepts the presentation for its nonce only.
+    let output = builder.build().unwrap().verify(&provider).unwrap();
+    output.verify_nonce(&nonce).unwrap();
+    assert!(output.verify_nonce(&Nonce::random()).is_err());
+
+    // The nonce must be revealed.
+    let mut builder = attestation.presentation_builder(&provider);
+    builder.identity_proof(secrets.identity_proof());
+
+    let output = builder.build().unwrap().verify(&provider).unwrap();
+    assert!(output.verify_nonce(&nonce).is_err());
 }
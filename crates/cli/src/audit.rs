@@ -0,0 +1,232 @@
+//! Audit log of notarized sessions.
+//!
+//! The audit log gives operators a record of the sessions they signed without
+//! retaining any user data. Records contain the attestation header hash and
+//! signature, transcript lengths and timings, but never any material derived
+//! from the transcript itself.
+
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use tlsn::{
+    attestation::Attestation,
+    connection::TranscriptLength,
+    hash::{Blake3, HashAlgorithm},
+};
+
+/// Default size at which the log file is rotated, 64MiB.
+const DEFAULT_MAX_SIZE: u64 = 64 * 1024 * 1024;
+/// Default number of rotated log files to keep.
+const DEFAULT_MAX_FILES: usize = 5;
+
+/// Record of a single session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct AuditRecord {
+    /// UNIX time in seconds when the session started.
+    pub(crate) started_at: u64,
+    /// Duration of the session in milliseconds.
+    pub(crate) duration_ms: u64,
+    /// Identifier of the client, if authentication is enabled.
+    pub(crate) client: Option<String>,
+    /// Outcome of the session.
+    pub(crate) outcome: AuditOutcome,
+}
+
+/// Outcome of a session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub(crate) enum AuditOutcome {
+    /// An attestation was signed.
+    Signed {
+        /// BLAKE3 hash of the bincode-encoded attestation header, hex encoded.
+        header_hash: String,
+        /// Signature of the attestation, hex encoded.
+        signature: String,
+        /// Number of bytes sent by the prover.
+        sent_bytes: u32,
+        /// Number of bytes received by the prover.
+        recv_bytes: u32,
+    },
+    /// The session failed.
+    Failed {
+        /// Error message.
+        error: String,
+    },
+}
+
+impl AuditOutcome {
+    /// Returns the outcome of a session which produced an attestation.
+    pub(crate) fn signed(attestation: &Attestation, length: &TranscriptLength) -> Result<Self> {
+        let header = bincode::serialize(&attestation.header)?;
+
+        Ok(Self::Signed {
+            header_hash: hex::encode(Blake3::default().hash(&header).as_bytes()),
+            signature: hex::encode(&attestation.signature.data),
+            sent_bytes: length.sent,
+            recv_bytes: length.received,
+        })
+    }
+}
+
+/// Destination of audit records.
+pub(crate) trait AuditSink: Send + Sync {
+    /// Writes a record.
+    fn write(&self, record: &AuditRecord) -> Result<()>;
+}
+
+/// Configuration of the audit log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct AuditConfig {
+    /// Path of the log file.
+    pub(crate) path: PathBuf,
+    /// Size in bytes at which the log file is rotated.
+    pub(crate) max_size: Option<u64>,
+    /// Number of rotated log files to keep.
+    pub(crate) max_files: Option<usize>,
+}
+
+impl AuditConfig {
+    pub(crate) fn validate(&self) -> Result<()> {
+        if self.max_size == Some(0) {
+            bail!("invalid value for `audit.max_size`: must be greater than 0");
+        }
+
+        Ok(())
+    }
+}
+
+/// Sink which appends records to a file as JSON lines.
+///
+/// Once the file exceeds its maximum size it is renamed to `<path>.1`,
+/// shifting older files up to `<path>.<max_files>`, and a new file is
+/// started.
+#[derive(Debug)]
+pub(crate) struct FileSink {
+    path: PathBuf,
+    max_size: u64,
+    max_files: usize,
+    file: Mutex<File>,
+}
+
+impl FileSink {
+    /// Opens the log file described by the configuration.
+    pub(crate) fn open(config: &AuditConfig) -> Result<Self> {
+        Ok(Self {
+            path: config.path.clone(),
+            max_size: config.max_size.unwrap_or(DEFAULT_MAX_SIZE),
+            max_files: config.max_files.unwrap_or(DEFAULT_MAX_FILES),
+            file: Mutex::new(open_append(&config.path)?),
+        })
+    }
+
+    fn rotate(&self, file: &mut File) -> Result<()> {
+        for i in (1..self.max_files).rev() {
+            let from = rotated_path(&self.path, i);
+            if from.exists() {
+                std::fs::rename(&from, rotated_path(&self.path, i + 1))?;
+            }
+        }
+
+        if self.max_files > 0 {
+            std::fs::rename(&self.path, rotated_path(&self.path, 1))?;
+        } else {
+            std::fs::remove_file(&self.path)?;
+        }
+
+        *file = open_append(&self.path)?;
+
+        Ok(())
+    }
+}
+
+impl AuditSink for FileSink {
+    fn write(&self, record: &AuditRecord) -> Result<()> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+
+        let mut file = self.file.lock().unwrap();
+
+        let len = file.metadata()?.len();
+        if len > 0 && len + line.len() as u64 > self.max_size {
+            self.rotate(&mut file)
+                .with_context(|| format!("failed to rotate {}", self.path.display()))?;
+        }
+
+        file.write_all(&line)?;
+        file.flush()?;
+
+        Ok(())
+    }
+}
+
+fn open_append(path: &Path) -> Result<File> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("failed to open {}", path.display()))
+}
+
+fn rotated_path(path: &Path, i: usize) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(format!(".{i}"));
+    path.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(error: &str) -> AuditRecord {
+        AuditRecord {
+            started_at: 0,
+            duration_ms: 0,
+            client: None,
+            outcome: AuditOutcome::Failed {
+                error: error.to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_file_sink_rotation() {
+        let dir = std::env::temp_dir().join(format!("tlsn-audit-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("audit.log");
+
+        let line_len = serde_json::to_vec(&record("a")).unwrap().len() as u64 + 1;
+        let sink = FileSink::open(&AuditConfig {
+            path: path.clone(),
+            max_size: Some(line_len),
+            max_files: Some(2),
+        })
+        .unwrap();
+
+        for error in ["a", "b", "c", "d"] {
+            sink.write(&record(error)).unwrap();
+        }
+
+        let read = |path: &Path| -> Vec<AuditRecord> {
+            std::fs::read_to_string(path)
+                .unwrap()
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect()
+        };
+
+        assert_eq!(read(&path), vec![record("d")]);
+        assert_eq!(read(&rotated_path(&path, 1)), vec![record("c")]);
+        assert_eq!(read(&rotated_path(&path, 2)), vec![record("b")]);
+        assert!(!rotated_path(&path, 3).exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,219 @@
+//! Client authorization of `tlsn serve`.
+//!
+//! Before any MPC setup the prover sends an [`AuthRequest`] with its
+//! credentials, which the notary checks with an [`Authorizer`]. The identity of
+//! an authorized client is recorded in the audit log.
+
+use std::{
+    collections::HashMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Result, anyhow, bail};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use subtle::ConstantTimeEq;
+
+/// First message sent by the prover to the notary.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct AuthRequest {
+    /// Credentials of the client, e.g. an API key or a JWT.
+    pub(crate) token: Option<String>,
+}
+
+/// Response of the notary to an [`AuthRequest`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) enum AuthResponse {
+    /// The client is authorized.
+    Accepted,
+    /// The client is not authorized.
+    Rejected(String),
+}
+
+/// Authorizes clients of the notary.
+pub(crate) trait Authorizer: Send + Sync {
+    /// Authorizes a client with the given credentials, returning its identity.
+    fn authorize(&self, token: Option<&str>) -> Result<String>;
+}
+
+/// Configuration of client authorization.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+pub(crate) enum AuthConfig {
+    /// Static API keys.
+    ApiKeys {
+        /// Identity of the client holding each key, by key.
+        keys: HashMap<String, String>,
+    },
+    /// JWTs signed with HMAC-SHA256.
+    Jwt {
+        /// Shared secret the tokens are signed with.
+        secret: String,
+        /// Required issuer of the tokens.
+        issuer: Option<String>,
+    },
+}
+
+impl AuthConfig {
+    pub(crate) fn validate(&self) -> Result<()> {
+        match self {
+            Self::ApiKeys { keys } if keys.keys().any(|key| key.is_empty()) => {
+                bail!("invalid value for `auth.keys`: keys must not be empty")
+            }
+            Self::Jwt { secret, .. } if secret.is_empty() => {
+                bail!("invalid value for `auth.secret`: must not be empty")
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the authorizer described by the configuration.
+    pub(crate) fn authorizer(&self) -> Box<dyn Authorizer> {
+        match self {
+            Self::ApiKeys { keys } => Box::new(ApiKeys { keys: keys.clone() }),
+            Self::Jwt { secret, issuer } => Box::new(Jwt {
+                secret: secret.as_bytes().to_vec(),
+                issuer: issuer.clone(),
+            }),
+        }
+    }
+}
+
+/// Authorizes clients holding one of a set of static API keys.
+#[derive(Debug)]
+pub(crate) struct ApiKeys {
+    keys: HashMap<String, String>,
+}
+
+impl Authorizer for ApiKeys {
+    fn authorize(&self, token: Option<&str>) -> Result<String> {
+        let token = token.ok_or_else(|| anyhow!("an API key is required"))?;
+
+        // Compare every key in constant time, rather than looking it up.
+        self.keys
+            .iter()
+            .find(|(key, _)| bool::from(key.as_bytes().ct_eq(token.as_bytes())))
+            .map(|(_, client)| client.clone())
+            .ok_or_else(|| anyhow!("invalid API key"))
+    }
+}
+
+/// Authorizes clients holding a JWT signed with HMAC-SHA256.
+///
+/// The identity of the client is the `sub` claim of the token. Tokens with an
+/// `exp` claim in the past are rejected.
+#[derive(Debug)]
+pub(crate) struct Jwt {
+    secret: Vec<u8>,
+    issuer: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct JwtHeader {
+    alg: String,
+}
+
+#[derive(Deserialize)]
+struct JwtClaims {
+    sub: String,
+    exp: Option<u64>,
+    iss: Option<String>,
+}
+
+impl Authorizer for Jwt {
+    fn authorize(&self, token: Option<&str>) -> Result<String> {
+        let token = token.ok_or_else(|| anyhow!("a token is required"))?;
+
+        let invalid = || anyhow!("invalid token");
+        let (message, signature) = token.rsplit_once('.').ok_or_else(invalid)?;
+        let (header, claims) = message.split_once('.').ok_or_else(invalid)?;
+
+        let header: JwtHeader =
+            serde_json::from_slice(&URL_SAFE_NO_PAD.decode(header).map_err(|_| invalid())?)
+                .map_err(|_| invalid())?;
+        if header.alg != "HS256" {
+            bail!("unsupported token algorithm: {}", header.alg);
+        }
+
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.secret).expect("HMAC accepts keys of any length");
+        mac.update(message.as_bytes());
+        mac.verify_slice(&URL_SAFE_NO_PAD.decode(signature).map_err(|_| invalid())?)
+            .map_err(|_| anyhow!("invalid token signature"))?;
+
+        let claims: JwtClaims =
+            serde_json::from_slice(&URL_SAFE_NO_PAD.decode(claims).map_err(|_| invalid())?)
+                .map_err(|_| invalid())?;
+
+        if let Some(exp) = claims.exp {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+            if exp <= now {
+                bail!("token has expired");
+            }
+        }
+
+        if let Some(issuer) = &self.issuer
+            && claims.iss.as_ref() != Some(issuer)
+        {
+            bail!("token has an unexpected issuer");
+        }
+
+        Ok(claims.sub)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jwt(secret: &[u8], claims: &str) -> String {
+        let message = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(r#"{"alg":"HS256","typ":"JWT"}"#),
+            URL_SAFE_NO_PAD.encode(claims)
+        );
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret).unwrap();
+        mac.update(message.as_bytes());
+
+        format!(
+            "{message}.{}",
+            URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes())
+        )
+    }
+
+    #[test]
+    fn test_api_keys() {
+        let authorizer = AuthConfig::ApiKeys {
+            keys: HashMap::from([("secret-key".to_string(), "alice".to_string())]),
+        }
+        .authorizer();
+
+        assert_eq!(authorizer.authorize(Some("secret-key")).unwrap(), "alice");
+        assert!(authorizer.authorize(Some("other-key")).is_err());
+        assert!(authorizer.authorize(None).is_err());
+    }
+
+    #[test]
+    fn test_jwt() {
+        let authorizer = AuthConfig::Jwt {
+            secret: "secret".to_string(),
+            issuer: Some("issuer".to_string()),
+        }
+        .authorizer();
+
+        let token = jwt(b"secret", r#"{"sub":"alice","iss":"issuer"}"#);
+        assert_eq!(authorizer.authorize(Some(&token)).unwrap(), "alice");
+
+        let token = jwt(b"other", r#"{"sub":"alice","iss":"issuer"}"#);
+        assert!(authorizer.authorize(Some(&token)).is_err());
+
+        let token = jwt(b"secret", r#"{"sub":"alice","iss":"issuer","exp":1}"#);
+        assert!(authorizer.authorize(Some(&token)).is_err());
+
+        let token = jwt(b"secret", r#"{"sub":"alice"}"#);
+        assert!(authorizer.authorize(Some(&token)).is_err());
+    }
+}
@@ -0,0 +1,248 @@
+//! Configuration files.
+//!
+//! Settings given on the command line take precedence over those in a
+//! configuration file.
+
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::{audit::AuditConfig, auth::AuthConfig};
+
+/// Transcript size limits.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Limits {
+    /// Maximum number of bytes that can be sent to the server.
+    pub(crate) max_sent_data: Option<usize>,
+    /// Maximum number of bytes that can be received from the server.
+    pub(crate) max_recv_data: Option<usize>,
+}
+
+impl Limits {
+    fn validate(&self) -> Result<()> {
+        if self.max_sent_data == Some(0) {
+            bail!("invalid value for `limits.max_sent_data`: must be greater than 0");
+        }
+        if self.max_recv_data == Some(0) {
+            bail!("invalid value for `limits.max_recv_data`: must be greater than 0");
+        }
+
+        Ok(())
+    }
+}
+
+/// Configuration of `tlsn serve`.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct NotaryConfig {
+    /// Address to listen on.
+    pub(crate) listen: Option<String>,
+    /// Path of the PEM-encoded signing key.
+    pub(crate) key: Option<PathBuf>,
+    /// Path of a PEM-encoded CA certificate to trust.
+    pub(crate) ca_cert: Option<PathBuf>,
+    /// Limits a prover may request.
+    #[serde(default)]
+    pub(crate) limits: Limits,
+    /// Timeout of a session in seconds.
+    pub(crate) timeout: Option<u64>,
+    /// Maximum duration in seconds from the TLS handshake until the
+    /// attestation is signed.
+    pub(crate) max_session_duration: Option<u64>,
+    /// Audit log of sessions.
+    pub(crate) audit: Option<AuditConfig>,
+    /// Address to serve the status endpoints on.
+    pub(crate) status_listen: Option<String>,
+    /// Authorization of clients.
+    pub(crate) auth: Option<AuthConfig>,
+    /// Address of a `tlsn sign` service which signs attestations instead of
+    /// the local key.
+    pub(crate) signer: Option<String>,
+    /// Secret shared with the signer, which authenticates the notary.
+    pub(crate) signer_secret: Option<String>,
+}
+
+impl NotaryConfig {
+    /// Loads the configuration from a TOML file.
+    pub(crate) fn from_toml(path: &Path) -> Result<Self> {
+        let config: Self = from_toml(path)?;
+        config
+            .validate()
+            .with_context(|| format!("invalid configuration in {}", path.display()))?;
+
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<()> {
+        validate_timeout(self.timeout)?;
+        if let Some(audit) = &self.audit {
+            audit.validate()?;
+        }
+        if let Some(auth) = &self.auth {
+            auth.validate()?;
+        }
+        validate_secret("signer_secret", self.signer_secret.as_deref())?;
+        if self.signer.is_some() && self.signer_secret.is_none() {
+            bail!("invalid value for `signer_secret`: must be set with `signer`");
+        }
+        self.limits.validate()
+    }
+
+    /// Returns the session timeout.
+    pub(crate) fn timeout(&self) -> Option<Duration> {
+        self.timeout.map(Duration::from_secs)
+    }
+}
+
+/// Configuration of `tlsn sign`.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct SignerConfig {
+    /// Address to listen on.
+    pub(crate) listen: Option<String>,
+    /// Path of the PEM-encoded signing key.
+    pub(crate) key: Option<PathBuf>,
+    /// Maximum duration in seconds from the TLS handshake until the
+    /// attestation is signed.
+    pub(crate) max_session_duration: Option<u64>,
+    /// Secret shared with the notary, which authenticates its requests.
+    pub(crate) secret: Option<String>,
+}
+
+impl SignerConfig {
+    /// Loads the configuration from a TOML file.
+    pub(crate) fn from_toml(path: &Path) -> Result<Self> {
+        let config: Self = from_toml(path)?;
+        config
+            .validate()
+            .with_context(|| format!("invalid configuration in {}", path.display()))?;
+
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<()> {
+        validate_secret("secret", self.secret.as_deref())
+    }
+}
+
+/// Configuration of `tlsn notarize`.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ProverConfig {
+    /// Address of the notary.
+    pub(crate) notary: Option<String>,
+    /// Path of a PEM-encoded CA certificate to trust.
+    pub(crate) ca_cert: Option<PathBuf>,
+    /// Limits to request from the notary.
+    #[serde(default)]
+    pub(crate) limits: Limits,
+    /// Timeout of the notarization in seconds.
+    pub(crate) timeout: Option<u64>,
+    /// Credentials to present to the notary.
+    pub(crate) token: Option<String>,
+}
+
+impl ProverConfig {
+    /// Loads the configuration from a TOML file.
+    pub(crate) fn from_toml(path: &Path) -> Result<Self> {
+        let config: Self = from_toml(path)?;
+        config
+            .validate()
+            .with_context(|| format!("invalid configuration in {}", path.display()))?;
+
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<()> {
+        validate_timeout(self.timeout)?;
+        self.limits.validate()
+    }
+
+    /// Returns the notarization timeout.
+    pub(crate) fn timeout(&self) -> Option<Duration> {
+        self.timeout.map(Duration::from_secs)
+    }
+}
+
+fn from_toml<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+
+    // Deserialization errors point at the offending field.
+    toml::from_str(&contents)
+        .with_context(|| format!("invalid configuration in {}", path.display()))
+}
+
+fn validate_timeout(timeout: Option<u64>) -> Result<()> {
+    if timeout == Some(0) {
+        bail!("invalid value for `timeout`: must be greater than 0");
+    }
+
+    Ok(())
+}
+
+fn validate_secret(field: &str, secret: Option<&str>) -> Result<()> {
+    if secret == Some("") {
+        bail!("invalid value for `{field}`: must not be empty");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notary_config_roundtrip() {
+        let config = NotaryConfig {
+            listen: Some("0.0.0.0:7047".to_string()),
+            key: Some(PathBuf::from("notary.pem")),
+            ca_cert: None,
+            limits: Limits {
+                max_sent_data: Some(4096),
+                max_recv_data: None,
+            },
+            timeout: Some(60),
+            max_session_duration: Some(300),
+            audit: Some(AuditConfig {
+                path: PathBuf::from("audit.log"),
+                max_size: None,
+                max_files: Some(3),
+            }),
+            status_listen: Some("127.0.0.1:7048".to_string()),
+            auth: Some(AuthConfig::Jwt {
+                secret: "secret".to_string(),
+                issuer: None,
+            }),
+            signer: Some("10.0.0.2:7049".to_string()),
+            signer_secret: Some("secret".to_string()),
+        };
+
+        let toml = toml::to_string(&config).unwrap();
+        let config_2: NotaryConfig = toml::from_str(&toml).unwrap();
+
+        assert_eq!(config, config_2);
+        assert!(config_2.validate().is_ok());
+    }
+
+    #[test]
+    fn test_prover_config_unknown_field() {
+        let err = toml::from_str::<ProverConfig>("[limits]\nmax_sent = 1024\n").unwrap_err();
+
+        assert!(err.to_string().contains("max_sent"));
+    }
+
+    #[test]
+    fn test_prover_config_invalid_limit() {
+        let config: ProverConfig = toml::from_str("[limits]\nmax_recv_data = 0\n").unwrap();
+        let err = config.validate().unwrap_err();
+
+        assert!(err.to_string().contains("limits.max_recv_data"));
+    }
+}
@@ -0,0 +1,188 @@
+//! File and network IO shared by the commands.
+
+use std::path::Path;
+
+use anyhow::{Context, Result, anyhow, bail};
+use futures::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use tlsn::{
+    attestation::{
+        Attestation, Secrets,
+        presentation::{Presentation, PresentationV0},
+    },
+    webpki::{CertificateDer, RootCertStore},
+};
+
+/// Maximum length of a message exchanged with the notary.
+const MAX_MSG_LEN: usize = 1 << 24;
+/// Magic bytes at the start of files written by the CLI.
+const FILE_MAGIC: &[u8; 4] = b"TLSN";
+/// Version of the file format.
+///
+/// The attestations and proofs in a file carry their own versions, which are
+/// checked when they are deserialized and verified. Files without the magic
+/// were written before the format was versioned, and are read as version 0.
+const FILE_VERSION: u16 = 1;
+
+/// A notarized session, written by `tlsn notarize`.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct SessionFile {
+    pub(crate) attestation: Attestation,
+    pub(crate) secrets: Secrets,
+}
+
+/// A value which is written to files.
+pub(crate) trait FileValue: Serialize + DeserializeOwned {
+    /// Layout of the value in version 0 files.
+    type V0: DeserializeOwned + Into<Self>;
+}
+
+impl FileValue for SessionFile {
+    type V0 = Self;
+}
+
+impl FileValue for Presentation {
+    type V0 = PresentationV0;
+}
+
+/// Sends a length-prefixed message.
+pub(crate) async fn send<T: Serialize>(io: &mut (impl AsyncWrite + Unpin), msg: &T) -> Result<()> {
+    let bytes = bincode::serialize(msg)?;
+    if bytes.len() > MAX_MSG_LEN {
+        bail!("message is too large: {} bytes", bytes.len());
+    }
+
+    io.write_all(&(bytes.len() as u32).to_be_bytes()).await?;
+    io.write_all(&bytes).await?;
+    io.flush().await?;
+
+    Ok(())
+}
+
+/// Receives a length-prefixed message.
+pub(crate) async fn recv<T: DeserializeOwned>(io: &mut (impl AsyncRead + Unpin)) -> Result<T> {
+    let mut len = [0u8; 4];
+    io.read_exact(&mut len).await?;
+
+    let len = u32::from_be_bytes(len) as usize;
+    if len > MAX_MSG_LEN {
+        bail!("message is too large: {len} bytes");
+    }
+
+    let mut bytes = vec![0u8; len];
+    io.read_exact(&mut bytes).await?;
+
+    Ok(bincode::deserialize(&bytes)?)
+}
+
+/// Reads a value from a file written by [`write_file`].
+pub(crate) async fn read_file<T: FileValue>(path: &Path) -> Result<T> {
+    let bytes = tokio::fs::read(path)
+        .await
+        .with_context(|| format!("failed to read {}", path.display()))?;
+
+    decode_file(&bytes).with_context(|| format!("failed to decode {}", path.display()))
+}
+
+/// Writes a value to a file, prefixed with the file format version.
+pub(crate) async fn write_file<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    tokio::fs::write(path, encode_file(value)?)
+        .await
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+fn encode_file<T: Serialize>(value: &T) -> Result<Vec<u8>> {
+    let mut bytes = FILE_MAGIC.to_vec();
+    bytes.extend_from_slice(&FILE_VERSION.to_be_bytes());
+    bincode::serialize_into(&mut bytes, value)?;
+
+    Ok(bytes)
+}
+
+fn decode_file<T: FileValue>(bytes: &[u8]) -> Result<T> {
+    let Some(bytes) = bytes.strip_prefix(FILE_MAGIC) else {
+        let value: T::V0 = bincode::deserialize(bytes)
+            .context("not a file written by tlsn, or a version 0 file which is corrupt")?;
+
+        return Ok(value.into());
+    };
+
+    let (version, bytes) = bytes
+        .split_first_chunk::<2>()
+        .ok_or_else(|| anyhow!("file is missing its version"))?;
+
+    match u16::from_be_bytes(*version) {
+        FILE_VERSION => Ok(bincode::deserialize(bytes)?),
+        version => bail!(
+            "unsupported file version {version}, this release supports versions up to {FILE_VERSION}"
+        ),
+    }
+}
+
+/// Returns the Mozilla root certificates, and optionally an additional CA
+/// certificate.
+pub(crate) fn root_store(ca_cert: Option<&Path>) -> Result<RootCertStore> {
+    let mut store = RootCertStore::mozilla();
+
+    if let Some(path) = ca_cert {
+        let pem =
+            std::fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+        let cert = CertificateDer::from_pem_slice(&pem)
+            .map_err(|_| anyhow!("invalid CA certificate: {}", path.display()))?;
+
+        store.roots.push(cert);
+    }
+
+    Ok(store)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Value {
+        data: Vec<u8>,
+    }
+
+    impl FileValue for Value {
+        type V0 = Self;
+    }
+
+    #[test]
+    fn test_file_roundtrip() {
+        let value = Value {
+            data: b"hello".to_vec(),
+        };
+
+        let bytes = encode_file(&value).unwrap();
+
+        assert!(bytes.starts_with(FILE_MAGIC));
+        assert_eq!(decode_file::<Value>(&bytes).unwrap(), value);
+    }
+
+    #[test]
+    fn test_decode_headerless_file() {
+        let value = Value {
+            data: b"hello".to_vec(),
+        };
+
+        let bytes = bincode::serialize(&value).unwrap();
+
+        assert_eq!(decode_file::<Value>(&bytes).unwrap(), value);
+
+        let err = decode_file::<Value>(b"hi").unwrap_err();
+
+        assert!(err.to_string().contains("not a file written by tlsn"));
+    }
+
+    #[test]
+    fn test_decode_newer_file() {
+        let mut bytes = FILE_MAGIC.to_vec();
+        bytes.extend_from_slice(&(FILE_VERSION + 1).to_be_bytes());
+
+        let err = decode_file::<Value>(&bytes).unwrap_err();
+
+        assert!(err.to_string().contains("unsupported file version"));
+    }
+}
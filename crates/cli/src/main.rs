@@ -0,0 +1,67 @@
+//! Command line interface for TLSNotary.
+//!
+//! The `tlsn` binary supports the following workflow:
+//!
+//! 1. `tlsn serve` runs a notary which accepts connections from provers.
+//! 2. `tlsn notarize` sends an HTTP request to a server with the help of a
+//!    notary, and writes the attestation and its secrets to a session file.
+//! 3. `tlsn present` builds a presentation from a session file which reveals
+//!    the selected parts of the transcript.
+//! 4. `tlsn verify` verifies a presentation against a trusted notary key.
+//!
+//! `tlsn sign` runs a signer which holds the signing key of a notary, for
+//! notaries which run the MPC and signing in separate services.
+
+#![deny(unreachable_pub, unused_must_use)]
+#![deny(clippy::all)]
+#![forbid(unsafe_code)]
+
+mod audit;
+mod auth;
+mod config;
+mod io;
+mod notarize;
+mod present;
+mod serve;
+mod sign;
+mod status;
+mod verify;
+
+use clap::{Parser, Subcommand};
+use tracing_subscriber::EnvFilter;
+
+#[derive(Parser, Debug)]
+#[command(name = "tlsn", version, about, long_about = None)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Runs a notary.
+    Serve(serve::Args),
+    /// Runs a signer for notaries.
+    Sign(sign::Args),
+    /// Notarizes an HTTP request.
+    Notarize(notarize::Args),
+    /// Builds a presentation from a notarized session.
+    Present(present::Args),
+    /// Verifies a presentation.
+    Verify(verify::Args),
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::from_default_env())
+        .init();
+
+    match Cli::parse().command {
+        Command::Serve(args) => serve::run(args).await,
+        Command::Sign(args) => sign::run(args).await,
+        Command::Notarize(args) => notarize::run(args).await,
+        Command::Present(args) => present::run(args).await,
+        Command::Verify(args) => verify::run(args).await,
+    }
+}
@@ -0,0 +1,270 @@
+//! `tlsn notarize` command.
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, anyhow, bail};
+use http_body_util::Empty;
+use hyper::{Request, Uri, body::Bytes};
+use hyper_util::rt::TokioIo;
+use tokio::net::TcpStream;
+use tokio_util::compat::{FuturesAsyncReadCompatExt, TokioAsyncReadCompatExt};
+use tracing::info;
+
+use tlsn::{
+    Session,
+    attestation::{
+        Attestation, CryptoProvider,
+        request::{Request as AttestationRequest, RequestConfig},
+    },
+    config::{
+        prove::ProveConfig,
+        prover::ProverConfig,
+        tls::TlsClientConfig,
+        tls_commit::{TlsCommitConfig, mpc::MpcTlsConfig},
+    },
+    connection::{HandshakeData, ServerName},
+    prover::ProverOutput,
+    transcript::TranscriptCommitConfig,
+};
+use tlsn_formats::http::{DefaultHttpCommitter, HttpCommit, HttpTranscript};
+
+use crate::{
+    auth::{AuthRequest, AuthResponse},
+    config,
+    io::{self, SessionFile},
+};
+
+#[derive(clap::Args, Debug)]
+pub(crate) struct Args {
+    /// HTTPS URL to send a GET request to.
+    #[arg(long)]
+    url: Uri,
+    /// Path of a TOML configuration file.
+    #[arg(long)]
+    config: Option<PathBuf>,
+    /// Address of the notary, e.g. `127.0.0.1:7047`.
+    #[arg(long)]
+    notary: Option<String>,
+    /// Additional request header. May be repeated.
+    #[arg(long = "header", value_name = "NAME:VALUE")]
+    headers: Vec<String>,
+    /// PEM-encoded CA certificate to trust in addition to the Mozilla roots.
+    #[arg(long)]
+    ca_cert: Option<PathBuf>,
+    /// Maximum number of bytes that can be sent to the server [default: 4096].
+    #[arg(long)]
+    max_sent_data: Option<usize>,
+    /// Maximum number of bytes that can be received from the server [default:
+    /// 16384].
+    #[arg(long)]
+    max_recv_data: Option<usize>,
+    /// Timeout of the notarization in seconds.
+    #[arg(long)]
+    timeout: Option<u64>,
+    /// Credentials to present to the notary, e.g. an API key or a JWT.
+    #[arg(long)]
+    token: Option<String>,
+    /// Path of the session file to write.
+    #[arg(long, short, default_value = "session.tlsn")]
+    out: PathBuf,
+}
+
+impl Args {
+    /// Returns the configuration file overridden by the command line.
+    fn to_config(&self) -> Result<config::ProverConfig> {
+        let mut config = match &self.config {
+            Some(path) => config::ProverConfig::from_toml(path)?,
+            None => config::ProverConfig::default(),
+        };
+
+        config.notary = self.notary.clone().or(config.notary);
+        config.ca_cert = self.ca_cert.clone().or(config.ca_cert);
+        config.limits.max_sent_data = self.max_sent_data.or(config.limits.max_sent_data);
+        config.limits.max_recv_data = self.max_recv_data.or(config.limits.max_recv_data);
+        config.timeout = self.timeout.or(config.timeout);
+        config.token = self.token.clone().or(config.token);
+
+        Ok(config)
+    }
+}
+
+/// Default maximum number of bytes that can be sent to the server.
+const DEFAULT_MAX_SENT_DATA: usize = 1 << 12;
+/// Default maximum number of bytes that can be received from the server.
+const DEFAULT_MAX_RECV_DATA: usize = 1 << 14;
+
+pub(crate) async fn run(args: Args) -> Result<()> {
+    let config = args.to_config()?;
+
+    match config.timeout() {
+        Some(timeout) => tokio::time::timeout(timeout, notarize(&args, &config))
+            .await
+            .map_err(|_| anyhow!("notarization timed out"))?,
+        None => notarize(&args, &config).await,
+    }
+}
+
+async fn notarize(args: &Args, config: &config::ProverConfig) -> Result<()> {
+    let notary = config.notary.as_deref().ok_or_else(|| {
+        anyhow!("a notary must be provided with --notary or in the configuration")
+    })?;
+
+    if args.url.scheme_str() != Some("https") {
+        bail!("only https URLs are supported");
+    }
+
+    let host = args
+        .url
+        .host()
+        .ok_or_else(|| anyhow!("URL is missing a host"))?
+        .to_string();
+    let port = args.url.port_u16().unwrap_or(443);
+    let path = args
+        .url
+        .path_and_query()
+        .map(|path| path.as_str())
+        .unwrap_or("/");
+    let server_name = ServerName::Dns(host.as_str().try_into()?);
+
+    let notary_socket = TcpStream::connect(notary)
+        .await
+        .with_context(|| format!("failed to connect to notary at {notary}"))?;
+
+    let mut notary_socket = notary_socket.compat();
+    io::send(
+        &mut notary_socket,
+        &AuthRequest {
+            token: config.token.clone(),
+        },
+    )
+    .await?;
+    if let AuthResponse::Rejected(reason) = io::recv(&mut notary_socket).await? {
+        bail!("notary rejected the client: {reason}");
+    }
+
+    let (driver, mut handle) = Session::new(notary_socket).split();
+    let driver_task = tokio::spawn(driver);
+
+    let prover = handle
+        .new_prover(ProverConfig::builder().build()?)?
+        .commit(
+            TlsCommitConfig::builder()
+                .protocol(
+                    MpcTlsConfig::builder()
+                        .max_sent_data(config.limits.max_sent_data.unwrap_or(DEFAULT_MAX_SENT_DATA))
+                        .max_recv_data(config.limits.max_recv_data.unwrap_or(DEFAULT_MAX_RECV_DATA))
+                        .build()?,
+                )
+                .build()?,
+        )
+        .await?;
+
+    let server_socket = TcpStream::connect((host.as_str(), port))
+        .await
+        .with_context(|| format!("failed to connect to {host}:{port}"))?;
+
+    let (tls_connection, prover_fut) = prover.connect(
+        TlsClientConfig::builder()
+            .server_name(server_name.clone())
+            .root_store(io::root_store(config.ca_cert.as_deref())?)
+            .build()?,
+        server_socket.compat(),
+    )?;
+    let prover_task = tokio::spawn(prover_fut);
+
+    let (mut request_sender, connection) =
+        hyper::client::conn::http1::handshake(TokioIo::new(tls_connection.compat())).await?;
+    tokio::spawn(connection);
+
+    let mut request = Request::builder()
+        .uri(path)
+        .header("Host", &host)
+        .header("Accept", "*/*")
+        // Compressed responses are not supported.
+        .header("Accept-Encoding", "identity")
+        .header("Connection", "close");
+    for header in &args.headers {
+        let (name, value) = header
+            .split_once(':')
+            .ok_or_else(|| anyhow!("header must have the form NAME:VALUE: {header}"))?;
+        request = request.header(name.trim(), value.trim());
+    }
+
+    info!("sending request to {host}");
+
+    let response = request_sender
+        .send_request(request.body(Empty::<Bytes>::new())?)
+        .await?;
+
+    info!("received response: {}", response.status());
+
+    let mut prover = prover_task.await??;
+
+    // Commit to the parts of the HTTP transcript separately so that they can be
+    // selectively revealed later.
+    let transcript = HttpTranscript::parse(prover.transcript())?;
+    let mut builder = TranscriptCommitConfig::builder(prover.transcript());
+    DefaultHttpCommitter::default().commit_transcript(&mut builder, &transcript)?;
+    let transcript_commit = builder.build()?;
+
+    let mut builder = RequestConfig::builder();
+    builder.transcript_commit(transcript_commit.clone());
+    let request_config = builder.build()?;
+
+    let mut builder = ProveConfig::builder(prover.transcript());
+    builder.transcript_commit(transcript_commit);
+    let prove_config = builder.build()?;
+
+    let ProverOutput {
+        transcript_commitments,
+        transcript_secrets,
+        ..
+    } = prover.prove(&prove_config).await?;
+
+    let transcript = prover.transcript().clone();
+    let tls_transcript = prover.tls_transcript().clone();
+    prover.close().await?;
+
+    // Close the session to regain the connection to the notary.
+    handle.close();
+    let mut notary_socket = driver_task.await??;
+
+    let mut builder = AttestationRequest::builder(&request_config);
+    builder
+        .server_name(server_name)
+        .handshake_data(HandshakeData {
+            certs: tls_transcript
+                .server_cert_chain()
+                .ok_or_else(|| anyhow!("server certificate chain is missing"))?
+                .to_vec(),
+            sig: tls_transcript
+                .server_signature()
+                .ok_or_else(|| anyhow!("server signature is missing"))?
+                .clone(),
+            binding: tls_transcript.certificate_binding().clone(),
+        })
+        .transcript(transcript)
+        .transcript_commitments(transcript_secrets, transcript_commitments);
+
+    let provider = CryptoProvider::default();
+    let (request, secrets) = builder.build(&provider)?;
+
+    io::send(&mut notary_socket, &request).await?;
+    let attestation: Attestation = io::recv(&mut notary_socket).await?;
+
+    // Check the attestation is consistent with our view of the session.
+    request.validate(&attestation, &provider)?;
+
+    io::write_file(
+        &args.out,
+        &SessionFile {
+            attestation,
+            secrets,
+        },
+    )
+    .await?;
+
+    println!("Session written to {}", args.out.display());
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+//! `tlsn present` command.
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use tlsn::attestation::CryptoProvider;
+use tlsn_formats::http::{HttpTranscript, RevealHttp};
+
+use crate::io::{self, SessionFile};
+
+#[derive(clap::Args, Debug)]
+pub(crate) struct Args {
+    /// Path of the session file written by `tlsn notarize`.
+    #[arg(long)]
+    session: PathBuf,
+    /// Selector of an item to reveal, e.g. `resp[0].body.balance`. May be
+    /// repeated.
+    #[arg(long = "reveal", value_name = "SELECTOR")]
+    reveal: Vec<String>,
+    /// Path of the presentation file to write.
+    #[arg(long, short, default_value = "presentation.tlsn")]
+    out: PathBuf,
+}
+
+pub(crate) async fn run(args: Args) -> Result<()> {
+    let SessionFile {
+        attestation,
+        secrets,
+    } = io::read_file(&args.session).await?;
+
+    let transcript = HttpTranscript::parse(secrets.transcript())?;
+
+    let mut builder = secrets.transcript_proof_builder();
+    for selector in &args.reveal {
+        builder.reveal_http(&transcript, selector)?;
+    }
+    let transcript_proof = builder.build()?;
+
+    let provider = CryptoProvider::default();
+    let mut builder = attestation.presentation_builder(&provider);
+    builder
+        .identity_proof(secrets.identity_proof())
+        .transcript_proof(transcript_proof);
+    let presentation = builder.build()?;
+
+    io::write_file(&args.out, &presentation).await?;
+
+    println!("Presentation written to {}", args.out.display());
+
+    Ok(())
+}
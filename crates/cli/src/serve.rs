@@ -0,0 +1,324 @@
+//! `tlsn serve` command.
+
+use std::{
+    path::PathBuf,
+    sync::Arc,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Result, anyhow};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_util::compat::TokioAsyncReadCompatExt;
+use tracing::{info, warn};
+
+use tlsn::{
+    Session,
+    attestation::{
+        Attestation, AttestationConfig, CryptoProvider, SessionEvidence,
+        request::Request as AttestationRequest,
+    },
+    config::verifier::VerifierConfig,
+    connection::TranscriptLength,
+    verifier::VerifierOutput,
+};
+
+use crate::{
+    audit::{AuditConfig, AuditOutcome, AuditRecord, AuditSink, FileSink},
+    auth::{AuthRequest, AuthResponse, Authorizer},
+    config::NotaryConfig,
+    io,
+    sign::{LocalSigner, SignerService, load_signing_key},
+    status::{self, Status},
+};
+
+/// Default address to listen on.
+const DEFAULT_LISTEN: &str = "127.0.0.1:7047";
+
+#[derive(clap::Args, Debug)]
+pub(crate) struct Args {
+    /// Path of a TOML configuration file.
+    #[arg(long)]
+    config: Option<PathBuf>,
+    /// Address to listen on [default: 127.0.0.1:7047].
+    #[arg(long)]
+    listen: Option<String>,
+    /// PEM-encoded PKCS#8 secp256k1 signing key of the notary.
+    #[arg(long)]
+    key: Option<PathBuf>,
+    /// PEM-encoded CA certificate to trust in addition to the Mozilla roots.
+    #[arg(long)]
+    ca_cert: Option<PathBuf>,
+    /// Maximum number of bytes a prover may send to the server.
+    #[arg(long)]
+    max_sent_data: Option<usize>,
+    /// Maximum number of bytes a prover may receive from the server.
+    #[arg(long)]
+    max_recv_data: Option<usize>,
+    /// Timeout of a session in seconds.
+    #[arg(long)]
+    timeout: Option<u64>,
+    /// Maximum duration in seconds from the TLS handshake until the
+    /// attestation is signed.
+    #[arg(long)]
+    max_session_duration: Option<u64>,
+    /// Path of a file to append an audit record of each session to.
+    #[arg(long)]
+    audit_log: Option<PathBuf>,
+    /// Address to serve the status endpoints on.
+    #[arg(long)]
+    status_listen: Option<String>,
+    /// Address of a `tlsn sign` service to hand off attestations to, instead
+    /// of signing them with --key.
+    #[arg(long)]
+    signer: Option<String>,
+}
+
+impl Args {
+    /// Returns the configuration file overridden by the command line.
+    fn into_config(self) -> Result<NotaryConfig> {
+        let mut config = match &self.config {
+            Some(path) => NotaryConfig::from_toml(path)?,
+            None => NotaryConfig::default(),
+        };
+
+        config.listen = self.listen.or(config.listen);
+        config.key = self.key.or(config.key);
+        config.ca_cert = self.ca_cert.or(config.ca_cert);
+        config.limits.max_sent_data = self.max_sent_data.or(config.limits.max_sent_data);
+        config.limits.max_recv_data = self.max_recv_data.or(config.limits.max_recv_data);
+        config.timeout = self.timeout.or(config.timeout);
+        config.max_session_duration = self.max_session_duration.or(config.max_session_duration);
+        config.status_listen = self.status_listen.or(config.status_listen);
+        config.signer = self.signer.or(config.signer);
+        if let Some(path) = self.audit_log {
+            match &mut config.audit {
+                Some(audit) => audit.path = path,
+                None => {
+                    config.audit = Some(AuditConfig {
+                        path,
+                        max_size: None,
+                        max_files: None,
+                    })
+                }
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+pub(crate) async fn run(args: Args) -> Result<()> {
+    let config = args.into_config()?;
+
+    let signer = match (&config.signer, &config.key) {
+        (Some(addr), key) => {
+            if key.is_some() {
+                warn!("ignoring the signing key, attestations are signed by {addr}");
+            }
+            let secret = config.signer_secret.as_ref().ok_or_else(|| {
+                anyhow!("a secret shared with the signer must be provided as `signer_secret` in the configuration")
+            })?;
+            SignerService::Remote {
+                addr: addr.clone(),
+                secret: secret.as_bytes().to_vec(),
+            }
+        }
+        (None, Some(key)) => SignerService::Local(LocalSigner::new(
+            &load_signing_key(key).await?,
+            config.max_session_duration,
+        )?),
+        (None, None) => {
+            return Err(anyhow!(
+                "a signing key must be provided with --key or in the configuration, or a signer with --signer"
+            ));
+        }
+    };
+    let signer = Arc::new(signer);
+
+    let mut builder =
+        VerifierConfig::builder().root_store(io::root_store(config.ca_cert.as_deref())?);
+    if let Some(max_sent_data) = config.limits.max_sent_data {
+        builder = builder.max_sent_data(max_sent_data);
+    }
+    if let Some(max_recv_data) = config.limits.max_recv_data {
+        builder = builder.max_recv_data(max_recv_data);
+    }
+    let verifier_config = builder.build()?;
+    let timeout = config.timeout();
+    let max_session_duration = config.max_session_duration;
+
+    let audit: Option<Arc<dyn AuditSink>> = match &config.audit {
+        Some(audit) => Some(Arc::new(FileSink::open(audit)?)),
+        None => None,
+    };
+    let authorizer: Option<Arc<dyn Authorizer>> = config
+        .auth
+        .as_ref()
+        .map(|auth| Arc::from(auth.authorizer()));
+
+    let status = Arc::new(Status::default());
+    if let Some(addr) = &config.status_listen {
+        let listener = TcpListener::bind(addr).await?;
+        let status = status.clone();
+        tokio::spawn(async move {
+            if let Err(e) = status::serve(listener, status).await {
+                warn!("status endpoints failed: {e:#}");
+            }
+        });
+    }
+
+    let listener = TcpListener::bind(config.listen.as_deref().unwrap_or(DEFAULT_LISTEN)).await?;
+    info!("listening on {}", listener.local_addr()?);
+
+    loop {
+        let (socket, addr) = listener.accept().await?;
+        let verifier_config = verifier_config.clone();
+        let signer = signer.clone();
+        let audit = audit.clone();
+        let authorizer = authorizer.clone();
+        let status = status.clone();
+
+        tokio::spawn(async move {
+            info!("accepted connection from {addr}");
+            let _session = status.start_session();
+
+            let started_at = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |time| time.as_secs());
+            let start = Instant::now();
+
+            let mut client = None;
+            let session = notarize(
+                socket,
+                verifier_config,
+                max_session_duration,
+                &signer,
+                authorizer.as_deref(),
+                &mut client,
+            );
+            let result = match timeout {
+                Some(timeout) => tokio::time::timeout(timeout, session)
+                    .await
+                    .unwrap_or_else(|_| Err(anyhow!("session timed out"))),
+                None => session.await,
+            };
+
+            let outcome = match result {
+                Ok((attestation, length)) => {
+                    info!("notarized session with {addr}");
+                    AuditOutcome::signed(&attestation, &length)
+                }
+                Err(e) => {
+                    warn!("session with {addr} failed: {e:#}");
+                    status.record_failure();
+                    Ok(AuditOutcome::Failed {
+                        error: format!("{e:#}"),
+                    })
+                }
+            };
+
+            if let Some(audit) = audit {
+                let record = outcome.and_then(|outcome| {
+                    audit.write(&AuditRecord {
+                        started_at,
+                        duration_ms: start.elapsed().as_millis() as u64,
+                        client,
+                        outcome,
+                    })
+                });
+
+                if let Err(e) = record {
+                    warn!("failed to write audit record: {e:#}");
+                }
+            }
+        });
+    }
+}
+
+async fn notarize(
+    socket: TcpStream,
+    config: VerifierConfig,
+    max_session_duration: Option<u64>,
+    signer: &SignerService,
+    authorizer: Option<&dyn Authorizer>,
+    client: &mut Option<String>,
+) -> Result<(Attestation, TranscriptLength)> {
+    let mut socket = socket.compat();
+
+    // Authorize the client before any MPC setup.
+    let AuthRequest { token } = io::recv(&mut socket).await?;
+    let identity = match authorizer {
+        Some(authorizer) => authorizer.authorize(token.as_deref()).map(Some),
+        None => Ok(None),
+    };
+    match identity {
+        Ok(identity) => {
+            io::send(&mut socket, &AuthResponse::Accepted).await?;
+            *client = identity;
+        }
+        Err(e) => {
+            io::send(&mut socket, &AuthResponse::Rejected(e.to_string())).await?;
+            return Err(e.context("client is not authorized"));
+        }
+    }
+
+    let (driver, mut handle) = Session::new(socket).split();
+    let driver_task = tokio::spawn(driver);
+
+    let verifier = handle
+        .new_verifier(config)?
+        .commit()
+        .await?
+        .accept()
+        .await?
+        .run()
+        .await?;
+
+    let (
+        VerifierOutput {
+            transcript_commitments,
+            ..
+        },
+        verifier,
+    ) = verifier.verify().await?.accept().await?;
+
+    let tls_transcript = verifier.tls_transcript().clone();
+    verifier.close().await?;
+
+    // Close the session to regain the connection to the prover.
+    handle.close();
+    let mut socket = driver_task.await??;
+
+    let request: AttestationRequest = io::recv(&mut socket).await?;
+
+    // The signer checks the attestation against the evidence of this session.
+    let evidence = SessionEvidence::new(&tls_transcript);
+
+    // The signer holds the key, the notary only needs to know which key signs.
+    let signer = signer.open().await?;
+
+    let mut builder = AttestationConfig::builder();
+    builder.supported_signature_algs([signer.alg()]);
+    if let Some(max_session_duration) = max_session_duration {
+        builder.max_session_duration(max_session_duration);
+    }
+    let attestation_config = builder.build()?;
+
+    let mut builder = Attestation::builder(&attestation_config).accept_request(request)?;
+    builder
+        .connection_info(evidence.connection_info().clone())
+        .server_ephemeral_key(tls_transcript.server_ephemeral_key().clone())
+        .transcript_commitments(transcript_commitments);
+
+    let transcript_length = evidence.connection_info().transcript_length.clone();
+    let unsigned = builder.build_unsigned(
+        &CryptoProvider::default(),
+        signer.verifying_key().clone(),
+        evidence,
+    )?;
+    let attestation = signer.sign(unsigned).await?;
+
+    io::send(&mut socket, &attestation).await?;
+
+    Ok((attestation, transcript_length))
+}
@@ -0,0 +1,368 @@
+//! `tlsn sign` command.
+//!
+//! A notary can be split into an MPC follower, `tlsn serve --signer <addr>`,
+//! and a signer which holds the signing key, so that the key can be kept in a
+//! different trust zone than the CPU-heavy MPC. The follower runs the TLS
+//! commitment protocol with the prover and hands off an
+//! [`UnsignedAttestation`] to the signer:
+//!
+//! 1. The signer sends a [`SignerHello`] with its verifying key and a fresh
+//!    challenge.
+//! 2. The follower sends a [`SignRequest`] with the [`UnsignedAttestation`],
+//!    authenticated with a MAC over the challenge keyed by a secret shared
+//!    with the signer.
+//! 3. The signer checks the MAC, validates the attestation against the
+//!    evidence of the session, signs it, and replies with a [`SignResponse`].
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{Context, Result, anyhow};
+use hmac::{Hmac, Mac};
+use k256::{ecdsa::SigningKey, pkcs8::DecodePrivateKey};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_util::compat::{Compat, TokioAsyncReadCompatExt};
+use tracing::{info, warn};
+
+use tlsn::attestation::{
+    Attestation, AttestationConfig, CryptoProvider, UnsignedAttestation,
+    signing::{Secp256k1Signer, SignatureAlgId, Signer, VerifyingKey},
+};
+
+use crate::{config::SignerConfig, io};
+
+/// Default address to listen on.
+const DEFAULT_LISTEN: &str = "127.0.0.1:7049";
+/// Domain separator of the MAC of a [`SignRequest`].
+const REQUEST_MAC_DOMAIN: &[u8] = b"tlsn-sign-request";
+
+#[derive(clap::Args, Debug)]
+pub(crate) struct Args {
+    /// Path of a TOML configuration file.
+    #[arg(long)]
+    config: Option<PathBuf>,
+    /// Address to listen on [default: 127.0.0.1:7049].
+    #[arg(long)]
+    listen: Option<String>,
+    /// PEM-encoded PKCS#8 secp256k1 signing key of the notary.
+    #[arg(long)]
+    key: Option<PathBuf>,
+    /// Maximum duration in seconds from the TLS handshake until the
+    /// attestation is signed.
+    #[arg(long)]
+    max_session_duration: Option<u64>,
+}
+
+impl Args {
+    /// Returns the configuration file overridden by the command line.
+    fn into_config(self) -> Result<SignerConfig> {
+        let mut config = match &self.config {
+            Some(path) => SignerConfig::from_toml(path)?,
+            None => SignerConfig::default(),
+        };
+
+        config.listen = self.listen.or(config.listen);
+        config.key = self.key.or(config.key);
+        config.max_session_duration = self.max_session_duration.or(config.max_session_duration);
+
+        Ok(config)
+    }
+}
+
+/// First message sent by the signer to the MPC follower.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct SignerHello {
+    /// Signature algorithm of the signer.
+    pub(crate) alg: SignatureAlgId,
+    /// Verifying key of the signer.
+    pub(crate) verifying_key: VerifyingKey,
+    /// Challenge the [`SignRequest`] is authenticated over, so that a
+    /// request can not be replayed.
+    pub(crate) challenge: [u8; 32],
+}
+
+/// Request of the MPC follower to sign an [`UnsignedAttestation`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct SignRequest {
+    /// The serialized [`UnsignedAttestation`].
+    pub(crate) unsigned: Vec<u8>,
+    /// HMAC-SHA256 of the challenge and the unsigned attestation, keyed by
+    /// the shared secret.
+    pub(crate) mac: Vec<u8>,
+}
+
+impl SignRequest {
+    /// Creates a request authenticated with the shared secret.
+    fn new(secret: &[u8], challenge: &[u8; 32], unsigned: &UnsignedAttestation) -> Result<Self> {
+        let unsigned = bincode::serialize(unsigned)?;
+        let mac = request_mac(secret, challenge, &unsigned)
+            .finalize()
+            .into_bytes()
+            .to_vec();
+
+        Ok(Self { unsigned, mac })
+    }
+
+    /// Authenticates the request with the shared secret, returning the
+    /// unsigned attestation.
+    fn authenticate(self, secret: &[u8], challenge: &[u8; 32]) -> Result<UnsignedAttestation> {
+        request_mac(secret, challenge, &self.unsigned)
+            .verify_slice(&self.mac)
+            .map_err(|_| anyhow!("the notary is not authenticated"))?;
+
+        Ok(bincode::deserialize(&self.unsigned)?)
+    }
+}
+
+fn request_mac(secret: &[u8], challenge: &[u8; 32], unsigned: &[u8]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(REQUEST_MAC_DOMAIN);
+    mac.update(challenge);
+    mac.update(unsigned);
+    mac
+}
+
+/// Response of the signer to an [`UnsignedAttestation`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) enum SignResponse {
+    /// The attestation was signed.
+    Signed(Box<Attestation>),
+    /// The attestation was rejected.
+    Rejected(String),
+}
+
+pub(crate) async fn run(args: Args) -> Result<()> {
+    let config = args.into_config()?;
+
+    let key = config.key.as_ref().ok_or_else(|| {
+        anyhow!("a signing key must be provided with --key or in the configuration")
+    })?;
+    let secret = config.secret.as_ref().ok_or_else(|| {
+        anyhow!("a secret shared with the notary must be provided as `secret` in the configuration")
+    })?;
+    let secret: Arc<[u8]> = secret.as_bytes().into();
+    let signer = Arc::new(LocalSigner::new(
+        &load_signing_key(key).await?,
+        config.max_session_duration,
+    )?);
+
+    let listener = TcpListener::bind(config.listen.as_deref().unwrap_or(DEFAULT_LISTEN)).await?;
+    info!("listening on {}", listener.local_addr()?);
+
+    loop {
+        let (socket, addr) = listener.accept().await?;
+        let signer = signer.clone();
+        let secret = secret.clone();
+
+        tokio::spawn(async move {
+            match handle(socket, &signer, &secret).await {
+                Ok(()) => info!("signed attestation for {addr}"),
+                Err(e) => warn!("signing for {addr} failed: {e:#}"),
+            }
+        });
+    }
+}
+
+async fn handle(socket: TcpStream, signer: &LocalSigner, secret: &[u8]) -> Result<()> {
+    let mut socket = socket.compat();
+
+    let challenge = rand::random();
+    io::send(&mut socket, &signer.hello(challenge)).await?;
+    let request: SignRequest = io::recv(&mut socket).await?;
+
+    match request
+        .authenticate(secret, &challenge)
+        .and_then(|unsigned| signer.sign(unsigned))
+    {
+        Ok(attestation) => {
+            io::send(&mut socket, &SignResponse::Signed(Box::new(attestation))).await?;
+            Ok(())
+        }
+        Err(e) => {
+            io::send(&mut socket, &SignResponse::Rejected(format!("{e:#}"))).await?;
+            Err(e)
+        }
+    }
+}
+
+/// Loads a PEM-encoded PKCS#8 secp256k1 signing key.
+pub(crate) async fn load_signing_key(path: &Path) -> Result<SigningKey> {
+    let pem = tokio::fs::read_to_string(path)
+        .await
+        .with_context(|| format!("failed to read {}", path.display()))?;
+
+    SigningKey::from_pkcs8_pem(&pem).context("invalid signing key")
+}
+
+/// Signs attestations with a local key.
+pub(crate) struct LocalSigner {
+    provider: CryptoProvider,
+    config: AttestationConfig,
+    alg: SignatureAlgId,
+    verifying_key: VerifyingKey,
+}
+
+impl LocalSigner {
+    pub(crate) fn new(signing_key: &SigningKey, max_session_duration: Option<u64>) -> Result<Self> {
+        let signer = Secp256k1Signer::new(&signing_key.to_bytes())?;
+        let alg = signer.alg_id();
+        let verifying_key = signer.verifying_key();
+
+        let mut provider = CryptoProvider::default();
+        provider.signer.set_signer(Box::new(signer));
+
+        let mut builder = AttestationConfig::builder();
+        builder.supported_signature_algs([alg]);
+        if let Some(max_session_duration) = max_session_duration {
+            builder.max_session_duration(max_session_duration);
+        }
+        let config = builder.build()?;
+
+        Ok(Self {
+            provider,
+            config,
+            alg,
+            verifying_key,
+        })
+    }
+
+    fn hello(&self, challenge: [u8; 32]) -> SignerHello {
+        SignerHello {
+            alg: self.alg,
+            verifying_key: self.verifying_key.clone(),
+            challenge,
+        }
+    }
+
+    fn sign(&self, unsigned: UnsignedAttestation) -> Result<Attestation> {
+        Ok(unsigned.sign(&self.config, &self.provider)?)
+    }
+}
+
+/// Signer used by `tlsn serve`.
+pub(crate) enum SignerService {
+    /// Signs with a local key.
+    Local(LocalSigner),
+    /// Hands off to a `tlsn sign` service.
+    Remote {
+        /// Address of the service.
+        addr: String,
+        /// Secret shared with the service.
+        secret: Vec<u8>,
+    },
+}
+
+impl SignerService {
+    /// Opens a session to sign a single attestation.
+    pub(crate) async fn open(&self) -> Result<SignerSession<'_>> {
+        match self {
+            Self::Local(signer) => Ok(SignerSession {
+                hello: signer.hello([0; 32]),
+                inner: SessionInner::Local(signer),
+            }),
+            Self::Remote { addr, secret } => {
+                let mut socket = TcpStream::connect(addr)
+                    .await
+                    .with_context(|| format!("failed to connect to signer at {addr}"))?
+                    .compat();
+                let hello: SignerHello = io::recv(&mut socket).await?;
+
+                Ok(SignerSession {
+                    hello,
+                    inner: SessionInner::Remote { socket, secret },
+                })
+            }
+        }
+    }
+}
+
+/// Session with a signer.
+pub(crate) struct SignerSession<'a> {
+    hello: SignerHello,
+    inner: SessionInner<'a>,
+}
+
+enum SessionInner<'a> {
+    Local(&'a LocalSigner),
+    Remote {
+        socket: Compat<TcpStream>,
+        secret: &'a [u8],
+    },
+}
+
+impl SignerSession<'_> {
+    /// Returns the signature algorithm of the signer.
+    pub(crate) fn alg(&self) -> SignatureAlgId {
+        self.hello.alg
+    }
+
+    /// Returns the verifying key of the signer.
+    pub(crate) fn verifying_key(&self) -> &VerifyingKey {
+        &self.hello.verifying_key
+    }
+
+    /// Signs the attestation.
+    pub(crate) async fn sign(self, unsigned: UnsignedAttestation) -> Result<Attestation> {
+        match self.inner {
+            SessionInner::Local(signer) => signer.sign(unsigned),
+            SessionInner::Remote { mut socket, secret } => {
+                let request = SignRequest::new(secret, &self.hello.challenge, &unsigned)?;
+                io::send(&mut socket, &request).await?;
+                match io::recv(&mut socket).await? {
+                    SignResponse::Signed(attestation) => Ok(*attestation),
+                    SignResponse::Rejected(reason) => {
+                        Err(anyhow!("signer rejected the attestation: {reason}"))
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_signer_hello() {
+        let signing_key = SigningKey::from_slice(&[1u8; 32]).unwrap();
+        let signer = LocalSigner::new(&signing_key, None).unwrap();
+
+        let hello = signer.hello([7; 32]);
+        assert_eq!(hello.alg, SignatureAlgId::SECP256K1);
+        assert_eq!(hello.challenge, [7; 32]);
+        assert_eq!(
+            hello.verifying_key.data,
+            signing_key.verifying_key().to_sec1_bytes().to_vec()
+        );
+    }
+
+    #[test]
+    fn test_sign_request_authenticate() {
+        let challenge = [7; 32];
+        let request = || SignRequest {
+            unsigned: b"unsigned".to_vec(),
+            mac: request_mac(b"secret", &challenge, b"unsigned")
+                .finalize()
+                .into_bytes()
+                .to_vec(),
+        };
+        let is_unauthenticated = |err: anyhow::Error| err.to_string().contains("not authenticated");
+
+        assert!(is_unauthenticated(
+            request().authenticate(b"other", &challenge).unwrap_err()
+        ));
+        assert!(is_unauthenticated(
+            request().authenticate(b"secret", &[8; 32]).unwrap_err()
+        ));
+
+        // The request is authenticated, but is not an unsigned attestation.
+        assert!(!is_unauthenticated(
+            request().authenticate(b"secret", &challenge).unwrap_err()
+        ));
+    }
+}
@@ -0,0 +1,159 @@
+//! Status endpoints of `tlsn serve`.
+//!
+//! A lightweight HTTP listener reports the health of the notary so that load
+//! balancers and provers can route to healthy notaries:
+//!
+//! - `GET /health` returns `200 OK` once the notary is accepting sessions.
+//! - `GET /status` returns a JSON [`StatusReport`].
+
+use std::{
+    convert::Infallible,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+    },
+};
+
+use anyhow::Result;
+use http_body_util::Full;
+use hyper::{
+    Method, Request, Response, StatusCode,
+    body::{Bytes, Incoming},
+    server::conn::http1,
+    service::service_fn,
+};
+use hyper_util::rt::TokioIo;
+use serde::{Deserialize, Serialize};
+use tokio::net::TcpListener;
+use tracing::{info, warn};
+
+/// Session counters of the notary.
+#[derive(Debug, Default)]
+pub(crate) struct Status {
+    active: AtomicUsize,
+    total: AtomicU64,
+    failed: AtomicU64,
+}
+
+impl Status {
+    /// Records the start of a session, which is active until the returned
+    /// guard is dropped.
+    pub(crate) fn start_session(self: &Arc<Self>) -> ActiveSession {
+        self.active.fetch_add(1, Ordering::Relaxed);
+        self.total.fetch_add(1, Ordering::Relaxed);
+
+        ActiveSession(self.clone())
+    }
+
+    /// Records a failed session.
+    pub(crate) fn record_failure(&self) {
+        self.failed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns a report of the current status.
+    pub(crate) fn report(&self) -> StatusReport {
+        StatusReport {
+            version: tlsn::version().to_string(),
+            active_sessions: self.active.load(Ordering::Relaxed),
+            total_sessions: self.total.load(Ordering::Relaxed),
+            failed_sessions: self.failed.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Guard of an active session.
+#[derive(Debug)]
+pub(crate) struct ActiveSession(Arc<Status>);
+
+impl Drop for ActiveSession {
+    fn drop(&mut self) {
+        self.0.active.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Status of the notary.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct StatusReport {
+    /// Protocol version, which provers must match.
+    pub(crate) version: String,
+    /// Number of sessions in progress.
+    pub(crate) active_sessions: usize,
+    /// Number of sessions since the notary started.
+    pub(crate) total_sessions: u64,
+    /// Number of failed sessions since the notary started.
+    pub(crate) failed_sessions: u64,
+}
+
+/// Serves the status endpoints.
+pub(crate) async fn serve(listener: TcpListener, status: Arc<Status>) -> Result<()> {
+    info!("serving status on {}", listener.local_addr()?);
+
+    loop {
+        let (socket, addr) = listener.accept().await?;
+        let status = status.clone();
+
+        tokio::spawn(async move {
+            let service = service_fn(move |request| {
+                let response = respond(&request, &status);
+                async move { Ok::<_, Infallible>(response) }
+            });
+
+            if let Err(e) = http1::Builder::new()
+                .keep_alive(false)
+                .serve_connection(TokioIo::new(socket), service)
+                .await
+            {
+                warn!("status request from {addr} failed: {e}");
+            }
+        });
+    }
+}
+
+fn respond(request: &Request<Incoming>, status: &Status) -> Response<Full<Bytes>> {
+    let response = |code, body: Vec<u8>| {
+        let mut response = Response::new(Full::new(Bytes::from(body)));
+        *response.status_mut() = code;
+        response
+    };
+
+    if request.method() != Method::GET {
+        return response(StatusCode::METHOD_NOT_ALLOWED, Vec::new());
+    }
+
+    match request.uri().path() {
+        "/health" => response(StatusCode::OK, b"ok".to_vec()),
+        "/status" => match serde_json::to_vec(&status.report()) {
+            Ok(body) => {
+                let mut response = response(StatusCode::OK, body);
+                response.headers_mut().insert(
+                    hyper::header::CONTENT_TYPE,
+                    hyper::header::HeaderValue::from_static("application/json"),
+                );
+                response
+            }
+            Err(_) => response(StatusCode::INTERNAL_SERVER_ERROR, Vec::new()),
+        },
+        _ => response(StatusCode::NOT_FOUND, Vec::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_counters() {
+        let status = Arc::new(Status::default());
+
+        let session = status.start_session();
+        let _session_2 = status.start_session();
+        status.record_failure();
+        drop(session);
+
+        let report = status.report();
+        assert_eq!(report.active_sessions, 1);
+        assert_eq!(report.total_sessions, 2);
+        assert_eq!(report.failed_sessions, 1);
+        assert_eq!(report.version, tlsn::version().to_string());
+    }
+}
@@ -0,0 +1,98 @@
+//! `tlsn verify` command.
+
+use std::{path::PathBuf, time::SystemTime};
+
+use anyhow::{Context, Result};
+use k256::pkcs8::DecodePublicKey;
+use tlsn::{
+    attestation::{
+        CryptoProvider,
+        presentation::{Presentation, VerificationPolicy, VerifiedSession},
+        signing::{KeyAlgId, VerifyingKey},
+    },
+    verifier::ServerCertVerifier,
+};
+
+use crate::io;
+
+#[derive(clap::Args, Debug)]
+pub(crate) struct Args {
+    /// Path of the presentation file written by `tlsn present`.
+    #[arg(long)]
+    proof: PathBuf,
+    /// PEM-encoded secp256k1 public key of the trusted notary.
+    #[arg(long)]
+    notary_key: PathBuf,
+    /// PEM-encoded CA certificate to trust in addition to the Mozilla roots.
+    #[arg(long)]
+    ca_cert: Option<PathBuf>,
+    /// Maximum age of the notarized connection in seconds.
+    #[arg(long)]
+    max_age: Option<u64>,
+    /// Maximum duration in seconds from the TLS handshake until the
+    /// attestation was signed.
+    #[arg(long)]
+    max_session_duration: Option<u64>,
+}
+
+pub(crate) async fn run(args: Args) -> Result<()> {
+    let presentation: Presentation = io::read_file(&args.proof).await?;
+
+    let pem = tokio::fs::read_to_string(&args.notary_key)
+        .await
+        .with_context(|| format!("failed to read {}", args.notary_key.display()))?;
+    let trusted_key = k256::ecdsa::VerifyingKey::from_public_key_pem(&pem)
+        .context("invalid notary key")?
+        .to_sec1_bytes();
+
+    let mut policy = VerificationPolicy::new();
+    policy.trust_key(VerifyingKey {
+        alg: KeyAlgId::K256,
+        data: trusted_key.to_vec(),
+    });
+    if let Some(max_age) = args.max_age {
+        policy.max_age(max_age);
+    }
+    if let Some(max_session_duration) = args.max_session_duration {
+        policy.max_session_duration(max_session_duration);
+    }
+
+    let provider = CryptoProvider {
+        cert: ServerCertVerifier::new(&io::root_store(args.ca_cert.as_deref())?)?,
+        ..Default::default()
+    };
+
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs();
+
+    let VerifiedSession {
+        server_name,
+        time,
+        signed_at,
+        transcript,
+        ..
+    } = presentation.verify_with_policy(&provider, &policy, now)?;
+
+    if let Some(server_name) = server_name {
+        println!("Server: {server_name}");
+    }
+    println!("Time: {time} (unix)");
+    println!("Signed: {signed_at} (unix)");
+
+    if let Some(mut transcript) = transcript {
+        // Mark the data which was not revealed.
+        transcript.set_unauthed(b'X');
+
+        println!(
+            "Sent:\n{}\n",
+            String::from_utf8_lossy(transcript.sent_unsafe())
+        );
+        println!(
+            "Received:\n{}",
+            String::from_utf8_lossy(transcript.received_unsafe())
+        );
+    }
+
+    Ok(())
+}
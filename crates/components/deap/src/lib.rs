@@ -105,6 +105,12 @@ where
     /// Finalizes the DEAP VM.
     ///
     /// This reveals all private inputs of the follower.
+    ///
+    /// # Errors
+    ///
+    /// For the follower, returns an error if any output decoded from the MPC
+    /// VM differs from the output proven in the ZK VM, see
+    /// [`DeapError::is_equality_check`].
     pub async fn finalize(&mut self, ctx: &mut Context) -> Result<(), VmError> {
         let mut mpc = self.mpc.try_lock().unwrap();
         let mut zk = self.zk.try_lock().unwrap();
@@ -159,7 +165,9 @@ where
 
                     // Asserts equality of all the output values from both VMs.
                     if zk_output != mpc_output {
-                        return Err(VmError::execute(Error::from(ErrorRepr::EqualityCheck)));
+                        return Err(VmError::execute(Error::from(ErrorRepr::EqualityCheck(
+                            output,
+                        ))));
                     }
                 }
             }
@@ -372,14 +380,39 @@ where
     }
 }
 
+/// DEAP error.
 #[derive(Debug, thiserror::Error)]
 #[error(transparent)]
-pub(crate) struct DeapError(#[from] ErrorRepr);
+pub struct DeapError(#[from] ErrorRepr);
+
+impl DeapError {
+    /// Returns the DEAP error which caused the VM error, if any.
+    ///
+    /// Errors returned by [`Deap::finalize`] are wrapped in a [`VmError`].
+    pub fn from_vm_error(err: &VmError) -> Option<&Self> {
+        let mut source = std::error::Error::source(err);
+        while let Some(err) = source {
+            if let Some(err) = err.downcast_ref::<Self>() {
+                return Some(err);
+            }
+            source = err.source();
+        }
+
+        None
+    }
+
+    /// Returns `true` if the outputs of the MPC and ZK VMs were not equal.
+    ///
+    /// This indicates that the leader used different inputs in each VM.
+    pub fn is_equality_check(&self) -> bool {
+        matches!(self.0, ErrorRepr::EqualityCheck(_))
+    }
+}
 
 #[derive(Debug, thiserror::Error)]
 enum ErrorRepr {
-    #[error("equality check failed")]
-    EqualityCheck,
+    #[error("equality check failed for output: {0}")]
+    EqualityCheck(Slice),
 }
 
 #[cfg(test)]
@@ -626,6 +659,7 @@ mod tests {
             }
         );
 
-        assert!(follower_res.is_err());
+        let err = follower_res.unwrap_err();
+        assert!(DeapError::from_vm_error(&err).is_some_and(DeapError::is_equality_check));
     }
 }
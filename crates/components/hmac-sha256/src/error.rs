@@ -26,6 +26,13 @@ impl PrfError {
         Self::new(ErrorKind::Vm, err)
     }
 
+    pub(crate) fn config(msg: impl Into<String>) -> Self {
+        Self {
+            kind: ErrorKind::Config,
+            source: Some(msg.into().into()),
+        }
+    }
+
     pub(crate) fn state(msg: impl Into<String>) -> Self {
         Self {
             kind: ErrorKind::State,
@@ -44,6 +51,7 @@ impl From<Sha256Error> for PrfError {
 pub(crate) enum ErrorKind {
     Vm,
     State,
+    Config,
     Hash,
 }
 
@@ -52,6 +60,7 @@ impl fmt::Display for PrfError {
         match self.kind {
             ErrorKind::Vm => write!(f, "vm error")?,
             ErrorKind::State => write!(f, "state error")?,
+            ErrorKind::Config => write!(f, "config error")?,
             ErrorKind::Hash => write!(f, "hash error")?,
         }
 
@@ -72,7 +72,7 @@ fn state_to_bytes(input: [u32; 8]) -> [u8; 32] {
 #[cfg(test)]
 mod tests {
     use crate::{
-        test_utils::{prf_cf_vd, prf_keys, prf_ms, prf_sf_vd},
+        test_utils::{prf_ems, prf_exporter, prf_keys, PrfVector},
         Mode, MpcPrf, SessionKeys,
     };
     use mpz_common::context::test_st_context;
@@ -86,41 +86,43 @@ mod tests {
     #[tokio::test]
     async fn test_prf_reduced() {
         let mode = Mode::Reduced;
-        test_prf(mode).await;
+        test_prf(mode, PrfVector::random(1)).await;
     }
 
     #[tokio::test]
     async fn test_prf_normal() {
         let mode = Mode::Normal;
-        test_prf(mode).await;
+        test_prf(mode, PrfVector::random(1)).await;
     }
 
-    async fn test_prf(mode: Mode) {
-        let mut rng = StdRng::seed_from_u64(1);
-        // Test input
-        let pms: [u8; 32] = rng.random();
-        let client_random: [u8; 32] = rng.random();
-        let server_random: [u8; 32] = rng.random();
-
-        let cf_hs_hash: [u8; 32] = rng.random();
-        let sf_hs_hash: [u8; 32] = rng.random();
-
-        // Expected output
-        let ms_expected = prf_ms(pms, client_random, server_random);
-
-        let [cwk_expected, swk_expected, civ_expected, siv_expected] =
-            prf_keys(ms_expected, client_random, server_random);
+    #[tokio::test]
+    async fn test_prf_fixed_vector_reduced() {
+        test_prf(Mode::Reduced, PrfVector::fixed()).await;
+    }
 
-        let cwk_expected: [u8; 16] = cwk_expected.try_into().unwrap();
-        let swk_expected: [u8; 16] = swk_expected.try_into().unwrap();
-        let civ_expected: [u8; 4] = civ_expected.try_into().unwrap();
-        let siv_expected: [u8; 4] = siv_expected.try_into().unwrap();
+    #[tokio::test]
+    async fn test_prf_fixed_vector_normal() {
+        test_prf(Mode::Normal, PrfVector::fixed()).await;
+    }
 
-        let cf_vd_expected = prf_cf_vd(ms_expected, cf_hs_hash);
-        let sf_vd_expected = prf_sf_vd(ms_expected, sf_hs_hash);
+    async fn test_prf(mode: Mode, vector: PrfVector) {
+        let PrfVector {
+            pms,
+            client_random,
+            server_random,
+            cf_hash: cf_hs_hash,
+            sf_hash: sf_hs_hash,
+            keys,
+            cf_vd: cf_vd_expected,
+            sf_vd: sf_vd_expected,
+            ..
+        } = vector;
 
-        let cf_vd_expected: [u8; 12] = cf_vd_expected.try_into().unwrap();
-        let sf_vd_expected: [u8; 12] = sf_vd_expected.try_into().unwrap();
+        // Expected output
+        let cwk_expected: [u8; 16] = keys[..16].try_into().unwrap();
+        let swk_expected: [u8; 16] = keys[16..32].try_into().unwrap();
+        let civ_expected: [u8; 4] = keys[32..36].try_into().unwrap();
+        let siv_expected: [u8; 4] = keys[36..].try_into().unwrap();
 
         // Set up vm and prf
         let (mut ctx_a, mut ctx_b) = test_st_context(128);
@@ -268,4 +270,130 @@ mod tests {
         assert_eq!(sf_vd_leader, sf_vd_follower);
         assert_eq!(sf_vd_leader, sf_vd_expected);
     }
+
+    #[tokio::test]
+    async fn test_prf_ems_exporter_reduced() {
+        test_prf_ems_exporter(Mode::Reduced).await;
+    }
+
+    #[tokio::test]
+    async fn test_prf_ems_exporter_normal() {
+        test_prf_ems_exporter(Mode::Normal).await;
+    }
+
+    async fn test_prf_ems_exporter(mode: Mode) {
+        const CB_LABEL: &[u8] = b"EXPORTER-Channel-Binding";
+        const TEST_LABEL: &[u8] = b"EXPORTER-test";
+
+        let mut rng = StdRng::seed_from_u64(2);
+        // Test input
+        let pms: [u8; 32] = rng.random();
+        let client_random: [u8; 32] = rng.random();
+        let server_random: [u8; 32] = rng.random();
+        let session_hash: [u8; 32] = rng.random();
+
+        // Expected output
+        let ms_expected = prf_ems(pms, session_hash);
+        let [cwk_expected, ..] = prf_keys(ms_expected, client_random, server_random);
+        let cb_expected = prf_exporter(
+            ms_expected,
+            CB_LABEL,
+            client_random,
+            server_random,
+            None,
+            32,
+        );
+        let test_expected = prf_exporter(
+            ms_expected,
+            TEST_LABEL,
+            client_random,
+            server_random,
+            Some(b"context"),
+            40,
+        );
+
+        // Set up vm and prf
+        let (mut ctx_a, mut ctx_b) = test_st_context(128);
+        let mut leader = IdealVm::new();
+        let mut follower = IdealVm::new();
+
+        let leader_pms: Array<U8, 32> = leader.alloc().unwrap();
+        leader.mark_public(leader_pms).unwrap();
+        leader.assign(leader_pms, pms).unwrap();
+        leader.commit(leader_pms).unwrap();
+
+        let follower_pms: Array<U8, 32> = follower.alloc().unwrap();
+        follower.mark_public(follower_pms).unwrap();
+        follower.assign(follower_pms, pms).unwrap();
+        follower.commit(follower_pms).unwrap();
+
+        let mut prf_leader = MpcPrf::new(mode);
+        let mut prf_follower = MpcPrf::new(mode);
+
+        for prf in [&mut prf_leader, &mut prf_follower] {
+            prf.set_extended_master_secret(true).unwrap();
+            prf.add_exporter(CB_LABEL, None, 32).unwrap();
+            prf.add_exporter(TEST_LABEL, Some(b"context"), 40).unwrap();
+        }
+
+        let leader_prf_out = prf_leader.alloc(&mut leader, leader_pms).unwrap();
+        let follower_prf_out = prf_follower.alloc(&mut follower, follower_pms).unwrap();
+
+        prf_leader.set_client_random(client_random).unwrap();
+        prf_follower.set_client_random(client_random).unwrap();
+
+        prf_leader.set_server_random(server_random).unwrap();
+        prf_follower.set_server_random(server_random).unwrap();
+
+        prf_leader.set_session_hash(session_hash).unwrap();
+        prf_follower.set_session_hash(session_hash).unwrap();
+
+        let mut cwk_leader = leader.decode(leader_prf_out.keys.client_write_key).unwrap();
+        let mut cwk_follower = follower
+            .decode(follower_prf_out.keys.client_write_key)
+            .unwrap();
+
+        let mut ekm_leader = prf_leader
+            .exporters()
+            .iter()
+            .map(|ekm| leader.decode(*ekm).unwrap())
+            .collect::<Vec<_>>();
+        let mut ekm_follower = prf_follower
+            .exporters()
+            .iter()
+            .map(|ekm| follower.decode(*ekm).unwrap())
+            .collect::<Vec<_>>();
+
+        while prf_leader.wants_flush() || prf_follower.wants_flush() {
+            tokio::try_join!(
+                async {
+                    prf_leader.flush(&mut leader).unwrap();
+                    leader.execute_all(&mut ctx_a).await
+                },
+                async {
+                    prf_follower.flush(&mut follower).unwrap();
+                    follower.execute_all(&mut ctx_b).await
+                }
+            )
+            .unwrap();
+        }
+
+        let cwk_leader = cwk_leader.try_recv().unwrap().unwrap();
+        let cwk_follower = cwk_follower.try_recv().unwrap().unwrap();
+
+        assert_eq!(cwk_leader, cwk_follower);
+        assert_eq!(cwk_leader.to_vec(), cwk_expected);
+
+        let ekm_leader = ekm_leader
+            .iter_mut()
+            .map(|ekm| ekm.try_recv().unwrap().unwrap())
+            .collect::<Vec<_>>();
+        let ekm_follower = ekm_follower
+            .iter_mut()
+            .map(|ekm| ekm.try_recv().unwrap().unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(ekm_leader, ekm_follower);
+        assert_eq!(ekm_leader, vec![cb_expected, test_expected]);
+    }
 }
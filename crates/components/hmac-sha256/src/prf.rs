@@ -21,12 +21,49 @@ mod function;
 use function::Prf;
 
 /// MPC PRF for computing TLS 1.2 HMAC-SHA256 PRF.
+///
+/// The PRF can also derive the extended master secret and export keying
+/// material, see [`MpcPrf::set_extended_master_secret`] and
+/// [`MpcPrf::add_exporter`]. MPC-TLS does not use either yet: it always
+/// derives the standard master secret, the client does not offer the
+/// extension, and whether it was used is neither part of the connection info
+/// nor attested.
 #[derive(Debug)]
 pub struct MpcPrf {
     mode: Mode,
+    extended_master_secret: bool,
+    exporters: Vec<Exporter>,
+    exporter_outputs: Vec<Vector<U8>>,
     state: State,
 }
 
+/// Keying material exporter (RFC 5705).
+#[derive(Debug)]
+struct Exporter {
+    label: &'static [u8],
+    context: Option<Vec<u8>>,
+    len: usize,
+}
+
+impl Exporter {
+    /// Returns the length of the seed without the label.
+    fn seed_len(&self) -> usize {
+        64 + self.context.as_ref().map_or(0, |context| 2 + context.len())
+    }
+
+    /// Returns the seed without the label.
+    fn seed(&self, client_random: [u8; 32], server_random: [u8; 32]) -> Vec<u8> {
+        let mut seed = Vec::with_capacity(self.seed_len());
+        seed.extend_from_slice(&client_random);
+        seed.extend_from_slice(&server_random);
+        if let Some(context) = &self.context {
+            seed.extend_from_slice(&(context.len() as u16).to_be_bytes());
+            seed.extend_from_slice(context);
+        }
+        seed
+    }
+}
+
 impl MpcPrf {
     /// Creates a new instance of the PRF.
     ///
@@ -36,10 +73,85 @@ impl MpcPrf {
     pub fn new(mode: Mode) -> MpcPrf {
         Self {
             mode,
+            extended_master_secret: false,
+            exporters: Vec::new(),
+            exporter_outputs: Vec::new(),
             state: State::Initialized,
         }
     }
 
+    /// Sets whether the extended master secret (RFC 7627) is derived instead
+    /// of the standard master secret, defaults to `false`.
+    ///
+    /// When enabled, the master secret is seeded with the session hash set
+    /// with [`MpcPrf::set_session_hash`] instead of the client and server
+    /// randoms. This must be called before [`MpcPrf::alloc`].
+    pub fn set_extended_master_secret(&mut self, enable: bool) -> Result<(), PrfError> {
+        let State::Initialized = self.state else {
+            return Err(PrfError::state(
+                "extended master secret must be set before allocation",
+            ));
+        };
+
+        self.extended_master_secret = enable;
+        Ok(())
+    }
+
+    /// Returns `true` if the extended master secret is derived.
+    pub fn extended_master_secret(&self) -> bool {
+        self.extended_master_secret
+    }
+
+    /// Adds keying material to export from the master secret (RFC 5705).
+    ///
+    /// This must be called before [`MpcPrf::alloc`], after which the output is
+    /// available with [`MpcPrf::exporters`] in the order the exporters were
+    /// added.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - The disambiguating label, e.g. `EXPORTER-Channel-Binding`.
+    /// * `context` - The optional context value.
+    /// * `len` - The length of the keying material in bytes.
+    pub fn add_exporter(
+        &mut self,
+        label: &'static [u8],
+        context: Option<&[u8]>,
+        len: usize,
+    ) -> Result<(), PrfError> {
+        let State::Initialized = self.state else {
+            return Err(PrfError::state("exporters must be added before allocation"));
+        };
+
+        if len == 0 {
+            return Err(PrfError::config(
+                "exported keying material must not be empty",
+            ));
+        }
+
+        if context.is_some_and(|context| context.len() > u16::MAX as usize) {
+            return Err(PrfError::config(
+                "exporter context must not be longer than 65535 bytes",
+            ));
+        }
+
+        self.exporters.push(Exporter {
+            label,
+            context: context.map(<[u8]>::to_vec),
+            len,
+        });
+
+        Ok(())
+    }
+
+    /// Returns the exported keying material, in the order the exporters were
+    /// added.
+    ///
+    /// This is empty until [`MpcPrf::alloc`] is called.
+    pub fn exporters(&self) -> &[Vector<U8>] {
+        &self.exporter_outputs
+    }
+
     /// Allocates resources for the PRF.
     ///
     /// # Arguments
@@ -62,8 +174,11 @@ impl MpcPrf {
         let outer_partial_pms = compute_partial(vm, pms, OPAD)?;
         let inner_partial_pms = compute_partial(vm, pms, IPAD)?;
 
-        let master_secret =
-            Prf::alloc_master_secret(mode, vm, outer_partial_pms, inner_partial_pms)?;
+        let master_secret = if self.extended_master_secret {
+            Prf::alloc_extended_master_secret(mode, vm, outer_partial_pms, inner_partial_pms)?
+        } else {
+            Prf::alloc_master_secret(mode, vm, outer_partial_pms, inner_partial_pms)?
+        };
         let ms = master_secret.output();
         let ms = merge_outputs(vm, ms, 48)?;
 
@@ -85,12 +200,30 @@ impl MpcPrf {
             inner_partial_ms.clone(),
         )?;
 
+        let mut exporters = Vec::with_capacity(self.exporters.len());
+        for exporter in &self.exporters {
+            let prf = Prf::alloc_exporter(
+                mode,
+                vm,
+                exporter.label,
+                outer_partial_ms.clone(),
+                inner_partial_ms.clone(),
+                exporter.len,
+                exporter.seed_len(),
+            )?;
+
+            self.exporter_outputs
+                .push(merge_outputs(vm, prf.output(), exporter.len)?);
+            exporters.push(prf);
+        }
+
         self.state = State::SessionKeys {
             client_random: None,
             master_secret,
             key_expansion,
             client_finished,
             server_finished,
+            exporters,
         };
 
         self.state.prf_output(vm)
@@ -122,6 +255,7 @@ impl MpcPrf {
             client_random,
             master_secret,
             key_expansion,
+            exporters,
             ..
         } = &mut self.state
         else {
@@ -131,14 +265,44 @@ impl MpcPrf {
         let client_random = client_random.expect("Client random should have been set by now");
         let server_random = random;
 
-        let mut seed_ms = client_random.to_vec();
-        seed_ms.extend_from_slice(&server_random);
-        master_secret.set_start_seed(seed_ms);
+        // The extended master secret is seeded with the session hash instead.
+        if !self.extended_master_secret {
+            let mut seed_ms = client_random.to_vec();
+            seed_ms.extend_from_slice(&server_random);
+            master_secret.set_start_seed(seed_ms);
+        }
 
         let mut seed_ke = server_random.to_vec();
         seed_ke.extend_from_slice(&client_random);
         key_expansion.set_start_seed(seed_ke);
 
+        for (exporter, prf) in self.exporters.iter().zip(exporters) {
+            prf.set_start_seed(exporter.seed(client_random, server_random));
+        }
+
+        Ok(())
+    }
+
+    /// Sets the session hash for the extended master secret.
+    ///
+    /// # Arguments
+    ///
+    /// * `session_hash` - The handshake transcript hash up to and including
+    ///   the ClientKeyExchange message.
+    #[instrument(level = "debug", skip_all, err)]
+    pub fn set_session_hash(&mut self, session_hash: [u8; 32]) -> Result<(), PrfError> {
+        if !self.extended_master_secret {
+            return Err(PrfError::state(
+                "session hash is only used for the extended master secret",
+            ));
+        }
+
+        let State::SessionKeys { master_secret, .. } = &mut self.state else {
+            return Err(PrfError::state("PRF not set up"));
+        };
+
+        master_secret.set_start_seed(session_hash.to_vec());
+
         Ok(())
     }
 
@@ -186,8 +350,13 @@ impl MpcPrf {
             State::SessionKeys {
                 master_secret,
                 key_expansion,
+                exporters,
                 ..
-            } => master_secret.wants_flush() || key_expansion.wants_flush(),
+            } => {
+                master_secret.wants_flush()
+                    || key_expansion.wants_flush()
+                    || exporters.iter().any(Prf::wants_flush)
+            }
             State::ClientFinished {
                 client_finished, ..
             } => client_finished.wants_flush(),
@@ -206,11 +375,20 @@ impl MpcPrf {
                 mut key_expansion,
                 client_finished,
                 server_finished,
+                mut exporters,
             } => {
                 master_secret.flush(vm)?;
                 key_expansion.flush(vm)?;
+                for exporter in &mut exporters {
+                    exporter.flush(vm)?;
+                }
 
-                if !master_secret.wants_flush() && !key_expansion.wants_flush() {
+                // The master secret may still be waiting for its seed, e.g. the
+                // session hash of the extended master secret.
+                if master_secret.is_done()
+                    && key_expansion.is_done()
+                    && exporters.iter().all(Prf::is_done)
+                {
                     State::ClientFinished {
                         client_finished,
                         server_finished,
@@ -222,6 +400,7 @@ impl MpcPrf {
                         key_expansion,
                         client_finished,
                         server_finished,
+                        exporters,
                     }
                 }
             }
@@ -41,6 +41,27 @@ impl Prf {
         Ok(prf)
     }
 
+    pub(crate) fn alloc_extended_master_secret(
+        mode: Mode,
+        vm: &mut dyn Vm<Binary>,
+        outer_partial: Sha256,
+        inner_partial: Sha256,
+    ) -> Result<Self, PrfError> {
+        let prf = match mode {
+            Mode::Reduced => Self::Reduced(reduced::PrfFunction::alloc_extended_master_secret(
+                vm,
+                outer_partial,
+                inner_partial,
+            )?),
+            Mode::Normal => Self::Normal(normal::PrfFunction::alloc_extended_master_secret(
+                vm,
+                outer_partial,
+                inner_partial,
+            )?),
+        };
+        Ok(prf)
+    }
+
     pub(crate) fn alloc_key_expansion(
         mode: Mode,
         vm: &mut dyn Vm<Binary>,
@@ -104,6 +125,42 @@ impl Prf {
         Ok(prf)
     }
 
+    pub(crate) fn alloc_exporter(
+        mode: Mode,
+        vm: &mut dyn Vm<Binary>,
+        label: &'static [u8],
+        outer_partial: Sha256,
+        inner_partial: Sha256,
+        output_len: usize,
+        seed_len: usize,
+    ) -> Result<Self, PrfError> {
+        let prf = match mode {
+            Mode::Reduced => Self::Reduced(reduced::PrfFunction::alloc_exporter(
+                vm,
+                label,
+                outer_partial,
+                inner_partial,
+                output_len,
+            )?),
+            Mode::Normal => Self::Normal(normal::PrfFunction::alloc_exporter(
+                vm,
+                label,
+                outer_partial,
+                inner_partial,
+                output_len,
+                seed_len,
+            )?),
+        };
+        Ok(prf)
+    }
+
+    pub(crate) fn is_done(&self) -> bool {
+        match self {
+            Prf::Reduced(prf) => prf.is_done(),
+            Prf::Normal(prf) => prf.is_done(),
+        }
+    }
+
     pub(crate) fn wants_flush(&self) -> bool {
         match self {
             Prf::Reduced(prf) => prf.wants_flush(),
@@ -23,6 +23,7 @@ pub(crate) struct PrfFunction {
 
 impl PrfFunction {
     const MS_LABEL: &[u8] = b"master secret";
+    const EMS_LABEL: &[u8] = b"extended master secret";
     const KEY_LABEL: &[u8] = b"key expansion";
     const CF_LABEL: &[u8] = b"client finished";
     const SF_LABEL: &[u8] = b"server finished";
@@ -35,6 +36,14 @@ impl PrfFunction {
         Self::alloc(vm, Self::MS_LABEL, outer_partial, inner_partial, 48, 64)
     }
 
+    pub(crate) fn alloc_extended_master_secret(
+        vm: &mut dyn Vm<Binary>,
+        outer_partial: Sha256,
+        inner_partial: Sha256,
+    ) -> Result<Self, PrfError> {
+        Self::alloc(vm, Self::EMS_LABEL, outer_partial, inner_partial, 48, 32)
+    }
+
     pub(crate) fn alloc_key_expansion(
         vm: &mut dyn Vm<Binary>,
         outer_partial: Sha256,
@@ -59,6 +68,28 @@ impl PrfFunction {
         Self::alloc(vm, Self::SF_LABEL, outer_partial, inner_partial, 12, 32)
     }
 
+    pub(crate) fn alloc_exporter(
+        vm: &mut dyn Vm<Binary>,
+        label: &'static [u8],
+        outer_partial: Sha256,
+        inner_partial: Sha256,
+        output_len: usize,
+        seed_len: usize,
+    ) -> Result<Self, PrfError> {
+        Self::alloc(
+            vm,
+            label,
+            outer_partial,
+            inner_partial,
+            output_len,
+            seed_len,
+        )
+    }
+
+    pub(crate) fn is_done(&self) -> bool {
+        matches!(self.state, State::Finished)
+    }
+
     pub(crate) fn wants_flush(&self) -> bool {
         let is_computing = match self.state {
             State::Computing => true,
@@ -45,6 +45,7 @@ enum PrfState {
 
 impl PrfFunction {
     const MS_LABEL: &[u8] = b"master secret";
+    const EMS_LABEL: &[u8] = b"extended master secret";
     const KEY_LABEL: &[u8] = b"key expansion";
     const CF_LABEL: &[u8] = b"client finished";
     const SF_LABEL: &[u8] = b"server finished";
@@ -57,6 +58,14 @@ impl PrfFunction {
         Self::alloc(vm, Self::MS_LABEL, outer_partial, inner_partial, 48)
     }
 
+    pub(crate) fn alloc_extended_master_secret(
+        vm: &mut dyn Vm<Binary>,
+        outer_partial: Sha256,
+        inner_partial: Sha256,
+    ) -> Result<Self, PrfError> {
+        Self::alloc(vm, Self::EMS_LABEL, outer_partial, inner_partial, 48)
+    }
+
     pub(crate) fn alloc_key_expansion(
         vm: &mut dyn Vm<Binary>,
         outer_partial: Sha256,
@@ -81,6 +90,20 @@ impl PrfFunction {
         Self::alloc(vm, Self::SF_LABEL, outer_partial, inner_partial, 12)
     }
 
+    pub(crate) fn alloc_exporter(
+        vm: &mut dyn Vm<Binary>,
+        label: &'static [u8],
+        outer_partial: Sha256,
+        inner_partial: Sha256,
+        len: usize,
+    ) -> Result<Self, PrfError> {
+        Self::alloc(vm, label, outer_partial, inner_partial, len)
+    }
+
+    pub(crate) fn is_done(&self) -> bool {
+        matches!(self.state, PrfState::Done)
+    }
+
     pub(crate) fn wants_flush(&self) -> bool {
         !matches!(self.state, PrfState::Done) && self.start_seed_label.is_some()
     }
@@ -20,6 +20,7 @@ pub(crate) enum State {
         key_expansion: Prf,
         client_finished: Prf,
         server_finished: Prf,
+        exporters: Vec<Prf>,
     },
     ClientFinished {
         client_finished: Prf,
@@ -5,6 +5,82 @@ pub(crate) const SHA256_IV: [u32; 8] = [
     0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
 ];
 
+/// Inputs and expected outputs of the TLS 1.2 key schedule.
+pub(crate) struct PrfVector {
+    pub(crate) pms: [u8; 32],
+    pub(crate) client_random: [u8; 32],
+    pub(crate) server_random: [u8; 32],
+    pub(crate) cf_hash: [u8; 32],
+    pub(crate) sf_hash: [u8; 32],
+    pub(crate) ms: [u8; 48],
+    pub(crate) keys: [u8; 40],
+    pub(crate) cf_vd: [u8; 12],
+    pub(crate) sf_vd: [u8; 12],
+}
+
+impl PrfVector {
+    /// Samples the inputs from a seeded rng and computes the outputs with the
+    /// reference implementation.
+    pub(crate) fn random(seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let pms: [u8; 32] = rng.random();
+        let client_random: [u8; 32] = rng.random();
+        let server_random: [u8; 32] = rng.random();
+        let cf_hash: [u8; 32] = rng.random();
+        let sf_hash: [u8; 32] = rng.random();
+
+        let ms = prf_ms(pms, client_random, server_random);
+        let keys: Vec<u8> = prf_keys(ms, client_random, server_random)
+            .into_iter()
+            .flatten()
+            .collect();
+
+        Self {
+            pms,
+            client_random,
+            server_random,
+            cf_hash,
+            sf_hash,
+            ms,
+            keys: keys.try_into().unwrap(),
+            cf_vd: prf_cf_vd(ms, cf_hash).try_into().unwrap(),
+            sf_vd: prf_sf_vd(ms, sf_hash).try_into().unwrap(),
+        }
+    }
+
+    /// A fixed vector with outputs computed by an independent implementation
+    /// of the TLS 1.2 PRF (Python's `hmac` module).
+    pub(crate) fn fixed() -> Self {
+        fn from_hex<const N: usize>(s: &str) -> [u8; N] {
+            hex::decode(s).unwrap().try_into().unwrap()
+        }
+
+        let pms: [u8; 32] = std::array::from_fn(|i| i as u8);
+        let client_random: [u8; 32] = std::array::from_fn(|i| 32 + i as u8);
+        let server_random: [u8; 32] = std::array::from_fn(|i| 64 + i as u8);
+        let hs_hash: [u8; 32] = std::array::from_fn(|i| 96 + i as u8);
+
+        Self {
+            pms,
+            client_random,
+            server_random,
+            cf_hash: hs_hash,
+            sf_hash: hs_hash,
+            ms: from_hex(
+                "518bc65fd30dabe86349152f98435c94d907b50c92a931c5\
+                 a2f9e3a4d90f3439f2726c763fb1a40aec90b8bc60173f7c",
+            ),
+            keys: from_hex(
+                "9052d2e0e4485b2e323effcbc8a47e0839454ba1588ffbc3\
+                 5dccffd8fe8f67f36aee3d733b5a6abc",
+            ),
+            cf_vd: from_hex("7dec70c0f70d2c1ad8e52dd0"),
+            sf_vd: from_hex("2d4806a12e979726e9c0ba1b"),
+        }
+    }
+}
+
 pub(crate) fn prf_ms(pms: [u8; 32], client_random: [u8; 32], server_random: [u8; 32]) -> [u8; 48] {
     let mut label_start_seed = b"master secret".to_vec();
     label_start_seed.extend_from_slice(&client_random);
@@ -15,6 +91,34 @@ pub(crate) fn prf_ms(pms: [u8; 32], client_random: [u8; 32], server_random: [u8;
     ms.try_into().unwrap()
 }
 
+pub(crate) fn prf_ems(pms: [u8; 32], session_hash: [u8; 32]) -> [u8; 48] {
+    let mut label_start_seed = b"extended master secret".to_vec();
+    label_start_seed.extend_from_slice(&session_hash);
+
+    let ms = phash(pms.to_vec(), &label_start_seed, 2)[..48].to_vec();
+
+    ms.try_into().unwrap()
+}
+
+pub(crate) fn prf_exporter(
+    ms: [u8; 48],
+    label: &[u8],
+    client_random: [u8; 32],
+    server_random: [u8; 32],
+    context: Option<&[u8]>,
+    len: usize,
+) -> Vec<u8> {
+    let mut label_start_seed = label.to_vec();
+    label_start_seed.extend_from_slice(&client_random);
+    label_start_seed.extend_from_slice(&server_random);
+    if let Some(context) = context {
+        label_start_seed.extend_from_slice(&(context.len() as u16).to_be_bytes());
+        label_start_seed.extend_from_slice(context);
+    }
+
+    phash(ms.to_vec(), &label_start_seed, len.div_ceil(32))[..len].to_vec()
+}
+
 pub(crate) fn prf_keys(
     ms: [u8; 48],
     client_random: [u8; 32],
@@ -244,3 +348,89 @@ fn test_prf_reference_sf() {
 
     assert_eq!(sf_vd, expected_sf_vd);
 }
+
+#[test]
+fn test_prf_reference_ems() {
+    use ring_prf::prf as prf_ref;
+
+    let mut rng = StdRng::from_seed([5; 32]);
+
+    let pms: [u8; 32] = rng.random();
+    let label: &[u8] = b"extended master secret";
+    let session_hash: [u8; 32] = rng.random();
+
+    let ms = prf_ems(pms, session_hash);
+
+    let mut expected_ms: [u8; 48] = [0; 48];
+    prf_ref(&mut expected_ms, &pms, label, &session_hash);
+
+    assert_eq!(ms, expected_ms);
+}
+
+#[test]
+fn test_prf_reference_exporter() {
+    use ring_prf::prf as prf_ref;
+
+    let mut rng = StdRng::from_seed([6; 32]);
+
+    let ms: [u8; 48] = rng.random();
+    let label: &[u8] = b"EXPORTER-test";
+    let client_random: [u8; 32] = rng.random();
+    let server_random: [u8; 32] = rng.random();
+    let context: &[u8] = b"context";
+    let mut seed = Vec::from(client_random);
+    seed.extend_from_slice(&server_random);
+    seed.extend_from_slice(&(context.len() as u16).to_be_bytes());
+    seed.extend_from_slice(context);
+
+    let ekm = prf_exporter(ms, label, client_random, server_random, Some(context), 40);
+
+    let mut expected_ekm: [u8; 40] = [0; 40];
+    prf_ref(&mut expected_ekm, &ms, label, &seed);
+
+    assert_eq!(ekm, expected_ekm);
+}
+
+#[test]
+fn test_prf_reference_known_answer() {
+    use ring_prf::prf as prf_ref;
+
+    // Widely used TLS 1.2 PRF (SHA-256) test vector from the IETF TLS working
+    // group mailing list.
+    let secret = hex::decode("9bbe436ba940f017b17652849a71db35").unwrap();
+    let seed = hex::decode("a0ba9f936cda311827a6f796ffd5198c").unwrap();
+    let label: &[u8] = b"test label";
+    let expected = hex::decode(
+        "e3f229ba727be17b8d122620557cd453c2aab21d07c3d495329b52d4e61edb5a\
+         6b301791e90d35c9c9a46b4e14baf9af0fa022f7077def17abfd3797c0564bab\
+         4fbc91666e9def9b97fce34f796789baa48082d122ee42c5a72e5a5110fff701\
+         87347b66",
+    )
+    .unwrap();
+
+    let mut label_seed = label.to_vec();
+    label_seed.extend_from_slice(&seed);
+
+    assert_eq!(phash(secret.clone(), &label_seed, 4)[..100], expected);
+
+    let mut output = [0u8; 100];
+    prf_ref(&mut output, &secret, label, &seed);
+
+    assert_eq!(output.to_vec(), expected);
+}
+
+#[test]
+fn test_prf_reference_fixed_vector() {
+    let vector = PrfVector::fixed();
+
+    let ms = prf_ms(vector.pms, vector.client_random, vector.server_random);
+    let keys: Vec<u8> = prf_keys(ms, vector.client_random, vector.server_random)
+        .into_iter()
+        .flatten()
+        .collect();
+
+    assert_eq!(ms, vector.ms);
+    assert_eq!(keys, vector.keys);
+    assert_eq!(prf_cf_vd(ms, vector.cf_hash), vector.cf_vd);
+    assert_eq!(prf_sf_vd(ms, vector.sf_hash), vector.sf_vd);
+}
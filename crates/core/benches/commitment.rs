@@ -0,0 +1,123 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rangeset::set::RangeSet;
+
+use tlsn_core::{
+    hash::{HashAlgId, HashProvider},
+    transcript::{
+        hash::{hash_plaintext, PlaintextHash, PlaintextHashSecret},
+        Direction, Transcript, TranscriptCommitment, TranscriptCommitmentKind, TranscriptProof,
+        TranscriptProofBuilder, TranscriptSecret,
+    },
+};
+
+const TRANSCRIPT_LEN: usize = 16 * 1024;
+const RANGE_COUNTS: &[usize] = &[1, 16, 128];
+const ALGS: &[(&str, HashAlgId)] = &[
+    ("sha256", HashAlgId::SHA256),
+    ("blake3", HashAlgId::BLAKE3),
+    ("keccak256", HashAlgId::KECCAK256),
+];
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let provider = HashProvider::default();
+    let transcript = Transcript::new(vec![42u8; TRANSCRIPT_LEN], vec![69u8; TRANSCRIPT_LEN]);
+
+    let mut group = c.benchmark_group("commitment");
+    for &(name, alg) in ALGS {
+        for &count in RANGE_COUNTS {
+            let id = format!("{name}/{count}");
+            let ranges = ranges(count);
+
+            group.bench_function(BenchmarkId::new("commit", &id), |b| {
+                b.iter(|| commit(&provider, &transcript, alg, &ranges))
+            });
+
+            let (commitments, secrets) = commit(&provider, &transcript, alg, &ranges);
+
+            group.bench_function(BenchmarkId::new("prove", &id), |b| {
+                b.iter(|| prove(&transcript, alg, &secrets, &ranges))
+            });
+
+            let proof = prove(&transcript, alg, &secrets, &ranges);
+            println!(
+                "commitment/proof_size/{id}: {} bytes",
+                bincode::serialize(&proof).unwrap().len()
+            );
+
+            group.bench_function(BenchmarkId::new("verify", &id), |b| {
+                b.iter(|| {
+                    proof
+                        .clone()
+                        .verify_with_provider(&provider, &transcript.length(), &commitments)
+                        .unwrap()
+                })
+            });
+        }
+    }
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
+
+/// Returns `count` disjoint ranges spread over the transcript.
+fn ranges(count: usize) -> Vec<RangeSet<usize>> {
+    let step = TRANSCRIPT_LEN / count;
+    (0..count)
+        .map(|i| RangeSet::from(i * step..i * step + step / 2))
+        .collect()
+}
+
+fn commit(
+    provider: &HashProvider,
+    transcript: &Transcript,
+    alg: HashAlgId,
+    ranges: &[RangeSet<usize>],
+) -> (Vec<TranscriptCommitment>, Vec<TranscriptSecret>) {
+    let mut rng = StdRng::seed_from_u64(0);
+    let hasher = provider.get(&alg).unwrap();
+
+    ranges
+        .iter()
+        .map(|idx| {
+            let blinder = rng.random();
+            let data = transcript
+                .get(Direction::Received, idx)
+                .unwrap()
+                .data()
+                .to_vec();
+
+            let commitment = PlaintextHash {
+                direction: Direction::Received,
+                idx: idx.clone(),
+                hash: hash_plaintext(hasher, &data, &blinder),
+            };
+            let secret = PlaintextHashSecret {
+                direction: Direction::Received,
+                idx: idx.clone(),
+                alg,
+                blinder,
+            };
+
+            (
+                TranscriptCommitment::Hash(commitment),
+                TranscriptSecret::Hash(secret),
+            )
+        })
+        .unzip()
+}
+
+fn prove(
+    transcript: &Transcript,
+    alg: HashAlgId,
+    secrets: &[TranscriptSecret],
+    ranges: &[RangeSet<usize>],
+) -> TranscriptProof {
+    let mut builder = TranscriptProofBuilder::new(transcript, secrets);
+    builder.commitment_kinds(&[TranscriptCommitmentKind::Hash { alg }]);
+    for idx in ranges {
+        builder.reveal_recv(idx).unwrap();
+    }
+
+    builder.build().unwrap()
+}
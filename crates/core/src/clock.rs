@@ -0,0 +1,43 @@
+//! Time sources.
+//!
+//! The prover and verifier read the time of the TLS handshake from the
+//! [`Clock`] of their configuration, which defaults to the system time.
+
+use std::{fmt::Debug, sync::Arc};
+
+/// A source of the current time.
+pub trait Clock: Debug + Send + Sync {
+    /// Returns the current UNIX timestamp in seconds.
+    fn now(&self) -> u64;
+}
+
+impl<C: Clock + ?Sized> Clock for Arc<C> {
+    fn now(&self) -> u64 {
+        (**self).now()
+    }
+}
+
+/// Clock backed by the system time.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        web_time::UNIX_EPOCH
+            .elapsed()
+            .expect("system time is available")
+            .as_secs()
+    }
+}
+
+/// Clock which always returns the same time.
+///
+/// This is useful for making tests deterministic.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedClock(pub u64);
+
+impl Clock for FixedClock {
+    fn now(&self) -> u64 {
+        self.0
+    }
+}
@@ -2,6 +2,7 @@
 
 pub mod prove;
 pub mod prover;
+pub mod session;
 pub mod tls;
 pub mod tls_commit;
 pub mod verifier;
@@ -6,7 +6,9 @@ use rangeset::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::transcript::{Direction, Transcript, TranscriptCommitConfig, TranscriptCommitRequest};
+use crate::transcript::{
+    Direction, TranscriptCommitConfig, TranscriptCommitRequest, TranscriptStorage,
+};
 
 /// Configuration to prove information to the verifier.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,7 +20,7 @@ pub struct ProveConfig {
 
 impl ProveConfig {
     /// Creates a new builder.
-    pub fn builder(transcript: &Transcript) -> ProveConfigBuilder<'_> {
+    pub fn builder(transcript: &dyn TranscriptStorage) -> ProveConfigBuilder<'_> {
         ProveConfigBuilder::new(transcript)
     }
 
@@ -54,7 +56,7 @@ impl ProveConfig {
 /// Builder for [`ProveConfig`].
 #[derive(Debug)]
 pub struct ProveConfigBuilder<'a> {
-    transcript: &'a Transcript,
+    transcript: &'a dyn TranscriptStorage,
     server_identity: bool,
     reveal: Option<(RangeSet<usize>, RangeSet<usize>)>,
     transcript_commit: Option<TranscriptCommitConfig>,
@@ -62,7 +64,7 @@ pub struct ProveConfigBuilder<'a> {
 
 impl<'a> ProveConfigBuilder<'a> {
     /// Creates a new builder.
-    pub fn new(transcript: &'a Transcript) -> Self {
+    pub fn new(transcript: &'a dyn TranscriptStorage) -> Self {
         Self {
             transcript,
             server_identity: false,
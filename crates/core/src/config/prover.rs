@@ -1,26 +1,149 @@
 //! Prover configuration.
 
+use std::sync::Arc;
+
 use serde::{Deserialize, Serialize};
 
+use crate::clock::{Clock, SystemClock};
+
+// Default capacity of the IO buffers, 16MiB.
+const DEFAULT_BUFFER_SIZE: usize = 16 * 1024 * 1024;
+
 /// Prover configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ProverConfig {}
+#[serde(try_from = "unchecked::ProverConfigUnchecked")]
+pub struct ProverConfig {
+    /// Capacity of the buffer for plaintext between the application and the
+    /// prover.
+    app_buffer_size: usize,
+    /// Capacity of the buffer for TLS data between the prover and the server.
+    tls_buffer_size: usize,
+    /// Source of the time of the TLS handshake.
+    #[serde(skip, default = "default_clock")]
+    clock: Arc<dyn Clock>,
+    /// Whether the prover runs without a verifier.
+    #[serde(skip)]
+    insecure_local_backend: bool,
+}
+
+fn default_clock() -> Arc<dyn Clock> {
+    Arc::new(SystemClock)
+}
+
+fn validate(config: ProverConfig) -> Result<ProverConfig, ProverConfigError> {
+    if config.app_buffer_size == 0 {
+        return Err(ErrorRepr::InvalidValue {
+            name: "app_buffer_size",
+            reason: "must be > 0".to_string(),
+        }
+        .into());
+    }
+
+    if config.tls_buffer_size == 0 {
+        return Err(ErrorRepr::InvalidValue {
+            name: "tls_buffer_size",
+            reason: "must be > 0".to_string(),
+        }
+        .into());
+    }
+
+    Ok(config)
+}
 
 impl ProverConfig {
     /// Creates a new builder.
     pub fn builder() -> ProverConfigBuilder {
         ProverConfigBuilder::default()
     }
+
+    /// Returns the capacity of the buffer for plaintext between the
+    /// application and the prover.
+    pub fn app_buffer_size(&self) -> usize {
+        self.app_buffer_size
+    }
+
+    /// Returns the capacity of the buffer for TLS data between the prover and
+    /// the server.
+    pub fn tls_buffer_size(&self) -> usize {
+        self.tls_buffer_size
+    }
+
+    /// Returns the clock which timestamps the TLS handshake.
+    pub fn clock(&self) -> &Arc<dyn Clock> {
+        &self.clock
+    }
+
+    /// Returns a configuration for running the prover with the insecure
+    /// local backend.
+    ///
+    /// The TLS connection is run by the prover alone with a plain RustCrypto
+    /// backend instead of MPC-TLS, without a verifier. Nothing about the
+    /// connection is attested, so this is only meant for integration tests and
+    /// dry runs. In exchange the backend supports TLS 1.3 and the
+    /// ChaCha20-Poly1305 suites, which MPC-TLS does not.
+    ///
+    /// This setting is never serialized, a deserialized configuration always
+    /// uses MPC-TLS.
+    #[cfg(feature = "insecure-local-backend")]
+    pub fn insecure_local_backend() -> Self {
+        Self {
+            insecure_local_backend: true,
+            ..Self::builder().build().expect("default config is valid")
+        }
+    }
+
+    /// Returns `true` if the prover runs with the insecure local backend, which
+    /// is enabled by the `insecure-local-backend` feature.
+    pub fn is_insecure_local_backend(&self) -> bool {
+        self.insecure_local_backend
+    }
 }
 
 /// Builder for [`ProverConfig`].
 #[derive(Debug, Default)]
-pub struct ProverConfigBuilder {}
+pub struct ProverConfigBuilder {
+    app_buffer_size: Option<usize>,
+    tls_buffer_size: Option<usize>,
+    clock: Option<Arc<dyn Clock>>,
+}
 
 impl ProverConfigBuilder {
+    /// Sets the capacity of the buffer for plaintext between the application
+    /// and the prover.
+    ///
+    /// Defaults to 16MiB.
+    pub fn app_buffer_size(mut self, app_buffer_size: usize) -> Self {
+        self.app_buffer_size = Some(app_buffer_size);
+        self
+    }
+
+    /// Sets the capacity of the buffer for TLS data between the prover and the
+    /// server.
+    ///
+    /// Defaults to 16MiB.
+    pub fn tls_buffer_size(mut self, tls_buffer_size: usize) -> Self {
+        self.tls_buffer_size = Some(tls_buffer_size);
+        self
+    }
+
+    /// Sets the clock which timestamps the TLS handshake.
+    ///
+    /// The time is also used to check the validity of the server certificate,
+    /// and must be within a few seconds of the verifier's clock. Defaults to
+    /// the system time.
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Some(Arc::new(clock));
+        self
+    }
+
     /// Builds the configuration.
     pub fn build(self) -> Result<ProverConfig, ProverConfigError> {
-        Ok(ProverConfig {})
+        validate(ProverConfig {
+            app_buffer_size: self.app_buffer_size.unwrap_or(DEFAULT_BUFFER_SIZE),
+            tls_buffer_size: self.tls_buffer_size.unwrap_or(DEFAULT_BUFFER_SIZE),
+            clock: self.clock.unwrap_or_else(default_clock),
+            insecure_local_backend: false,
+        })
     }
 }
 
@@ -30,4 +153,52 @@ impl ProverConfigBuilder {
 pub struct ProverConfigError(#[from] ErrorRepr);
 
 #[derive(Debug, thiserror::Error)]
-enum ErrorRepr {}
+enum ErrorRepr {
+    #[error("invalid value for field({name}): {reason}")]
+    InvalidValue { name: &'static str, reason: String },
+}
+
+mod unchecked {
+    use super::*;
+
+    #[derive(Deserialize)]
+    pub(super) struct ProverConfigUnchecked {
+        app_buffer_size: usize,
+        tls_buffer_size: usize,
+    }
+
+    impl TryFrom<ProverConfigUnchecked> for ProverConfig {
+        type Error = ProverConfigError;
+
+        fn try_from(value: ProverConfigUnchecked) -> Result<Self, Self::Error> {
+            validate(ProverConfig {
+                app_buffer_size: value.app_buffer_size,
+                tls_buffer_size: value.tls_buffer_size,
+                clock: default_clock(),
+                insecure_local_backend: false,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prover_config_deserialize() {
+        let config = ProverConfig::builder()
+            .app_buffer_size(1024)
+            .build()
+            .unwrap();
+
+        let config: ProverConfig =
+            bincode::deserialize(&bincode::serialize(&config).unwrap()).unwrap();
+        assert_eq!(config.app_buffer_size(), 1024);
+        assert_eq!(config.tls_buffer_size(), DEFAULT_BUFFER_SIZE);
+
+        // A buffer size of 0 is rejected like in the builder.
+        let bytes = bincode::serialize(&(0usize, DEFAULT_BUFFER_SIZE)).unwrap();
+        assert!(bincode::deserialize::<ProverConfig>(&bytes).is_err());
+    }
+}
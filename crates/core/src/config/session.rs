@@ -0,0 +1,187 @@
+//! Session configuration.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Session configuration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionConfig {
+    /// Keep-alive configuration, disabled if `None`.
+    keep_alive: Option<KeepAlive>,
+    /// Whether the messages of the session are recorded.
+    #[serde(default)]
+    record: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct KeepAlive {
+    interval: Duration,
+    peer_timeout: Duration,
+}
+
+impl SessionConfig {
+    /// Creates a new builder.
+    pub fn builder() -> SessionConfigBuilder {
+        SessionConfigBuilder::default()
+    }
+
+    /// Returns the interval at which keep-alive pings are sent, if keep-alive
+    /// is enabled.
+    pub fn keep_alive_interval(&self) -> Option<Duration> {
+        self.keep_alive.map(|keep_alive| keep_alive.interval)
+    }
+
+    /// Returns the duration without any data from the peer after which it is
+    /// considered disconnected, if keep-alive is enabled.
+    pub fn peer_timeout(&self) -> Option<Duration> {
+        self.keep_alive.map(|keep_alive| keep_alive.peer_timeout)
+    }
+
+    /// Returns `true` if the messages of the session are recorded.
+    pub fn record(&self) -> bool {
+        self.record
+    }
+}
+
+/// Builder for [`SessionConfig`].
+#[derive(Debug, Default)]
+pub struct SessionConfigBuilder {
+    keep_alive_interval: Option<Duration>,
+    peer_timeout: Option<Duration>,
+    record: bool,
+}
+
+impl SessionConfigBuilder {
+    /// Enables keep-alive pings at the given interval.
+    ///
+    /// Pings keep connections through NATs and proxies alive during long
+    /// sessions, and allow detecting a dead peer. Both parties must enable
+    /// keep-alive, otherwise an idle peer is considered disconnected.
+    /// Disabled by default.
+    pub fn keep_alive_interval(mut self, interval: Duration) -> Self {
+        self.keep_alive_interval = Some(interval);
+        self
+    }
+
+    /// Sets the duration without any data from the peer after which it is
+    /// considered disconnected.
+    ///
+    /// Requires keep-alive to be enabled. Defaults to 3 times the keep-alive
+    /// interval.
+    pub fn peer_timeout(mut self, timeout: Duration) -> Self {
+        self.peer_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets whether the data read and written on each stream of the session
+    /// is recorded, for debugging failed runs.
+    ///
+    /// The recording is kept in memory for the lifetime of the session.
+    /// Disabled by default.
+    pub fn record(mut self, record: bool) -> Self {
+        self.record = record;
+        self
+    }
+
+    /// Builds the configuration.
+    pub fn build(self) -> Result<SessionConfig, SessionConfigError> {
+        let record = self.record;
+        let Some(interval) = self.keep_alive_interval else {
+            if self.peer_timeout.is_some() {
+                return Err(ErrorRepr::InvalidValue {
+                    name: "peer_timeout",
+                    reason: "requires keep_alive_interval to be set".to_string(),
+                }
+                .into());
+            }
+
+            return Ok(SessionConfig {
+                keep_alive: None,
+                record,
+            });
+        };
+
+        if interval.is_zero() {
+            return Err(ErrorRepr::InvalidValue {
+                name: "keep_alive_interval",
+                reason: "must be > 0".to_string(),
+            }
+            .into());
+        }
+
+        let peer_timeout = self.peer_timeout.unwrap_or(3 * interval);
+        if peer_timeout <= interval {
+            return Err(ErrorRepr::InvalidValue {
+                name: "peer_timeout",
+                reason: format!("must be > keep_alive_interval ({interval:?})"),
+            }
+            .into());
+        }
+
+        Ok(SessionConfig {
+            keep_alive: Some(KeepAlive {
+                interval,
+                peer_timeout,
+            }),
+            record,
+        })
+    }
+}
+
+/// Error for [`SessionConfig`].
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct SessionConfigError(#[from] ErrorRepr);
+
+#[derive(Debug, thiserror::Error)]
+enum ErrorRepr {
+    #[error("invalid value for field({name}): {reason}")]
+    InvalidValue { name: &'static str, reason: String },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_config_keep_alive() {
+        let config = SessionConfig::default();
+        assert_eq!(config.keep_alive_interval(), None);
+        assert_eq!(config.peer_timeout(), None);
+
+        let config = SessionConfig::builder()
+            .keep_alive_interval(Duration::from_secs(10))
+            .build()
+            .unwrap();
+        assert_eq!(config.keep_alive_interval(), Some(Duration::from_secs(10)));
+        assert_eq!(config.peer_timeout(), Some(Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn test_session_config_record() {
+        assert!(!SessionConfig::default().record());
+        assert!(SessionConfig::builder()
+            .record(true)
+            .build()
+            .unwrap()
+            .record());
+    }
+
+    #[test]
+    fn test_session_config_invalid() {
+        assert!(SessionConfig::builder()
+            .peer_timeout(Duration::from_secs(10))
+            .build()
+            .is_err());
+        assert!(SessionConfig::builder()
+            .keep_alive_interval(Duration::ZERO)
+            .build()
+            .is_err());
+        assert!(SessionConfig::builder()
+            .keep_alive_interval(Duration::from_secs(10))
+            .peer_timeout(Duration::from_secs(5))
+            .build()
+            .is_err());
+    }
+}
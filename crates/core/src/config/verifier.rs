@@ -1,13 +1,29 @@
 //! Verifier configuration.
 
+use std::sync::Arc;
+
 use serde::{Deserialize, Serialize};
 
-use crate::webpki::RootCertStore;
+use crate::{
+    clock::{Clock, SystemClock},
+    webpki::RootCertStore,
+};
 
 /// Verifier configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VerifierConfig {
     root_store: RootCertStore,
+    #[serde(default)]
+    max_sent_data: Option<usize>,
+    #[serde(default)]
+    max_recv_data: Option<usize>,
+    /// Source of the time of the TLS handshake.
+    #[serde(skip, default = "default_clock")]
+    clock: Arc<dyn Clock>,
+}
+
+fn default_clock() -> Arc<dyn Clock> {
+    Arc::new(SystemClock)
 }
 
 impl VerifierConfig {
@@ -20,12 +36,32 @@ impl VerifierConfig {
     pub fn root_store(&self) -> &RootCertStore {
         &self.root_store
     }
+
+    /// Returns the maximum number of bytes the prover may request to send, if
+    /// limited.
+    pub fn max_sent_data(&self) -> Option<usize> {
+        self.max_sent_data
+    }
+
+    /// Returns the maximum number of bytes the prover may request to receive,
+    /// if limited.
+    pub fn max_recv_data(&self) -> Option<usize> {
+        self.max_recv_data
+    }
+
+    /// Returns the clock which bounds the time of the TLS handshake.
+    pub fn clock(&self) -> &Arc<dyn Clock> {
+        &self.clock
+    }
 }
 
 /// Builder for [`VerifierConfig`].
 #[derive(Debug, Default)]
 pub struct VerifierConfigBuilder {
     root_store: Option<RootCertStore>,
+    max_sent_data: Option<usize>,
+    max_recv_data: Option<usize>,
+    clock: Option<Arc<dyn Clock>>,
 }
 
 impl VerifierConfigBuilder {
@@ -35,12 +71,47 @@ impl VerifierConfigBuilder {
         self
     }
 
+    /// Sets the maximum number of bytes the prover may request to send.
+    ///
+    /// Commitment requests exceeding this limit are rejected before any
+    /// resources are allocated for them.
+    pub fn max_sent_data(mut self, max_sent_data: usize) -> Self {
+        self.max_sent_data = Some(max_sent_data);
+        self
+    }
+
+    /// Sets the maximum number of bytes the prover may request to receive.
+    ///
+    /// Commitment requests exceeding this limit are rejected before any
+    /// resources are allocated for them.
+    pub fn max_recv_data(mut self, max_recv_data: usize) -> Self {
+        self.max_recv_data = Some(max_recv_data);
+        self
+    }
+
+    /// Sets the clock which bounds the time of the TLS handshake.
+    ///
+    /// The handshake is timestamped by the prover, and that time is also used
+    /// to check the validity of the server certificate. The verifier only
+    /// checks that the prover's time is within a few seconds of its own
+    /// clock. Defaults to the system time.
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Some(Arc::new(clock));
+        self
+    }
+
     /// Builds the configuration.
     pub fn build(self) -> Result<VerifierConfig, VerifierConfigError> {
         let root_store = self
             .root_store
             .ok_or(ErrorRepr::MissingField { name: "root_store" })?;
-        Ok(VerifierConfig { root_store })
+
+        Ok(VerifierConfig {
+            root_store,
+            max_sent_data: self.max_sent_data,
+            max_recv_data: self.max_recv_data,
+            clock: self.clock.unwrap_or_else(default_clock),
+        })
     }
 }
 
@@ -114,6 +114,21 @@ impl TryFrom<String> for DnsName {
 pub enum KeyType {
     /// secp256r1.
     SECP256R1 = 0x0017,
+    /// X25519.
+    X25519 = 0x001d,
+    /// X25519MLKEM768, a post-quantum hybrid which is only used in TLS 1.3.
+    X25519MLKEM768 = 0x11ec,
+}
+
+impl KeyType {
+    /// Returns the TLS named group of the key type.
+    fn named_group(&self) -> NamedGroup {
+        match self {
+            KeyType::SECP256R1 => NamedGroup::secp256r1,
+            KeyType::X25519 => NamedGroup::X25519,
+            KeyType::X25519MLKEM768 => NamedGroup::X25519MLKEM768,
+        }
+    }
 }
 
 /// Signature algorithm used on the key exchange parameters.
@@ -211,12 +226,8 @@ pub struct ServerEphemKey {
 impl ServerEphemKey {
     /// Encodes the key exchange parameters as in TLS.
     pub(crate) fn kx_params(&self) -> Vec<u8> {
-        let group = match self.typ {
-            KeyType::SECP256R1 => NamedGroup::secp256r1,
-        };
-
         let mut kx_params = Vec::new();
-        ServerECDHParams::new(group, &self.key).encode(&mut kx_params);
+        ServerECDHParams::new(self.typ.named_group(), &self.key).encode(&mut kx_params);
 
         kx_params
     }
@@ -226,12 +237,15 @@ impl TryFrom<tls_core::key::PublicKey> for ServerEphemKey {
     type Error = &'static str;
 
     fn try_from(value: tls_core::key::PublicKey) -> Result<Self, Self::Error> {
-        let tls_core::msgs::enums::NamedGroup::secp256r1 = value.group else {
-            return Err("unsupported key type");
+        let typ = match value.group {
+            NamedGroup::secp256r1 => KeyType::SECP256R1,
+            NamedGroup::X25519 => KeyType::X25519,
+            NamedGroup::X25519MLKEM768 => KeyType::X25519MLKEM768,
+            _ => return Err("unsupported key type"),
         };
 
         Ok(ServerEphemKey {
-            typ: KeyType::SECP256R1,
+            typ,
             key: value.key,
         })
     }
@@ -246,6 +260,9 @@ pub struct ConnectionInfo {
     pub version: TlsVersion,
     /// Transcript length.
     pub transcript_length: TranscriptLength,
+    /// Whether the server did not end the connection with `close_notify`, see
+    /// [`TlsTranscript::is_truncated`](crate::transcript::TlsTranscript::is_truncated).
+    pub truncated: bool,
 }
 
 /// Transcript length information.
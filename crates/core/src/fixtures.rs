@@ -31,6 +31,7 @@ impl ConnectionFixture {
                 time: 1671637529,
                 version: TlsVersion::V1_2,
                 transcript_length,
+                truncated: false,
             },
             server_cert_data: HandshakeData {
                 certs: vec![
@@ -76,6 +77,7 @@ impl ConnectionFixture {
                 time: 1671637529,
                 version: TlsVersion::V1_2,
                 transcript_length,
+                truncated: false,
             },
             server_cert_data: HandshakeData {
                 certs: vec![
@@ -33,14 +33,28 @@ pub fn transcript_fixture(sent: &[u8], recv: &[u8]) -> TlsTranscript {
     TranscriptGenerator::new(KEY, IV).generate(sent, recv)
 }
 
+/// Creates a transcript fixture for testing, in which a zero-length
+/// application data record precedes every record carrying data, as sent by
+/// some servers as padding.
+pub fn transcript_fixture_with_empty_records(sent: &[u8], recv: &[u8]) -> TlsTranscript {
+    let mut generator = TranscriptGenerator::new(KEY, IV);
+    generator.empty_records = true;
+    generator.generate(sent, recv)
+}
+
 struct TranscriptGenerator {
     key: [u8; 16],
     iv: [u8; 4],
+    empty_records: bool,
 }
 
 impl TranscriptGenerator {
     fn new(key: [u8; 16], iv: [u8; 4]) -> Self {
-        Self { key, iv }
+        Self {
+            key,
+            iv,
+            empty_records: false,
+        }
     }
 
     fn generate(&self, sent: &[u8], recv: &[u8]) -> TlsTranscript {
@@ -88,9 +102,15 @@ impl TranscriptGenerator {
         let handshake = self.gen_handshake(vd);
         records.push(handshake);
 
-        for (seq, msg) in (1_u64..).zip(plaintext.chunks(RECORD_SIZE)) {
-            let record = self.gen_app_data(seq, msg);
-            records.push(record);
+        let mut seq = 1_u64;
+        for msg in plaintext.chunks(RECORD_SIZE) {
+            if self.empty_records {
+                records.push(self.gen_app_data(seq, &[]));
+                seq += 1;
+            }
+
+            records.push(self.gen_app_data(seq, msg));
+            seq += 1;
         }
 
         records
@@ -4,6 +4,7 @@ use std::{collections::HashMap, fmt::Display};
 
 use rand::{distr::StandardUniform, prelude::Distribution};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use subtle::{Choice, ConstantTimeEq};
 
 /// Maximum length of a hash value.
 const MAX_LEN: usize = 64;
@@ -104,7 +105,10 @@ pub struct TypedHash {
 }
 
 /// A hash value.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+///
+/// Equality is checked in constant time, as hash values may be compared
+/// against commitments to secret data.
+#[derive(Debug, Clone, Copy)]
 pub struct Hash {
     // To avoid heap allocation, we use a fixed-size array.
     // 64 bytes should be sufficient for most hash algorithms.
@@ -112,6 +116,27 @@ pub struct Hash {
     len: usize,
 }
 
+impl ConstantTimeEq for Hash {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        // The length of a hash is public.
+        self.as_bytes().ct_eq(other.as_bytes())
+    }
+}
+
+impl PartialEq for Hash {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for Hash {}
+
+impl std::hash::Hash for Hash {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::hash::Hash::hash(self.as_bytes(), state);
+    }
+}
+
 impl Default for Hash {
     fn default() -> Self {
         Self {
@@ -236,10 +261,20 @@ pub trait HashAlgorithm {
 
     /// Computes the hash of the provided data with a prefix.
     fn hash_prefixed(&self, prefix: &[u8], data: &[u8]) -> Hash;
+
+    /// Computes the hash of the concatenation of the provided chunks.
+    ///
+    /// The default implementation copies the chunks into a buffer,
+    /// implementations should override it to hash them incrementally.
+    fn hash_chunks(&self, chunks: &[&[u8]]) -> Hash {
+        self.hash(&chunks.concat())
+    }
 }
 
 /// A hash blinder.
-#[derive(Clone, Serialize, Deserialize)]
+///
+/// The blinder is zeroized when dropped.
+#[derive(Clone, Serialize, Deserialize, zeroize::Zeroize, zeroize::ZeroizeOnDrop)]
 pub struct Blinder([u8; 16]);
 
 opaque_debug::implement!(Blinder);
@@ -305,6 +340,14 @@ mod sha2 {
             hasher.update(data);
             super::Hash::new(hasher.finalize().as_ref())
         }
+
+        fn hash_chunks(&self, chunks: &[&[u8]]) -> super::Hash {
+            let mut hasher = ::sha2::Sha256::default();
+            for chunk in chunks {
+                hasher.update(chunk);
+            }
+            super::Hash::new(hasher.finalize().as_ref())
+        }
     }
 }
 
@@ -331,6 +374,14 @@ mod blake3 {
             hasher.update(data);
             super::Hash::new(hasher.finalize().as_bytes())
         }
+
+        fn hash_chunks(&self, chunks: &[&[u8]]) -> super::Hash {
+            let mut hasher = ::blake3::Hasher::new();
+            for chunk in chunks {
+                hasher.update(chunk);
+            }
+            super::Hash::new(hasher.finalize().as_bytes())
+        }
     }
 }
 
@@ -364,7 +415,53 @@ mod keccak {
             hasher.finalize(&mut output);
             super::Hash::new(&output)
         }
+
+        fn hash_chunks(&self, chunks: &[&[u8]]) -> super::Hash {
+            let mut hasher = tiny_keccak::Keccak::v256();
+            for chunk in chunks {
+                hasher.update(chunk);
+            }
+            let mut output = vec![0; 32];
+            hasher.finalize(&mut output);
+            super::Hash::new(&output)
+        }
     }
 }
 
 pub use keccak::Keccak256;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_eq() {
+        let a = Hash::new(&[1; 32]);
+        let mut b = [1; 32];
+
+        assert_eq!(a, Hash::new(&b));
+
+        b[31] = 0;
+        assert_ne!(a, Hash::new(&b));
+        // Equal prefixes of different lengths are not equal.
+        assert_ne!(a, Hash::new(&[1; 31]));
+        assert_ne!(a, Hash::new(&[1; 33]));
+    }
+
+    #[test]
+    fn test_hash_chunks() {
+        let provider = HashProvider::default();
+        for alg in [HashAlgId::SHA256, HashAlgId::BLAKE3, HashAlgId::KECCAK256] {
+            let hasher = provider.get(&alg).unwrap();
+
+            assert_eq!(
+                hasher.hash_chunks(&[b"hello".as_slice(), b"", b" world"]),
+                hasher.hash(b"hello world")
+            );
+            assert_eq!(
+                hasher.hash_chunks(&[b"hello".as_slice(), b" world"]),
+                hasher.hash_prefixed(b"hello", b" world")
+            );
+        }
+    }
+}
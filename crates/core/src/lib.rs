@@ -4,6 +4,7 @@
 #![deny(clippy::all)]
 #![forbid(unsafe_code)]
 
+pub mod clock;
 pub mod connection;
 #[cfg(any(test, feature = "fixtures"))]
 pub mod fixtures;
@@ -143,6 +143,174 @@ impl MerkleTree {
     }
 }
 
+/// Incremental Merkle tree.
+///
+/// Leaves are appended one at a time. The roots of the perfect subtrees
+/// covering the leaves are cached, so the root of the tree can be computed at
+/// any point without rehashing the leaves.
+///
+/// The tree has the same shape as a [`MerkleTree`] with the same leaves, so
+/// both produce the same root and proofs.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct IncrementalMerkleTree {
+    alg: HashAlgId,
+    leaves: Vec<Hash>,
+    /// Roots of the perfect subtrees, indexed by height.
+    frontier: Vec<Option<Hash>>,
+}
+
+opaque_debug::implement!(IncrementalMerkleTree);
+
+impl IncrementalMerkleTree {
+    /// Creates a new empty tree.
+    pub fn new(alg: HashAlgId) -> Self {
+        Self {
+            alg,
+            leaves: Vec::new(),
+            frontier: Vec::new(),
+        }
+    }
+
+    /// Returns the hash algorithm used to create the tree.
+    pub fn algorithm(&self) -> HashAlgId {
+        self.alg
+    }
+
+    /// Returns the number of leaves.
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    /// Returns `true` if the tree has no leaves.
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    /// Returns the leaves.
+    pub fn leaves(&self) -> &[Hash] {
+        &self.leaves
+    }
+
+    /// Appends a leaf to the tree, returning its index.
+    ///
+    /// # Panics
+    ///
+    /// - If the provided hasher is not the same as the one used to create the
+    ///   tree.
+    pub fn append(&mut self, hasher: &dyn HashAlgorithm, leaf: Hash) -> usize {
+        assert_eq!(self.alg, hasher.id(), "hash algorithm mismatch");
+
+        push_frontier(hasher, &mut self.frontier, leaf);
+        self.leaves.push(leaf);
+
+        self.leaves.len() - 1
+    }
+
+    /// Returns the root of the tree, or `None` if the tree is empty.
+    ///
+    /// # Panics
+    ///
+    /// - If the provided hasher is not the same as the one used to create the
+    ///   tree.
+    pub fn root(&self, hasher: &dyn HashAlgorithm) -> Option<TypedHash> {
+        assert_eq!(self.alg, hasher.id(), "hash algorithm mismatch");
+
+        fold_frontier(hasher, &self.frontier).map(|value| TypedHash {
+            alg: self.alg,
+            value,
+        })
+    }
+
+    /// Returns the root of the tree as it was when it had `len` leaves, or
+    /// `None` if `len` is zero.
+    ///
+    /// # Panics
+    ///
+    /// - If the provided hasher is not the same as the one used to create the
+    ///   tree.
+    /// - If `len` is greater than the number of leaves.
+    pub fn root_at(&self, hasher: &dyn HashAlgorithm, len: usize) -> Option<TypedHash> {
+        assert_eq!(self.alg, hasher.id(), "hash algorithm mismatch");
+        assert!(len <= self.leaves.len(), "tree has fewer leaves than {len}");
+
+        let mut frontier = Vec::new();
+        for leaf in &self.leaves[..len] {
+            push_frontier(hasher, &mut frontier, *leaf);
+        }
+
+        fold_frontier(hasher, &frontier).map(|value| TypedHash {
+            alg: self.alg,
+            value,
+        })
+    }
+
+    /// Returns a Merkle proof for the provided indices against the root of the
+    /// tree as it was when it had `len` leaves, see [`root_at`](Self::root_at).
+    ///
+    /// # Panics
+    ///
+    /// - If the provided hasher is not the same as the one used to create the
+    ///   tree.
+    /// - If `len` is greater than the number of leaves.
+    /// - If the provided indices are not unique and sorted.
+    /// - If the provided indices are out of bounds.
+    pub fn proof_at(
+        &self,
+        hasher: &dyn HashAlgorithm,
+        indices: &[usize],
+        len: usize,
+    ) -> MerkleProof {
+        assert!(len <= self.leaves.len(), "tree has fewer leaves than {len}");
+
+        let mut tree = MerkleTree::new(self.alg);
+        tree.insert(hasher, self.leaves[..len].to_vec());
+        tree.proof(indices)
+    }
+
+    /// Returns a Merkle proof for the provided indices against the current
+    /// root.
+    ///
+    /// # Panics
+    ///
+    /// See [`proof_at`](Self::proof_at).
+    pub fn proof(&self, hasher: &dyn HashAlgorithm, indices: &[usize]) -> MerkleProof {
+        self.proof_at(hasher, indices, self.leaves.len())
+    }
+}
+
+/// Adds a leaf to a frontier of perfect subtree roots.
+fn push_frontier(hasher: &dyn HashAlgorithm, frontier: &mut Vec<Option<Hash>>, leaf: Hash) {
+    let mut node = leaf;
+    for slot in frontier.iter_mut() {
+        match slot.take() {
+            Some(left) => node = hash_pair(hasher, &left, &node),
+            None => {
+                *slot = Some(node);
+                return;
+            }
+        }
+    }
+    frontier.push(Some(node));
+}
+
+/// Computes the root from a frontier of perfect subtree roots.
+///
+/// Subtrees are combined from the lowest up, which matches promoting the
+/// unpaired node of a level to the next level.
+fn fold_frontier(hasher: &dyn HashAlgorithm, frontier: &[Option<Hash>]) -> Option<Hash> {
+    frontier.iter().flatten().fold(None, |acc, node| match acc {
+        Some(right) => Some(hash_pair(hasher, node, &right)),
+        None => Some(*node),
+    })
+}
+
+fn hash_pair(hasher: &dyn HashAlgorithm, left: &Hash, right: &Hash) -> Hash {
+    let mut data = Vec::with_capacity(left.as_bytes().len() + right.as_bytes().len());
+    data.extend_from_slice(left.as_bytes());
+    data.extend_from_slice(right.as_bytes());
+    hasher.hash(&data)
+}
+
 #[cfg(test)]
 mod test {
     use crate::hash::{Blake3, Keccak256, Sha256};
@@ -337,4 +505,70 @@ mod test {
             .verify(&hasher, &tree.root(), choose_leaves([2, 3], &leaves))
             .is_err());
     }
+
+    #[rstest]
+    #[case::sha2(Sha256::default())]
+    #[case::blake3(Blake3::default())]
+    #[case::keccak(Keccak256::default())]
+    fn test_incremental_root<H: HashAlgorithm>(#[case] hasher: H) {
+        let leaves = leaves(&hasher, (0..17).map(T));
+        let mut incremental = IncrementalMerkleTree::new(hasher.id());
+
+        assert!(incremental.root(&hasher).is_none());
+
+        for (i, leaf) in leaves.iter().enumerate() {
+            assert_eq!(incremental.append(&hasher, *leaf), i);
+
+            let mut tree = MerkleTree::new(hasher.id());
+            tree.insert(&hasher, leaves[..=i].to_vec());
+
+            assert_eq!(incremental.root(&hasher), Some(tree.root()));
+        }
+
+        for len in 1..=leaves.len() {
+            let mut tree = MerkleTree::new(hasher.id());
+            tree.insert(&hasher, leaves[..len].to_vec());
+
+            assert_eq!(incremental.root_at(&hasher, len), Some(tree.root()));
+        }
+    }
+
+    #[rstest]
+    #[case::sha2(Sha256::default())]
+    #[case::blake3(Blake3::default())]
+    #[case::keccak(Keccak256::default())]
+    fn test_incremental_proof_at<H: HashAlgorithm>(#[case] hasher: H) {
+        let leaves = leaves(&hasher, (0..9).map(T));
+        let mut tree = IncrementalMerkleTree::new(hasher.id());
+        for leaf in &leaves[..5] {
+            tree.append(&hasher, *leaf);
+        }
+
+        let early_root = tree.root(&hasher).unwrap();
+
+        for leaf in &leaves[5..] {
+            tree.append(&hasher, *leaf);
+        }
+
+        let proof = tree.proof_at(&hasher, &[1, 4], 5);
+        assert!(proof
+            .verify(&hasher, &early_root, choose_leaves([1, 4], &leaves))
+            .is_ok());
+        assert!(proof
+            .verify(
+                &hasher,
+                &tree.root(&hasher).unwrap(),
+                choose_leaves([1, 4], &leaves)
+            )
+            .is_err());
+
+        let proof = tree.proof(&hasher, &[1, 8]);
+        assert!(proof
+            .verify(
+                &hasher,
+                &tree.root(&hasher).unwrap(),
+                choose_leaves([1, 8], &leaves)
+            )
+            .is_ok());
+    }
 }
@@ -17,10 +17,18 @@
 //! transcript to a Verifier in the form of a [`PartialTranscript`]. A Verifier
 //! always learns the length of the transcript, but sensitive data can be
 //! withheld.
+//!
+//! ## Storage
+//!
+//! A [`Transcript`] keeps the plaintext in memory. Where the plaintext lives
+//! can be controlled with a [`TranscriptStorage`], see [`FileStorage`] and
+//! [`EncryptedStorage`].
 
 mod commit;
 pub mod hash;
+mod index;
 mod proof;
+mod storage;
 mod tls;
 
 use std::{fmt, ops::Range};
@@ -35,17 +43,23 @@ use serde::{Deserialize, Serialize};
 use crate::connection::TranscriptLength;
 
 pub use commit::{
-    TranscriptCommitConfig, TranscriptCommitConfigBuilder, TranscriptCommitConfigBuilderError,
-    TranscriptCommitRequest, TranscriptCommitment, TranscriptCommitmentKind, TranscriptSecret,
+    CommitmentProfile, TranscriptCommitConfig, TranscriptCommitConfigBuilder,
+    TranscriptCommitConfigBuilderError, TranscriptCommitRequest, TranscriptCommitment,
+    TranscriptCommitmentKind, TranscriptSecret,
 };
+pub use index::{TranscriptIdx, TranscriptIdxError};
 pub use proof::{
-    TranscriptProof, TranscriptProofBuilder, TranscriptProofBuilderError, TranscriptProofError,
+    CommittedLength, TranscriptProof, TranscriptProofBuilder, TranscriptProofBuilderError,
+    TranscriptProofError, TranscriptProofV0, TRANSCRIPT_PROOF_VERSION,
 };
-pub use tls::{ContentType, Record, TlsTranscript};
+pub use storage::{EncryptedStorage, FileStorage, TranscriptStorage, TranscriptStorageError};
+pub use tls::{ContentType, HandshakeMessage, Record, RecordBoundary, TlsTranscript};
 
 /// A transcript contains the plaintext of all application data communicated
 /// between the Prover and the Server.
-#[derive(Clone, Serialize, Deserialize)]
+///
+/// The plaintext is zeroized when the transcript is dropped.
+#[derive(Clone, Serialize, Deserialize, zeroize::Zeroize, zeroize::ZeroizeOnDrop)]
 pub struct Transcript {
     /// Data sent from the Prover to the Server.
     sent: Vec<u8>,
@@ -343,6 +357,57 @@ impl PartialTranscript {
         authed.iter_values().map(move |i| data[i])
     }
 
+    /// Returns the data in the given range if all of it has been
+    /// authenticated.
+    ///
+    /// # Arguments
+    ///
+    /// * `direction` - The direction of the data.
+    /// * `range` - The range of the data.
+    pub fn get(&self, direction: Direction, range: Range<usize>) -> Option<&[u8]> {
+        let (data, authed) = match direction {
+            Direction::Sent => (&self.sent, &self.sent_authed_idx),
+            Direction::Received => (&self.received, &self.received_authed_idx),
+        };
+
+        if range.start > range.end || range.end > data.len() {
+            return None;
+        }
+
+        if range.is_empty() || RangeSet::from(range.clone()).is_subset(authed) {
+            Some(&data[range])
+        } else {
+            None
+        }
+    }
+
+    /// Returns an iterator over the authenticated segments of the transcript,
+    /// in ascending order.
+    pub fn authed_segments(
+        &self,
+        direction: Direction,
+    ) -> impl Iterator<Item = (Range<usize>, &[u8])> + '_ {
+        let (data, authed) = match direction {
+            Direction::Sent => (&self.sent, &self.sent_authed_idx),
+            Direction::Received => (&self.received, &self.received_authed_idx),
+        };
+
+        authed
+            .iter()
+            .map(move |range| (range.clone(), &data[range]))
+    }
+
+    /// Returns a displayable view of the transcript in which every byte which
+    /// hasn't been authenticated is rendered as [`REDACTION_MARKER`].
+    ///
+    /// Authenticated data is rendered as UTF-8, replacing invalid sequences.
+    pub fn display(&self, direction: Direction) -> RedactedDisplay<'_> {
+        RedactedDisplay {
+            transcript: self,
+            direction,
+        }
+    }
+
     /// Unions the authenticated data of this transcript with another.
     ///
     /// # Panics
@@ -431,6 +496,43 @@ impl PartialTranscript {
     }
 }
 
+/// Marker used to render redacted bytes of a [`PartialTranscript`].
+pub const REDACTION_MARKER: char = '█';
+
+/// Displays a [`PartialTranscript`] with redacted bytes replaced by
+/// [`REDACTION_MARKER`].
+///
+/// Created by [`PartialTranscript::display`].
+#[derive(Debug, Clone, Copy)]
+pub struct RedactedDisplay<'a> {
+    transcript: &'a PartialTranscript,
+    direction: Direction,
+}
+
+impl fmt::Display for RedactedDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let len = match self.direction {
+            Direction::Sent => self.transcript.len_sent(),
+            Direction::Received => self.transcript.len_received(),
+        };
+
+        let mut pos = 0;
+        for (range, data) in self.transcript.authed_segments(self.direction) {
+            for _ in pos..range.start {
+                write!(f, "{REDACTION_MARKER}")?;
+            }
+            write!(f, "{}", String::from_utf8_lossy(data))?;
+            pos = range.end;
+        }
+
+        for _ in pos..len {
+            write!(f, "{REDACTION_MARKER}")?;
+        }
+
+        Ok(())
+    }
+}
+
 /// The direction of data communicated over a TLS connection.
 ///
 /// This is used to differentiate between data sent from the Prover to the TLS
@@ -684,6 +786,15 @@ mod tests {
         assert_eq!(partial_transcript, deserialized_transcript);
     }
 
+    #[rstest]
+    fn test_transcript_zeroize(mut transcript: Transcript) {
+        use zeroize::Zeroize;
+
+        transcript.zeroize();
+
+        assert_eq!(transcript.len(), (0, 0));
+    }
+
     #[rstest]
     fn test_transcript_to_partial_success(transcript: Transcript) {
         let partial = transcript.to_partial(RangeSet::from(0..2), RangeSet::from(3..7));
@@ -707,6 +818,49 @@ mod tests {
         assert!(!partial.contains(Direction::Received, &RangeSet::from([4..6, 7..13])))
     }
 
+    #[rstest]
+    fn test_partial_transcript_get(partial_transcript: PartialTranscript) {
+        assert_eq!(
+            partial_transcript.get(Direction::Sent, 1..4),
+            Some([1, 2, 3].as_slice())
+        );
+        assert_eq!(
+            partial_transcript.get(Direction::Received, 7..9),
+            Some([7, 8].as_slice())
+        );
+        assert_eq!(partial_transcript.get(Direction::Sent, 3..7), None);
+        assert_eq!(partial_transcript.get(Direction::Received, 10..13), None);
+        #[allow(clippy::reversed_empty_ranges)]
+        let reversed = 3..1;
+        assert_eq!(partial_transcript.get(Direction::Sent, reversed), None);
+    }
+
+    #[rstest]
+    fn test_partial_transcript_authed_segments(partial_transcript: PartialTranscript) {
+        let segments: Vec<_> = partial_transcript
+            .authed_segments(Direction::Received)
+            .collect();
+        assert_eq!(
+            segments,
+            vec![(2..5, [2, 3, 4].as_slice()), (7..10, [7, 8, 9].as_slice())]
+        );
+    }
+
+    #[test]
+    fn test_partial_transcript_display() {
+        let transcript = Transcript::new(b"GET /secret HTTP/1.1", b"HTTP/1.1 200 OK");
+        let partial = transcript.to_partial(RangeSet::from([0..5, 11..20]), RangeSet::from(0..12));
+
+        assert_eq!(
+            partial.display(Direction::Sent).to_string(),
+            "GET /██████ HTTP/1.1"
+        );
+        assert_eq!(
+            partial.display(Direction::Received).to_string(),
+            "HTTP/1.1 200███"
+        );
+    }
+
     #[rstest]
     fn test_partial_transcript_unauthed(transcript: Transcript) {
         let partial = transcript.to_partial(RangeSet::from(0..2), RangeSet::from(3..7));
@@ -2,14 +2,19 @@
 
 use std::{collections::HashSet, fmt};
 
-use rangeset::iter::{FromRangeIterator, IntoRangeIterator};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use rangeset::{
+    iter::{FromRangeIterator, IntoRangeIterator},
+    ops::Set,
+};
 use serde::{Deserialize, Serialize};
 
 use crate::{
     hash::HashAlgId,
     transcript::{
         hash::{PlaintextHash, PlaintextHashSecret},
-        Direction, RangeSet, Transcript,
+        Direction, RangeSet, TranscriptStorage,
     },
 };
 
@@ -24,6 +29,23 @@ pub enum TranscriptCommitmentKind {
     },
 }
 
+impl TranscriptCommitmentKind {
+    /// Returns the recommended commitment kind for the given profile.
+    ///
+    /// See the `commitment` benchmark for the measurements this is based on.
+    pub fn recommended(profile: CommitmentProfile) -> Self {
+        let alg = match profile {
+            // Keccak-256 is natively supported by the EVM.
+            CommitmentProfile::OnChain => HashAlgId::KECCAK256,
+            // BLAKE3 has the smallest circuit of the supported hashes, and all
+            // of them produce proofs of the same size.
+            CommitmentProfile::ProverTime | CommitmentProfile::ProofSize => HashAlgId::BLAKE3,
+        };
+
+        Self::Hash { alg }
+    }
+}
+
 impl fmt::Display for TranscriptCommitmentKind {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -32,6 +54,18 @@ impl fmt::Display for TranscriptCommitmentKind {
     }
 }
 
+/// Target to optimize for when selecting a [`TranscriptCommitmentKind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CommitmentProfile {
+    /// Commitments are verified on-chain.
+    OnChain,
+    /// Minimal prover time.
+    ProverTime,
+    /// Minimal proof size.
+    ProofSize,
+}
+
 /// Transcript commitment.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[non_exhaustive]
@@ -52,11 +86,14 @@ pub enum TranscriptSecret {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TranscriptCommitConfig {
     commits: Vec<((Direction, RangeSet<usize>), TranscriptCommitmentKind)>,
+    /// Seed of the blinders, never serialized.
+    #[serde(skip)]
+    blinder_seed: Option<[u8; 32]>,
 }
 
 impl TranscriptCommitConfig {
     /// Creates a new commit config builder.
-    pub fn builder(transcript: &Transcript) -> TranscriptCommitConfigBuilder<'_> {
+    pub fn builder(transcript: &dyn TranscriptStorage) -> TranscriptCommitConfigBuilder<'_> {
         TranscriptCommitConfigBuilder::new(transcript)
     }
 
@@ -74,6 +111,18 @@ impl TranscriptCommitConfig {
         })
     }
 
+    /// Returns the RNG which generates the blinders of the hash commitments.
+    ///
+    /// The RNG is seeded from the thread RNG, unless a seed was set with
+    /// [`TranscriptCommitConfigBuilder::blinder_seed`]. The blinders are
+    /// generated in the order of [`iter_hash`](Self::iter_hash).
+    pub fn blinder_rng(&self) -> ChaCha20Rng {
+        match self.blinder_seed {
+            Some(seed) => ChaCha20Rng::from_seed(seed),
+            None => ChaCha20Rng::from_rng(&mut rand::rng()),
+        }
+    }
+
     /// Returns a request for the transcript commitments.
     pub fn to_request(&self) -> TranscriptCommitRequest {
         TranscriptCommitRequest {
@@ -88,19 +137,25 @@ impl TranscriptCommitConfig {
 /// A builder for [`TranscriptCommitConfig`].
 #[derive(Debug)]
 pub struct TranscriptCommitConfigBuilder<'a> {
-    transcript: &'a Transcript,
+    transcript: &'a dyn TranscriptStorage,
     default_kind: TranscriptCommitmentKind,
+    prune_redundant: bool,
+    decoys: usize,
+    blinder_seed: Option<[u8; 32]>,
     commits: HashSet<((Direction, RangeSet<usize>), TranscriptCommitmentKind)>,
 }
 
 impl<'a> TranscriptCommitConfigBuilder<'a> {
     /// Creates a new commit config builder.
-    pub fn new(transcript: &'a Transcript) -> Self {
+    pub fn new(transcript: &'a dyn TranscriptStorage) -> Self {
         Self {
             transcript,
             default_kind: TranscriptCommitmentKind::Hash {
                 alg: HashAlgId::BLAKE3,
             },
+            prune_redundant: false,
+            decoys: 0,
+            blinder_seed: None,
             commits: HashSet::default(),
         }
     }
@@ -111,6 +166,45 @@ impl<'a> TranscriptCommitConfigBuilder<'a> {
         self
     }
 
+    /// Sets whether redundant commitments should be pruned, defaults to
+    /// `false`.
+    ///
+    /// A commitment is redundant if its ranges are exactly covered by other
+    /// commitments of the same kind which are nested within it, for example a
+    /// commitment to an HTTP body alongside commitments to each of its
+    /// fields. Any reveal of such a commitment can be opened with the nested
+    /// commitments instead, so pruning it avoids hashing the same plaintext
+    /// twice at the cost of a proof containing more openings.
+    pub fn prune_redundant(&mut self, prune_redundant: bool) -> &mut Self {
+        self.prune_redundant = prune_redundant;
+        self
+    }
+
+    /// Sets the number of decoy commitments to add for each commitment,
+    /// defaults to `0`.
+    ///
+    /// The decoys of a commitment are commitments of the same kind to
+    /// sub-ranges of it, so they only commit to data which is revealed
+    /// whenever the commitment itself is revealed. They can be opened to pad
+    /// a proof to a fixed number of openings, see
+    /// [`TranscriptProofBuilder::pad_openings`](crate::transcript::TranscriptProofBuilder::pad_openings).
+    /// Commitments which are too short to split get fewer decoys.
+    pub fn decoys(&mut self, count: usize) -> &mut Self {
+        self.decoys = count;
+        self
+    }
+
+    /// Sets the seed of the RNG which generates the blinders, see
+    /// [`TranscriptCommitConfig::blinder_rng`].
+    ///
+    /// This is for deterministic tests only. The blinders hide the committed
+    /// data, so anyone who knows the seed can brute-force low entropy data
+    /// from the commitments. The seed is never serialized.
+    pub fn blinder_seed(&mut self, seed: [u8; 32]) -> &mut Self {
+        self.blinder_seed = Some(seed);
+        self
+    }
+
     /// Adds a commitment.
     ///
     /// # Arguments
@@ -133,6 +227,15 @@ impl<'a> TranscriptCommitConfigBuilder<'a> {
         direction: Direction,
         kind: TranscriptCommitmentKind,
     ) -> Result<&mut Self, TranscriptCommitConfigBuilderError> {
+        // A commitment to no data can never be opened to reveal anything, the
+        // smallest commitment covers a single byte.
+        if idx.is_empty() {
+            return Err(TranscriptCommitConfigBuilderError::new(
+                ErrorKind::Index,
+                format!("commitment range must not be empty ({direction})"),
+            ));
+        }
+
         if idx.end().unwrap_or(0) > self.transcript.len_of_direction(direction) {
             return Err(TranscriptCommitConfigBuilderError::new(
                 ErrorKind::Index,
@@ -200,14 +303,176 @@ impl<'a> TranscriptCommitConfigBuilder<'a> {
         )
     }
 
+    /// Adds commitments with the default kind to all of the transcript except
+    /// the given ranges.
+    ///
+    /// A separate commitment is added for each contiguous range which is not
+    /// excluded, so that each of them can be revealed independently.
+    ///
+    /// # Arguments
+    ///
+    /// * `direction` - The direction of the transcript.
+    /// * `exclude` - The ranges to exclude.
+    pub fn commit_all_except(
+        &mut self,
+        direction: Direction,
+        exclude: impl IntoRangeIterator<usize>,
+    ) -> Result<&mut Self, TranscriptCommitConfigBuilderError> {
+        let exclude = RangeSet::from_range_iter(exclude);
+        let len = self.transcript.len_of_direction(direction);
+
+        if exclude.end().unwrap_or(0) > len {
+            return Err(TranscriptCommitConfigBuilderError::new(
+                ErrorKind::Index,
+                format!(
+                    "range is out of bounds of the transcript ({}): {} > {}",
+                    direction,
+                    exclude.end().unwrap_or(0),
+                    len
+                ),
+            ));
+        }
+
+        let include = (0..len).difference(&exclude).into_set();
+        for range in include.iter() {
+            self.commit_with_kind_inner(RangeSet::from(range), direction, self.default_kind)?;
+        }
+
+        Ok(self)
+    }
+
+    /// Adds commitments with the default kind to all of the transcript in
+    /// fixed size blocks.
+    ///
+    /// A separate commitment is added for each block, the last block may be
+    /// shorter. Any range which is aligned to block boundaries can later be
+    /// revealed by composing the commitments of the blocks it spans. This
+    /// bounds the number of commitments to `len / block_size` while keeping
+    /// the choice of revealed ranges open.
+    ///
+    /// # Arguments
+    ///
+    /// * `direction` - The direction of the transcript.
+    /// * `block_size` - The size of each block in bytes.
+    pub fn commit_blocks(
+        &mut self,
+        direction: Direction,
+        block_size: usize,
+    ) -> Result<&mut Self, TranscriptCommitConfigBuilderError> {
+        if block_size == 0 {
+            return Err(TranscriptCommitConfigBuilderError::new(
+                ErrorKind::BlockSize,
+                "block size must be greater than zero",
+            ));
+        }
+
+        let len = self.transcript.len_of_direction(direction);
+        for start in (0..len).step_by(block_size) {
+            let end = (start + block_size).min(len);
+            self.commit_with_kind_inner(RangeSet::from(start..end), direction, self.default_kind)?;
+        }
+
+        Ok(self)
+    }
+
     /// Builds the configuration.
     pub fn build(self) -> Result<TranscriptCommitConfig, TranscriptCommitConfigBuilderError> {
+        let mut commits: Vec<_> = if self.prune_redundant {
+            self.commits
+                .iter()
+                .filter(|commit| !is_redundant(commit, &self.commits))
+                .cloned()
+                .collect()
+        } else {
+            Vec::from_iter(self.commits)
+        };
+
+        if self.decoys > 0 {
+            let mut decoys = HashSet::new();
+            for ((direction, idx), kind) in &commits {
+                for decoy in split(idx, self.decoys + 1).into_iter().take(self.decoys) {
+                    decoys.insert(((*direction, decoy), *kind));
+                }
+            }
+            commits.retain(|commit| !decoys.contains(commit));
+            commits.extend(decoys);
+        }
+
+        // The commitments are sorted so that the blinders are assigned in the
+        // same order for the same configuration.
+        commits.sort_by_key(|((direction, idx), kind)| {
+            let TranscriptCommitmentKind::Hash { alg } = kind;
+            (
+                *direction == Direction::Received,
+                idx.iter()
+                    .map(|range| (range.start, range.end))
+                    .collect::<Vec<_>>(),
+                alg.as_u8(),
+            )
+        });
+
         Ok(TranscriptCommitConfig {
-            commits: Vec::from_iter(self.commits),
+            commits,
+            blinder_seed: self.blinder_seed,
         })
     }
 }
 
+/// Returns `true` if the commitment is exactly covered by the other
+/// commitments of the same direction and kind which are nested within it.
+///
+/// Nested commitments are strict subsets, so a pruned commitment is always
+/// covered by commitments which are themselves either kept or covered.
+fn is_redundant(
+    commit: &((Direction, RangeSet<usize>), TranscriptCommitmentKind),
+    commits: &HashSet<((Direction, RangeSet<usize>), TranscriptCommitmentKind)>,
+) -> bool {
+    let ((direction, idx), kind) = commit;
+
+    let mut covered = RangeSet::default();
+    for ((other_direction, other_idx), other_kind) in commits {
+        if other_direction == direction
+            && other_kind == kind
+            && other_idx != idx
+            && other_idx.is_subset(idx)
+        {
+            covered.union_mut(other_idx);
+        }
+    }
+
+    &covered == idx
+}
+
+/// Splits the index into at most `parts` non-empty pieces of about the same
+/// length, in order.
+fn split(idx: &RangeSet<usize>, parts: usize) -> Vec<RangeSet<usize>> {
+    let len = idx.len();
+
+    let mut pieces = Vec::with_capacity(parts);
+    for part in 0..parts {
+        let (start, end) = (part * len / parts, (part + 1) * len / parts);
+        if start == end {
+            continue;
+        }
+
+        // Maps the positions within the index to positions in the transcript.
+        let mut piece = RangeSet::default();
+        let mut offset = 0;
+        for range in idx.iter() {
+            let (lo, hi) = (start.max(offset), end.min(offset + range.len()));
+            if lo < hi {
+                piece.union_mut(&RangeSet::from(
+                    range.start + lo - offset..range.start + hi - offset,
+                ));
+            }
+            offset += range.len();
+        }
+        pieces.push(piece);
+    }
+
+    pieces
+}
+
 /// Error for [`TranscriptCommitConfigBuilder`].
 #[derive(Debug, thiserror::Error)]
 pub struct TranscriptCommitConfigBuilderError {
@@ -230,12 +495,14 @@ impl TranscriptCommitConfigBuilderError {
 #[derive(Debug)]
 enum ErrorKind {
     Index,
+    BlockSize,
 }
 
 impl fmt::Display for TranscriptCommitConfigBuilderError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self.kind {
             ErrorKind::Index => f.write_str("index error")?,
+            ErrorKind::BlockSize => f.write_str("block size error")?,
         }
 
         if let Some(source) = &self.source {
@@ -267,6 +534,7 @@ impl TranscriptCommitRequest {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::transcript::Transcript;
 
     #[test]
     fn test_range_out_of_bounds() {
@@ -278,5 +546,223 @@ mod tests {
 
         assert!(builder.commit_sent(&(10..15)).is_err());
         assert!(builder.commit_recv(&(10..15)).is_err());
+        assert!(builder
+            .commit_all_except(Direction::Sent, &(10..15))
+            .is_err());
+    }
+
+    #[test]
+    fn test_blinder_seed() {
+        use crate::hash::Blinder;
+        use rand::Rng;
+
+        let transcript = Transcript::new([0; 32], [0; 32]);
+        let build = |seed: Option<[u8; 32]>| {
+            let mut builder = TranscriptCommitConfigBuilder::new(&transcript);
+            if let Some(seed) = seed {
+                builder.blinder_seed(seed);
+            }
+            builder
+                .commit_sent(&(0..8))
+                .unwrap()
+                .commit_sent(&(4..16))
+                .unwrap()
+                .commit_recv(&(0..32))
+                .unwrap();
+            builder.build().unwrap()
+        };
+        let blinders = |config: &TranscriptCommitConfig| {
+            let mut rng = config.blinder_rng();
+            config
+                .iter_hash()
+                .map(|(idx, _)| (idx.clone(), rng.random::<Blinder>().as_bytes().to_vec()))
+                .collect::<Vec<_>>()
+        };
+
+        let a = build(Some([1; 32]));
+        let b = build(Some([1; 32]));
+        assert_eq!(blinders(&a), blinders(&b));
+        assert_ne!(blinders(&a), blinders(&build(Some([2; 32]))));
+        assert_ne!(blinders(&build(None)), blinders(&build(None)));
+
+        // The seed is not serialized.
+        let a: TranscriptCommitConfig =
+            bincode::deserialize(&bincode::serialize(&a).unwrap()).unwrap();
+        assert!(a.blinder_seed.is_none());
+    }
+
+    #[test]
+    fn test_empty_commitment() {
+        let transcript = Transcript::new([0; 12], [0; 12]);
+        let mut builder = TranscriptCommitConfigBuilder::new(&transcript);
+
+        assert!(builder.commit_sent(&(4..4)).is_err());
+        assert!(builder.commit_recv(&RangeSet::default()).is_err());
+
+        // A single byte is the smallest commitment.
+        builder.commit_sent(&(0..1)).unwrap();
+        assert_eq!(builder.build().unwrap().iter_hash().count(), 1);
+    }
+
+    #[test]
+    fn test_recommended() {
+        assert_eq!(
+            TranscriptCommitmentKind::recommended(CommitmentProfile::OnChain),
+            TranscriptCommitmentKind::Hash {
+                alg: HashAlgId::KECCAK256
+            }
+        );
+        assert_eq!(
+            TranscriptCommitmentKind::recommended(CommitmentProfile::ProverTime),
+            TranscriptCommitmentKind::Hash {
+                alg: HashAlgId::BLAKE3
+            }
+        );
+    }
+
+    #[test]
+    fn test_prune_redundant() {
+        let transcript = Transcript::new([0; 12], [0; 12]);
+        let mut builder = TranscriptCommitConfigBuilder::new(&transcript);
+
+        builder
+            .prune_redundant(true)
+            .commit_recv(&(0..12))
+            .unwrap()
+            .commit_recv(&(0..4))
+            .unwrap()
+            .commit_recv(&(4..12))
+            .unwrap()
+            .commit_recv(&(4..8))
+            .unwrap()
+            .commit_sent(&(0..12))
+            .unwrap()
+            .commit_sent(&(0..6))
+            .unwrap();
+
+        let config = builder.build().unwrap();
+
+        let recv = config
+            .iter_hash()
+            .filter(|((direction, _), _)| *direction == Direction::Received)
+            .map(|((_, idx), _)| idx.clone())
+            .collect::<HashSet<_>>();
+
+        // The full commitment is covered by `0..4` and `4..12`, but `4..12` is
+        // only partially covered by `4..8` and is kept.
+        assert_eq!(
+            recv,
+            HashSet::from([
+                RangeSet::from(0..4),
+                RangeSet::from(4..8),
+                RangeSet::from(4..12)
+            ])
+        );
+        assert_eq!(
+            config
+                .iter_hash()
+                .filter(|((direction, _), _)| *direction == Direction::Sent)
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_decoys() {
+        let transcript = Transcript::new([0; 12], [0; 12]);
+        let mut builder = TranscriptCommitConfigBuilder::new(&transcript);
+
+        builder
+            .decoys(2)
+            .commit_recv(&(0..12))
+            .unwrap()
+            .commit_sent(&RangeSet::from([0..2, 5..8]))
+            .unwrap()
+            .commit_sent(&(10..11))
+            .unwrap();
+
+        let config = builder.build().unwrap();
+
+        let idxs = |dir: Direction| {
+            config
+                .iter_hash()
+                .filter(|((direction, _), _)| *direction == dir)
+                .map(|((_, idx), _)| idx.clone())
+                .collect::<HashSet<_>>()
+        };
+
+        // The decoys are the first two thirds of each commitment, a single
+        // byte can not be split.
+        assert_eq!(
+            idxs(Direction::Received),
+            HashSet::from([
+                RangeSet::from(0..12),
+                RangeSet::from(0..4),
+                RangeSet::from(4..8)
+            ])
+        );
+        assert_eq!(
+            idxs(Direction::Sent),
+            HashSet::from([
+                RangeSet::from([0..2, 5..8]),
+                RangeSet::from(0..1),
+                RangeSet::from([1..2, 5..6]),
+                RangeSet::from(10..11)
+            ])
+        );
+    }
+
+    #[test]
+    fn test_commit_all_except() {
+        let transcript = Transcript::new([0; 12], [0; 12]);
+        let mut builder = TranscriptCommitConfigBuilder::new(&transcript);
+
+        builder
+            .commit_all_except(Direction::Sent, &RangeSet::from([0..2, 5..8]))
+            .unwrap();
+        builder
+            .commit_all_except(Direction::Received, &(0..12))
+            .unwrap();
+
+        let config = builder.build().unwrap();
+        let mut idxs = config
+            .iter_hash()
+            .map(|((direction, idx), _)| (*direction, idx.clone()))
+            .collect::<Vec<_>>();
+        idxs.sort_by_key(|(_, idx)| idx.end());
+
+        assert_eq!(
+            idxs,
+            vec![
+                (Direction::Sent, RangeSet::from(2..5)),
+                (Direction::Sent, RangeSet::from(8..12)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_commit_blocks() {
+        let transcript = Transcript::new([0; 40], [0; 12]);
+        let mut builder = TranscriptCommitConfigBuilder::new(&transcript);
+
+        assert!(builder.commit_blocks(Direction::Sent, 0).is_err());
+
+        builder.commit_blocks(Direction::Sent, 16).unwrap();
+
+        let config = builder.build().unwrap();
+        let mut idxs = config
+            .iter_hash()
+            .map(|((direction, idx), _)| (*direction, idx.clone()))
+            .collect::<Vec<_>>();
+        idxs.sort_by_key(|(_, idx)| idx.end());
+
+        assert_eq!(
+            idxs,
+            vec![
+                (Direction::Sent, RangeSet::from(0..16)),
+                (Direction::Sent, RangeSet::from(16..32)),
+                (Direction::Sent, RangeSet::from(32..40)),
+            ]
+        );
     }
 }
@@ -0,0 +1,404 @@
+//! Direction-qualified transcript indices.
+//!
+//! A [`TranscriptIdx`] is a set of byte positions in one direction of a
+//! transcript. It upholds the following invariants:
+//!
+//! - The ranges are sorted, disjoint and non-adjacent, as in any [`RangeSet`].
+//! - Positions are relative to the start of the direction, i.e. the first
+//!   received byte is at position `0`, not at the length of the sent data.
+//! - Operations on two indices are only defined for the same direction.
+//!
+//! Some applications address a transcript as a single sequence, with the sent
+//! data followed by the received data. [`TranscriptIdx::to_combined`] and
+//! [`TranscriptIdx::from_combined`] convert between both representations, so
+//! that off-by-one errors at the boundary between the directions are handled
+//! in one place.
+
+use std::ops::Range;
+
+use rangeset::{
+    iter::{FromRangeIterator, IntoRangeIterator},
+    ops::Set,
+    set::RangeSet,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{connection::TranscriptLength, transcript::Direction};
+
+/// Index of data in one direction of a transcript.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TranscriptIdx {
+    direction: Direction,
+    idx: RangeSet<usize>,
+}
+
+impl TranscriptIdx {
+    /// Creates a new index.
+    ///
+    /// # Arguments
+    ///
+    /// * `direction` - The direction of the transcript.
+    /// * `idx` - The ranges of the index, which may overlap or be unsorted.
+    pub fn new(direction: Direction, idx: impl IntoRangeIterator<usize>) -> Self {
+        Self {
+            direction,
+            idx: RangeSet::from_range_iter(idx),
+        }
+    }
+
+    /// Creates an empty index.
+    pub fn empty(direction: Direction) -> Self {
+        Self {
+            direction,
+            idx: RangeSet::default(),
+        }
+    }
+
+    /// Returns the direction of the index.
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// Returns the ranges of the index.
+    pub fn idx(&self) -> &RangeSet<usize> {
+        &self.idx
+    }
+
+    /// Returns the inner parts of the index.
+    pub fn into_parts(self) -> (Direction, RangeSet<usize>) {
+        (self.direction, self.idx)
+    }
+
+    /// Returns the number of bytes in the index.
+    pub fn len(&self) -> usize {
+        self.idx.len()
+    }
+
+    /// Returns `true` if the index is empty.
+    pub fn is_empty(&self) -> bool {
+        self.idx.is_empty()
+    }
+
+    /// Returns `true` if the index is within the bounds of a transcript with
+    /// the given length.
+    pub fn is_in_bounds(&self, length: &TranscriptLength) -> bool {
+        self.idx.end().unwrap_or(0) <= direction_len(length, self.direction)
+    }
+
+    /// Returns `true` if all positions of the index are also in `other`.
+    ///
+    /// Returns `false` if the directions differ, unless the index is empty.
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.is_empty() || (self.direction == other.direction && self.idx.is_subset(&other.idx))
+    }
+
+    /// Returns the union with another index.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directions differ.
+    pub fn union(&self, other: &Self) -> Result<Self, TranscriptIdxError> {
+        self.check_direction(other)?;
+
+        let mut idx = self.idx.clone();
+        idx.union_mut(&other.idx);
+
+        Ok(Self {
+            direction: self.direction,
+            idx,
+        })
+    }
+
+    /// Returns the positions of this index which are not in `other`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directions differ.
+    pub fn difference(&self, other: &Self) -> Result<Self, TranscriptIdxError> {
+        self.check_direction(other)?;
+
+        Ok(Self {
+            direction: self.direction,
+            idx: self.idx.difference(&other.idx).into_set(),
+        })
+    }
+
+    /// Translates the index forward by `offset` bytes.
+    ///
+    /// This is used to map an index relative to a slice of the transcript,
+    /// e.g. a parsed message, to an index relative to the whole direction.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a position overflows.
+    pub fn shift_right(&self, offset: usize) -> Result<Self, TranscriptIdxError> {
+        self.map_ranges(|range| {
+            Some(range.start.checked_add(offset)?..range.end.checked_add(offset)?)
+        })
+    }
+
+    /// Translates the index backward by `offset` bytes.
+    ///
+    /// This is the inverse of [`shift_right`](Self::shift_right).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a position is less than `offset`.
+    pub fn shift_left(&self, offset: usize) -> Result<Self, TranscriptIdxError> {
+        self.map_ranges(|range| {
+            Some(range.start.checked_sub(offset)?..range.end.checked_sub(offset)?)
+        })
+    }
+
+    /// Converts the index to an index of the combined transcript, in which the
+    /// received data follows the sent data.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the index is out of bounds.
+    pub fn to_combined(
+        &self,
+        length: &TranscriptLength,
+    ) -> Result<RangeSet<usize>, TranscriptIdxError> {
+        if !self.is_in_bounds(length) {
+            return Err(TranscriptIdxError("index is out of bounds"));
+        }
+
+        match self.direction {
+            Direction::Sent => Ok(self.idx.clone()),
+            Direction::Received => self
+                .shift_right(length.sent as usize)
+                .map(|idx| idx.into_parts().1),
+        }
+    }
+
+    /// Splits an index of the combined transcript, in which the received data
+    /// follows the sent data, into the sent and received indices.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the index is out of bounds of the combined
+    /// transcript.
+    pub fn from_combined(
+        idx: &RangeSet<usize>,
+        length: &TranscriptLength,
+    ) -> Result<(Self, Self), TranscriptIdxError> {
+        let sent_len = length.sent as usize;
+        let total_len = sent_len + length.received as usize;
+
+        if idx.end().unwrap_or(0) > total_len {
+            return Err(TranscriptIdxError("index is out of bounds"));
+        }
+
+        let sent = Self {
+            direction: Direction::Sent,
+            idx: idx.difference(&(sent_len..total_len)).into_set(),
+        };
+        let received = Self {
+            direction: Direction::Received,
+            idx: idx.difference(&(0..sent_len)).into_set(),
+        }
+        .shift_left(sent_len)?;
+
+        Ok((sent, received))
+    }
+
+    fn check_direction(&self, other: &Self) -> Result<(), TranscriptIdxError> {
+        if self.direction != other.direction {
+            return Err(TranscriptIdxError("directions do not match"));
+        }
+
+        Ok(())
+    }
+
+    fn map_ranges(
+        &self,
+        f: impl Fn(Range<usize>) -> Option<Range<usize>>,
+    ) -> Result<Self, TranscriptIdxError> {
+        let ranges = self
+            .idx
+            .iter()
+            .map(f)
+            .collect::<Option<Vec<_>>>()
+            .ok_or(TranscriptIdxError("position is out of range"))?;
+
+        Ok(Self {
+            direction: self.direction,
+            idx: RangeSet::from_range_iter(ranges.into_iter()),
+        })
+    }
+}
+
+impl From<(Direction, Range<usize>)> for TranscriptIdx {
+    fn from((direction, range): (Direction, Range<usize>)) -> Self {
+        Self::new(direction, &range)
+    }
+}
+
+impl From<(Direction, RangeSet<usize>)> for TranscriptIdx {
+    fn from((direction, idx): (Direction, RangeSet<usize>)) -> Self {
+        Self { direction, idx }
+    }
+}
+
+impl From<TranscriptIdx> for (Direction, RangeSet<usize>) {
+    fn from(idx: TranscriptIdx) -> Self {
+        idx.into_parts()
+    }
+}
+
+/// Invalid transcript index error.
+#[derive(Debug, thiserror::Error)]
+#[error("invalid transcript index: {0}")]
+pub struct TranscriptIdxError(&'static str);
+
+fn direction_len(length: &TranscriptLength, direction: Direction) -> usize {
+    match direction {
+        Direction::Sent => length.sent as usize,
+        Direction::Received => length.received as usize,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    const LENGTH: TranscriptLength = TranscriptLength {
+        sent: 64,
+        received: 96,
+    };
+
+    /// Returns a random index within `0..len`.
+    fn random_idx(rng: &mut StdRng, len: usize) -> RangeSet<usize> {
+        let count = rng.random_range(0..6);
+        RangeSet::from_range_iter((0..count).map(|_| {
+            let start = rng.random_range(0..len);
+            let end = rng.random_range(start + 1..=len);
+            start..end
+        }))
+    }
+
+    /// Returns the positions of the index.
+    fn positions(idx: &RangeSet<usize>) -> BTreeSet<usize> {
+        idx.iter().flatten().collect()
+    }
+
+    #[test]
+    fn test_boundaries() {
+        let length = TranscriptLength {
+            sent: 4,
+            received: 4,
+        };
+
+        // The last sent byte and the first received byte are adjacent in the
+        // combined transcript, but not merged.
+        let (sent, received) = TranscriptIdx::from_combined(&(3..5).into(), &length).unwrap();
+        assert_eq!(sent, TranscriptIdx::from((Direction::Sent, 3..4)));
+        assert_eq!(received, TranscriptIdx::from((Direction::Received, 0..1)));
+
+        assert!(TranscriptIdx::from((Direction::Received, 0..4)).is_in_bounds(&length));
+        assert!(!TranscriptIdx::from((Direction::Received, 0..5)).is_in_bounds(&length));
+        assert!(TranscriptIdx::from_combined(&(0..9).into(), &length).is_err());
+
+        let empty = TranscriptIdx::from((Direction::Sent, 2..2));
+        assert!(empty.is_empty());
+        assert!(empty.is_subset(&TranscriptIdx::empty(Direction::Received)));
+    }
+
+    #[test]
+    fn test_direction_mismatch() {
+        let sent = TranscriptIdx::from((Direction::Sent, 0..4));
+        let received = TranscriptIdx::from((Direction::Received, 0..4));
+
+        assert!(sent.union(&received).is_err());
+        assert!(sent.difference(&received).is_err());
+        assert!(!sent.is_subset(&received));
+    }
+
+    #[test]
+    fn test_shift_out_of_range() {
+        let idx = TranscriptIdx::from((Direction::Sent, 2..4));
+
+        assert!(idx.shift_left(3).is_err());
+        assert!(idx.shift_right(usize::MAX).is_err());
+        assert_eq!(
+            idx.shift_left(2).unwrap(),
+            TranscriptIdx::from((Direction::Sent, 0..2))
+        );
+    }
+
+    #[test]
+    fn test_prop_union_difference() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for _ in 0..1000 {
+            let a = TranscriptIdx::new(Direction::Sent, &random_idx(&mut rng, 64));
+            let b = TranscriptIdx::new(Direction::Sent, &random_idx(&mut rng, 64));
+
+            let union = a.union(&b).unwrap();
+            let difference = a.difference(&b).unwrap();
+
+            // The operations agree with the same operations on sets of
+            // positions.
+            let (a_pos, b_pos) = (positions(a.idx()), positions(b.idx()));
+            assert_eq!(
+                positions(union.idx()),
+                a_pos.union(&b_pos).copied().collect()
+            );
+            assert_eq!(
+                positions(difference.idx()),
+                a_pos.difference(&b_pos).copied().collect()
+            );
+            assert!(a.is_subset(&union) && b.is_subset(&union));
+            assert!(difference.is_subset(&a));
+
+            // Removing and adding back `b` covers both indices.
+            assert_eq!(difference.union(&b).unwrap(), union);
+            assert_eq!(union.len(), difference.len() + b.len());
+        }
+    }
+
+    #[test]
+    fn test_prop_shift() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for _ in 0..1000 {
+            let idx = TranscriptIdx::new(Direction::Received, &random_idx(&mut rng, 64));
+            let offset = rng.random_range(0..64);
+
+            let shifted = idx.shift_right(offset).unwrap();
+            assert_eq!(shifted.len(), idx.len());
+            assert_eq!(
+                positions(shifted.idx()),
+                positions(idx.idx())
+                    .into_iter()
+                    .map(|i| i + offset)
+                    .collect()
+            );
+            assert_eq!(shifted.shift_left(offset).unwrap(), idx);
+        }
+    }
+
+    #[test]
+    fn test_prop_combined() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let total_len = (LENGTH.sent + LENGTH.received) as usize;
+
+        for _ in 0..1000 {
+            let combined = random_idx(&mut rng, total_len);
+
+            let (sent, received) = TranscriptIdx::from_combined(&combined, &LENGTH).unwrap();
+            assert!(sent.is_in_bounds(&LENGTH) && received.is_in_bounds(&LENGTH));
+            assert_eq!(sent.len() + received.len(), combined.len());
+
+            // Converting back yields the same combined index.
+            let mut roundtrip = sent.to_combined(&LENGTH).unwrap();
+            roundtrip.union_mut(&received.to_combined(&LENGTH).unwrap());
+            assert_eq!(roundtrip, combined);
+        }
+    }
+}
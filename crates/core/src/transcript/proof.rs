@@ -4,16 +4,16 @@ use rangeset::{
     iter::{FromRangeIterator, IntoRangeIterator, RangeIterator},
     ops::{Cover, Set},
 };
-use serde::{Deserialize, Serialize};
-use std::{collections::HashSet, fmt};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize};
+use std::{collections::HashSet, fmt, ops::Range};
 
 use crate::{
     connection::TranscriptLength,
     display::FmtRangeSet,
-    hash::{HashAlgId, HashProvider},
+    hash::{Blinder, HashAlgId, HashAlgorithm, HashProvider, TypedHash},
     transcript::{
         commit::{TranscriptCommitment, TranscriptCommitmentKind},
-        hash::{hash_plaintext, PlaintextHash, PlaintextHashSecret},
+        hash::{PlaintextHash, PlaintextHashSecret},
         Direction, PartialTranscript, RangeSet, Transcript, TranscriptSecret,
     },
 };
@@ -32,16 +32,97 @@ const DEFAULT_COMMITMENT_KINDS: &[TranscriptCommitmentKind] = &[
     },
 ];
 
+/// Version of the serialized [`TranscriptProof`] format.
+///
+/// Proofs of other versions fail to deserialize.
+pub const TRANSCRIPT_PROOF_VERSION: u8 = 1;
+
+/// Version of a serialized [`TranscriptProof`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+struct ProofVersion(u8);
+
+impl Default for ProofVersion {
+    fn default() -> Self {
+        Self(TRANSCRIPT_PROOF_VERSION)
+    }
+}
+
+impl<'de> Deserialize<'de> for ProofVersion {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let version = u8::deserialize(deserializer)?;
+        if version != TRANSCRIPT_PROOF_VERSION {
+            return Err(D::Error::custom(format!(
+                "unsupported transcript proof version {version}, this release supports version {TRANSCRIPT_PROOF_VERSION}"
+            )));
+        }
+
+        Ok(Self(version))
+    }
+}
+
 /// Proof of the contents of a transcript.
 #[derive(Clone, Serialize, Deserialize)]
 pub struct TranscriptProof {
+    version: ProofVersion,
     transcript: PartialTranscript,
     hash_secrets: Vec<PlaintextHashSecret>,
+    #[serde(default)]
+    lengths: Vec<CommittedLength>,
 }
 
 opaque_debug::implement!(TranscriptProof);
 
+/// A [`TranscriptProof`] in the layout of earlier releases, which did not
+/// start with a version.
+///
+/// Proofs of that layout fail to deserialize as a [`TranscriptProof`], instead
+/// they can be deserialized as this type and converted.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TranscriptProofV0 {
+    transcript: PartialTranscript,
+    hash_secrets: Vec<PlaintextHashSecret>,
+    #[serde(default)]
+    lengths: Vec<CommittedLength>,
+}
+
+opaque_debug::implement!(TranscriptProofV0);
+
+impl From<TranscriptProofV0> for TranscriptProof {
+    fn from(proof: TranscriptProofV0) -> Self {
+        Self {
+            version: ProofVersion::default(),
+            transcript: proof.transcript,
+            hash_secrets: proof.hash_secrets,
+            lengths: proof.lengths,
+        }
+    }
+}
+
+/// Maximum number of bytes of each revealed range shown by
+/// [`TranscriptProof::describe`].
+const PREVIEW_LEN: usize = 32;
+
 impl TranscriptProof {
+    /// Returns a human-readable summary of the proof.
+    ///
+    /// The summary lists the revealed ranges in each direction along with a
+    /// preview of their contents. The proof is not verified, so the summary
+    /// must not be relied upon for anything other than inspection.
+    pub fn describe(&self) -> String {
+        self.to_string()
+    }
+
+    /// Returns the committed ranges whose lengths are disclosed without their
+    /// contents.
+    ///
+    /// These are checked against the commitments when the proof is verified.
+    pub fn lengths(&self) -> &[CommittedLength] {
+        &self.lengths
+    }
+
     /// Verifies the proof.
     ///
     /// Returns a partial transcript of authenticated data.
@@ -57,6 +138,76 @@ impl TranscriptProof {
         length: &TranscriptLength,
         commitments: impl IntoIterator<Item = &'a TranscriptCommitment>,
     ) -> Result<PartialTranscript, TranscriptProofError> {
+        let hash_commitments = self.check(length, commitments)?;
+        let openings = self.openings(provider)?;
+        self.verify_openings(&openings, &hash_commitments)?;
+
+        Ok(self.transcript)
+    }
+
+    /// Verifies the proof, passing the authenticated data to a sink instead of
+    /// returning it.
+    ///
+    /// All of the openings are verified before any data is passed to the
+    /// sink, so no data of an invalid proof reaches it. The data of each
+    /// opening is then passed along with its direction and range in the
+    /// transcript. Data covered by more than one opening is only passed once.
+    /// This suits consumers which process the authenticated data range by
+    /// range, e.g. to write it out.
+    ///
+    /// This does not reduce memory use: the proof, including all of the
+    /// revealed data, is deserialized before it is verified.
+    ///
+    /// The data is passed in the order of the openings, which is not
+    /// necessarily the order of the transcript. If the sink returns an error,
+    /// no more data is passed to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - The hash provider to use for verification.
+    /// * `length` - The transcript length.
+    /// * `commitments` - The commitments to verify against.
+    /// * `sink` - Called with the authenticated data.
+    pub fn verify_streaming<'a, F, E>(
+        self,
+        provider: &HashProvider,
+        length: &TranscriptLength,
+        commitments: impl IntoIterator<Item = &'a TranscriptCommitment>,
+        mut sink: F,
+    ) -> Result<(), TranscriptProofError>
+    where
+        F: FnMut(Direction, Range<usize>, &[u8]) -> Result<(), E>,
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        let hash_commitments = self.check(length, commitments)?;
+        let openings = self.openings(provider)?;
+        self.verify_openings(&openings, &hash_commitments)?;
+
+        let mut streamed_sent = RangeSet::default();
+        let mut streamed_recv = RangeSet::default();
+        for opening in &openings {
+            let (data, streamed) = match opening.direction {
+                Direction::Sent => (self.transcript.sent_unsafe(), &mut streamed_sent),
+                Direction::Received => (self.transcript.received_unsafe(), &mut streamed_recv),
+            };
+
+            for range in opening.idx.difference(&*streamed).into_set().iter() {
+                sink(opening.direction, range.clone(), &data[range])
+                    .map_err(|e| TranscriptProofError::new(ErrorKind::Sink, e))?;
+            }
+            streamed.union_mut(opening.idx);
+        }
+
+        Ok(())
+    }
+
+    /// Checks the transcript length and the committed lengths of the proof,
+    /// returning the hash commitments.
+    fn check<'a>(
+        &self,
+        length: &TranscriptLength,
+        commitments: impl IntoIterator<Item = &'a TranscriptCommitment>,
+    ) -> Result<HashSet<&'a PlaintextHash>, TranscriptProofError> {
         let mut hash_commitments = HashSet::new();
         // Index commitments.
         for commitment in commitments {
@@ -76,55 +227,108 @@ impl TranscriptProof {
             ));
         }
 
+        for CommittedLength { direction, idx } in &self.lengths {
+            let len = match direction {
+                Direction::Sent => length.sent,
+                Direction::Received => length.received,
+            } as usize;
+
+            if idx.end().unwrap_or(0) > len {
+                return Err(TranscriptProofError::new(
+                    ErrorKind::Length,
+                    "committed length index is out of bounds",
+                ));
+            }
+
+            if !hash_commitments
+                .iter()
+                .any(|hash| hash.direction == *direction && &hash.idx == idx)
+            {
+                return Err(TranscriptProofError::new(
+                    ErrorKind::Length,
+                    "committed length does not match any commitment",
+                ));
+            }
+        }
+
+        Ok(hash_commitments)
+    }
+
+    /// Checks that every opening matches a commitment.
+    fn verify_openings(
+        &self,
+        openings: &[Opening<'_>],
+        hash_commitments: &HashSet<&PlaintextHash>,
+    ) -> Result<(), TranscriptProofError> {
+        // Hashing the openings is independent, so it is done in parallel if
+        // enabled.
+        let transcript = &self.transcript;
+        let hash_opening = |opening: &Opening<'_>| opening.hash(transcript);
+
+        #[cfg(feature = "rayon")]
+        let hashes: Vec<PlaintextHash> = {
+            use rayon::prelude::*;
+            openings.par_iter().map(hash_opening).collect()
+        };
+        #[cfg(not(feature = "rayon"))]
+        let hashes: Vec<PlaintextHash> = openings.iter().map(hash_opening).collect();
+
+        for expected in hashes {
+            if !hash_commitments.contains(&expected) {
+                return Err(TranscriptProofError::new(
+                    ErrorKind::Hash,
+                    "hash opening does not match any commitment",
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the hash openings of the proof.
+    ///
+    /// Checks that the openings are in bounds and that they cover all the
+    /// authenticated data, but not that they match the commitments.
+    fn openings<'a>(
+        &'a self,
+        provider: &'a HashProvider,
+    ) -> Result<Vec<Opening<'a>>, TranscriptProofError> {
+        let mut openings = Vec::with_capacity(self.hash_secrets.len());
         let mut total_auth_sent = RangeSet::default();
         let mut total_auth_recv = RangeSet::default();
-
-        let mut buffer = Vec::new();
         for PlaintextHashSecret {
             direction,
             idx,
             alg,
             blinder,
-        } in self.hash_secrets
+        } in &self.hash_secrets
         {
-            let hasher = provider.get(&alg).map_err(|_| {
+            let hasher = provider.get(alg).map_err(|_| {
                 TranscriptProofError::new(
                     ErrorKind::Hash,
                     format!("hash opening has unknown algorithm: {alg}"),
                 )
             })?;
 
-            let (plaintext, auth) = match direction {
-                Direction::Sent => (self.transcript.sent_unsafe(), &mut total_auth_sent),
-                Direction::Received => (self.transcript.received_unsafe(), &mut total_auth_recv),
-            };
-
-            if idx.end().unwrap_or(0) > plaintext.len() {
+            if !self.transcript.contains(*direction, idx) {
                 return Err(TranscriptProofError::new(
                     ErrorKind::Hash,
                     "hash opening index is out of bounds",
                 ));
             }
 
-            buffer.clear();
-            for range in idx.iter() {
-                buffer.extend_from_slice(&plaintext[range]);
-            }
-
-            let expected = PlaintextHash {
-                direction,
-                idx,
-                hash: hash_plaintext(hasher, &buffer, &blinder),
+            let auth = match direction {
+                Direction::Sent => &mut total_auth_sent,
+                Direction::Received => &mut total_auth_recv,
             };
+            auth.union_mut(idx);
 
-            if !hash_commitments.contains(&expected) {
-                return Err(TranscriptProofError::new(
-                    ErrorKind::Hash,
-                    "hash opening does not match any commitment",
-                ));
-            }
-
-            auth.union_mut(&expected.idx);
+            openings.push(Opening {
+                direction: *direction,
+                idx,
+                hasher,
+                blinder,
+            });
         }
 
         // Assert that all the authenticated data are covered by the proof.
@@ -137,10 +341,101 @@ impl TranscriptProof {
             ));
         }
 
-        Ok(self.transcript)
+        Ok(openings)
     }
 }
 
+/// A hash opening of a [`TranscriptProof`].
+struct Opening<'a> {
+    direction: Direction,
+    idx: &'a RangeSet<usize>,
+    hasher: &'a (dyn HashAlgorithm + Send + Sync),
+    blinder: &'a Blinder,
+}
+
+impl Opening<'_> {
+    /// Computes the hash of the opened data, see
+    /// [`hash_plaintext`](crate::transcript::hash::hash_plaintext).
+    fn hash(&self, transcript: &PartialTranscript) -> PlaintextHash {
+        let plaintext = match self.direction {
+            Direction::Sent => transcript.sent_unsafe(),
+            Direction::Received => transcript.received_unsafe(),
+        };
+
+        // The data is hashed in place rather than being copied into a buffer.
+        let mut chunks: Vec<&[u8]> = self.idx.iter().map(|range| &plaintext[range]).collect();
+        chunks.push(self.blinder.as_bytes());
+
+        PlaintextHash {
+            direction: self.direction,
+            idx: self.idx.clone(),
+            hash: TypedHash {
+                alg: self.hasher.id(),
+                value: self.hasher.hash_chunks(&chunks),
+            },
+        }
+    }
+}
+
+impl fmt::Display for TranscriptProof {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for direction in [Direction::Sent, Direction::Received] {
+            let len = match direction {
+                Direction::Sent => self.transcript.len_sent(),
+                Direction::Received => self.transcript.len_received(),
+            };
+            let segments: Vec<_> = self.transcript.authed_segments(direction).collect();
+            let revealed: usize = segments.iter().map(|(range, _)| range.len()).sum();
+
+            writeln!(
+                f,
+                "{direction}: {} range(s) revealed, {revealed}/{len} bytes",
+                segments.len()
+            )?;
+
+            for (range, data) in segments {
+                write!(f, "  {}..{}: ", range.start, range.end)?;
+                fmt_preview(f, data)?;
+                writeln!(f)?;
+            }
+        }
+
+        for length in &self.lengths {
+            writeln!(
+                f,
+                "{}: {} byte(s) disclosed by length only",
+                length.direction,
+                length.len()
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes a short preview of `data`, as ASCII if it is printable and as hex
+/// otherwise.
+fn fmt_preview(f: &mut fmt::Formatter<'_>, data: &[u8]) -> fmt::Result {
+    let preview = &data[..data.len().min(PREVIEW_LEN)];
+
+    if preview
+        .iter()
+        .all(|byte| byte.is_ascii_graphic() || *byte == b' ')
+    {
+        write!(f, "{:?}", String::from_utf8_lossy(preview))?;
+    } else {
+        for byte in preview {
+            write!(f, "{byte:02x}")?;
+        }
+    }
+
+    if data.len() > PREVIEW_LEN {
+        write!(f, "...")?;
+    }
+
+    Ok(())
+}
+
 /// Error for [`TranscriptProof`].
 #[derive(Debug, thiserror::Error)]
 pub struct TranscriptProofError {
@@ -163,7 +458,9 @@ impl TranscriptProofError {
 #[derive(Debug)]
 enum ErrorKind {
     Hash,
+    Length,
     Proof,
+    Sink,
 }
 
 impl fmt::Display for TranscriptProofError {
@@ -172,7 +469,9 @@ impl fmt::Display for TranscriptProofError {
 
         match self.kind {
             ErrorKind::Hash => f.write_str("hash error")?,
+            ErrorKind::Length => f.write_str("length error")?,
             ErrorKind::Proof => f.write_str("proof error")?,
+            ErrorKind::Sink => f.write_str("sink error")?,
         }
 
         if let Some(source) = &self.source {
@@ -183,6 +482,28 @@ impl fmt::Display for TranscriptProofError {
     }
 }
 
+/// A committed range of the transcript whose length is disclosed without its
+/// contents.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommittedLength {
+    /// Direction of the transcript.
+    pub direction: Direction,
+    /// Committed index.
+    pub idx: RangeSet<usize>,
+}
+
+impl CommittedLength {
+    /// Returns the length of the committed data.
+    pub fn len(&self) -> usize {
+        self.idx.len()
+    }
+
+    /// Returns `true` if the committed data is empty.
+    pub fn is_empty(&self) -> bool {
+        self.idx.is_empty()
+    }
+}
+
 /// Union of ranges to reveal.
 #[derive(Clone, Debug, PartialEq)]
 struct QueryIdx {
@@ -231,6 +552,9 @@ pub struct TranscriptProofBuilder<'a> {
     committed_sent: RangeSet<usize>,
     committed_recv: RangeSet<usize>,
     query_idx: QueryIdx,
+    lengths: Vec<CommittedLength>,
+    max_proof_size: Option<usize>,
+    pad_openings: Option<usize>,
 }
 
 impl<'a> TranscriptProofBuilder<'a> {
@@ -262,6 +586,9 @@ impl<'a> TranscriptProofBuilder<'a> {
             committed_sent,
             committed_recv,
             query_idx: QueryIdx::new(),
+            lengths: Vec::new(),
+            max_proof_size: None,
+            pad_openings: None,
         }
     }
 
@@ -280,6 +607,34 @@ impl<'a> TranscriptProofBuilder<'a> {
         self
     }
 
+    /// Sets the maximum size of the proof in bytes.
+    ///
+    /// [`build`](Self::build) returns an error if the
+    /// [estimated size](Self::estimate_size) of the proof exceeds it.
+    pub fn max_proof_size(&mut self, bytes: usize) -> &mut Self {
+        self.max_proof_size = Some(bytes);
+        self
+    }
+
+    /// Pads the proof to contain exactly `count` openings.
+    ///
+    /// The number of openings otherwise depends on how the revealed ranges
+    /// line up with the commitments, which can leak the structure of the
+    /// revealed data. The padding openings are redundant commitments to
+    /// sub-ranges of the revealed data, e.g. the
+    /// [decoys](crate::transcript::TranscriptCommitConfigBuilder::decoys)
+    /// added at commit time, so they reveal nothing beyond the revealed data
+    /// and are indistinguishable from the other openings. The openings of a
+    /// padded proof are ordered by their position in the transcript.
+    ///
+    /// [`build`](Self::build) returns an error if the proof needs more than
+    /// `count` openings, or if there are not enough redundant commitments to
+    /// pad it.
+    pub fn pad_openings(&mut self, count: usize) -> &mut Self {
+        self.pad_openings = Some(count);
+        self
+    }
+
     /// Reveals the given ranges in the transcript.
     ///
     /// # Arguments
@@ -355,14 +710,88 @@ impl<'a> TranscriptProofBuilder<'a> {
         self.reveal_inner(RangeSet::from_range_iter(ranges), Direction::Received)
     }
 
+    /// Discloses the length of the given ranges in the transcript without
+    /// revealing their contents.
+    ///
+    /// The ranges must exactly match the index of a commitment so that the
+    /// verifier can check the length against it.
+    ///
+    /// # Arguments
+    ///
+    /// * `ranges` - The committed ranges.
+    /// * `direction` - The direction of the transcript.
+    pub fn reveal_len(
+        &mut self,
+        ranges: impl IntoRangeIterator<usize>,
+        direction: Direction,
+    ) -> Result<&mut Self, TranscriptProofBuilderError> {
+        let idx = RangeSet::from_range_iter(ranges);
+
+        if !self
+            .hash_secrets
+            .iter()
+            .any(|hash| hash.direction == direction && hash.idx == idx)
+        {
+            return Err(TranscriptProofBuilderError::new(
+                BuilderErrorKind::MissingCommitment,
+                format!(
+                    "commitment is missing for ranges in {direction} transcript: {}",
+                    FmtRangeSet(&idx)
+                ),
+            ));
+        }
+
+        let length = CommittedLength { direction, idx };
+        if !self.lengths.contains(&length) {
+            self.lengths.push(length);
+        }
+
+        Ok(self)
+    }
+
+    /// Returns the estimated size in bytes of the proof with the currently
+    /// revealed data, without building it.
+    ///
+    /// The estimate is the size of the proof serialized with a fixed-width
+    /// encoding such as `bincode`, which is an upper bound for encodings using
+    /// variable-width integers.
+    pub fn estimate_size(&self) -> Result<usize, TranscriptProofBuilderError> {
+        let hash_secrets = self.select_hash_secrets()?;
+
+        Ok(self.proof_size(&hash_secrets))
+    }
+
     /// Builds the transcript proof.
     pub fn build(self) -> Result<TranscriptProof, TranscriptProofBuilderError> {
-        let mut transcript_proof = TranscriptProof {
+        let hash_secrets = self.select_hash_secrets()?;
+
+        if let Some(max_proof_size) = self.max_proof_size {
+            let size = self.proof_size(&hash_secrets);
+            if size > max_proof_size {
+                return Err(TranscriptProofBuilderError::new(
+                    BuilderErrorKind::Size,
+                    format!(
+                        "estimated proof size of {size} bytes exceeds the maximum of {max_proof_size} bytes"
+                    ),
+                ));
+            }
+        }
+
+        Ok(TranscriptProof {
+            version: ProofVersion::default(),
             transcript: self
                 .transcript
                 .to_partial(self.query_idx.sent.clone(), self.query_idx.recv.clone()),
-            hash_secrets: Vec::new(),
-        };
+            hash_secrets: hash_secrets.into_iter().cloned().collect(),
+            lengths: self.lengths,
+        })
+    }
+
+    /// Selects the secrets needed to open the revealed ranges.
+    fn select_hash_secrets(
+        &self,
+    ) -> Result<Vec<&'a PlaintextHashSecret>, TranscriptProofBuilderError> {
+        let mut selected = Vec::new();
         let mut uncovered_query_idx = self.query_idx.clone();
         let mut commitment_kinds_iter = self.commitment_kinds.iter();
 
@@ -391,16 +820,8 @@ impl<'a> TranscriptProofBuilder<'a> {
                         );
                         uncovered_query_idx.recv = recv_uncovered;
 
-                        transcript_proof.hash_secrets.extend(
-                            sent_hashes
-                                .into_iter()
-                                .map(|s| PlaintextHashSecret::clone(s)),
-                        );
-                        transcript_proof.hash_secrets.extend(
-                            recv_hashes
-                                .into_iter()
-                                .map(|s| PlaintextHashSecret::clone(s)),
-                        );
+                        selected.extend(sent_hashes.into_iter().copied());
+                        selected.extend(recv_hashes.into_iter().copied());
                     }
                     #[allow(unreachable_patterns)]
                     kind => {
@@ -425,7 +846,104 @@ impl<'a> TranscriptProofBuilder<'a> {
             ));
         }
 
-        Ok(transcript_proof)
+        if let Some(count) = self.pad_openings {
+            self.pad(&mut selected, count)?;
+        }
+
+        Ok(selected)
+    }
+
+    /// Pads the selected secrets to `count` with secrets of commitments to
+    /// revealed data which were not selected.
+    fn pad(
+        &self,
+        selected: &mut Vec<&'a PlaintextHashSecret>,
+        count: usize,
+    ) -> Result<(), TranscriptProofBuilderError> {
+        if selected.len() > count {
+            return Err(TranscriptProofBuilderError::new(
+                BuilderErrorKind::Padding,
+                format!(
+                    "proof needs {} openings which exceeds the padded count of {count}",
+                    selected.len()
+                ),
+            ));
+        }
+
+        let redundant: Vec<_> = self
+            .hash_secrets
+            .iter()
+            .filter(|hash| {
+                let revealed = match hash.direction {
+                    Direction::Sent => &self.query_idx.sent,
+                    Direction::Received => &self.query_idx.recv,
+                };
+
+                hash.idx.is_subset(revealed)
+                    && self
+                        .commitment_kinds
+                        .contains(&TranscriptCommitmentKind::Hash { alg: hash.alg })
+                    && !selected.iter().any(|other| std::ptr::eq(*other, **hash))
+            })
+            .copied()
+            .take(count - selected.len())
+            .collect();
+
+        if selected.len() + redundant.len() < count {
+            return Err(TranscriptProofBuilderError::new(
+                BuilderErrorKind::Padding,
+                format!(
+                    "not enough redundant commitments to pad the proof to {count} openings, \
+                     only {} are available",
+                    selected.len() + redundant.len()
+                ),
+            ));
+        }
+
+        selected.extend(redundant);
+        // Orders the openings by position so that the padding can not be
+        // told apart by its place in the proof.
+        selected.sort_by_key(|hash| {
+            (
+                hash.direction == Direction::Received,
+                hash.idx.iter().next().map(|range| range.start),
+            )
+        });
+
+        Ok(())
+    }
+
+    /// Returns the size of the proof in a fixed-width encoding.
+    fn proof_size(&self, hash_secrets: &[&PlaintextHashSecret]) -> usize {
+        // Lengths and integers are encoded as u64, enum tags as u32.
+        const INT: usize = 8;
+        const TAG: usize = 4;
+
+        let ranges = |idx: &RangeSet<usize>| INT + 2 * INT * idx.iter().count();
+
+        let transcript = INT
+            + self.query_idx.sent.len()
+            + INT
+            + self.query_idx.recv.len()
+            + ranges(&self.query_idx.sent)
+            + ranges(&self.query_idx.recv)
+            + 2 * INT;
+
+        let hash_secrets = INT
+            + hash_secrets
+                .iter()
+                .map(|secret| TAG + ranges(&secret.idx) + 1 + 16)
+                .sum::<usize>();
+
+        let lengths = INT
+            + self
+                .lengths
+                .iter()
+                .map(|length| TAG + ranges(&length.idx))
+                .sum::<usize>();
+
+        // The version is a single byte.
+        1 + transcript + hash_secrets + lengths
     }
 }
 
@@ -467,6 +985,8 @@ enum BuilderErrorKind {
         kinds: Vec<TranscriptCommitmentKind>,
     },
     NotSupported,
+    Size,
+    Padding,
 }
 
 impl fmt::Display for TranscriptProofBuilderError {
@@ -480,6 +1000,8 @@ impl fmt::Display for TranscriptProofBuilderError {
                 "unable to cover the following ranges in transcript using available {kinds:?} commitments: {uncovered}"
             ))?,
             BuilderErrorKind::NotSupported => f.write_str("not supported")?,
+            BuilderErrorKind::Size => f.write_str("size error")?,
+            BuilderErrorKind::Padding => f.write_str("padding error")?,
         }
 
         if let Some(source) = &self.source {
@@ -498,10 +1020,42 @@ mod tests {
     use rstest::rstest;
     use tlsn_data_fixtures::http::{request::GET_WITH_HEADER, response::OK_JSON};
 
-    use crate::hash::{Blinder, HashAlgId};
+    use crate::{
+        hash::{Blinder, HashAlgId},
+        transcript::{hash::hash_plaintext, TranscriptCommitConfig, TranscriptCommitConfigBuilder},
+    };
 
     use super::*;
 
+    /// Creates the hash commitments of the config, and their secrets.
+    fn commit(
+        rng: &mut impl Rng,
+        provider: &HashProvider,
+        transcript: &Transcript,
+        config: &TranscriptCommitConfig,
+    ) -> (Vec<TranscriptCommitment>, Vec<TranscriptSecret>) {
+        config
+            .iter_hash()
+            .map(|((direction, idx), alg)| {
+                let blinder: Blinder = rng.random();
+                let data = transcript.get(*direction, idx).unwrap();
+                (
+                    TranscriptCommitment::Hash(PlaintextHash {
+                        direction: *direction,
+                        idx: idx.clone(),
+                        hash: hash_plaintext(provider.get(alg).unwrap(), data.data(), &blinder),
+                    }),
+                    TranscriptSecret::Hash(PlaintextHashSecret {
+                        direction: *direction,
+                        idx: idx.clone(),
+                        alg: *alg,
+                        blinder,
+                    }),
+                )
+            })
+            .unzip()
+    }
+
     #[rstest]
     fn test_reveal_range_out_of_bounds() {
         let transcript = Transcript::new(
@@ -580,6 +1134,369 @@ mod tests {
         );
     }
 
+    #[rstest]
+    #[case::one_byte(1)]
+    #[case::partial_block(15)]
+    #[case::full_block(16)]
+    fn test_reveal_tiny_transcript(#[case] len: usize) {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let provider = HashProvider::default();
+
+        // A single record carrying at most the request line.
+        let request_line = &b"GET / HTTP/1.1\r\n"[..len];
+        let transcript = Transcript::new(request_line, b"HTTP/1.1 204 No Content\r\n\r\n");
+
+        let mut config = TranscriptCommitConfigBuilder::new(&transcript);
+        config.commit_blocks(Direction::Sent, 16).unwrap();
+        let config = config.build().unwrap();
+
+        let (commitments, secrets) = commit(&mut rng, &provider, &transcript, &config);
+
+        // The whole request line is covered by a single commitment.
+        assert_eq!(commitments.len(), 1);
+
+        let mut builder = TranscriptProofBuilder::new(&transcript, &secrets);
+        builder.reveal_sent(&(0..len)).unwrap();
+
+        let partial_transcript = builder
+            .build()
+            .unwrap()
+            .verify_with_provider(&provider, &transcript.length(), &commitments)
+            .unwrap();
+
+        assert_eq!(partial_transcript.sent_unsafe(), request_line);
+        assert_eq!(partial_transcript.sent_authed(), &RangeSet::from(0..len));
+    }
+
+    #[rstest]
+    fn test_describe() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let transcript = Transcript::new(b"GET / HTTP/1.1", [0xde, 0xad, 0xbe, 0xef, 0x00]);
+
+        let secrets = [
+            (Direction::Sent, RangeSet::from(0..3)),
+            (Direction::Received, RangeSet::from(0..4)),
+        ]
+        .into_iter()
+        .map(|(direction, idx)| {
+            TranscriptSecret::Hash(PlaintextHashSecret {
+                direction,
+                idx,
+                alg: HashAlgId::SHA256,
+                blinder: rng.random(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+        let mut builder = TranscriptProofBuilder::new(&transcript, &secrets);
+        builder.reveal_sent(&(0..3)).unwrap();
+        builder.reveal_recv(&(0..4)).unwrap();
+
+        let proof = builder.build().unwrap();
+
+        assert_eq!(
+            proof.describe(),
+            "sent: 1 range(s) revealed, 3/14 bytes\n  0..3: \"GET\"\n\
+             received: 1 range(s) revealed, 4/5 bytes\n  0..4: deadbeef\n"
+        );
+    }
+
+    #[rstest]
+    fn test_reveal_len() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let provider = HashProvider::default();
+        let transcript = Transcript::new(GET_WITH_HEADER, OK_JSON);
+        let (_, recv_len) = transcript.len();
+
+        let status_idx = RangeSet::from(0..15);
+        let body_idx = RangeSet::from(15..recv_len);
+        let alg = HashAlgId::SHA256;
+        let hasher = provider.get(&alg).unwrap();
+
+        let mut commitments = Vec::new();
+        let mut secrets = Vec::new();
+        for idx in [&status_idx, &body_idx] {
+            let blinder: Blinder = rng.random();
+            let data = transcript
+                .get(Direction::Received, idx)
+                .unwrap()
+                .data()
+                .to_vec();
+
+            commitments.push(TranscriptCommitment::Hash(PlaintextHash {
+                direction: Direction::Received,
+                idx: idx.clone(),
+                hash: hash_plaintext(hasher, &data, &blinder),
+            }));
+            secrets.push(TranscriptSecret::Hash(PlaintextHashSecret {
+                direction: Direction::Received,
+                idx: idx.clone(),
+                alg,
+                blinder,
+            }));
+        }
+
+        let mut builder = TranscriptProofBuilder::new(&transcript, &secrets);
+        builder.reveal_recv(&status_idx).unwrap();
+        builder.reveal_len(&body_idx, Direction::Received).unwrap();
+
+        // Lengths can only be disclosed for exactly committed ranges.
+        let err = builder
+            .reveal_len(&(15..20), Direction::Received)
+            .unwrap_err();
+        assert!(matches!(err.kind, BuilderErrorKind::MissingCommitment));
+
+        let transcript_proof = builder.build().unwrap();
+        assert_eq!(
+            transcript_proof.lengths(),
+            &[CommittedLength {
+                direction: Direction::Received,
+                idx: body_idx.clone(),
+            }]
+        );
+
+        let partial_transcript = transcript_proof
+            .clone()
+            .verify_with_provider(&provider, &transcript.length(), &commitments)
+            .unwrap();
+
+        assert_eq!(partial_transcript.received_authed(), &status_idx);
+
+        // Verification fails if the length does not match a commitment.
+        let err = transcript_proof
+            .verify_with_provider(&provider, &transcript.length(), &commitments[..1])
+            .unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::Length));
+    }
+
+    #[rstest]
+    fn test_max_proof_size() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let transcript = Transcript::new(GET_WITH_HEADER, OK_JSON);
+        let (sent_len, recv_len) = transcript.len();
+
+        let secrets = [
+            (Direction::Sent, 0..sent_len),
+            (Direction::Received, 0..recv_len),
+        ]
+        .into_iter()
+        .map(|(direction, range)| {
+            TranscriptSecret::Hash(PlaintextHashSecret {
+                direction,
+                idx: RangeSet::from(range),
+                alg: HashAlgId::SHA256,
+                blinder: rng.random(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+        let mut builder = TranscriptProofBuilder::new(&transcript, &secrets);
+        builder.reveal_sent(&(0..sent_len)).unwrap();
+        let sent_size = builder.estimate_size().unwrap();
+
+        builder.reveal_recv(&(0..recv_len)).unwrap();
+        let size = builder.estimate_size().unwrap();
+        assert!(size > sent_size + recv_len);
+
+        let transcript_proof = builder.build().unwrap();
+        assert!(bincode::serialize(&transcript_proof).unwrap().len() <= size);
+
+        let mut builder = TranscriptProofBuilder::new(&transcript, &secrets);
+        builder.max_proof_size(size - 1);
+        builder.reveal_sent(&(0..sent_len)).unwrap();
+        builder.reveal_recv(&(0..recv_len)).unwrap();
+
+        let err = builder.build().unwrap_err();
+        assert!(matches!(err.kind, BuilderErrorKind::Size));
+    }
+
+    #[test]
+    fn test_proof_version() {
+        let transcript = Transcript::new(GET_WITH_HEADER, OK_JSON);
+        let secrets = [TranscriptSecret::Hash(PlaintextHashSecret {
+            direction: Direction::Sent,
+            idx: RangeSet::from(0..4),
+            alg: HashAlgId::SHA256,
+            blinder: rand::rngs::StdRng::seed_from_u64(0).random(),
+        })];
+
+        let mut builder = TranscriptProofBuilder::new(&transcript, &secrets);
+        builder.reveal_sent(&(0..4)).unwrap();
+
+        let mut bytes = bincode::serialize(&builder.build().unwrap()).unwrap();
+        assert_eq!(bytes[0], TRANSCRIPT_PROOF_VERSION);
+        assert!(bincode::deserialize::<TranscriptProof>(&bytes).is_ok());
+
+        bytes[0] += 1;
+        let err = bincode::deserialize::<TranscriptProof>(&bytes).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("unsupported transcript proof version"));
+    }
+
+    #[test]
+    fn test_proof_v0() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let provider = HashProvider::default();
+        let transcript = Transcript::new(GET_WITH_HEADER, OK_JSON);
+
+        let mut config = TranscriptCommitConfigBuilder::new(&transcript);
+        config.commit_sent(&(0..4)).unwrap();
+        let config = config.build().unwrap();
+
+        let (commitments, secrets) = commit(&mut rng, &provider, &transcript, &config);
+
+        let mut builder = TranscriptProofBuilder::new(&transcript, &secrets);
+        builder.reveal_sent(&(0..4)).unwrap();
+        let proof = builder.build().unwrap();
+
+        // A proof written by an earlier release.
+        let bytes = bincode::serialize(&TranscriptProofV0 {
+            transcript: proof.transcript.clone(),
+            hash_secrets: proof.hash_secrets.clone(),
+            lengths: Vec::new(),
+        })
+        .unwrap();
+
+        assert!(bincode::deserialize::<TranscriptProof>(&bytes).is_err());
+
+        let proof: TranscriptProof = bincode::deserialize::<TranscriptProofV0>(&bytes)
+            .unwrap()
+            .into();
+        let partial = proof
+            .verify_with_provider(&provider, &transcript.length(), &commitments)
+            .unwrap();
+        assert_eq!(partial.sent_authed(), &RangeSet::from(0..4));
+    }
+
+    #[rstest]
+    fn test_verify_streaming() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let provider = HashProvider::default();
+        let transcript = Transcript::new(GET_WITH_HEADER, OK_JSON);
+
+        let mut config = TranscriptCommitConfigBuilder::new(&transcript);
+        config
+            .commit_sent(&(0..10))
+            .unwrap()
+            .commit_sent(&(5..20))
+            .unwrap()
+            .commit_recv(&(0..8))
+            .unwrap();
+        let config = config.build().unwrap();
+
+        let (commitments, secrets) = commit(&mut rng, &provider, &transcript, &config);
+
+        let mut builder = TranscriptProofBuilder::new(&transcript, &secrets);
+        builder.reveal_sent(&(0..20)).unwrap();
+        builder.reveal_recv(&(0..8)).unwrap();
+        let transcript_proof = builder.build().unwrap();
+
+        // Overlapping openings are streamed once.
+        let mut sent = vec![0; 20];
+        let mut received = Vec::new();
+        let mut streamed = 0;
+        transcript_proof
+            .clone()
+            .verify_streaming(
+                &provider,
+                &transcript.length(),
+                &commitments,
+                |direction, range, data| {
+                    streamed += data.len();
+                    match direction {
+                        Direction::Sent => sent[range].copy_from_slice(data),
+                        Direction::Received => received.extend_from_slice(data),
+                    }
+                    Ok::<_, std::io::Error>(())
+                },
+            )
+            .unwrap();
+
+        assert_eq!(streamed, 28);
+        assert_eq!(sent, transcript.sent()[..20]);
+        assert_eq!(received, transcript.received()[..8]);
+
+        let err = transcript_proof
+            .clone()
+            .verify_streaming(&provider, &transcript.length(), &commitments, |_, _, _| {
+                Err("sink is closed")
+            })
+            .unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::Sink));
+
+        // The received opening does not match any commitment, nothing is
+        // streamed even though the sent openings are valid.
+        let sent_commitments = commitments.iter().filter(|commitment| {
+            let TranscriptCommitment::Hash(hash) = commitment;
+            hash.direction == Direction::Sent
+        });
+        let mut streamed = 0;
+        let err = transcript_proof
+            .verify_streaming(
+                &provider,
+                &transcript.length(),
+                sent_commitments,
+                |_, _, data| {
+                    streamed += data.len();
+                    Ok::<_, std::io::Error>(())
+                },
+            )
+            .unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::Hash));
+        assert_eq!(streamed, 0);
+    }
+
+    #[test]
+    fn test_pad_openings() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let provider = HashProvider::default();
+        let transcript = Transcript::new(GET_WITH_HEADER, OK_JSON);
+
+        let mut config = TranscriptCommitConfigBuilder::new(&transcript);
+        config
+            .decoys(3)
+            .commit_sent(&(0..12))
+            .unwrap()
+            .commit_recv(&(0..16))
+            .unwrap();
+        let config = config.build().unwrap();
+
+        let (commitments, secrets) = commit(&mut rng, &provider, &transcript, &config);
+
+        let build = |pad: Option<usize>| {
+            let mut builder = TranscriptProofBuilder::new(&transcript, &secrets);
+            if let Some(count) = pad {
+                builder.pad_openings(count);
+            }
+            builder.reveal_sent(&(0..12)).unwrap();
+            builder.reveal_recv(&(0..16)).unwrap();
+            builder.build()
+        };
+
+        let unpadded = build(None).unwrap();
+        let padded = build(Some(8)).unwrap();
+        assert!(unpadded.hash_secrets.len() < 8);
+        assert_eq!(padded.hash_secrets.len(), 8);
+
+        // The padding authenticates the same data.
+        let unpadded = unpadded
+            .verify_with_provider(&provider, &transcript.length(), &commitments)
+            .unwrap();
+        let padded = padded
+            .verify_with_provider(&provider, &transcript.length(), &commitments)
+            .unwrap();
+        assert_eq!(padded, unpadded);
+
+        // Only the 2 commitments and their 6 decoys can be opened.
+        let err = build(Some(9)).unwrap_err();
+        assert!(matches!(err.kind, BuilderErrorKind::Padding));
+
+        // The proof needs at least one opening per direction.
+        let err = build(Some(1)).unwrap_err();
+        assert!(matches!(err.kind, BuilderErrorKind::Padding));
+    }
+
     #[rstest]
     #[case::sha256(HashAlgId::SHA256)]
     #[case::blake3(HashAlgId::BLAKE3)]
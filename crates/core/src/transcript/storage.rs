@@ -0,0 +1,658 @@
+//! Transcript storage.
+//!
+//! A [`Transcript`] keeps the plaintext in memory. Embedders which need to
+//! control where the plaintext lives, e.g. to keep large transcripts out of
+//! memory or to keep them encrypted at rest, can use a [`FileStorage`], an
+//! [`EncryptedStorage`], or implement [`TranscriptStorage`] themselves.
+
+use std::{
+    borrow::Cow,
+    error::Error,
+    fmt,
+    fs::{self, File, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use chacha20poly1305::{
+    aead::{Aead, NewAead},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use rangeset::set::RangeSet;
+use zeroize::Zeroizing;
+
+use crate::{
+    connection::TranscriptLength,
+    transcript::{Direction, PartialTranscript, Subsequence, Transcript},
+};
+
+/// Storage of the plaintext of a transcript.
+///
+/// The data of each direction is append-only. Implementations must not leak
+/// the plaintext in their [`Debug`](fmt::Debug) output.
+pub trait TranscriptStorage: fmt::Debug {
+    /// Returns the length of the data in the given direction.
+    fn len_of_direction(&self, direction: Direction) -> usize;
+
+    /// Reads a range of the data in the given direction.
+    ///
+    /// Returns an error if the range is out of bounds.
+    fn read(
+        &self,
+        direction: Direction,
+        range: Range<usize>,
+    ) -> Result<Cow<'_, [u8]>, TranscriptStorageError>;
+
+    /// Appends data in the given direction.
+    fn append(&mut self, direction: Direction, data: &[u8]) -> Result<(), TranscriptStorageError>;
+
+    /// Returns the transcript length.
+    fn transcript_length(&self) -> TranscriptLength {
+        TranscriptLength {
+            sent: self.len_of_direction(Direction::Sent) as u32,
+            received: self.len_of_direction(Direction::Received) as u32,
+        }
+    }
+
+    /// Reads all of the data in the given direction.
+    fn read_all(&self, direction: Direction) -> Result<Cow<'_, [u8]>, TranscriptStorageError> {
+        self.read(direction, 0..self.len_of_direction(direction))
+    }
+
+    /// Reads the subsequence with the provided index.
+    fn read_subsequence(
+        &self,
+        direction: Direction,
+        idx: &RangeSet<usize>,
+    ) -> Result<Subsequence, TranscriptStorageError> {
+        let mut data = Vec::with_capacity(idx.len());
+        for range in idx.iter() {
+            data.extend_from_slice(&self.read(direction, range)?);
+        }
+
+        Ok(Subsequence::new(idx.clone(), data).expect("data is same length as index"))
+    }
+
+    /// Reads a partial transcript containing the provided indices.
+    ///
+    /// # Arguments
+    ///
+    /// * `sent_idx` - The indices of the sent data to include.
+    /// * `recv_idx` - The indices of the received data to include.
+    fn read_partial(
+        &self,
+        sent_idx: RangeSet<usize>,
+        recv_idx: RangeSet<usize>,
+    ) -> Result<PartialTranscript, TranscriptStorageError> {
+        let mut partial = PartialTranscript::new(
+            self.len_of_direction(Direction::Sent),
+            self.len_of_direction(Direction::Received),
+        );
+        partial.union_subsequence(
+            Direction::Sent,
+            &self.read_subsequence(Direction::Sent, &sent_idx)?,
+        );
+        partial.union_subsequence(
+            Direction::Received,
+            &self.read_subsequence(Direction::Received, &recv_idx)?,
+        );
+
+        Ok(partial)
+    }
+
+    /// Loads the transcript into memory.
+    fn load(&self) -> Result<Transcript, TranscriptStorageError> {
+        Ok(Transcript::new(
+            self.read_all(Direction::Sent)?.into_owned(),
+            self.read_all(Direction::Received)?.into_owned(),
+        ))
+    }
+}
+
+/// Returns an error if the range is out of bounds of the data.
+fn check_range(range: &Range<usize>, len: usize) -> Result<(), TranscriptStorageError> {
+    if range.start > range.end || range.end > len {
+        return Err(TranscriptStorageError::new(format!(
+            "range {range:?} is out of bounds of the data ({len})"
+        )));
+    }
+
+    Ok(())
+}
+
+impl TranscriptStorage for Transcript {
+    fn len_of_direction(&self, direction: Direction) -> usize {
+        Transcript::len_of_direction(self, direction)
+    }
+
+    fn read(
+        &self,
+        direction: Direction,
+        range: Range<usize>,
+    ) -> Result<Cow<'_, [u8]>, TranscriptStorageError> {
+        let data = match direction {
+            Direction::Sent => self.sent(),
+            Direction::Received => self.received(),
+        };
+        check_range(&range, data.len())?;
+
+        Ok(Cow::Borrowed(&data[range]))
+    }
+
+    fn append(&mut self, direction: Direction, data: &[u8]) -> Result<(), TranscriptStorageError> {
+        match direction {
+            Direction::Sent => self.sent.extend_from_slice(data),
+            Direction::Received => self.received.extend_from_slice(data),
+        }
+
+        Ok(())
+    }
+}
+
+/// Transcript storage backed by files.
+///
+/// The data of each direction is stored in a separate file in a directory.
+/// The files are not removed when the storage is dropped, see
+/// [`remove`](Self::remove).
+#[derive(Debug)]
+pub struct FileStorage {
+    dir: PathBuf,
+    sent: StorageFile,
+    received: StorageFile,
+}
+
+#[derive(Debug)]
+struct StorageFile {
+    file: Mutex<File>,
+    len: usize,
+}
+
+impl StorageFile {
+    fn open(path: &Path, truncate: bool) -> std::io::Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(truncate)
+            .open(path)?;
+        let len = file.metadata()?.len() as usize;
+
+        Ok(Self {
+            file: Mutex::new(file),
+            len,
+        })
+    }
+}
+
+impl FileStorage {
+    const SENT: &'static str = "sent";
+    const RECEIVED: &'static str = "received";
+
+    /// Creates a new, empty, storage in the given directory.
+    ///
+    /// The directory is created if it does not exist, existing data is
+    /// truncated.
+    pub fn create(dir: impl AsRef<Path>) -> Result<Self, TranscriptStorageError> {
+        Self::open_with(dir.as_ref(), true)
+    }
+
+    /// Opens the storage in the given directory, keeping existing data.
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, TranscriptStorageError> {
+        Self::open_with(dir.as_ref(), false)
+    }
+
+    fn open_with(dir: &Path, truncate: bool) -> Result<Self, TranscriptStorageError> {
+        fs::create_dir_all(dir)?;
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            sent: StorageFile::open(&dir.join(Self::SENT), truncate)?,
+            received: StorageFile::open(&dir.join(Self::RECEIVED), truncate)?,
+        })
+    }
+
+    /// Returns the directory of the storage.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Removes the files of the storage.
+    pub fn remove(self) -> Result<(), TranscriptStorageError> {
+        let Self { dir, .. } = self;
+        fs::remove_file(dir.join(Self::SENT))?;
+        fs::remove_file(dir.join(Self::RECEIVED))?;
+
+        Ok(())
+    }
+
+    fn file(&self, direction: Direction) -> &StorageFile {
+        match direction {
+            Direction::Sent => &self.sent,
+            Direction::Received => &self.received,
+        }
+    }
+}
+
+impl TranscriptStorage for FileStorage {
+    fn len_of_direction(&self, direction: Direction) -> usize {
+        self.file(direction).len
+    }
+
+    fn read(
+        &self,
+        direction: Direction,
+        range: Range<usize>,
+    ) -> Result<Cow<'_, [u8]>, TranscriptStorageError> {
+        let file = self.file(direction);
+        check_range(&range, file.len)?;
+
+        let mut buf = vec![0; range.len()];
+        let mut f = file.file.lock().unwrap();
+        f.seek(SeekFrom::Start(range.start as u64))?;
+        f.read_exact(&mut buf)?;
+
+        Ok(Cow::Owned(buf))
+    }
+
+    fn append(&mut self, direction: Direction, data: &[u8]) -> Result<(), TranscriptStorageError> {
+        let file = match direction {
+            Direction::Sent => &mut self.sent,
+            Direction::Received => &mut self.received,
+        };
+
+        let f = file.file.get_mut().unwrap();
+        f.seek(SeekFrom::End(0))?;
+        f.write_all(data)?;
+        f.flush()?;
+        file.len += data.len();
+
+        Ok(())
+    }
+}
+
+/// Transcript storage which encrypts the data before passing it to an inner
+/// storage.
+///
+/// The data is encrypted with ChaCha20-Poly1305 in chunks of at most
+/// [`MAX_CHUNK_LEN`](Self::MAX_CHUNK_LEN) bytes, under a key which is only
+/// held in memory, so the plaintext is not recoverable from the inner storage
+/// once the key is dropped.
+///
+/// Each direction of the inner storage starts with a random salt, which
+/// derives the key of that direction from the storage key, so reusing a key
+/// for several storages never reuses a nonce. Each chunk is stored as its
+/// length followed by the ciphertext and tag, and its nonce is its position in
+/// the direction. Reading a modified or reordered chunk returns an error,
+/// removing chunks from the end of a direction is not detected.
+pub struct EncryptedStorage<S> {
+    inner: S,
+    sent: EncryptedStream,
+    received: EncryptedStream,
+}
+
+opaque_debug::implement!(EncryptedStorage<S>);
+
+/// A direction of an [`EncryptedStorage`].
+struct EncryptedStream {
+    key: Zeroizing<[u8; 32]>,
+    chunks: Vec<Chunk>,
+    len: usize,
+}
+
+/// A chunk of an [`EncryptedStream`].
+struct Chunk {
+    /// Offset of the plaintext in the direction.
+    start: usize,
+    /// Offset of the ciphertext in the inner storage.
+    offset: usize,
+    /// Length of the plaintext.
+    len: usize,
+}
+
+impl Chunk {
+    fn end(&self) -> usize {
+        self.start + self.len
+    }
+}
+
+impl EncryptedStream {
+    const SALT_LEN: usize = 32;
+    const LEN_PREFIX: usize = 4;
+    const TAG_LEN: usize = 16;
+
+    /// Opens the direction of the inner storage, writing a new salt if it is
+    /// empty.
+    fn open<S: TranscriptStorage>(
+        inner: &mut S,
+        direction: Direction,
+        key: &[u8; 32],
+    ) -> Result<Self, TranscriptStorageError> {
+        let inner_len = inner.len_of_direction(direction);
+        let salt: [u8; Self::SALT_LEN] = if inner_len == 0 {
+            let salt = rand::random();
+            inner.append(direction, &salt)?;
+            salt
+        } else {
+            inner
+                .read(direction, 0..Self::SALT_LEN)?
+                .as_ref()
+                .try_into()
+                .expect("salt has the correct length")
+        };
+
+        let mut stream = Self {
+            key: Zeroizing::new(blake3::keyed_hash(key, &salt).into()),
+            chunks: Vec::new(),
+            len: 0,
+        };
+
+        let mut pos = Self::SALT_LEN;
+        while pos < inner_len {
+            let prefix = inner.read(direction, pos..pos + Self::LEN_PREFIX)?;
+            let len = u32::from_le_bytes(prefix.as_ref().try_into().expect("prefix is 4 bytes"));
+            let offset = pos + Self::LEN_PREFIX;
+            pos = offset + len as usize + Self::TAG_LEN;
+            if pos > inner_len {
+                return Err(TranscriptStorageError::new(format!(
+                    "chunk {} of the {direction} data is truncated",
+                    stream.chunks.len()
+                )));
+            }
+
+            stream.chunks.push(Chunk {
+                start: stream.len,
+                offset,
+                len: len as usize,
+            });
+            stream.len += len as usize;
+        }
+
+        Ok(stream)
+    }
+
+    fn cipher(&self) -> ChaCha20Poly1305 {
+        ChaCha20Poly1305::new(Key::from_slice(&*self.key))
+    }
+
+    fn nonce(index: usize) -> [u8; 12] {
+        let mut nonce = [0; 12];
+        nonce[..8].copy_from_slice(&(index as u64).to_le_bytes());
+        nonce
+    }
+}
+
+impl<S: TranscriptStorage> EncryptedStorage<S> {
+    /// Maximum length of the plaintext of a chunk.
+    pub const MAX_CHUNK_LEN: usize = 1 << 14;
+
+    /// Creates a new storage with a random key.
+    ///
+    /// Returns an error if the inner storage is not empty, as its data can
+    /// not be decrypted with a new key.
+    pub fn new(inner: S) -> Result<Self, TranscriptStorageError> {
+        if inner.len_of_direction(Direction::Sent) != 0
+            || inner.len_of_direction(Direction::Received) != 0
+        {
+            return Err(TranscriptStorageError::new(
+                "inner storage must be empty to encrypt with a new key",
+            ));
+        }
+
+        Self::with_key(inner, rand::random())
+    }
+
+    /// Creates a new storage with the given key.
+    ///
+    /// The inner storage must be empty or only contain data encrypted with
+    /// the same key.
+    pub fn with_key(mut inner: S, key: [u8; 32]) -> Result<Self, TranscriptStorageError> {
+        let key = Zeroizing::new(key);
+        let sent = EncryptedStream::open(&mut inner, Direction::Sent, &key)?;
+        let received = EncryptedStream::open(&mut inner, Direction::Received, &key)?;
+
+        Ok(Self {
+            inner,
+            sent,
+            received,
+        })
+    }
+
+    /// Returns the inner storage.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    fn stream(&self, direction: Direction) -> &EncryptedStream {
+        match direction {
+            Direction::Sent => &self.sent,
+            Direction::Received => &self.received,
+        }
+    }
+}
+
+impl<S: TranscriptStorage> TranscriptStorage for EncryptedStorage<S> {
+    fn len_of_direction(&self, direction: Direction) -> usize {
+        self.stream(direction).len
+    }
+
+    fn read(
+        &self,
+        direction: Direction,
+        range: Range<usize>,
+    ) -> Result<Cow<'_, [u8]>, TranscriptStorageError> {
+        let stream = self.stream(direction);
+        check_range(&range, stream.len)?;
+
+        let cipher = stream.cipher();
+        let first = stream
+            .chunks
+            .partition_point(|chunk| chunk.end() <= range.start);
+
+        let mut data = Vec::with_capacity(range.len());
+        for (index, chunk) in stream
+            .chunks
+            .iter()
+            .enumerate()
+            .skip(first)
+            .take_while(|(_, chunk)| chunk.start < range.end)
+        {
+            let ciphertext = self.inner.read(
+                direction,
+                chunk.offset..chunk.offset + chunk.len + EncryptedStream::TAG_LEN,
+            )?;
+            let plaintext = Zeroizing::new(
+                cipher
+                    .decrypt(Nonce::from_slice(&EncryptedStream::nonce(index)), ciphertext.as_ref())
+                    .map_err(|_| {
+                        TranscriptStorageError::new(format!(
+                            "failed to decrypt chunk {index} of the {direction} data, wrong key or modified data"
+                        ))
+                    })?,
+            );
+
+            let start = range.start.max(chunk.start) - chunk.start;
+            let end = range.end.min(chunk.end()) - chunk.start;
+            data.extend_from_slice(&plaintext[start..end]);
+        }
+
+        Ok(Cow::Owned(data))
+    }
+
+    fn append(&mut self, direction: Direction, data: &[u8]) -> Result<(), TranscriptStorageError> {
+        let stream = match direction {
+            Direction::Sent => &mut self.sent,
+            Direction::Received => &mut self.received,
+        };
+        let cipher = stream.cipher();
+
+        for plaintext in data.chunks(Self::MAX_CHUNK_LEN) {
+            let index = stream.chunks.len();
+            let ciphertext = cipher
+                .encrypt(Nonce::from_slice(&EncryptedStream::nonce(index)), plaintext)
+                .map_err(|_| TranscriptStorageError::new("failed to encrypt chunk"))?;
+
+            let mut bytes = Vec::with_capacity(EncryptedStream::LEN_PREFIX + ciphertext.len());
+            bytes.extend_from_slice(&(plaintext.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(&ciphertext);
+
+            let offset = self.inner.len_of_direction(direction) + EncryptedStream::LEN_PREFIX;
+            self.inner.append(direction, &bytes)?;
+
+            stream.chunks.push(Chunk {
+                start: stream.len,
+                offset,
+                len: plaintext.len(),
+            });
+            stream.len += plaintext.len();
+        }
+
+        Ok(())
+    }
+}
+
+/// Error for [`TranscriptStorage`].
+#[derive(Debug, thiserror::Error)]
+#[error("transcript storage error: {0}")]
+pub struct TranscriptStorageError(Box<dyn Error + Send + Sync>);
+
+impl TranscriptStorageError {
+    /// Creates a new transcript storage error.
+    pub fn new<E>(err: E) -> Self
+    where
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        Self(err.into())
+    }
+}
+
+impl From<std::io::Error> for TranscriptStorageError {
+    fn from(err: std::io::Error) -> Self {
+        Self::new(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SENT: &[u8] = b"GET / HTTP/1.1\r\nHost: example.com\r\n\r\n";
+    const RECV: &[u8] = b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhello";
+
+    fn fill(storage: &mut impl TranscriptStorage) {
+        // Append in uneven pieces, so the data spans several chunk boundaries.
+        for chunk in SENT.chunks(3) {
+            storage.append(Direction::Sent, chunk).unwrap();
+        }
+        for chunk in RECV.chunks(5) {
+            storage.append(Direction::Received, chunk).unwrap();
+        }
+    }
+
+    fn check(storage: &impl TranscriptStorage) {
+        assert_eq!(storage.load().unwrap().sent(), SENT);
+        assert_eq!(storage.load().unwrap().received(), RECV);
+        assert_eq!(
+            storage.read(Direction::Received, 9..15).unwrap(),
+            &RECV[9..15]
+        );
+        assert!(storage.read(Direction::Sent, 0..SENT.len() + 1).is_err());
+
+        let idx = RangeSet::from([0..3, 5..9]);
+        let partial = storage
+            .read_partial(idx.clone(), RangeSet::default())
+            .unwrap();
+        assert_eq!(
+            partial,
+            Transcript::new(SENT, RECV).to_partial(idx, RangeSet::default())
+        );
+    }
+
+    #[test]
+    fn test_memory_storage() {
+        let mut storage = Transcript::new(Vec::new(), Vec::new());
+        fill(&mut storage);
+        check(&storage);
+    }
+
+    #[test]
+    fn test_file_storage() {
+        let dir = std::env::temp_dir().join(format!("tlsn-transcript-{}", rand::random::<u64>()));
+
+        let mut storage = FileStorage::create(&dir).unwrap();
+        fill(&mut storage);
+        check(&storage);
+        drop(storage);
+
+        let storage = FileStorage::open(&dir).unwrap();
+        check(&storage);
+        storage.remove().unwrap();
+        fs::remove_dir(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_encrypted_storage() {
+        let mut storage = EncryptedStorage::new(Transcript::new(Vec::new(), Vec::new())).unwrap();
+        fill(&mut storage);
+        check(&storage);
+
+        let inner = storage.into_inner();
+        assert!(!inner
+            .sent()
+            .windows(SENT.len())
+            .any(|window| window == SENT));
+        assert!(EncryptedStorage::new(inner).is_err());
+    }
+
+    #[test]
+    fn test_encrypted_storage_reopen() {
+        let key = [1u8; 32];
+        let mut storage =
+            EncryptedStorage::with_key(Transcript::new(Vec::new(), Vec::new()), key).unwrap();
+        fill(&mut storage);
+        storage
+            .append(
+                Direction::Sent,
+                &vec![0; 2 * EncryptedStorage::<Transcript>::MAX_CHUNK_LEN],
+            )
+            .unwrap();
+
+        let storage = EncryptedStorage::with_key(storage.into_inner(), key).unwrap();
+        assert_eq!(
+            storage.len_of_direction(Direction::Sent),
+            SENT.len() + 2 * EncryptedStorage::<Transcript>::MAX_CHUNK_LEN
+        );
+        assert_eq!(storage.read(Direction::Sent, 0..SENT.len()).unwrap(), SENT);
+
+        // A wrong key fails to decrypt.
+        let storage = EncryptedStorage::with_key(storage.into_inner(), [2u8; 32]).unwrap();
+        assert!(storage.read(Direction::Sent, 0..1).is_err());
+    }
+
+    #[test]
+    fn test_encrypted_storage_key_reuse() {
+        let key = [1u8; 32];
+        let mut storages = [(); 2].map(|_| {
+            EncryptedStorage::with_key(Transcript::new(Vec::new(), Vec::new()), key).unwrap()
+        });
+        for storage in &mut storages {
+            fill(storage);
+        }
+
+        let [a, b] = storages.map(EncryptedStorage::into_inner);
+        assert_ne!(a.sent(), b.sent());
+    }
+
+    #[test]
+    fn test_encrypted_storage_modified() {
+        let mut storage = EncryptedStorage::new(Transcript::new(Vec::new(), Vec::new())).unwrap();
+        fill(&mut storage);
+
+        // Flip a bit of the first chunk of the received data.
+        storage.inner.received[EncryptedStream::SALT_LEN + EncryptedStream::LEN_PREFIX] ^= 1;
+
+        assert!(storage.read(Direction::Received, 0..1).is_err());
+        assert!(storage.read(Direction::Sent, 0..SENT.len()).is_ok());
+    }
+}
@@ -1,5 +1,7 @@
 //! TLS transcript.
 
+use std::ops::Range;
+
 use crate::{
     connection::{
         CertBinding, CertBindingV1_2, ServerEphemKey, ServerSignature, TlsVersion, VerifyData,
@@ -67,6 +69,7 @@ pub struct TlsTranscript {
     certificate_binding: CertBinding,
     sent: Vec<Record>,
     recv: Vec<Record>,
+    handshake: Vec<HandshakeMessage>,
 }
 
 impl TlsTranscript {
@@ -184,12 +187,14 @@ impl TlsTranscript {
             }
         }
 
-        // Verify last record received was either application data or close notify.
+        // Verify last record received was either application data or an alert.
+        //
+        // Any alert from the server terminates the connection, so the
+        // application data before it is still valid.
         if let Some(record) = recv_iter.next_back() {
             match record.typ {
                 ContentType::ApplicationData => {}
                 ContentType::Alert => {
-                    // Ensure the alert is a close notify.
                     let payload =
                         record
                             .plaintext
@@ -199,15 +204,9 @@ impl TlsTranscript {
                             ))?;
 
                     let mut reader = Reader::init(payload);
-                    let payload = AlertMessagePayload::read(&mut reader).ok_or(
+                    AlertMessagePayload::read(&mut reader).ok_or(
                         TlsTranscriptError::validation("alert message was malformed"),
                     )?;
-
-                    let AlertDescription::CloseNotify = payload.description else {
-                        return Err(TlsTranscriptError::validation(
-                            "received alert that is not close notify",
-                        ));
-                    };
                 }
                 typ => {
                     return Err(TlsTranscriptError::validation(format!(
@@ -236,6 +235,19 @@ impl TlsTranscript {
             }
         }
 
+        // AES-GCM ciphertext has the same length as the plaintext, which the
+        // application data offsets rely on.
+        for record in sent.iter().chain(recv.iter()) {
+            if let Some(plaintext) = &record.plaintext {
+                if plaintext.len() != record.ciphertext.len() {
+                    return Err(TlsTranscriptError::validation(format!(
+                        "record plaintext length does not match ciphertext length: seq {}",
+                        record.seq
+                    )));
+                }
+            }
+        }
+
         Ok(Self {
             time,
             version,
@@ -244,9 +256,15 @@ impl TlsTranscript {
             certificate_binding,
             sent,
             recv,
+            handshake: Vec::new(),
         })
     }
 
+    /// Sets the plaintext handshake messages of the connection.
+    pub fn set_handshake_messages(&mut self, messages: Vec<HandshakeMessage>) {
+        self.handshake = messages;
+    }
+
     /// Returns the start time of the connection.
     pub fn time(&self) -> u64 {
         self.time
@@ -292,44 +310,126 @@ impl TlsTranscript {
         &self.recv
     }
 
+    /// Returns `true` if the server did not end the connection with
+    /// `close_notify`.
+    ///
+    /// The received data of such a connection may have been cut short, either
+    /// by the prover or by an attacker on the network.
+    pub fn is_truncated(&self) -> bool {
+        let Some(record) = self.recv.last() else {
+            return true;
+        };
+
+        if record.typ != ContentType::Alert {
+            return true;
+        }
+
+        let Some(payload) = record.plaintext.as_ref() else {
+            return true;
+        };
+
+        !matches!(
+            AlertMessagePayload::read(&mut Reader::init(payload)),
+            Some(AlertMessagePayload {
+                description: AlertDescription::CloseNotify,
+                ..
+            })
+        )
+    }
+
+    /// Returns the handshake messages in the order they were sent or received.
+    ///
+    /// The handshake messages are only known to the party which has the
+    /// session keys, so this is empty for the verifier.
+    pub fn handshake_messages(&self) -> &[HandshakeMessage] {
+        &self.handshake
+    }
+
+    /// Returns the application data records in the given direction which
+    /// carry data.
+    ///
+    /// Zero-length application data records, which some servers send as
+    /// padding or heartbeats, do not contribute to the application data
+    /// transcript and are skipped.
+    pub fn application_data(&self, direction: Direction) -> impl Iterator<Item = &Record> {
+        let records = match direction {
+            Direction::Sent => &self.sent,
+            Direction::Received => &self.recv,
+        };
+
+        records
+            .iter()
+            .filter(|record| record.has_application_data())
+    }
+
+    /// Returns the boundaries of the application data records in the given
+    /// direction.
+    ///
+    /// The boundaries are ordered and their ranges index into the application
+    /// data transcript returned by [`to_transcript`](Self::to_transcript),
+    /// which allows aligning work to record boundaries. Zero-length records
+    /// have no boundary.
+    pub fn record_boundaries(&self, direction: Direction) -> Vec<RecordBoundary> {
+        let records = match direction {
+            Direction::Sent => &self.sent,
+            Direction::Received => &self.recv,
+        };
+
+        let mut offset = 0;
+        records
+            .iter()
+            .enumerate()
+            .filter(|(_, record)| record.has_application_data())
+            .map(|(index, record)| {
+                // AES-GCM ciphertext has the same length as the plaintext.
+                let len = record.ciphertext.len();
+                let boundary = RecordBoundary {
+                    direction,
+                    index,
+                    seq: record.seq,
+                    range: offset..offset + len,
+                };
+                offset += len;
+                boundary
+            })
+            .collect()
+    }
+
     /// Returns the application data transcript.
     pub fn to_transcript(&self) -> Result<Transcript, TlsTranscriptError> {
         let mut sent = Vec::new();
         let mut recv = Vec::new();
 
-        for record in self
-            .sent
-            .iter()
-            .filter(|record| record.typ == ContentType::ApplicationData)
-        {
-            let plaintext = record
-                .plaintext
-                .as_ref()
-                .ok_or(ErrorRepr::Incomplete {
-                    direction: Direction::Sent,
+        for (direction, transcript) in [
+            (Direction::Sent, &mut sent),
+            (Direction::Received, &mut recv),
+        ] {
+            for record in self.application_data(direction) {
+                let plaintext = record.plaintext.as_ref().ok_or(ErrorRepr::Incomplete {
+                    direction,
                     seq: record.seq,
-                })?
-                .clone();
-            sent.extend_from_slice(&plaintext);
+                })?;
+                transcript.extend_from_slice(plaintext);
+            }
         }
 
-        for record in self
-            .recv
-            .iter()
-            .filter(|record| record.typ == ContentType::ApplicationData)
-        {
-            let plaintext = record
-                .plaintext
-                .as_ref()
-                .ok_or(ErrorRepr::Incomplete {
-                    direction: Direction::Received,
-                    seq: record.seq,
-                })?
-                .clone();
-            recv.extend_from_slice(&plaintext);
+        Ok(Transcript::new(sent, recv))
+    }
+
+    /// Moves the application data plaintext out of the records into a
+    /// transcript.
+    ///
+    /// Afterwards the application data records only hold the ciphertext, so
+    /// the plaintext only lives in the returned transcript.
+    pub fn take_transcript(&mut self) -> Result<Transcript, TlsTranscriptError> {
+        let transcript = self.to_transcript()?;
+        for record in self.sent.iter_mut().chain(self.recv.iter_mut()) {
+            if record.has_application_data() {
+                record.plaintext = None;
+            }
         }
 
-        Ok(Transcript::new(sent, recv))
+        Ok(transcript)
     }
 }
 
@@ -350,8 +450,40 @@ pub struct Record {
     pub tag: Option<Vec<u8>>,
 }
 
+impl Record {
+    /// Returns `true` if the record is an application data record which
+    /// carries data, i.e. it is not a zero-length record.
+    pub fn has_application_data(&self) -> bool {
+        self.typ == ContentType::ApplicationData && !self.ciphertext.is_empty()
+    }
+}
+
 opaque_debug::implement!(Record);
 
+/// A plaintext TLS handshake message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandshakeMessage {
+    /// Direction of the message.
+    pub direction: Direction,
+    /// Handshake message type.
+    pub typ: u8,
+    /// Encoded message, including the handshake header.
+    pub payload: Vec<u8>,
+}
+
+/// Boundary of an application data record in the transcript.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordBoundary {
+    /// Direction of the record.
+    pub direction: Direction,
+    /// Index of the record among all records sent in the same direction.
+    pub index: usize,
+    /// Sequence number of the record.
+    pub seq: u64,
+    /// Range of the record plaintext in the application data transcript.
+    pub range: Range<usize>,
+}
+
 #[derive(Debug, thiserror::Error)]
 #[error("TLS transcript error: {0}")]
 pub struct TlsTranscriptError(#[from] ErrorRepr);
@@ -369,3 +501,261 @@ enum ErrorRepr {
     #[error("incomplete transcript ({direction}): seq {seq}")]
     Incomplete { direction: Direction, seq: u64 },
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::transcript::{
+        transcript_fixture, transcript_fixture_with_empty_records, RECORD_SIZE,
+    };
+
+    #[test]
+    fn test_is_truncated() {
+        let mut transcript = transcript_fixture(b"GET / HTTP/1.1\r\n\r\n", b"HTTP/1.1 200 OK\r\n");
+        assert!(transcript.is_truncated());
+
+        let seq = transcript.recv.last().unwrap().seq + 1;
+        let alert = |description: u8| Record {
+            seq,
+            typ: ContentType::Alert,
+            // Warning level.
+            plaintext: Some(vec![1, description]),
+            explicit_nonce: seq.to_be_bytes().to_vec(),
+            ciphertext: vec![0; 2],
+            tag: None,
+        };
+
+        // user_canceled
+        transcript.recv.push(alert(90));
+        assert!(transcript.is_truncated());
+
+        // close_notify
+        *transcript.recv.last_mut().unwrap() = alert(0);
+        assert!(!transcript.is_truncated());
+    }
+
+    #[test]
+    fn test_record_boundaries() {
+        let sent = vec![1u8; RECORD_SIZE + 10];
+        let recv = vec![2u8; 2 * RECORD_SIZE];
+        let transcript = transcript_fixture(&sent, &recv);
+
+        let sent_boundaries = transcript.record_boundaries(Direction::Sent);
+        let recv_boundaries = transcript.record_boundaries(Direction::Received);
+
+        // The first record is the finished message.
+        assert_eq!(
+            sent_boundaries,
+            vec![
+                RecordBoundary {
+                    direction: Direction::Sent,
+                    index: 1,
+                    seq: 1,
+                    range: 0..RECORD_SIZE,
+                },
+                RecordBoundary {
+                    direction: Direction::Sent,
+                    index: 2,
+                    seq: 2,
+                    range: RECORD_SIZE..RECORD_SIZE + 10,
+                },
+            ]
+        );
+        assert_eq!(recv_boundaries.len(), 2);
+        assert_eq!(
+            recv_boundaries.last().unwrap().range.end,
+            transcript.to_transcript().unwrap().received().len()
+        );
+    }
+
+    #[test]
+    fn test_empty_records() {
+        let sent = vec![1u8; RECORD_SIZE + 10];
+        let recv = vec![2u8; 2 * RECORD_SIZE + 1];
+        let transcript = transcript_fixture(&sent, &recv);
+        let padded = transcript_fixture_with_empty_records(&sent, &recv);
+
+        assert!(padded.recv().iter().any(
+            |record| record.typ == ContentType::ApplicationData && record.ciphertext.is_empty()
+        ));
+
+        // Zero-length records do not shift the application data.
+        let padded_transcript = padded.to_transcript().unwrap();
+        assert_eq!(padded_transcript.sent(), sent.as_slice());
+        assert_eq!(padded_transcript.received(), recv.as_slice());
+
+        for direction in [Direction::Sent, Direction::Received] {
+            let boundaries = padded.record_boundaries(direction);
+            let ranges = boundaries
+                .iter()
+                .map(|boundary| boundary.range.clone())
+                .collect::<Vec<_>>();
+            let expected = transcript
+                .record_boundaries(direction)
+                .into_iter()
+                .map(|boundary| boundary.range)
+                .collect::<Vec<_>>();
+
+            assert_eq!(ranges, expected);
+            assert_eq!(padded.application_data(direction).count(), boundaries.len());
+
+            // Boundaries refer to the records carrying data.
+            let records = match direction {
+                Direction::Sent => padded.sent(),
+                Direction::Received => padded.recv(),
+            };
+            for boundary in boundaries {
+                let record = &records[boundary.index];
+                assert_eq!(record.seq, boundary.seq);
+                assert_eq!(record.ciphertext.len(), boundary.range.len());
+            }
+        }
+    }
+
+    #[test]
+    fn test_take_transcript() {
+        let sent = vec![1u8; RECORD_SIZE + 10];
+        let recv = vec![2u8; 2 * RECORD_SIZE];
+        let mut tls_transcript = transcript_fixture(&sent, &recv);
+
+        let transcript = tls_transcript.take_transcript().unwrap();
+        assert_eq!(transcript.sent(), sent.as_slice());
+        assert_eq!(transcript.received(), recv.as_slice());
+
+        for direction in [Direction::Sent, Direction::Received] {
+            assert!(tls_transcript
+                .application_data(direction)
+                .all(|record| record.plaintext.is_none()));
+        }
+        // The handshake records keep their plaintext.
+        assert!(tls_transcript.sent()[0].plaintext.is_some());
+
+        // The plaintext can not be recovered from the records anymore.
+        let seq = tls_transcript
+            .application_data(Direction::Sent)
+            .next()
+            .unwrap()
+            .seq;
+        let err = tls_transcript.to_transcript().unwrap_err();
+        assert!(matches!(
+            err.0,
+            ErrorRepr::Incomplete {
+                direction: Direction::Sent,
+                seq: err_seq,
+            } if err_seq == seq
+        ));
+    }
+
+    #[test]
+    fn test_record_length_mismatch_rejected() {
+        let transcript = transcript_fixture(&[1u8; 10], &[2u8; 10]);
+
+        let mut recv = transcript.recv().to_vec();
+        recv.last_mut().unwrap().plaintext.as_mut().unwrap().push(0);
+
+        let finished_vd = |records: &[Record]| records[0].plaintext.as_ref().unwrap()[4..].to_vec();
+        let verify_data = VerifyData {
+            client_finished: finished_vd(transcript.sent()),
+            server_finished: finished_vd(&recv),
+        };
+
+        assert!(TlsTranscript::new(
+            transcript.time(),
+            *transcript.version(),
+            transcript.server_cert_chain().map(|chain| chain.to_vec()),
+            transcript.server_signature().cloned(),
+            transcript.certificate_binding().clone(),
+            verify_data,
+            transcript.sent().to_vec(),
+            recv,
+        )
+        .is_err());
+    }
+
+    fn alert_record(seq: u64, description: AlertDescription) -> Record {
+        let mut payload = Vec::new();
+        AlertMessagePayload {
+            level: tls_core::msgs::enums::AlertLevel::Fatal,
+            description,
+        }
+        .encode(&mut payload);
+
+        Record {
+            seq,
+            typ: ContentType::Alert,
+            ciphertext: payload.clone(),
+            plaintext: Some(payload),
+            explicit_nonce: seq.to_be_bytes().to_vec(),
+            tag: None,
+        }
+    }
+
+    /// Rebuilds the fixture transcript with an alert appended in the given
+    /// direction.
+    fn append_alert(
+        transcript: &TlsTranscript,
+        direction: Direction,
+        description: AlertDescription,
+    ) -> Result<TlsTranscript, TlsTranscriptError> {
+        let mut sent = transcript.sent().to_vec();
+        let mut recv = transcript.recv().to_vec();
+
+        let records = match direction {
+            Direction::Sent => &mut sent,
+            Direction::Received => &mut recv,
+        };
+        records.push(alert_record(records.len() as u64, description));
+
+        let finished_vd = |records: &[Record]| records[0].plaintext.as_ref().unwrap()[4..].to_vec();
+        let verify_data = VerifyData {
+            client_finished: finished_vd(&sent),
+            server_finished: finished_vd(&recv),
+        };
+
+        TlsTranscript::new(
+            transcript.time(),
+            *transcript.version(),
+            transcript.server_cert_chain().map(|chain| chain.to_vec()),
+            transcript.server_signature().cloned(),
+            transcript.certificate_binding().clone(),
+            verify_data,
+            sent,
+            recv,
+        )
+    }
+
+    #[test]
+    fn test_received_fatal_alert() {
+        let transcript = transcript_fixture(&[1u8; 10], &[2u8; RECORD_SIZE + 10]);
+
+        let with_alert = append_alert(
+            &transcript,
+            Direction::Received,
+            AlertDescription::InternalError,
+        )
+        .unwrap();
+
+        // The alert is not part of the application data.
+        assert_eq!(
+            with_alert.to_transcript().unwrap().received(),
+            transcript.to_transcript().unwrap().received()
+        );
+        assert_eq!(
+            with_alert.record_boundaries(Direction::Received),
+            transcript.record_boundaries(Direction::Received)
+        );
+    }
+
+    #[test]
+    fn test_sent_fatal_alert_rejected() {
+        let transcript = transcript_fixture(&[1u8; 10], &[2u8; 10]);
+
+        assert!(append_alert(&transcript, Direction::Sent, AlertDescription::CloseNotify).is_ok());
+        assert!(append_alert(
+            &transcript,
+            Direction::Sent,
+            AlertDescription::InternalError
+        )
+        .is_err());
+    }
+}
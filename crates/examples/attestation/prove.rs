@@ -404,6 +404,7 @@ async fn notary<S: AsyncWrite + AsyncRead + Send + Sync + Unpin + 'static>(
                 sent: sent_len as u32,
                 received: recv_len as u32,
             },
+            truncated: tls_transcript.is_truncated(),
         })
         .server_ephemeral_key(tls_transcript.server_ephemeral_key().clone())
         .transcript_commitments(transcript_commitments);
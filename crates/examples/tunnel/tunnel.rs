@@ -0,0 +1,240 @@
+use std::{
+    env, io,
+    net::{IpAddr, SocketAddr},
+};
+
+use anyhow::Result;
+use http_body_util::Empty;
+use hyper::{body::Bytes, Request, StatusCode};
+use hyper_util::rt::TokioIo;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+};
+use tokio_util::compat::{Compat, FuturesAsyncReadCompatExt, TokioAsyncReadCompatExt};
+use tracing::instrument;
+
+use tlsn::{
+    config::{
+        prove::ProveConfig,
+        prover::ProverConfig,
+        tls::TlsClientConfig,
+        tls_commit::{mpc::MpcTlsConfig, TlsCommitConfig},
+        verifier::VerifierConfig,
+    },
+    connection::ServerName,
+    verifier::VerifierOutput,
+    webpki::{CertificateDer, RootCertStore},
+    Session,
+};
+use tlsn_server_fixture::DEFAULT_FIXTURE_PORT;
+use tlsn_server_fixture_certs::{CA_CERT_DER, SERVER_DOMAIN};
+
+#[tokio::main]
+async fn main() {
+    tracing_subscriber::fmt::init();
+
+    let server_host: String = env::var("SERVER_HOST").unwrap_or("127.0.0.1".into());
+    let server_port: u16 = env::var("SERVER_PORT")
+        .map(|port| port.parse().expect("port should be valid integer"))
+        .unwrap_or(DEFAULT_FIXTURE_PORT);
+    let server_ip: IpAddr = server_host.parse().expect("Invalid IP address");
+    let server_addr = SocketAddr::from((server_ip, server_port));
+
+    // Use the given proxy, or start a local one which forwards to the test
+    // server.
+    let proxy_addr = match env::var("PROXY_ADDR") {
+        Ok(addr) => addr.parse().expect("proxy address should be valid"),
+        Err(_) => spawn_proxy(server_addr).await.unwrap(),
+    };
+
+    // Connect prover and verifier.
+    let (prover_socket, verifier_socket) = tokio::io::duplex(1 << 23);
+    let prover = prover(prover_socket, proxy_addr, server_port);
+    let verifier = verifier(verifier_socket);
+    tokio::try_join!(prover, verifier).unwrap();
+
+    println!("Successfully notarized {SERVER_DOMAIN} through the proxy at {proxy_addr}");
+}
+
+#[instrument(skip(verifier_socket))]
+async fn prover<T: AsyncWrite + AsyncRead + Send + Unpin + 'static>(
+    verifier_socket: T,
+    proxy_addr: SocketAddr,
+    server_port: u16,
+) -> Result<()> {
+    let session = Session::new(verifier_socket.compat());
+    let (driver, mut handle) = session.split();
+    let driver_task = tokio::spawn(driver);
+
+    let prover = handle
+        .new_prover(ProverConfig::builder().build()?)?
+        .commit(
+            TlsCommitConfig::builder()
+                .protocol(
+                    MpcTlsConfig::builder()
+                        .max_sent_data(tlsn_examples::MAX_SENT_DATA)
+                        .max_recv_data(tlsn_examples::MAX_RECV_DATA)
+                        .build()?,
+                )
+                .build()?,
+        )
+        .await?;
+
+    // The tunnel is opened to the server name of the TLS configuration, which
+    // is also used for SNI of the inner TLS session. Any stream works here,
+    // e.g. a SOCKS5 connection or an outer TLS session to a proxy.
+    let (tls_connection, prover_fut) = prover
+        .connect_via(
+            TlsClientConfig::builder()
+                .server_name(ServerName::Dns(SERVER_DOMAIN.try_into()?))
+                .root_store(RootCertStore {
+                    roots: vec![CertificateDer(CA_CERT_DER.to_vec())],
+                })
+                .build()?,
+            |server_name| http_connect(proxy_addr, server_name, server_port),
+        )
+        .await?;
+    let tls_connection = TokioIo::new(tls_connection.compat());
+
+    let prover_task = tokio::spawn(prover_fut);
+
+    let (mut request_sender, connection) =
+        hyper::client::conn::http1::handshake(tls_connection).await?;
+    tokio::spawn(connection);
+
+    let request = Request::builder()
+        .uri("/formats/html")
+        .header("Host", SERVER_DOMAIN)
+        .header("Connection", "close")
+        .method("GET")
+        .body(Empty::<Bytes>::new())?;
+    let response = request_sender.send_request(request).await?;
+
+    assert!(response.status() == StatusCode::OK);
+
+    let mut prover = prover_task.await??;
+
+    let mut builder = ProveConfig::builder(prover.transcript());
+    builder.server_identity();
+    builder.reveal_recv(&(0..prover.transcript().received().len()))?;
+    let config = builder.build()?;
+
+    prover.prove(&config).await?;
+    prover.close().await?;
+
+    handle.close();
+    driver_task.await??;
+
+    Ok(())
+}
+
+#[instrument(skip(socket))]
+async fn verifier<T: AsyncWrite + AsyncRead + Send + Sync + Unpin + 'static>(
+    socket: T,
+) -> Result<()> {
+    let session = Session::new(socket.compat());
+    let (driver, mut handle) = session.split();
+    let driver_task = tokio::spawn(driver);
+
+    let verifier = handle.new_verifier(
+        VerifierConfig::builder()
+            .root_store(RootCertStore {
+                roots: vec![CertificateDer(CA_CERT_DER.to_vec())],
+            })
+            .build()?,
+    )?;
+
+    let verifier = verifier
+        .commit()
+        .await?
+        .accept()
+        .await?
+        .run()
+        .await?
+        .verify()
+        .await?;
+    let (VerifierOutput { server_name, .. }, verifier) = verifier.accept().await?;
+    verifier.close().await?;
+
+    handle.close();
+    driver_task.await??;
+
+    // The verified server name is the one of the inner TLS session, not the
+    // proxy.
+    let ServerName::Dns(server_name) = server_name.expect("prover should reveal the server name");
+    assert_eq!(server_name.as_str(), SERVER_DOMAIN);
+
+    Ok(())
+}
+
+/// Opens a tunnel to the server through an HTTP CONNECT proxy.
+async fn http_connect(
+    proxy_addr: SocketAddr,
+    server_name: ServerName,
+    server_port: u16,
+) -> io::Result<Compat<TcpStream>> {
+    let mut stream = TcpStream::connect(proxy_addr).await?;
+    stream
+        .write_all(
+            format!(
+                "CONNECT {server_name}:{server_port} HTTP/1.1\r\n\
+                 Host: {server_name}:{server_port}\r\n\r\n"
+            )
+            .as_bytes(),
+        )
+        .await?;
+
+    // Read the response head byte by byte, so that no data of the tunnel is
+    // buffered.
+    let mut head = Vec::new();
+    while !head.ends_with(b"\r\n\r\n") {
+        let mut byte = [0u8];
+        stream.read_exact(&mut byte).await?;
+        head.push(byte[0]);
+    }
+
+    let status = String::from_utf8_lossy(&head);
+    if !status.starts_with("HTTP/1.1 200") && !status.starts_with("HTTP/1.0 200") {
+        return Err(io::Error::other(format!(
+            "proxy refused to connect: {}",
+            status.lines().next().unwrap_or_default()
+        )));
+    }
+
+    Ok(stream.compat())
+}
+
+/// Starts a minimal HTTP CONNECT proxy which forwards every tunnel to the
+/// test server, as its domain does not resolve.
+async fn spawn_proxy(server_addr: SocketAddr) -> io::Result<SocketAddr> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+
+    tokio::spawn(async move {
+        while let Ok((client, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let mut client = BufReader::new(client);
+
+                let mut line = String::new();
+                while line != "\r\n" {
+                    line.clear();
+                    if client.read_line(&mut line).await? == 0 {
+                        return Ok(());
+                    }
+                }
+
+                let mut server = TcpStream::connect(server_addr).await?;
+                client
+                    .get_mut()
+                    .write_all(b"HTTP/1.1 200 Connection established\r\n\r\n")
+                    .await?;
+                tokio::io::copy_bidirectional(&mut client, &mut server).await?;
+
+                io::Result::Ok(())
+            });
+        }
+    });
+
+    Ok(addr)
+}
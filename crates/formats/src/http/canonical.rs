@@ -0,0 +1,329 @@
+//! Canonical HTTP requests.
+//!
+//! A relying party is often only interested in what was requested, e.g. `GET
+//! https://example.com/api?id=..`, and not in how the request was formatted.
+//! A [`CanonicalRequest`] captures the method, host, path and sorted query
+//! keys of a request, so that it is robust to header ordering and other
+//! formatting differences.
+//!
+//! A [`RequestDigest`] is a digest of a canonical request along with the
+//! ranges of the transcript it was computed from. The Prover commits to these
+//! ranges, see [`DefaultHttpCommitter::with_request_digest`], so that the
+//! digest is linked to the raw transcript: once the ranges are revealed the
+//! digest can be [verified](RequestDigest::verify) against them.
+//!
+//! [`DefaultHttpCommitter::with_request_digest`]: crate::http::DefaultHttpCommitter::with_request_digest
+
+use rangeset::{iter::FromRangeIterator, ops::Set, set::RangeSet};
+use serde::{Deserialize, Serialize};
+use tlsn_core::{
+    hash::{HashAlgorithm, HashProvider, TypedHash},
+    transcript::{Direction, PartialTranscript},
+};
+
+use crate::http::Request;
+
+/// Prefix of the canonical request encoding, for domain separation.
+const DIGEST_PREFIX: &[u8] = b"tlsn.http.canonical-request";
+
+/// HTTP canonical request error.
+#[derive(Debug, thiserror::Error)]
+#[error("http canonical request error: {0}")]
+pub struct HttpCanonicalError(String);
+
+/// A canonical HTTP request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CanonicalRequest {
+    method: String,
+    host: String,
+    path: String,
+    query_keys: Vec<String>,
+}
+
+impl CanonicalRequest {
+    /// Creates the canonical form of a request.
+    pub fn new(request: &Request) -> Result<Self, HttpCanonicalError> {
+        let host = host_header(request)
+            .map(|header| {
+                std::str::from_utf8(header.value.as_bytes())
+                    .map_err(|_| HttpCanonicalError("host header is not UTF-8".to_string()))
+            })
+            .transpose()?;
+
+        Self::from_parts(
+            request.request.method.as_str(),
+            request.request.target.as_str(),
+            host,
+        )
+    }
+
+    fn from_parts(
+        method: &str,
+        target: &str,
+        host: Option<&str>,
+    ) -> Result<Self, HttpCanonicalError> {
+        let (authority, path_and_query) = if target == "*" {
+            (None, "")
+        } else if let Some((_, rest)) = target.split_once("://") {
+            // absolute-form, the authority takes precedence over the Host
+            // header.
+            match rest.find('/') {
+                Some(pos) => (Some(&rest[..pos]), &rest[pos..]),
+                None => (Some(rest), "/"),
+            }
+        } else if target.starts_with('/') {
+            (None, target)
+        } else {
+            // authority-form, e.g. a CONNECT request.
+            (Some(target), "")
+        };
+
+        let host = authority
+            .or(host)
+            .map(|host| host.trim().to_ascii_lowercase())
+            .filter(|host| !host.is_empty())
+            .ok_or_else(|| HttpCanonicalError("request is missing a host".to_string()))?;
+
+        let path_and_query = path_and_query
+            .split_once('#')
+            .map_or(path_and_query, |(path_and_query, _)| path_and_query);
+        let (path, query) = path_and_query
+            .split_once('?')
+            .unwrap_or((path_and_query, ""));
+
+        let mut query_keys: Vec<String> = query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                pair.split_once('=')
+                    .map_or(pair, |(key, _)| key)
+                    .to_string()
+            })
+            .collect();
+        query_keys.sort();
+
+        Ok(Self {
+            method: method.to_ascii_uppercase(),
+            host,
+            path: path.to_string(),
+            query_keys,
+        })
+    }
+
+    /// Returns the method.
+    pub fn method(&self) -> &str {
+        &self.method
+    }
+
+    /// Returns the host, in lowercase.
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    /// Returns the path.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the sorted query keys.
+    pub fn query_keys(&self) -> &[String] {
+        &self.query_keys
+    }
+
+    /// Returns the canonical encoding of the request.
+    ///
+    /// The method, host, path and `&` separated query keys, each terminated by
+    /// a `\n`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        format!(
+            "{}\n{}\n{}\n{}\n",
+            self.method,
+            self.host,
+            self.path,
+            self.query_keys.join("&")
+        )
+        .into_bytes()
+    }
+
+    /// Computes the digest of the request.
+    pub fn digest(&self, hasher: &dyn HashAlgorithm) -> TypedHash {
+        TypedHash {
+            alg: hasher.id(),
+            value: hasher.hash_prefixed(DIGEST_PREFIX, &self.to_bytes()),
+        }
+    }
+}
+
+/// A digest of a [`CanonicalRequest`] and the ranges of the transcript it was
+/// computed from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequestDigest {
+    direction: Direction,
+    method: RangeSet<usize>,
+    target: RangeSet<usize>,
+    host: Option<RangeSet<usize>>,
+    digest: TypedHash,
+}
+
+impl RequestDigest {
+    /// Computes the digest of a request.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The request.
+    /// * `direction` - The direction of the request in the transcript.
+    /// * `hasher` - The hash algorithm.
+    pub fn new(
+        request: &Request,
+        direction: Direction,
+        hasher: &dyn HashAlgorithm,
+    ) -> Result<Self, HttpCanonicalError> {
+        let digest = CanonicalRequest::new(request)?.digest(hasher);
+
+        Ok(Self {
+            direction,
+            method: RangeSet::from_range_iter(&request.request.method),
+            target: RangeSet::from_range_iter(&request.request.target),
+            host: host_header(request).map(|header| RangeSet::from_range_iter(&header.value)),
+            digest,
+        })
+    }
+
+    /// Returns the direction of the request in the transcript.
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// Returns the ranges of the transcript the digest was computed from.
+    ///
+    /// These are the method, the target and the value of the host header.
+    pub fn idx(&self) -> RangeSet<usize> {
+        let mut idx = self.method.clone();
+        idx.union_mut(&self.target);
+        if let Some(host) = &self.host {
+            idx.union_mut(host);
+        }
+
+        idx
+    }
+
+    /// Returns the digest.
+    pub fn digest(&self) -> &TypedHash {
+        &self.digest
+    }
+
+    /// Verifies the digest against an authenticated transcript, returning the
+    /// canonical request.
+    ///
+    /// The [ranges](Self::idx) of the digest must be authenticated.
+    ///
+    /// # Arguments
+    ///
+    /// * `transcript` - The authenticated transcript.
+    /// * `provider` - The hash provider.
+    pub fn verify(
+        &self,
+        transcript: &PartialTranscript,
+        provider: &HashProvider,
+    ) -> Result<CanonicalRequest, HttpCanonicalError> {
+        let (data, authed) = match self.direction {
+            Direction::Sent => (transcript.sent_unsafe(), transcript.sent_authed()),
+            Direction::Received => (transcript.received_unsafe(), transcript.received_authed()),
+        };
+
+        if !self.idx().is_subset(authed) {
+            return Err(HttpCanonicalError(
+                "request digest ranges are not authenticated".to_string(),
+            ));
+        }
+
+        let read = |idx: &RangeSet<usize>| {
+            let mut bytes = Vec::with_capacity(idx.len());
+            for range in idx.iter() {
+                bytes.extend_from_slice(&data[range]);
+            }
+            String::from_utf8(bytes)
+                .map_err(|_| HttpCanonicalError("request digest ranges are not UTF-8".to_string()))
+        };
+
+        let hasher = provider
+            .get(&self.digest.alg)
+            .map_err(|e| HttpCanonicalError(e.to_string()))?;
+        let host = self.host.as_ref().map(read).transpose()?;
+        let request = CanonicalRequest::from_parts(
+            &read(&self.method)?,
+            &read(&self.target)?,
+            host.as_deref(),
+        )?;
+
+        if request.digest(hasher) != self.digest {
+            return Err(HttpCanonicalError(
+                "request digest does not match the transcript".to_string(),
+            ));
+        }
+
+        Ok(request)
+    }
+}
+
+fn host_header(request: &Request) -> Option<&crate::http::Header> {
+    request.headers_with_name("host").next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::parse_request;
+    use tlsn_core::{hash::Sha256, transcript::Transcript};
+
+    #[test]
+    fn test_canonical_request() {
+        let a = parse_request(
+            b"GET /api/items?b=2&a=1&c HTTP/1.1\r\nHost: Example.com\r\nAccept: */*\r\n\r\n"
+                .as_slice(),
+        )
+        .unwrap();
+        let b = parse_request(
+            b"get /api/items?c=3&a=&b=1 HTTP/1.1\r\nAccept: */*\r\nhost: example.com\r\n\r\n"
+                .as_slice(),
+        )
+        .unwrap();
+
+        let canonical = CanonicalRequest::new(&a).unwrap();
+        assert_eq!(canonical, CanonicalRequest::new(&b).unwrap());
+        assert_eq!(
+            canonical.to_bytes(),
+            b"GET\nexample.com\n/api/items\na&b&c\n"
+        );
+    }
+
+    #[test]
+    fn test_canonical_request_absolute_form() {
+        let request =
+            CanonicalRequest::from_parts("GET", "https://example.com?x=1", Some("ignored"))
+                .unwrap();
+
+        assert_eq!(request.host(), "example.com");
+        assert_eq!(request.path(), "/");
+        assert_eq!(request.query_keys(), ["x"]);
+
+        assert!(CanonicalRequest::from_parts("GET", "/", None).is_err());
+    }
+
+    #[test]
+    fn test_request_digest_verify() {
+        let src = b"GET /a?k=v HTTP/1.1\r\nHost: example.com\r\nSecret: 1\r\n\r\n";
+        let request = parse_request(src.as_slice()).unwrap();
+        let digest = RequestDigest::new(&request, Direction::Sent, &Sha256::default()).unwrap();
+
+        let transcript = Transcript::new(src, []);
+        let provider = HashProvider::default();
+
+        let partial = transcript.to_partial(digest.idx(), RangeSet::default());
+        let canonical = digest.verify(&partial, &provider).unwrap();
+        assert_eq!(canonical, CanonicalRequest::new(&request).unwrap());
+
+        let partial = transcript.to_partial(digest.method.clone(), RangeSet::default());
+        assert!(digest.verify(&partial, &provider).is_err());
+    }
+}
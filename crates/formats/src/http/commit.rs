@@ -1,9 +1,15 @@
 use std::error::Error;
 
-use tlsn_core::transcript::{Direction, TranscriptCommitConfigBuilder};
+use tlsn_core::{
+    hash::{HashAlgId, HashProvider},
+    transcript::{Direction, TranscriptCommitConfigBuilder, TranscriptCommitmentKind},
+};
 
 use crate::{
-    http::{Body, BodyContent, Header, HttpTranscript, MessageKind, Request, Response, Target},
+    http::{
+        Body, BodyContent, Header, HttpTranscript, MessageKind, Request, RequestDigest, Response,
+        Target,
+    },
     json::{DefaultJsonCommitter, JsonCommit},
 };
 
@@ -141,6 +147,8 @@ pub trait HttpCommit {
 
         self.commit_target(builder, direction, request, &request.request.target)?;
 
+        self.commit_request_digest(builder, direction, request)?;
+
         for header in &request.headers {
             self.commit_request_header(builder, direction, request, header)?;
         }
@@ -180,6 +188,25 @@ pub trait HttpCommit {
         Ok(())
     }
 
+    /// Commits to the ranges of a request its [`RequestDigest`] is computed
+    /// from.
+    ///
+    /// The default implementation does nothing.
+    ///
+    /// # Arguments
+    ///
+    /// * `builder` - The transcript commitment builder.
+    /// * `direction` - The direction of the request (sent or received).
+    /// * `request` - The request to commit to.
+    fn commit_request_digest(
+        &mut self,
+        builder: &mut TranscriptCommitConfigBuilder,
+        direction: Direction,
+        request: &Request,
+    ) -> Result<(), HttpCommitError> {
+        Ok(())
+    }
+
     /// Commits to a request header.
     ///
     /// The default implementation commits to the entire header, and the header
@@ -409,9 +436,71 @@ pub trait HttpCommit {
 
 /// The default HTTP committer.
 #[derive(Debug, Default, Clone)]
-pub struct DefaultHttpCommitter {}
+pub struct DefaultHttpCommitter {
+    request_digest: Option<HashAlgId>,
+    request_digests: Vec<RequestDigest>,
+}
+
+impl DefaultHttpCommitter {
+    /// Computes a [`RequestDigest`] of each request with the given hash
+    /// algorithm, and commits to the ranges it is computed from with a hash
+    /// commitment of the same algorithm.
+    pub fn with_request_digest(mut self, alg: HashAlgId) -> Self {
+        self.request_digest = Some(alg);
+        self
+    }
+
+    /// Returns the digests of the committed requests.
+    pub fn request_digests(&self) -> &[RequestDigest] {
+        &self.request_digests
+    }
+}
 
-impl HttpCommit for DefaultHttpCommitter {}
+impl HttpCommit for DefaultHttpCommitter {
+    fn commit_request_digest(
+        &mut self,
+        builder: &mut TranscriptCommitConfigBuilder,
+        direction: Direction,
+        request: &Request,
+    ) -> Result<(), HttpCommitError> {
+        let Some(alg) = self.request_digest else {
+            return Ok(());
+        };
+
+        let provider = HashProvider::default();
+        let hasher = provider.get(&alg).map_err(|e| {
+            HttpCommitError::new_with_source(
+                MessageKind::Request,
+                "unsupported request digest hash algorithm",
+                e,
+            )
+        })?;
+        let digest = RequestDigest::new(request, direction, hasher).map_err(|e| {
+            HttpCommitError::new_with_source(
+                MessageKind::Request,
+                "failed to compute request digest",
+                e,
+            )
+        })?;
+
+        builder
+            .commit_with_kind(
+                &digest.idx(),
+                direction,
+                TranscriptCommitmentKind::Hash { alg },
+            )
+            .map_err(|e| {
+                HttpCommitError::new_with_source(
+                    MessageKind::Request,
+                    "failed to commit to request digest ranges",
+                    e,
+                )
+            })?;
+        self.request_digests.push(digest);
+
+        Ok(())
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -440,6 +529,31 @@ mod tests {
         builder.build().unwrap();
     }
 
+    #[test]
+    fn test_http_commit_request_digest() {
+        let src = fixtures::request::GET_WITH_HEADER;
+        let transcript = Transcript::new(src, []);
+        let request = parse_request(Bytes::from_static(src)).unwrap();
+        let mut committer = DefaultHttpCommitter::default().with_request_digest(HashAlgId::SHA256);
+        let mut builder = TranscriptCommitConfigBuilder::new(&transcript);
+
+        committer
+            .commit_request(&mut builder, Direction::Sent, &request)
+            .unwrap();
+
+        let [digest] = committer.request_digests() else {
+            panic!("expected a single request digest");
+        };
+        let idx = digest.idx();
+        assert!(builder
+            .build()
+            .unwrap()
+            .iter_hash()
+            .any(|((direction, commit_idx), alg)| {
+                *direction == Direction::Sent && *commit_idx == idx && *alg == HashAlgId::SHA256
+            }));
+    }
+
     #[rstest]
     #[case::empty(fixtures::response::OK_EMPTY)]
     #[case::empty_header(fixtures::response::OK_EMPTY_HEADER)]
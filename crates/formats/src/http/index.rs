@@ -0,0 +1,186 @@
+use std::collections::BTreeMap;
+
+use rangeset::{
+    iter::{FromRangeIterator, IntoRangeIterator},
+    set::RangeSet,
+};
+use serde::{Deserialize, Serialize};
+use tlsn_core::transcript::Direction;
+
+use crate::{
+    http::{BodyContent, Header, HttpTranscript},
+    json::JsonValue,
+};
+
+/// An index of the items in an HTTP transcript.
+///
+/// The index maps keys identifying semantic items of the transcript to their
+/// location in the transcript. It is intended to be built when committing to
+/// the transcript and stored alongside the commitment secrets, so that proofs
+/// can be constructed later without parsing the transcript again.
+///
+/// # Keys
+///
+/// Requests and responses are identified by `req[i]` and `resp[i]`
+/// respectively, where `i` is the index of the message in the transcript.
+/// Items within a message are separated by `.`:
+///
+/// - `req[i].method` - The request method.
+/// - `req[i].target` - The request target.
+/// - `resp[i].status` - The response status line.
+/// - `req[i].header.<name>` - A header, where `<name>` is lowercase. If a
+///   header appears more than once only the first one is indexed.
+/// - `req[i].body` - The body.
+/// - `req[i].body.<path>` - A value in a JSON body, where `<path>` is a `.`
+///   separated path of object keys. Array elements are not indexed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HttpIndex {
+    items: BTreeMap<String, (Direction, RangeSet<usize>)>,
+}
+
+impl HttpIndex {
+    /// Builds an index of the provided transcript.
+    pub fn new(transcript: &HttpTranscript) -> Self {
+        let mut index = Self::default();
+
+        for (i, request) in transcript.requests.iter().enumerate() {
+            let prefix = format!("req[{i}]");
+            let direction = Direction::Sent;
+
+            index.insert(&prefix, direction, request);
+            index.insert(
+                format!("{prefix}.method"),
+                direction,
+                &request.request.method,
+            );
+            index.insert(
+                format!("{prefix}.target"),
+                direction,
+                &request.request.target,
+            );
+            index.insert_headers(&prefix, direction, &request.headers);
+
+            if let Some(body) = &request.body {
+                index.insert(format!("{prefix}.body"), direction, body);
+                if let BodyContent::Json(value) = &body.content {
+                    index.insert_json(&format!("{prefix}.body"), direction, value);
+                }
+            }
+        }
+
+        for (i, response) in transcript.responses.iter().enumerate() {
+            let prefix = format!("resp[{i}]");
+            let direction = Direction::Received;
+
+            index.insert(&prefix, direction, response);
+            index.insert(format!("{prefix}.status"), direction, &response.status);
+            index.insert_headers(&prefix, direction, &response.headers);
+
+            if let Some(body) = &response.body {
+                index.insert(format!("{prefix}.body"), direction, body);
+                if let BodyContent::Json(value) = &body.content {
+                    index.insert_json(&format!("{prefix}.body"), direction, value);
+                }
+            }
+        }
+
+        index
+    }
+
+    /// Returns the direction and ranges of the item with the given key.
+    pub fn get(&self, key: &str) -> Option<(Direction, &RangeSet<usize>)> {
+        self.items
+            .get(key)
+            .map(|(direction, idx)| (*direction, idx))
+    }
+
+    /// Returns an iterator over the keys and locations of the indexed items,
+    /// ordered by key.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Direction, &RangeSet<usize>)> {
+        self.items
+            .iter()
+            .map(|(key, (direction, idx))| (key.as_str(), *direction, idx))
+    }
+
+    /// Returns the number of indexed items.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if the index is empty.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    fn insert(
+        &mut self,
+        key: impl Into<String>,
+        direction: Direction,
+        ranges: impl IntoRangeIterator<usize>,
+    ) {
+        self.items
+            .entry(key.into())
+            .or_insert_with(|| (direction, RangeSet::from_range_iter(ranges)));
+    }
+
+    fn insert_headers(&mut self, prefix: &str, direction: Direction, headers: &[Header]) {
+        for header in headers {
+            let name = header.name.as_str().to_ascii_lowercase();
+            self.insert(format!("{prefix}.header.{name}"), direction, header);
+        }
+    }
+
+    fn insert_json(&mut self, prefix: &str, direction: Direction, value: &JsonValue) {
+        let JsonValue::Object(object) = value else {
+            return;
+        };
+
+        for kv in &object.elems {
+            let key = format!("{prefix}.{}", kv.key.as_str());
+            self.insert(&key, direction, &kv.value);
+            self.insert_json(&key, direction, &kv.value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tlsn_core::transcript::Transcript;
+    use tlsn_data_fixtures::http as fixtures;
+
+    #[test]
+    fn test_http_index() {
+        let transcript = Transcript::new(
+            fixtures::request::GET_WITH_HEADER,
+            fixtures::response::OK_JSON,
+        );
+        let http = HttpTranscript::parse(&transcript).unwrap();
+        let index = HttpIndex::new(&http);
+
+        let (direction, idx) = index.get("req[0].target").unwrap();
+        assert_eq!(direction, Direction::Sent);
+        assert_eq!(
+            idx,
+            &RangeSet::from_range_iter(&http.requests[0].request.target)
+        );
+
+        let (direction, idx) = index.get("resp[0]").unwrap();
+        assert_eq!(direction, Direction::Received);
+        assert_eq!(idx, &RangeSet::from(0..transcript.received().len()));
+
+        assert!(index.get("resp[0].body").is_some());
+        assert!(index.get("resp[1]").is_none());
+    }
+
+    #[test]
+    fn test_http_index_serde_roundtrip() {
+        let transcript = Transcript::new(fixtures::request::POST_JSON, fixtures::response::OK_JSON);
+        let index = HttpIndex::new(&HttpTranscript::parse(&transcript).unwrap());
+
+        let bytes = bincode::serialize(&index).unwrap();
+        let index_2: HttpIndex = bincode::deserialize(&bytes).unwrap();
+
+        assert_eq!(index, index_2);
+    }
+}
@@ -1,8 +1,20 @@
 //! Tooling for working with HTTP data.
 
+mod canonical;
 mod commit;
+mod index;
+mod padding;
+mod policy;
+mod select;
+mod template;
 
+pub use canonical::{CanonicalRequest, HttpCanonicalError, RequestDigest};
 pub use commit::{DefaultHttpCommitter, HttpCommit, HttpCommitError};
+pub use index::HttpIndex;
+pub use padding::{HttpPaddingError, PaddingPolicy, PADDING_HEADER};
+pub use policy::{Assertion, AssertionResult, Comparison, HttpPolicy, PolicyReport};
+pub use select::{HttpSelectError, RevealHttp};
+pub use template::{CommitTemplate, TemplateHttpCommitter, UnresolvedSelector};
 
 #[doc(hidden)]
 pub use spansy::http;
@@ -11,7 +23,7 @@ pub use http::{
     parse_request, parse_response, Body, BodyContent, Header, HeaderName, HeaderValue, Method,
     Reason, Request, RequestLine, Requests, Response, Responses, Status, Target,
 };
-use tlsn_core::transcript::Transcript;
+use tlsn_core::transcript::{Direction, TranscriptStorage, TranscriptStorageError};
 
 /// The kind of HTTP message.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -32,12 +44,23 @@ pub struct HttpTranscript {
 }
 
 impl HttpTranscript {
-    /// Parses the HTTP transcript from the provided transcripts.
-    pub fn parse(transcript: &Transcript) -> Result<Self, spansy::ParseError> {
-        let requests =
-            Requests::new_from_slice(transcript.sent()).collect::<Result<Vec<_>, _>>()?;
-        let responses =
-            Responses::new_from_slice(transcript.received()).collect::<Result<Vec<_>, _>>()?;
+    /// Parses the HTTP transcript from the provided transcript storage.
+    pub fn parse<T: TranscriptStorage + ?Sized>(
+        transcript: &T,
+    ) -> Result<Self, HttpTranscriptError> {
+        let sent = transcript
+            .read_all(Direction::Sent)
+            .map_err(ErrorRepr::Storage)?;
+        let received = transcript
+            .read_all(Direction::Received)
+            .map_err(ErrorRepr::Storage)?;
+
+        let requests = Requests::new_from_slice(&sent)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(ErrorRepr::Parse)?;
+        let responses = Responses::new_from_slice(&received)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(ErrorRepr::Parse)?;
 
         Ok(Self {
             requests,
@@ -45,3 +68,16 @@ impl HttpTranscript {
         })
     }
 }
+
+/// Error for [`HttpTranscript::parse`].
+#[derive(Debug, thiserror::Error)]
+#[error("http transcript error: {0}")]
+pub struct HttpTranscriptError(#[from] ErrorRepr);
+
+#[derive(Debug, thiserror::Error)]
+enum ErrorRepr {
+    #[error("failed to read transcript: {0}")]
+    Storage(TranscriptStorageError),
+    #[error("failed to parse transcript: {0}")]
+    Parse(spansy::ParseError),
+}
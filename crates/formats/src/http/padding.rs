@@ -0,0 +1,219 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::http::Requests;
+
+/// Name of the header used to pad requests.
+pub const PADDING_HEADER: &str = "X-Padding";
+
+/// Length of a padding header with an empty value, including the CRLF.
+const PADDING_HEADER_OVERHEAD: usize = PADDING_HEADER.len() + 4;
+
+/// HTTP padding error.
+#[derive(Debug, thiserror::Error)]
+#[error("http padding error: {0}")]
+pub struct HttpPaddingError(String);
+
+/// A policy for padding HTTP requests to a fixed set of lengths.
+///
+/// The length of the transcript is disclosed in an attestation, which can
+/// leak information about its contents. Padding requests to one of a small
+/// number of bucket lengths before they are sent limits what can be learned
+/// from the length of the sent data.
+///
+/// Requests are padded by inserting a [`PADDING_HEADER`] header, so the policy
+/// only applies to data sent to the server. The policy is formatted as a
+/// comma separated list of bucket lengths, e.g. `256,512,1024`, which can be
+/// included in an attestation as an extension so that a verifier knows the
+/// sent length is not meaningful.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaddingPolicy {
+    buckets: Vec<usize>,
+}
+
+impl PaddingPolicy {
+    /// Extension identifier for recording the policy in an attestation.
+    pub const EXTENSION_ID: &'static [u8] = b"tlsn.http.padding";
+
+    /// Creates a new padding policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `buckets` - The lengths requests are padded to.
+    pub fn new(buckets: impl IntoIterator<Item = usize>) -> Result<Self, HttpPaddingError> {
+        let mut buckets: Vec<usize> = buckets.into_iter().collect();
+        buckets.sort_unstable();
+        buckets.dedup();
+
+        if buckets.is_empty() {
+            return Err(HttpPaddingError(
+                "padding policy must have at least one bucket".to_string(),
+            ));
+        }
+
+        Ok(Self { buckets })
+    }
+
+    /// Returns the bucket lengths in ascending order.
+    pub fn buckets(&self) -> &[usize] {
+        &self.buckets
+    }
+
+    /// Returns the length a request of the given length is padded to, or
+    /// `None` if it does not fit in any bucket.
+    pub fn padded_len(&self, len: usize) -> Option<usize> {
+        self.buckets
+            .iter()
+            .copied()
+            .find(|&bucket| bucket == len || bucket >= len + PADDING_HEADER_OVERHEAD)
+    }
+
+    /// Returns the request padded to the smallest bucket it fits in.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - A single serialized HTTP request.
+    pub fn pad_request(&self, request: &[u8]) -> Result<Vec<u8>, HttpPaddingError> {
+        let mut requests = Requests::new_from_slice(request);
+        match (requests.next(), requests.next()) {
+            (Some(Ok(_)), None) => {}
+            (Some(Err(e)), _) => {
+                return Err(HttpPaddingError(format!("failed to parse request: {e}")))
+            }
+            _ => return Err(HttpPaddingError("expected exactly one request".to_string())),
+        }
+
+        let padded_len = self.padded_len(request.len()).ok_or_else(|| {
+            HttpPaddingError(format!(
+                "request of length {} exceeds the largest bucket",
+                request.len()
+            ))
+        })?;
+
+        if padded_len == request.len() {
+            return Ok(request.to_vec());
+        }
+
+        // Insert the header before the empty line terminating the headers.
+        let headers_end = request
+            .windows(4)
+            .position(|window| window == b"\r\n\r\n")
+            .expect("request was parsed")
+            + 2;
+        let value_len = padded_len - request.len() - PADDING_HEADER_OVERHEAD;
+
+        let mut padded = Vec::with_capacity(padded_len);
+        padded.extend_from_slice(&request[..headers_end]);
+        padded.extend_from_slice(PADDING_HEADER.as_bytes());
+        padded.extend_from_slice(b": ");
+        padded.resize(padded.len() + value_len, b'0');
+        padded.extend_from_slice(b"\r\n");
+        padded.extend_from_slice(&request[headers_end..]);
+
+        debug_assert_eq!(padded.len(), padded_len);
+
+        Ok(padded)
+    }
+}
+
+impl fmt::Display for PaddingPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, bucket) in self.buckets.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{bucket}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for PaddingPolicy {
+    type Err = HttpPaddingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let buckets = s
+            .split(',')
+            .map(|bucket| {
+                bucket
+                    .trim()
+                    .parse::<usize>()
+                    .map_err(|_| HttpPaddingError(format!("invalid bucket length: {bucket}")))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Self::new(buckets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use tlsn_data_fixtures::http as fixtures;
+
+    #[rstest]
+    #[case::get(fixtures::request::GET_EMPTY)]
+    #[case::get_with_header(fixtures::request::GET_WITH_HEADER)]
+    #[case::post_json(fixtures::request::POST_JSON)]
+    fn test_pad_request(#[case] request: &[u8]) {
+        let policy = PaddingPolicy::new([256, 512, 1024]).unwrap();
+
+        let padded = policy.pad_request(request).unwrap();
+        let parsed = Requests::new_from_slice(&padded).next().unwrap().unwrap();
+
+        assert_eq!(Some(padded.len()), policy.padded_len(request.len()));
+        assert!(policy.buckets().contains(&padded.len()));
+
+        assert!(parsed
+            .headers
+            .iter()
+            .any(|header| header.name.as_str() == PADDING_HEADER));
+
+        // Removing the padding header restores the original request.
+        let start = padded
+            .windows(PADDING_HEADER.len())
+            .position(|window| window == PADDING_HEADER.as_bytes())
+            .unwrap();
+        let end = start
+            + padded[start..]
+                .windows(2)
+                .position(|window| window == b"\r\n")
+                .unwrap()
+            + 2;
+        let mut unpadded = padded[..start].to_vec();
+        unpadded.extend_from_slice(&padded[end..]);
+
+        assert_eq!(unpadded, request);
+    }
+
+    #[test]
+    fn test_pad_request_too_large() {
+        let policy = PaddingPolicy::new([16]).unwrap();
+
+        assert!(policy
+            .pad_request(fixtures::request::GET_WITH_HEADER)
+            .is_err());
+    }
+
+    #[test]
+    fn test_padded_len_skips_small_gaps() {
+        let policy = PaddingPolicy::new([100, 105, 200]).unwrap();
+
+        assert_eq!(policy.padded_len(100), Some(100));
+        assert_eq!(policy.padded_len(99), Some(200));
+        assert_eq!(policy.padded_len(201), None);
+    }
+
+    #[test]
+    fn test_padding_policy_string_roundtrip() {
+        let policy: PaddingPolicy = "1024, 256,512".parse().unwrap();
+
+        assert_eq!(policy.buckets(), &[256, 512, 1024]);
+        assert_eq!(policy.to_string(), "256,512,1024");
+        assert!("".parse::<PaddingPolicy>().is_err());
+        assert!("256,foo".parse::<PaddingPolicy>().is_err());
+    }
+}
@@ -0,0 +1,360 @@
+use std::fmt;
+
+use rangeset::ops::Set;
+use serde::{Deserialize, Serialize};
+use tlsn_core::transcript::{Direction, PartialTranscript, Transcript};
+
+use crate::http::HttpTranscript;
+
+/// A comparison operator for numeric assertions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Comparison {
+    /// Equal to.
+    Eq,
+    /// Not equal to.
+    Ne,
+    /// Less than.
+    Lt,
+    /// Less than or equal to.
+    Le,
+    /// Greater than.
+    Gt,
+    /// Greater than or equal to.
+    Ge,
+}
+
+impl Comparison {
+    fn eval(&self, lhs: f64, rhs: f64) -> bool {
+        match self {
+            Comparison::Eq => lhs == rhs,
+            Comparison::Ne => lhs != rhs,
+            Comparison::Lt => lhs < rhs,
+            Comparison::Le => lhs <= rhs,
+            Comparison::Gt => lhs > rhs,
+            Comparison::Ge => lhs >= rhs,
+        }
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Comparison::Eq => "==",
+            Comparison::Ne => "!=",
+            Comparison::Lt => "<",
+            Comparison::Le => "<=",
+            Comparison::Gt => ">",
+            Comparison::Ge => ">=",
+        })
+    }
+}
+
+/// An assertion over an item of an HTTP transcript.
+///
+/// Items are identified by selectors, see [`HttpTranscript::select`]. An
+/// assertion only passes if the item is fully authenticated.
+///
+/// The value of an item is its text, except that for headers it is the
+/// header value, and for JSON strings the surrounding quotes are removed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Assertion {
+    /// The item exists.
+    Exists {
+        /// Selector of the item.
+        selector: String,
+    },
+    /// The value of the item equals the given value.
+    Equals {
+        /// Selector of the item.
+        selector: String,
+        /// The expected value.
+        value: String,
+    },
+    /// The value of the item is a number which compares to the given value.
+    Compare {
+        /// Selector of the item.
+        selector: String,
+        /// The comparison operator.
+        op: Comparison,
+        /// The value to compare to.
+        value: f64,
+    },
+}
+
+impl Assertion {
+    /// Returns the selector of the item the assertion applies to.
+    pub fn selector(&self) -> &str {
+        match self {
+            Assertion::Exists { selector }
+            | Assertion::Equals { selector, .. }
+            | Assertion::Compare { selector, .. } => selector,
+        }
+    }
+
+    fn eval(&self, http: &HttpTranscript, transcript: &PartialTranscript) -> Result<(), String> {
+        let selector = self.selector();
+        let (direction, idx) = http.select(selector).map_err(|e| e.msg().to_string())?;
+
+        let (data, authed) = match direction {
+            Direction::Sent => (transcript.sent_unsafe(), transcript.sent_authed()),
+            Direction::Received => (transcript.received_unsafe(), transcript.received_authed()),
+        };
+
+        if !idx.is_subset(authed) {
+            return Err(format!("{selector} is not authenticated"));
+        }
+
+        let mut bytes = Vec::with_capacity(idx.len());
+        for range in idx.iter() {
+            bytes.extend_from_slice(&data[range]);
+        }
+        let text = String::from_utf8(bytes).map_err(|_| format!("{selector} is not UTF-8"))?;
+        let value = item_value(selector, &text);
+
+        match self {
+            Assertion::Exists { .. } => Ok(()),
+            Assertion::Equals {
+                value: expected, ..
+            } => {
+                if value == expected {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "expected {selector} to equal {expected:?}, got {value:?}"
+                    ))
+                }
+            }
+            Assertion::Compare {
+                op,
+                value: expected,
+                ..
+            } => {
+                let value: f64 = value
+                    .parse()
+                    .map_err(|_| format!("expected {selector} to be a number, got {value:?}"))?;
+
+                if op.eval(value, *expected) {
+                    Ok(())
+                } else {
+                    Err(format!("expected {selector} {op} {expected}, got {value}"))
+                }
+            }
+        }
+    }
+}
+
+/// Returns the value of an item from its text.
+fn item_value<'a>(selector: &str, text: &'a str) -> &'a str {
+    let item = selector.split_once('.').map(|(_, item)| item).unwrap_or("");
+
+    if item.starts_with("header.") {
+        let value = text.split_once(':').map(|(_, value)| value).unwrap_or("");
+        return value.trim();
+    }
+
+    let text = text.trim();
+    if item.starts_with("body.") {
+        if let Some(text) = text.strip_prefix('"').and_then(|t| t.strip_suffix('"')) {
+            return text;
+        }
+    }
+
+    text
+}
+
+/// A set of assertions which a verifier requires of an HTTP transcript.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HttpPolicy {
+    assertions: Vec<Assertion>,
+}
+
+impl HttpPolicy {
+    /// Creates a new empty policy.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an assertion to the policy.
+    pub fn assert(&mut self, assertion: Assertion) -> &mut Self {
+        self.assertions.push(assertion);
+        self
+    }
+
+    /// Returns the assertions of the policy.
+    pub fn assertions(&self) -> &[Assertion] {
+        &self.assertions
+    }
+
+    /// Evaluates the policy against a verified transcript.
+    ///
+    /// The transcript is parsed with unauthenticated bytes replaced by `X`, so
+    /// the parts of the messages which determine their structure, such as the
+    /// request line and header names, must be revealed.
+    pub fn evaluate(&self, transcript: &PartialTranscript) -> PolicyReport {
+        let mut masked = transcript.clone();
+        masked.set_unauthed(b'X');
+
+        let http = HttpTranscript::parse(&Transcript::new(
+            masked.sent_unsafe(),
+            masked.received_unsafe(),
+        ))
+        .map_err(|e| format!("failed to parse transcript: {e}"));
+
+        let results = self
+            .assertions
+            .iter()
+            .map(|assertion| AssertionResult {
+                assertion: assertion.clone(),
+                outcome: http
+                    .as_ref()
+                    .map_err(Clone::clone)
+                    .and_then(|http| assertion.eval(http, transcript)),
+            })
+            .collect();
+
+        PolicyReport { results }
+    }
+}
+
+/// The result of evaluating an [`Assertion`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssertionResult {
+    /// The assertion.
+    pub assertion: Assertion,
+    /// The outcome, with a reason if the assertion failed.
+    pub outcome: Result<(), String>,
+}
+
+impl AssertionResult {
+    /// Returns `true` if the assertion passed.
+    pub fn passed(&self) -> bool {
+        self.outcome.is_ok()
+    }
+}
+
+/// Report of evaluating an [`HttpPolicy`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolicyReport {
+    results: Vec<AssertionResult>,
+}
+
+impl PolicyReport {
+    /// Returns `true` if all assertions passed.
+    pub fn passed(&self) -> bool {
+        self.results.iter().all(AssertionResult::passed)
+    }
+
+    /// Returns the results of the assertions, in the order they were added
+    /// to the policy.
+    pub fn results(&self) -> &[AssertionResult] {
+        &self.results
+    }
+
+    /// Returns an iterator over the assertions which failed.
+    pub fn failures(&self) -> impl Iterator<Item = &AssertionResult> {
+        self.results.iter().filter(|result| !result.passed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rangeset::{iter::RangeIterator, set::RangeSet};
+    use rstest::rstest;
+    use tlsn_data_fixtures::http as fixtures;
+
+    fn partial_transcript() -> PartialTranscript {
+        let transcript = Transcript::new(
+            fixtures::request::GET_WITH_HEADER,
+            fixtures::response::OK_JSON,
+        );
+
+        // Reveal everything except the cookie value.
+        let secret = b"very-secret-cookie";
+        let start = fixtures::response::OK_JSON
+            .windows(secret.len())
+            .position(|window| window == secret)
+            .unwrap();
+        let (sent_len, recv_len) = transcript.len();
+        let recv_idx = (0..recv_len)
+            .difference(&(start..start + secret.len()))
+            .into_set();
+
+        transcript.to_partial(RangeSet::from(0..sent_len), recv_idx)
+    }
+
+    fn exists(selector: &str) -> Assertion {
+        Assertion::Exists {
+            selector: selector.to_string(),
+        }
+    }
+
+    fn equals(selector: &str, value: &str) -> Assertion {
+        Assertion::Equals {
+            selector: selector.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    fn compare(selector: &str, op: Comparison, value: f64) -> Assertion {
+        Assertion::Compare {
+            selector: selector.to_string(),
+            op,
+            value,
+        }
+    }
+
+    #[rstest]
+    #[case::target(equals("req[0].target", "/"), true)]
+    #[case::method(equals("req[0].method", "GET"), true)]
+    #[case::header(equals("req[0].header.host", "localhost"), true)]
+    #[case::header_mismatch(equals("req[0].header.host", "example.com"), false)]
+    #[case::json_string(equals("resp[0].body.foo", "bar"), true)]
+    #[case::json_exists(exists("resp[0].body.buzz"), true)]
+    #[case::json_missing(exists("resp[0].body.missing"), false)]
+    #[case::json_ge(compare("resp[0].body.bazz", Comparison::Ge, 18.0), true)]
+    #[case::json_lt(compare("resp[0].body.bazz", Comparison::Lt, 18.0), false)]
+    #[case::json_not_number(compare("resp[0].body.foo", Comparison::Eq, 0.0), false)]
+    #[case::hidden(exists("resp[0].header.cookie"), false)]
+    fn test_assertion(#[case] assertion: Assertion, #[case] passed: bool) {
+        let mut policy = HttpPolicy::new();
+        policy.assert(assertion);
+
+        let report = policy.evaluate(&partial_transcript());
+
+        assert_eq!(report.passed(), passed, "{:?}", report.results());
+    }
+
+    #[test]
+    fn test_policy_report() {
+        let mut policy = HttpPolicy::new();
+        policy
+            .assert(equals("req[0].target", "/"))
+            .assert(exists("resp[0].header.cookie"));
+
+        let report = policy.evaluate(&partial_transcript());
+
+        assert!(!report.passed());
+        assert_eq!(report.results().len(), 2);
+        assert_eq!(
+            report.failures().map(|r| &r.assertion).collect::<Vec<_>>(),
+            vec![&exists("resp[0].header.cookie")]
+        );
+    }
+
+    #[test]
+    fn test_policy_serde() {
+        let mut policy = HttpPolicy::new();
+        policy.assert(equals("req[0].target", "/")).assert(compare(
+            "resp[0].body.bazz",
+            Comparison::Gt,
+            100.0,
+        ));
+
+        let bytes = bincode::serialize(&policy).unwrap();
+
+        assert_eq!(bincode::deserialize::<HttpPolicy>(&bytes).unwrap(), policy);
+    }
+}
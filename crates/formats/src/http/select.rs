@@ -0,0 +1,243 @@
+use std::error::Error;
+
+use rangeset::{iter::FromRangeIterator, set::RangeSet};
+use tlsn_core::{
+    config::prove::ProveConfigBuilder,
+    transcript::{Direction, TranscriptProofBuilder},
+};
+
+use crate::{
+    http::{Body, BodyContent, Header, HttpTranscript},
+    json::JsonValue,
+};
+
+/// HTTP selector error.
+#[derive(Debug, thiserror::Error)]
+#[error("http selector error: {msg}")]
+pub struct HttpSelectError {
+    msg: String,
+    #[source]
+    source: Option<Box<dyn Error + Send + Sync>>,
+}
+
+impl HttpSelectError {
+    fn new(msg: impl Into<String>) -> Self {
+        Self {
+            msg: msg.into(),
+            source: None,
+        }
+    }
+
+    fn new_with_source<E>(msg: impl Into<String>, source: E) -> Self
+    where
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        Self {
+            msg: msg.into(),
+            source: Some(source.into()),
+        }
+    }
+
+    /// Returns the error message.
+    pub fn msg(&self) -> &str {
+        &self.msg
+    }
+}
+
+impl HttpTranscript {
+    /// Returns the direction and ranges of the item identified by the
+    /// selector.
+    ///
+    /// Selectors use the same syntax as the keys of an
+    /// [`HttpIndex`](crate::http::HttpIndex), e.g. `req[0].target`,
+    /// `resp[0].header.date` or `resp[1].body.foo.bar`. Unlike the index,
+    /// header names are matched case-insensitively.
+    pub fn select(&self, selector: &str) -> Result<(Direction, RangeSet<usize>), HttpSelectError> {
+        let (message, item) = match selector.split_once('.') {
+            Some((message, item)) => (message, Some(item)),
+            None => (selector, None),
+        };
+
+        let (kind, idx) = message
+            .strip_suffix(']')
+            .and_then(|message| message.split_once('['))
+            .and_then(|(kind, idx)| Some((kind, idx.parse::<usize>().ok()?)))
+            .ok_or_else(|| {
+                HttpSelectError::new(format!(
+                    "expected selector to start with req[i] or resp[i]: {selector}"
+                ))
+            })?;
+
+        let not_found = || HttpSelectError::new(format!("no item matches selector: {selector}"));
+
+        let idx = match kind {
+            "req" => {
+                let request = self.requests.get(idx).ok_or_else(not_found)?;
+                match item {
+                    None => RangeSet::from_range_iter(request),
+                    Some("method") => RangeSet::from_range_iter(&request.request.method),
+                    Some("target") => RangeSet::from_range_iter(&request.request.target),
+                    Some(item) => select_item(&request.headers, request.body.as_ref(), item)
+                        .ok_or_else(not_found)?,
+                }
+            }
+            "resp" => {
+                let response = self.responses.get(idx).ok_or_else(not_found)?;
+                match item {
+                    None => RangeSet::from_range_iter(response),
+                    Some("status") => RangeSet::from_range_iter(&response.status),
+                    Some(item) => select_item(&response.headers, response.body.as_ref(), item)
+                        .ok_or_else(not_found)?,
+                }
+            }
+            _ => {
+                return Err(HttpSelectError::new(format!(
+                    "expected selector to start with req[i] or resp[i]: {selector}"
+                )))
+            }
+        };
+
+        let direction = if kind == "req" {
+            Direction::Sent
+        } else {
+            Direction::Received
+        };
+
+        Ok((direction, idx))
+    }
+}
+
+fn select_item(headers: &[Header], body: Option<&Body>, item: &str) -> Option<RangeSet<usize>> {
+    if let Some(name) = item.strip_prefix("header.") {
+        return headers
+            .iter()
+            .find(|header| header.name.as_str().eq_ignore_ascii_case(name))
+            .map(RangeSet::from_range_iter);
+    }
+
+    let body = body?;
+    match item.strip_prefix("body") {
+        Some("") => Some(RangeSet::from_range_iter(body)),
+        Some(path) => {
+            let path = path.strip_prefix('.')?;
+            let BodyContent::Json(JsonValue::Object(object)) = &body.content else {
+                return None;
+            };
+
+            object.get(path).map(RangeSet::from_range_iter)
+        }
+        None => None,
+    }
+}
+
+/// Extension trait for revealing items of an HTTP transcript by selector.
+pub trait RevealHttp {
+    /// Reveals the item of the transcript identified by the selector.
+    ///
+    /// See [`HttpTranscript::select`] for the selector syntax.
+    ///
+    /// # Arguments
+    ///
+    /// * `transcript` - The parsed HTTP transcript.
+    /// * `selector` - The selector of the item to reveal.
+    fn reveal_http(
+        &mut self,
+        transcript: &HttpTranscript,
+        selector: &str,
+    ) -> Result<&mut Self, HttpSelectError>;
+}
+
+impl RevealHttp for ProveConfigBuilder<'_> {
+    fn reveal_http(
+        &mut self,
+        transcript: &HttpTranscript,
+        selector: &str,
+    ) -> Result<&mut Self, HttpSelectError> {
+        let (direction, idx) = transcript.select(selector)?;
+
+        self.reveal(direction, &idx).map_err(|e| {
+            HttpSelectError::new_with_source(format!("failed to reveal {selector}"), e)
+        })
+    }
+}
+
+impl RevealHttp for TranscriptProofBuilder<'_> {
+    fn reveal_http(
+        &mut self,
+        transcript: &HttpTranscript,
+        selector: &str,
+    ) -> Result<&mut Self, HttpSelectError> {
+        let (direction, idx) = transcript.select(selector)?;
+
+        self.reveal(&idx, direction).map_err(|e| {
+            HttpSelectError::new_with_source(format!("failed to reveal {selector}"), e)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use tlsn_core::transcript::Transcript;
+    use tlsn_data_fixtures::http as fixtures;
+
+    use crate::http::HttpIndex;
+
+    #[rstest]
+    #[case::request("req[0]")]
+    #[case::method("req[0].method")]
+    #[case::target("req[0].target")]
+    #[case::request_body("req[0].body")]
+    #[case::response("resp[0]")]
+    #[case::status("resp[0].status")]
+    #[case::response_body("resp[0].body")]
+    fn test_select_matches_index(#[case] selector: &str) {
+        let transcript = Transcript::new(fixtures::request::POST_JSON, fixtures::response::OK_JSON);
+        let http = HttpTranscript::parse(&transcript).unwrap();
+        let index = HttpIndex::new(&http);
+
+        let (direction, idx) = http.select(selector).unwrap();
+        let (expected_direction, expected_idx) = index.get(selector).unwrap();
+
+        assert_eq!(direction, expected_direction);
+        assert_eq!(&idx, expected_idx);
+    }
+
+    #[rstest]
+    #[case::unknown_message("foo[0]")]
+    #[case::missing_index("req")]
+    #[case::bad_index("req[a]")]
+    #[case::out_of_bounds("resp[1]")]
+    #[case::unknown_item("req[0].foo")]
+    #[case::missing_header("resp[0].header.x-missing")]
+    #[case::missing_json("resp[0].body.missing")]
+    fn test_select_invalid(#[case] selector: &str) {
+        let transcript = Transcript::new(fixtures::request::POST_JSON, fixtures::response::OK_JSON);
+        let http = HttpTranscript::parse(&transcript).unwrap();
+
+        assert!(http.select(selector).is_err());
+    }
+
+    #[test]
+    fn test_reveal_http() {
+        let transcript = Transcript::new(
+            fixtures::request::GET_WITH_HEADER,
+            fixtures::response::OK_JSON,
+        );
+        let http = HttpTranscript::parse(&transcript).unwrap();
+
+        let mut builder = ProveConfigBuilder::new(&transcript);
+        builder
+            .reveal_http(&http, "req[0].target")
+            .unwrap()
+            .reveal_http(&http, "resp[0].status")
+            .unwrap();
+
+        let config = builder.build().unwrap();
+        let (sent, recv) = config.reveal().unwrap();
+
+        assert_eq!(sent, &http.select("req[0].target").unwrap().1);
+        assert_eq!(recv, &http.select("resp[0].status").unwrap().1);
+    }
+}
@@ -0,0 +1,177 @@
+use serde::{Deserialize, Serialize};
+use tlsn_core::transcript::{Direction, TranscriptCommitConfigBuilder};
+use tracing::warn;
+
+use crate::http::{HttpCommit, HttpCommitError, HttpTranscript, MessageKind};
+
+/// A named set of selectors of the items to commit to.
+///
+/// A template is useful when notarizing the same endpoint repeatedly, as the
+/// items of interest are the same in each transcript even if their ranges are
+/// not. Selectors are resolved against each transcript, see
+/// [`HttpTranscript::select`] for the syntax.
+///
+/// Templates can be persisted with any `serde` format and committed to with a
+/// [`TemplateHttpCommitter`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitTemplate {
+    name: String,
+    selectors: Vec<String>,
+}
+
+impl CommitTemplate {
+    /// Creates a new empty template.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            selectors: Vec::new(),
+        }
+    }
+
+    /// Adds a selector of an item to commit to.
+    pub fn select(&mut self, selector: impl Into<String>) -> &mut Self {
+        self.selectors.push(selector.into());
+        self
+    }
+
+    /// Returns the name of the template.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the selectors, in the order they were added.
+    pub fn selectors(&self) -> &[String] {
+        &self.selectors
+    }
+}
+
+/// A selector of a [`CommitTemplate`] which did not resolve against a
+/// transcript.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnresolvedSelector {
+    /// The selector.
+    pub selector: String,
+    /// The reason it did not resolve.
+    pub reason: String,
+}
+
+/// An HTTP committer which commits to the items of a [`CommitTemplate`].
+///
+/// Each item is committed to as a whole with the default commitment kind of
+/// the builder. Selectors which do not resolve against the transcript are
+/// skipped and logged, see [`unresolved`](Self::unresolved).
+///
+/// Only [`commit_transcript`](HttpCommit::commit_transcript) uses the
+/// template, the other methods keep their default implementations.
+#[derive(Debug, Clone)]
+pub struct TemplateHttpCommitter {
+    template: CommitTemplate,
+    unresolved: Vec<UnresolvedSelector>,
+}
+
+impl TemplateHttpCommitter {
+    /// Creates a committer for the given template.
+    pub fn from_template(template: &CommitTemplate) -> Self {
+        Self {
+            template: template.clone(),
+            unresolved: Vec::new(),
+        }
+    }
+
+    /// Returns the template.
+    pub fn template(&self) -> &CommitTemplate {
+        &self.template
+    }
+
+    /// Returns the selectors which did not resolve against the last committed
+    /// transcript.
+    pub fn unresolved(&self) -> &[UnresolvedSelector] {
+        &self.unresolved
+    }
+}
+
+impl HttpCommit for TemplateHttpCommitter {
+    fn commit_transcript(
+        &mut self,
+        builder: &mut TranscriptCommitConfigBuilder,
+        transcript: &HttpTranscript,
+    ) -> Result<(), HttpCommitError> {
+        self.unresolved.clear();
+
+        for selector in &self.template.selectors {
+            let (direction, idx) = match transcript.select(selector) {
+                Ok(item) => item,
+                Err(e) => {
+                    warn!(
+                        "selector {selector} of template {} did not resolve: {}",
+                        self.template.name,
+                        e.msg()
+                    );
+                    self.unresolved.push(UnresolvedSelector {
+                        selector: selector.clone(),
+                        reason: e.msg().to_string(),
+                    });
+                    continue;
+                }
+            };
+
+            let kind = match direction {
+                Direction::Sent => MessageKind::Request,
+                Direction::Received => MessageKind::Response,
+            };
+
+            builder.commit(&idx, direction).map_err(|e| {
+                HttpCommitError::new_with_source(kind, format!("failed to commit to {selector}"), e)
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tlsn_core::transcript::Transcript;
+    use tlsn_data_fixtures::http as fixtures;
+
+    #[test]
+    fn test_template_commit() {
+        let transcript = Transcript::new(fixtures::request::POST_JSON, fixtures::response::OK_JSON);
+        let http = HttpTranscript::parse(&transcript).unwrap();
+
+        let mut template = CommitTemplate::new("daily");
+        template
+            .select("req[0].target")
+            .select("resp[0].status")
+            .select("resp[0].body.missing")
+            .select("resp[1]");
+
+        // Templates round-trip through serialization.
+        let template: CommitTemplate =
+            bincode::deserialize(&bincode::serialize(&template).unwrap()).unwrap();
+
+        let mut committer = TemplateHttpCommitter::from_template(&template);
+        let mut builder = TranscriptCommitConfigBuilder::new(&transcript);
+        committer.commit_transcript(&mut builder, &http).unwrap();
+
+        let unresolved: Vec<_> = committer
+            .unresolved()
+            .iter()
+            .map(|unresolved| unresolved.selector.as_str())
+            .collect();
+        assert_eq!(unresolved, ["resp[0].body.missing", "resp[1]"]);
+
+        let config = builder.build().unwrap();
+        let mut committed: Vec<_> = config.iter_hash().map(|(idx, _)| idx.clone()).collect();
+        committed.sort_by_key(|(direction, _)| *direction == Direction::Received);
+
+        assert_eq!(
+            committed,
+            [
+                http.select("req[0].target").unwrap(),
+                http.select("resp[0].status").unwrap()
+            ]
+        );
+    }
+}
@@ -14,6 +14,7 @@
 
 pub mod http;
 pub mod json;
+pub mod normalize;
 
 #[doc(hidden)]
 pub use spansy;
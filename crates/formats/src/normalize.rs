@@ -0,0 +1,178 @@
+//! Normalization of transcript data before parsing.
+//!
+//! Some servers produce data which is well-formed in spirit but trips up
+//! strict parsers, e.g. a byte order mark, bare `\n` line endings or leading
+//! whitespace. A [`Normalized`] view cleans up the data with a sequence of
+//! [`Normalizer`]s while keeping track of where each byte came from, so that
+//! ranges found by parsing the normalized view can be translated back to the
+//! original transcript with [`Normalized::to_original`].
+//!
+//! Commitments and reveals must always use the translated ranges, as they are
+//! anchored to the bytes which were actually sent or received.
+
+use rangeset::{
+    iter::{FromRangeIterator, IntoRangeIterator},
+    set::RangeSet,
+};
+
+/// A normalization pass.
+pub trait Normalizer {
+    /// Normalizes `data`, pushing the output into `out`.
+    ///
+    /// Every pushed byte is tagged with the index of the byte in `data` it was
+    /// derived from.
+    fn normalize(&self, data: &[u8], out: &mut NormalizedBuf);
+}
+
+/// Output buffer of a [`Normalizer`].
+#[derive(Debug, Default)]
+pub struct NormalizedBuf {
+    data: Vec<u8>,
+    offsets: Vec<usize>,
+}
+
+impl NormalizedBuf {
+    /// Pushes a byte derived from the input byte at `offset`.
+    pub fn push(&mut self, byte: u8, offset: usize) {
+        self.data.push(byte);
+        self.offsets.push(offset);
+    }
+
+    /// Pushes a slice copied from the input starting at `offset`.
+    pub fn extend(&mut self, bytes: &[u8], offset: usize) {
+        self.data.extend_from_slice(bytes);
+        self.offsets.extend(offset..offset + bytes.len());
+    }
+}
+
+/// A normalized view of transcript data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Normalized {
+    data: Vec<u8>,
+    /// Offset in the original data of each normalized byte.
+    offsets: Vec<usize>,
+}
+
+impl Normalized {
+    /// Normalizes `data` by applying the normalizers in order.
+    pub fn new(data: &[u8], normalizers: &[&dyn Normalizer]) -> Self {
+        let mut normalized = Self {
+            data: data.to_vec(),
+            offsets: (0..data.len()).collect(),
+        };
+
+        for normalizer in normalizers {
+            let mut out = NormalizedBuf::default();
+            normalizer.normalize(&normalized.data, &mut out);
+
+            normalized.offsets = out
+                .offsets
+                .into_iter()
+                .map(|offset| normalized.offsets[offset])
+                .collect();
+            normalized.data = out.data;
+        }
+
+        normalized
+    }
+
+    /// Returns the normalized data.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Translates ranges of the normalized data to the ranges of the original
+    /// data they were derived from.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the ranges are out of bounds of the normalized data.
+    pub fn to_original(&self, idx: impl IntoRangeIterator<usize>) -> RangeSet<usize> {
+        let idx = RangeSet::from_range_iter(idx);
+
+        RangeSet::from_range_iter(
+            idx.iter()
+                .flatten()
+                .map(|i| self.offsets[i])
+                .map(|offset| offset..offset + 1),
+        )
+    }
+}
+
+/// Strips a leading UTF-8 byte order mark.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StripBom;
+
+impl Normalizer for StripBom {
+    fn normalize(&self, data: &[u8], out: &mut NormalizedBuf) {
+        const BOM: &[u8] = b"\xEF\xBB\xBF";
+
+        match data.strip_prefix(BOM) {
+            Some(rest) => out.extend(rest, BOM.len()),
+            None => out.extend(data, 0),
+        }
+    }
+}
+
+/// Replaces bare `\n` line endings with `\r\n`.
+///
+/// The inserted `\r` is attributed to the `\n` it precedes.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CrLf;
+
+impl Normalizer for CrLf {
+    fn normalize(&self, data: &[u8], out: &mut NormalizedBuf) {
+        for (i, &byte) in data.iter().enumerate() {
+            if byte == b'\n' && (i == 0 || data[i - 1] != b'\r') {
+                out.push(b'\r', i);
+            }
+            out.push(byte, i);
+        }
+    }
+}
+
+/// Strips leading ASCII whitespace.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TrimStart;
+
+impl Normalizer for TrimStart {
+    fn normalize(&self, data: &[u8], out: &mut NormalizedBuf) {
+        let start = data
+            .iter()
+            .position(|byte| !byte.is_ascii_whitespace())
+            .unwrap_or(data.len());
+
+        out.extend(&data[start..], start);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::parse_request;
+
+    #[test]
+    fn test_normalize() {
+        let data = b"\xEF\xBB\xBF\r\n a\nb\r\nc";
+        let normalized = Normalized::new(data, &[&StripBom, &TrimStart, &CrLf]);
+
+        assert_eq!(normalized.data(), b"a\r\nb\r\nc");
+        assert_eq!(normalized.to_original(&(0..1)), RangeSet::from(6..7));
+        // The inserted `\r` maps to the original `\n`.
+        assert_eq!(normalized.to_original(&(1..3)), RangeSet::from(7..8));
+        assert_eq!(normalized.to_original(&(3..7)), RangeSet::from(8..12));
+    }
+
+    #[test]
+    fn test_normalize_parse() {
+        let data = b"\xEF\xBB\xBFGET / HTTP/1.1\nHost: example.com\n\n";
+        let normalized = Normalized::new(data, &[&StripBom, &CrLf]);
+
+        let request = parse_request(normalized.data()).unwrap();
+        let host = request.headers_with_name("host").next().unwrap();
+
+        let idx = normalized.to_original(&host.value);
+        assert_eq!(idx, RangeSet::from(24..35));
+        assert_eq!(&data[24..35], b"example.com");
+    }
+}
@@ -0,0 +1,3 @@
+//! Time sources.
+
+pub use tlsn_core::clock::{Clock, FixedClock, SystemClock};
@@ -1,6 +1,10 @@
+use std::sync::Arc;
+
 use derive_builder::Builder;
 use hmac_sha256::Mode as PrfMode;
 
+use crate::clock::{Clock, SystemClock};
+
 /// Number of TLS protocol bytes that will be sent.
 const PROTOCOL_DATA_SENT: usize = 32;
 /// Number of TLS protocol bytes that will be received.
@@ -63,6 +67,9 @@ pub struct Config {
     /// Configuration options for the PRF.
     #[builder(setter(custom))]
     pub(crate) prf: PrfMode,
+    /// Source of the current time.
+    #[builder(setter(custom))]
+    pub(crate) clock: Arc<dyn Clock>,
 }
 
 impl Config {
@@ -79,6 +86,14 @@ impl ConfigBuilder {
         self
     }
 
+    /// Sets the clock used to timestamp the handshake.
+    ///
+    /// Defaults to the system time.
+    pub fn clock(&mut self, clock: impl Clock + 'static) -> &mut Self {
+        self.clock = Some(Arc::new(clock));
+        self
+    }
+
     /// Builds the configuration.
     pub fn build(&self) -> Result<Config, ConfigBuilderError> {
         let defer_decryption = self.defer_decryption.unwrap_or(true);
@@ -110,6 +125,7 @@ impl ConfigBuilder {
             .unwrap_or_else(|| PROTOCOL_RECORD_COUNT_RECV + default_record_count(max_recv_online));
 
         let prf = self.prf.unwrap_or(PrfMode::Normal);
+        let clock = self.clock.clone().unwrap_or_else(|| Arc::new(SystemClock));
 
         Ok(Config {
             defer_decryption,
@@ -119,6 +135,7 @@ impl ConfigBuilder {
             max_recv_online,
             max_recv,
             prf,
+            clock,
         })
     }
 }
@@ -131,4 +148,17 @@ mod tests {
     fn test_default_record_count() {
         assert_eq!(default_record_count(1 << 15), 15);
     }
+
+    #[test]
+    fn test_clock() {
+        let config = Config::builder()
+            .max_sent(1 << 12)
+            .max_recv_online(1 << 12)
+            .max_recv(1 << 14)
+            .clock(crate::clock::FixedClock(42))
+            .build()
+            .unwrap();
+
+        assert_eq!(config.clock.now(), 42);
+    }
 }
@@ -251,10 +251,7 @@ impl MpcTlsFollower {
                         return Err(MpcTlsError::hs("time already set"));
                     }
 
-                    let this_time = web_time::UNIX_EPOCH
-                        .elapsed()
-                        .expect("system time is available")
-                        .as_secs();
+                    let this_time = self.config.clock.now();
 
                     if prover_time.abs_diff(this_time) > MAX_TIME_DIFF {
                         return Err(MpcTlsError::hs("handshake time difference exceeds limit"));
@@ -29,7 +29,9 @@ use mpz_ot::{
 use mpz_share_conversion::{ShareConversionReceiver, ShareConversionSender};
 use mpz_vm_core::prelude::*;
 use serio::SinkExt;
-use tls_backend::{Backend, BackendError, BackendNotifier, BackendNotify};
+use tls_backend::{
+    Backend, BackendError, BackendNotifier, BackendNotify, HandshakeOrigin, HandshakeRecord,
+};
 use tls_core::{
     cert::ServerCertDetails,
     ke::ServerKxDetails,
@@ -45,7 +47,7 @@ use tls_core::{
 };
 use tlsn_core::{
     connection::{CertBinding, CertBindingV1_2, ServerSignature, TlsVersion, VerifyData},
-    transcript::TlsTranscript,
+    transcript::{Direction, HandshakeMessage, TlsTranscript},
     webpki::CertificateDer,
 };
 use tracing::{debug, instrument, trace, warn};
@@ -53,6 +55,24 @@ use tracing::{debug, instrument, trace, warn};
 /// Controller for MPC-TLS leader.
 pub type LeaderCtrl = actor::MpcTlsLeaderCtrl;
 
+/// Key exchange groups supported by MPC-TLS.
+///
+/// Supporting another group requires an MPC key exchange for it, see
+/// [`key_exchange`].
+const KX_GROUPS: [NamedGroup; 1] = [NamedGroup::secp256r1];
+
+/// Returns an error if the key exchange group is not supported by MPC-TLS.
+fn check_kx_group(group: NamedGroup) -> Result<(), BackendError> {
+    if !KX_GROUPS.contains(&group) {
+        return Err(BackendError::UnsupportedKxGroup(
+            group,
+            format!("not supported by the MPC-TLS backend, which only supports {KX_GROUPS:?}"),
+        ));
+    }
+
+    Ok(())
+}
+
 /// MPC-TLS leader.
 #[derive(Debug)]
 pub struct MpcTlsLeader {
@@ -65,6 +85,8 @@ pub struct MpcTlsLeader {
     notifier: BackendNotifier,
     /// Whether the record layer is decrypting application data.
     is_decrypting: bool,
+    /// Handshake messages sent and received by the client.
+    handshake: Vec<HandshakeRecord>,
 }
 
 impl MpcTlsLeader {
@@ -125,6 +147,7 @@ impl MpcTlsLeader {
             },
             notifier: BackendNotifier::new(),
             is_decrypting,
+            handshake: Vec::new(),
         }
     }
 
@@ -353,7 +376,7 @@ impl MpcTlsLeader {
                 .expect("only supported key scheme should have been accepted"),
         });
 
-        let transcript = TlsTranscript::new(
+        let mut transcript = TlsTranscript::new(
             time,
             version,
             Some(server_cert_chain),
@@ -368,6 +391,20 @@ impl MpcTlsLeader {
         )
         .map_err(MpcTlsError::other)?;
 
+        transcript.set_handshake_messages(
+            self.handshake
+                .iter()
+                .map(|record| HandshakeMessage {
+                    direction: match record.origin {
+                        HandshakeOrigin::Client => Direction::Sent,
+                        HandshakeOrigin::Server => Direction::Received,
+                    },
+                    typ: record.typ.get_u8(),
+                    payload: record.payload.clone(),
+                })
+                .collect(),
+        );
+
         self.state = State::Closed {
             ctx,
             vm,
@@ -462,6 +499,14 @@ impl Backend for MpcTlsLeader {
         Ok(*client_random)
     }
 
+    async fn supported_kx_groups(&mut self) -> Result<Vec<NamedGroup>, BackendError> {
+        Ok(KX_GROUPS.to_vec())
+    }
+
+    async fn set_kx_group(&mut self, group: NamedGroup) -> Result<(), BackendError> {
+        check_kx_group(group)
+    }
+
     #[instrument(level = "debug", skip_all, err)]
     async fn get_client_key_share(&mut self) -> Result<PublicKey, BackendError> {
         let State::Handshake { ke, .. } = &self.state else {
@@ -494,10 +539,7 @@ impl Backend for MpcTlsLeader {
             );
         };
 
-        let now = web_time::UNIX_EPOCH
-            .elapsed()
-            .expect("system time is available")
-            .as_secs();
+        let now = self.config.clock.now();
 
         *time = Some(now);
 
@@ -530,9 +572,7 @@ impl Backend for MpcTlsLeader {
             );
         };
 
-        if key.group != NamedGroup::secp256r1 {
-            return Err(BackendError::InvalidServerKey);
-        }
+        check_kx_group(key.group)?;
 
         ctx.io_mut()
             .send(Message::SetServerKey(SetServerKey { key: key.clone() }))
@@ -1055,6 +1095,18 @@ impl Backend for MpcTlsLeader {
     async fn server_closed(&mut self) -> Result<(), BackendError> {
         self.close_connection().await.map_err(BackendError::from)
     }
+
+    async fn push_handshake_message(
+        &mut self,
+        record: HandshakeRecord,
+    ) -> Result<(), BackendError> {
+        self.handshake.push(record);
+        Ok(())
+    }
+
+    async fn handshake_transcript(&mut self) -> Result<Vec<HandshakeRecord>, BackendError> {
+        Ok(self.handshake.clone())
+    }
 }
 
 enum State {
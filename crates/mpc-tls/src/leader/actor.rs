@@ -1,18 +1,20 @@
 use crate::{
-    leader::{MpcTlsLeader, State},
+    leader::{check_kx_group, MpcTlsLeader, State, KX_GROUPS},
     MpcTlsError,
 };
 use async_trait::async_trait;
 use ludi::{mailbox, Actor, Address, Context as LudiCtx, Dispatch, Error, Handler, Message, Wrap};
 use mpz_common::Context;
 use std::future::Future;
-use tls_backend::{Backend, BackendError, BackendNotify, DecryptMode, EncryptMode};
+use tls_backend::{
+    Backend, BackendError, BackendNotify, DecryptMode, EncryptMode, HandshakeRecord,
+};
 use tls_core::{
     cert::ServerCertDetails,
     ke::ServerKxDetails,
     key::PublicKey,
     msgs::{
-        enums::ProtocolVersion,
+        enums::{NamedGroup, ProtocolVersion},
         handshake::Random,
         message::{OpaqueMessage, PlainMessage},
     },
@@ -262,6 +264,18 @@ impl Dispatch<MpcTlsLeader> for MpcTlsLeaderMsg {
                 })
                 .await;
             }
+            MpcTlsLeaderMsg::BackendMsgPushHandshakeMessage(msg) => {
+                msg.dispatch(actor, ctx, |value| {
+                    ret(Self::Return::BackendMsgPushHandshakeMessage(value))
+                })
+                .await;
+            }
+            MpcTlsLeaderMsg::BackendMsgHandshakeTranscript(msg) => {
+                msg.dispatch(actor, ctx, |value| {
+                    ret(Self::Return::BackendMsgHandshakeTranscript(value))
+                })
+                .await;
+            }
             MpcTlsLeaderMsg::DeferDecryption(msg) => {
                 msg.dispatch(actor, ctx, |value| {
                     ret(Self::Return::DeferDecryption(value))
@@ -320,6 +334,15 @@ impl Backend for MpcTlsLeaderCtrl {
             .map_err(|err| BackendError::InternalError(err.to_string()))?
     }
 
+    async fn supported_kx_groups(&mut self) -> Result<Vec<NamedGroup>, BackendError> {
+        Ok(KX_GROUPS.to_vec())
+    }
+
+    async fn set_kx_group(&mut self, group: NamedGroup) -> Result<(), BackendError> {
+        // The supported groups are fixed, so the leader is not involved.
+        check_kx_group(group)
+    }
+
     async fn get_client_key_share(&mut self) -> Result<PublicKey, BackendError> {
         self.address
             .send(BackendMsgGetClientKeyShare)
@@ -458,6 +481,23 @@ impl Backend for MpcTlsLeaderCtrl {
             .await
             .map_err(|err| BackendError::InternalError(err.to_string()))?
     }
+
+    async fn push_handshake_message(
+        &mut self,
+        record: HandshakeRecord,
+    ) -> Result<(), BackendError> {
+        self.address
+            .send(BackendMsgPushHandshakeMessage { record })
+            .await
+            .map_err(|err| BackendError::InternalError(err.to_string()))?
+    }
+
+    async fn handshake_transcript(&mut self) -> Result<Vec<HandshakeRecord>, BackendError> {
+        self.address
+            .send(BackendMsgHandshakeTranscript)
+            .await
+            .map_err(|err| BackendError::InternalError(err.to_string()))?
+    }
 }
 
 impl Dispatch<MpcTlsLeader> for BackendMsgSetProtocolVersion {
@@ -985,6 +1025,48 @@ impl Handler<BackendMsgServerClosed> for MpcTlsLeader {
     }
 }
 
+impl Dispatch<MpcTlsLeader> for BackendMsgPushHandshakeMessage {
+    fn dispatch<R: FnOnce(Self::Return) + Send>(
+        self,
+        actor: &mut MpcTlsLeader,
+        ctx: &mut LudiCtx<MpcTlsLeader>,
+        ret: R,
+    ) -> impl Future<Output = ()> + Send {
+        actor.process(self, ctx, ret)
+    }
+}
+
+impl Handler<BackendMsgPushHandshakeMessage> for MpcTlsLeader {
+    async fn handle(
+        &mut self,
+        msg: BackendMsgPushHandshakeMessage,
+        _ctx: &mut LudiCtx<Self>,
+    ) -> <BackendMsgPushHandshakeMessage as Message>::Return {
+        self.push_handshake_message(msg.record).await
+    }
+}
+
+impl Dispatch<MpcTlsLeader> for BackendMsgHandshakeTranscript {
+    fn dispatch<R: FnOnce(Self::Return) + Send>(
+        self,
+        actor: &mut MpcTlsLeader,
+        ctx: &mut LudiCtx<MpcTlsLeader>,
+        ret: R,
+    ) -> impl Future<Output = ()> + Send {
+        actor.process(self, ctx, ret)
+    }
+}
+
+impl Handler<BackendMsgHandshakeTranscript> for MpcTlsLeader {
+    async fn handle(
+        &mut self,
+        _msg: BackendMsgHandshakeTranscript,
+        _ctx: &mut LudiCtx<Self>,
+    ) -> <BackendMsgHandshakeTranscript as Message>::Return {
+        self.handshake_transcript().await
+    }
+}
+
 impl Dispatch<MpcTlsLeader> for EnableDecryption {
     fn dispatch<R: FnOnce(Self::Return) + Send>(
         self,
@@ -1052,6 +1134,8 @@ pub enum MpcTlsLeaderMsg {
     BackendMsgGetNotify(BackendMsgGetNotify),
     BackendMsgIsEmpty(BackendMsgIsEmpty),
     BackendMsgServerClosed(BackendMsgServerClosed),
+    BackendMsgPushHandshakeMessage(BackendMsgPushHandshakeMessage),
+    BackendMsgHandshakeTranscript(BackendMsgHandshakeTranscript),
     DeferDecryption(EnableDecryption),
     Stop(Stop),
 }
@@ -1087,6 +1171,8 @@ pub enum MpcTlsLeaderMsgReturn {
     BackendMsgGetNotify(<BackendMsgGetNotify as Message>::Return),
     BackendMsgIsEmpty(<BackendMsgIsEmpty as Message>::Return),
     BackendMsgServerClosed(<BackendMsgServerClosed as Message>::Return),
+    BackendMsgPushHandshakeMessage(<BackendMsgPushHandshakeMessage as Message>::Return),
+    BackendMsgHandshakeTranscript(<BackendMsgHandshakeTranscript as Message>::Return),
     DeferDecryption(<EnableDecryption as Message>::Return),
     Stop(<Stop as Message>::Return),
 }
@@ -1736,6 +1822,58 @@ impl Wrap<BackendMsgServerClosed> for MpcTlsLeaderMsg {
     }
 }
 
+#[allow(missing_docs)]
+#[derive(Debug)]
+pub struct BackendMsgPushHandshakeMessage {
+    pub record: HandshakeRecord,
+}
+
+impl Message for BackendMsgPushHandshakeMessage {
+    type Return = Result<(), BackendError>;
+}
+
+impl From<BackendMsgPushHandshakeMessage> for MpcTlsLeaderMsg {
+    fn from(value: BackendMsgPushHandshakeMessage) -> Self {
+        MpcTlsLeaderMsg::BackendMsgPushHandshakeMessage(value)
+    }
+}
+
+impl Wrap<BackendMsgPushHandshakeMessage> for MpcTlsLeaderMsg {
+    fn unwrap_return(
+        ret: Self::Return,
+    ) -> Result<<BackendMsgPushHandshakeMessage as Message>::Return, Error> {
+        match ret {
+            Self::Return::BackendMsgPushHandshakeMessage(value) => Ok(value),
+            _ => Err(Error::Wrapper),
+        }
+    }
+}
+
+#[allow(missing_docs)]
+#[derive(Debug)]
+pub struct BackendMsgHandshakeTranscript;
+
+impl Message for BackendMsgHandshakeTranscript {
+    type Return = Result<Vec<HandshakeRecord>, BackendError>;
+}
+
+impl From<BackendMsgHandshakeTranscript> for MpcTlsLeaderMsg {
+    fn from(value: BackendMsgHandshakeTranscript) -> Self {
+        MpcTlsLeaderMsg::BackendMsgHandshakeTranscript(value)
+    }
+}
+
+impl Wrap<BackendMsgHandshakeTranscript> for MpcTlsLeaderMsg {
+    fn unwrap_return(
+        ret: Self::Return,
+    ) -> Result<<BackendMsgHandshakeTranscript as Message>::Return, Error> {
+        match ret {
+            Self::Return::BackendMsgHandshakeTranscript(value) => Ok(value),
+            _ => Err(Error::Wrapper),
+        }
+    }
+}
+
 /// Message to enable or disable the decryption of messages.
 #[allow(missing_docs)]
 #[derive(Debug)]
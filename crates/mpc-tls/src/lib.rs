@@ -4,6 +4,7 @@
 #![deny(clippy::all)]
 #![forbid(unsafe_code)]
 
+pub mod clock;
 mod config;
 mod decode;
 mod error;
@@ -17,6 +18,8 @@ pub use config::{Config, ConfigBuilder, ConfigBuilderError};
 pub use error::MpcTlsError;
 pub use follower::MpcTlsFollower;
 pub use leader::{LeaderCtrl, MpcTlsLeader};
+#[cfg(fuzzing)]
+pub use record_layer::fuzz;
 
 use std::{future::Future, pin::Pin, sync::Arc};
 
@@ -4,6 +4,8 @@ pub(crate) mod aead;
 mod aes_gcm;
 mod decrypt;
 mod encrypt;
+#[cfg(fuzzing)]
+pub mod fuzz;
 
 use std::{collections::VecDeque, mem::take, sync::Arc};
 
@@ -35,6 +37,8 @@ pub(crate) use encrypt::EncryptMode;
 const MAX_RECORD_SIZE: usize = 1026 * 16;
 // This limits how much the leader can cause the follower to allocate.
 const MAX_BUFFER_SIZE: usize = (16 * (1 << 20)) / MAX_RECORD_SIZE;
+const EXPLICIT_NONCE_LEN: usize = 8;
+const TAG_LEN: usize = 16;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct PlainRecord {
@@ -327,6 +331,12 @@ impl RecordLayer {
     ) -> Result<(), MpcTlsError> {
         if self.decrypt_buffer.len() >= MAX_BUFFER_SIZE {
             return Err(MpcTlsError::peer("decrypt buffer is full"));
+        } else if explicit_nonce.len() != EXPLICIT_NONCE_LEN || tag.len() != TAG_LEN {
+            return Err(MpcTlsError::peer(format!(
+                "invalid record: explicit nonce length={}, tag length={}",
+                explicit_nonce.len(),
+                tag.len()
+            )));
         } else if self.recv + ciphertext.len() > self.max_recv {
             return Err(MpcTlsError::record_layer(format!(
                 "attempted to receive more data than was configured, increase `max_recv` in the config: current={}, additional={}, max={}",
@@ -53,6 +53,21 @@ impl AesGcm {
         }
     }
 
+    /// Creates a leader instance which is ready to decrypt with the given key
+    /// and IV.
+    #[cfg(fuzzing)]
+    pub(crate) fn new_ready(key: [u8; 16], iv: [u8; 4]) -> Self {
+        Self {
+            role: Role::Leader,
+            key: None,
+            iv: None,
+            state: State::Ready {
+                key: Some(key),
+                iv: Some(iv),
+            },
+        }
+    }
+
     pub(crate) fn set_key(&mut self, key: Array<U8, 16>, iv: Array<U8, 4>) {
         self.key = Some(key);
         self.iv = Some(iv);
@@ -0,0 +1,165 @@
+//! Fuzzing entry points of the record layer.
+//!
+//! The entry points feed arbitrary bytes, as they could be sent by a hostile
+//! server, into the incoming path of the leader and forward the records to a
+//! mock follower, the way [`MpcTlsLeader`](crate::MpcTlsLeader) does. They
+//! panic if the record layer panics, or if it retains more data than it was
+//! configured for.
+//!
+//! Only available with `--cfg fuzzing`, which is set by `cargo fuzz`.
+
+use std::io::Read;
+
+use mpz_core::Block;
+use mpz_share_conversion::ideal::ideal_share_convert;
+use tls_core::msgs::{deframer::MessageDeframer, enums::ContentType, message::OpaqueMessage};
+
+use crate::{
+    record_layer::{aead::MpcAesGcm, aes_gcm::AesGcm, DecryptMode, RecordLayer, MAX_BUFFER_SIZE},
+    utils::opaque_into_parts,
+    Role,
+};
+
+/// Maximum number of bytes the fuzzed record layers may receive.
+pub const MAX_RECV: usize = 1 << 16;
+
+/// Server write key used by [`decrypt_incoming`].
+pub const SERVER_WRITE_KEY: [u8; 16] = [42u8; 16];
+
+/// Server write IV used by [`decrypt_incoming`].
+pub const SERVER_WRITE_IV: [u8; 4] = [7u8; 4];
+
+/// Buffers incoming records in the record layers of the leader and a mock
+/// follower.
+pub fn buffer_incoming(data: &[u8]) {
+    let (mut leader, mut follower) = record_layers();
+
+    deframe(data, |msg| {
+        push_incoming(&mut leader, &mut follower, msg)?;
+        check_bounds(&leader);
+        check_bounds(&follower);
+
+        Some(())
+    });
+}
+
+/// Buffers incoming records and decrypts them locally, as the leader does
+/// once the server write key is known.
+///
+/// The records are decrypted with [`SERVER_WRITE_KEY`] and
+/// [`SERVER_WRITE_IV`].
+pub fn decrypt_incoming(data: &[u8]) {
+    let (mut leader, mut follower) = record_layers();
+    let mut decrypter = AesGcm::new_ready(SERVER_WRITE_KEY, SERVER_WRITE_IV);
+
+    deframe(data, |msg| {
+        push_incoming(&mut leader, &mut follower, msg)?;
+        check_bounds(&leader);
+        check_bounds(&follower);
+
+        for op in leader.decrypt_buffer.drain(..) {
+            if let Ok(plaintext) =
+                decrypter.decrypt(op.explicit_nonce, op.aad, op.ciphertext.clone(), op.tag)
+            {
+                assert_eq!(plaintext.len(), op.ciphertext.len());
+            }
+        }
+        follower.decrypt_buffer.clear();
+
+        Some(())
+    });
+}
+
+/// Returns the record layers of the leader and the mock follower.
+fn record_layers() -> (RecordLayer, RecordLayer) {
+    let (encrypt_0, encrypt_1) = ideal_share_convert(Block::ZERO);
+    let (decrypt_0, decrypt_1) = ideal_share_convert(Block::ZERO);
+
+    let mut leader = RecordLayer::new(
+        Role::Leader,
+        MpcAesGcm::new(encrypt_0, Role::Leader),
+        MpcAesGcm::new(decrypt_0, Role::Leader),
+    );
+    let mut follower = RecordLayer::new(
+        Role::Follower,
+        MpcAesGcm::new(encrypt_1, Role::Follower),
+        MpcAesGcm::new(decrypt_1, Role::Follower),
+    );
+
+    for record_layer in [&mut leader, &mut follower] {
+        record_layer.max_recv = MAX_RECV;
+        record_layer.start_traffic();
+    }
+
+    (leader, follower)
+}
+
+/// Deframes the data, calling `f` with every message until it returns `None`.
+fn deframe(mut data: &[u8], mut f: impl FnMut(OpaqueMessage) -> Option<()>) {
+    let mut deframer = MessageDeframer::new();
+    while !deframer.desynced {
+        match deframer.read(&mut data as &mut dyn Read) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+
+        while let Some(msg) = deframer.frames.pop_front() {
+            if f(msg).is_none() {
+                return;
+            }
+        }
+    }
+}
+
+/// Pushes an incoming message to the leader, and forwards it to the follower
+/// if it is accepted.
+///
+/// Returns `None` if the leader rejects the message, which aborts the
+/// connection.
+fn push_incoming(
+    leader: &mut RecordLayer,
+    follower: &mut RecordLayer,
+    msg: OpaqueMessage,
+) -> Option<()> {
+    let OpaqueMessage {
+        typ,
+        version,
+        payload,
+    } = msg;
+    let (explicit_nonce, ciphertext, tag) = opaque_into_parts(payload.0).ok()?;
+
+    let mode = match typ {
+        ContentType::ApplicationData => DecryptMode::Private,
+        _ => DecryptMode::Public,
+    };
+
+    leader
+        .push_decrypt(
+            typ,
+            version,
+            explicit_nonce.clone(),
+            ciphertext.clone(),
+            tag.clone(),
+            mode,
+        )
+        .ok()?;
+
+    follower
+        .push_decrypt(typ, version, explicit_nonce, ciphertext, tag, mode)
+        .expect("follower accepts records accepted by the leader");
+
+    Some(())
+}
+
+/// Asserts that the record layer stays within its configured limits.
+fn check_bounds(record_layer: &RecordLayer) {
+    let buffered: usize = record_layer
+        .decrypt_buffer
+        .iter()
+        .map(|op| op.ciphertext.len())
+        .sum();
+
+    assert!(record_layer.decrypt_buffer.len() <= MAX_BUFFER_SIZE);
+    assert!(record_layer.recv <= record_layer.max_recv);
+    assert!(buffered <= record_layer.recv);
+}
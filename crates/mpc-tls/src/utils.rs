@@ -7,13 +7,40 @@ use crate::MpcTlsError;
 pub(crate) fn opaque_into_parts(
     mut msg: Vec<u8>,
 ) -> Result<(Vec<u8>, Vec<u8>, Vec<u8>), MpcTlsError> {
+    if msg.len() < 8 + 16 {
+        return Err(MpcTlsError::peer(format!(
+            "record is too short for explicit nonce and tag: {}",
+            msg.len()
+        )));
+    }
+
     let tag = msg.split_off(msg.len() - 16);
     let ciphertext = msg.split_off(8);
     let explicit_nonce = msg;
 
-    if explicit_nonce.len() != 8 {
-        return Err(MpcTlsError::other("explicit nonce length is not 8"));
+    Ok((explicit_nonce, ciphertext, tag))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_opaque_into_parts() {
+        let msg: Vec<u8> = (0..32).collect();
+        let (explicit_nonce, ciphertext, tag) = opaque_into_parts(msg).unwrap();
+
+        assert_eq!(explicit_nonce, (0..8).collect::<Vec<u8>>());
+        assert_eq!(ciphertext, (8..16).collect::<Vec<u8>>());
+        assert_eq!(tag, (16..32).collect::<Vec<u8>>());
+
+        assert!(opaque_into_parts(vec![0u8; 24]).unwrap().1.is_empty());
     }
 
-    Ok((explicit_nonce, ciphertext, tag))
+    #[test]
+    fn test_opaque_into_parts_too_short() {
+        for len in [0, 1, 8, 16, 23] {
+            assert!(opaque_into_parts(vec![0u8; len]).is_err());
+        }
+    }
 }
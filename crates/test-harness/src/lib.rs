@@ -0,0 +1,360 @@
+//! End-to-end test harness for TLSNotary.
+//!
+//! This crate wires up a server, a prover and a verifier over in-memory
+//! [`tokio::io::duplex`] streams so that the entire protocol can be run from
+//! a single test function.
+//!
+//! The harness does not spawn any tasks. All of the participants are driven
+//! concurrently from the calling future, which means it can be used from any
+//! async runtime, including `wasm-bindgen-test`. On native targets the
+//! [`fixture_server`] can be used as the TLS server, on other targets a server
+//! must be provided by the caller.
+//!
+//! The `smoke` tests of this crate run with `wasm-pack test`. On wasm they
+//! only cover setup: building the configurations and opening and closing a
+//! session pair. The full protocol runs need the server fixture, which is not
+//! available on wasm, so they only run natively and are ignored by default as
+//! they are slow.
+//!
+//! With [`HarnessConfig::record`] set, the messages of both sessions are
+//! recorded, see [`tlsn::record`], which is useful for debugging failed runs.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # async fn example() {
+//! use tlsn_test_harness::{HarnessConfig, fixture_server, reveal_all, run};
+//!
+//! let output = run(&HarnessConfig::default(), fixture_server, reveal_all).await;
+//!
+//! assert!(output.verifier_output.transcript.unwrap().is_complete());
+//! # }
+//! ```
+
+#![deny(missing_docs, unreachable_pub, unused_must_use)]
+#![deny(clippy::all)]
+#![forbid(unsafe_code)]
+
+use std::future::Future;
+
+use futures::{AsyncReadExt, AsyncWriteExt};
+use tlsn::{
+    Session,
+    config::{
+        prove::ProveConfig,
+        prover::ProverConfig,
+        session::SessionConfig,
+        tls::TlsClientConfig,
+        tls_commit::{TlsCommitConfig, mpc::MpcTlsConfig},
+        verifier::VerifierConfig,
+    },
+    connection::ServerName,
+    prover::{Prover, ProverOutput, state as prover_state},
+    record::Recording,
+    transcript::Transcript,
+    verifier::{Verifier, VerifierOutput, state as verifier_state},
+    webpki::{CertificateDer, RootCertStore},
+};
+use tlsn_server_fixture_certs::{CA_CERT_DER, SERVER_DOMAIN};
+use tokio::io::DuplexStream;
+use tokio_util::compat::{Compat, TokioAsyncReadCompatExt};
+
+/// IO type of the in-memory streams used by the harness.
+pub type Io = Compat<DuplexStream>;
+
+/// Buffer size of the duplex stream between the prover and the verifier.
+const SESSION_BUF_SIZE: usize = 1 << 24;
+/// Buffer size of the duplex stream between the prover and the server.
+const SERVER_BUF_SIZE: usize = 1 << 17;
+
+/// Harness configuration.
+#[derive(Debug, Clone)]
+pub struct HarnessConfig {
+    /// Maximum number of bytes that can be sent from prover to server.
+    pub max_sent_data: usize,
+    /// Maximum number of application records sent from prover to server.
+    pub max_sent_records: usize,
+    /// Maximum number of bytes that can be received by prover from server.
+    pub max_recv_data: usize,
+    /// Maximum number of application records received by prover from server.
+    pub max_recv_records_online: usize,
+    /// Request the prover sends to the server.
+    pub request: Vec<u8>,
+    /// Whether the prover closes its side of the connection before reading the
+    /// response. Otherwise the prover reads until the server closes the
+    /// connection, and closes its side afterwards.
+    pub close_before_read: bool,
+    /// Whether the messages of the prover and verifier sessions are recorded.
+    pub record: bool,
+}
+
+impl Default for HarnessConfig {
+    fn default() -> Self {
+        Self {
+            max_sent_data: 1 << 12,
+            max_sent_records: 4,
+            max_recv_data: 1 << 14,
+            max_recv_records_online: 6,
+            request: b"GET / HTTP/1.1\r\nConnection: close\r\n\r\n".to_vec(),
+            close_before_read: true,
+            record: false,
+        }
+    }
+}
+
+/// Output of a full protocol run.
+#[derive(Debug)]
+pub struct HarnessOutput {
+    /// The full transcript seen by the prover.
+    pub transcript: Transcript,
+    /// Output of the prover.
+    pub prover_output: ProverOutput,
+    /// Output of the verifier.
+    pub verifier_output: VerifierOutput,
+    /// Recording of the prover session, if enabled.
+    pub prover_recording: Option<Recording>,
+    /// Recording of the verifier session, if enabled.
+    pub verifier_recording: Option<Recording>,
+}
+
+/// Returns a pair of sessions connected by an in-memory duplex stream.
+pub fn session_pair() -> (Session<Io>, Session<Io>) {
+    session_pair_with_config(SessionConfig::default())
+}
+
+/// Returns a pair of sessions with the given configuration, connected by an
+/// in-memory duplex stream.
+pub fn session_pair_with_config(config: SessionConfig) -> (Session<Io>, Session<Io>) {
+    let (socket_0, socket_1) = tokio::io::duplex(SESSION_BUF_SIZE);
+
+    (
+        Session::with_config(socket_0.compat(), config.clone()),
+        Session::with_config(socket_1.compat(), config),
+    )
+}
+
+/// Returns the root certificate store which trusts the server fixture.
+pub fn root_store() -> RootCertStore {
+    RootCertStore {
+        roots: vec![CertificateDer(CA_CERT_DER.to_vec())],
+    }
+}
+
+/// Returns the TLS commitment configuration for the given harness config.
+pub fn tls_commit_config(config: &HarnessConfig) -> TlsCommitConfig {
+    TlsCommitConfig::builder()
+        .protocol(
+            MpcTlsConfig::builder()
+                .max_sent_data(config.max_sent_data)
+                .max_sent_records(config.max_sent_records)
+                .max_recv_data(config.max_recv_data)
+                .max_recv_records_online(config.max_recv_records_online)
+                .build()
+                .expect("mpc tls config should be valid"),
+        )
+        .build()
+        .expect("tls commit config should be valid")
+}
+
+/// Returns the TLS client configuration for connecting to the server fixture.
+pub fn tls_client_config() -> TlsClientConfig {
+    TlsClientConfig::builder()
+        .server_name(ServerName::Dns(
+            SERVER_DOMAIN.try_into().expect("server domain is valid"),
+        ))
+        .root_store(root_store())
+        .build()
+        .expect("tls client config should be valid")
+}
+
+/// Returns a verifier configuration which trusts the server fixture.
+pub fn verifier_config() -> VerifierConfig {
+    VerifierConfig::builder()
+        .root_store(root_store())
+        .build()
+        .expect("verifier config should be valid")
+}
+
+/// Returns a prove configuration which reveals the server identity and the
+/// entire transcript.
+pub fn reveal_all(transcript: &Transcript) -> ProveConfig {
+    let (sent_len, recv_len) = transcript.len();
+
+    let mut builder = ProveConfig::builder(transcript);
+    builder.server_identity();
+    builder
+        .reveal_sent(&(0..sent_len))
+        .expect("range is in bounds");
+    builder
+        .reveal_recv(&(0..recv_len))
+        .expect("range is in bounds");
+
+    builder.build().expect("prove config should be valid")
+}
+
+/// Serves a single connection using the server fixture.
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn fixture_server(socket: Io) {
+    tlsn_server_fixture::bind(socket)
+        .await
+        .expect("server fixture should not error");
+}
+
+/// Runs the full protocol.
+///
+/// The prover connects to the server returned by `server`, sends
+/// [`HarnessConfig::request`] and reads the response until the connection is
+/// closed. Afterwards the prover proves to the verifier using the
+/// configuration returned by `prove`.
+///
+/// # Panics
+///
+/// Panics if any of the participants fail.
+pub async fn run<S, SFut, P>(config: &HarnessConfig, server: S, prove: P) -> HarnessOutput
+where
+    S: FnOnce(Io) -> SFut,
+    SFut: Future<Output = ()>,
+    P: FnOnce(&Transcript) -> ProveConfig,
+{
+    let session_config = SessionConfig::builder()
+        .record(config.record)
+        .build()
+        .expect("session config should be valid");
+    let (mut session_p, mut session_v) = session_pair_with_config(session_config);
+
+    let prover = session_p
+        .new_prover(ProverConfig::builder().build().unwrap())
+        .expect("prover should be created");
+    let verifier = session_v
+        .new_verifier(verifier_config())
+        .expect("verifier should be created");
+
+    let (driver_p, handle_p) = session_p.split();
+    let (driver_v, handle_v) = session_v.split();
+
+    let protocol = async {
+        let ((transcript, prover_output), verifier_output) = futures::join!(
+            run_prover(prover, config, server, prove),
+            run_verifier(verifier)
+        );
+
+        handle_p.close();
+        handle_v.close();
+
+        HarnessOutput {
+            transcript,
+            prover_output,
+            verifier_output,
+            prover_recording: handle_p.recording(),
+            verifier_recording: handle_v.recording(),
+        }
+    };
+
+    let (driver_p, driver_v, output) = futures::join!(driver_p, driver_v, protocol);
+
+    driver_p.expect("prover session should close cleanly");
+    driver_v.expect("verifier session should close cleanly");
+
+    output
+}
+
+/// Runs the prover against the server and proves to the verifier.
+///
+/// # Panics
+///
+/// Panics if the prover or the server fail.
+pub async fn run_prover<S, SFut, P>(
+    prover: Prover<prover_state::Initialized>,
+    config: &HarnessConfig,
+    server: S,
+    prove: P,
+) -> (Transcript, ProverOutput)
+where
+    S: FnOnce(Io) -> SFut,
+    SFut: Future<Output = ()>,
+    P: FnOnce(&Transcript) -> ProveConfig,
+{
+    let (client_socket, server_socket) = tokio::io::duplex(SERVER_BUF_SIZE);
+
+    let prover = prover
+        .commit(tls_commit_config(config))
+        .await
+        .expect("commitment should be accepted");
+
+    let (mut tls_connection, prover_fut) = prover
+        .connect(tls_client_config(), client_socket.compat())
+        .expect("prover should connect");
+
+    let client = async {
+        tls_connection
+            .write_all(&config.request)
+            .await
+            .expect("request should be sent");
+
+        if config.close_before_read {
+            tls_connection
+                .close()
+                .await
+                .expect("connection should close");
+        }
+
+        let mut response = Vec::new();
+        tls_connection
+            .read_to_end(&mut response)
+            .await
+            .expect("response should be received");
+
+        if !config.close_before_read {
+            tls_connection
+                .close()
+                .await
+                .expect("connection should close");
+        }
+    };
+
+    let (_, _, prover) = futures::join!(server(server_socket.compat()), client, prover_fut);
+    let mut prover = prover.expect("prover should complete the connection");
+
+    // The plaintext only lives in the transcript, not in the TLS records.
+    assert!(prover.tls_transcript().to_transcript().is_err());
+
+    let prove_config = prove(prover.transcript());
+    let transcript = prover.transcript().clone();
+
+    let output = prover
+        .prove(&prove_config)
+        .await
+        .expect("prover should prove");
+    prover.close().await.expect("prover should close");
+
+    (transcript, output)
+}
+
+/// Runs the verifier, accepting the commitment and the proof.
+///
+/// # Panics
+///
+/// Panics if the verifier fails.
+pub async fn run_verifier(verifier: Verifier<verifier_state::Initialized>) -> VerifierOutput {
+    let verifier = verifier
+        .commit()
+        .await
+        .expect("commitment request should be received")
+        .accept()
+        .await
+        .expect("commitment should be accepted")
+        .run()
+        .await
+        .expect("commitment protocol should complete");
+
+    let (output, verifier) = verifier
+        .verify()
+        .await
+        .expect("proof request should be received")
+        .accept()
+        .await
+        .expect("proof should verify");
+    verifier.close().await.expect("verifier should close");
+
+    output
+}
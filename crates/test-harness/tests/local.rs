@@ -0,0 +1,86 @@
+//! Connections using the insecure local backend, which runs the prover
+//! without a verifier.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use futures::{AsyncReadExt, AsyncWriteExt};
+use tlsn::{
+    config::prover::ProverConfig,
+    prover::local::{CipherSuite, LocalProver, ProtocolVersion},
+};
+use tlsn_test_harness::{HarnessConfig, fixture_server, session_pair, tls_client_config};
+use tokio_util::compat::TokioAsyncReadCompatExt;
+
+async fn run_local(suite: CipherSuite, version: ProtocolVersion) {
+    let config = HarnessConfig::default();
+    let (client_socket, server_socket) = tokio::io::duplex(1 << 17);
+
+    let (mut tls_connection, prover_fut) = LocalProver::new(ProverConfig::insecure_local_backend())
+        .unwrap()
+        .cipher_suites(&[suite])
+        .unwrap()
+        .connect(tls_client_config(), client_socket.compat())
+        .unwrap();
+
+    let client = async {
+        tls_connection.write_all(&config.request).await.unwrap();
+        tls_connection.close().await.unwrap();
+
+        let mut response = Vec::new();
+        tls_connection.read_to_end(&mut response).await.unwrap();
+        response
+    };
+
+    let (_, response, output) =
+        futures::join!(fixture_server(server_socket.compat()), client, prover_fut);
+    let output = output.unwrap();
+
+    assert_eq!(output.cipher_suite, suite);
+    assert_eq!(output.protocol_version, version);
+    assert_eq!(output.transcript.sent(), config.request.as_slice());
+    assert_eq!(output.transcript.received(), response.as_slice());
+    assert!(response.starts_with(b"HTTP/1.1"));
+}
+
+#[tokio::test]
+async fn test_tls13_aes_gcm() {
+    run_local(
+        CipherSuite::TLS13_AES_128_GCM_SHA256,
+        ProtocolVersion::TLSv1_3,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_tls13_chacha20_poly1305() {
+    run_local(
+        CipherSuite::TLS13_CHACHA20_POLY1305_SHA256,
+        ProtocolVersion::TLSv1_3,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_tls12_chacha20_poly1305() {
+    run_local(
+        CipherSuite::TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256,
+        ProtocolVersion::TLSv1_2,
+    )
+    .await;
+}
+
+#[test]
+fn test_session_rejects_local_backend() {
+    let (mut session, _) = session_pair();
+
+    assert!(
+        session
+            .new_prover(ProverConfig::insecure_local_backend())
+            .is_err()
+    );
+}
+
+#[test]
+fn test_local_prover_requires_local_backend() {
+    assert!(LocalProver::new(ProverConfig::builder().build().unwrap()).is_err());
+}
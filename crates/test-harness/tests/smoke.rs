@@ -0,0 +1,46 @@
+//! Smoke tests of the harness wiring, which run on native targets and with
+//! `wasm-bindgen-test`.
+//!
+//! On wasm this is limited to setup, no protocol is run: the server fixture
+//! is native only, so the full runs in `test.rs` do not build for wasm.
+
+use tlsn::transcript::Transcript;
+use tlsn_test_harness::{
+    HarnessConfig, reveal_all, session_pair, tls_client_config, tls_commit_config, verifier_config,
+};
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn test_configs() {
+    let _ = tls_commit_config(&HarnessConfig::default());
+    let _ = tls_client_config();
+    let _ = verifier_config();
+
+    let transcript = Transcript::new(
+        b"GET / HTTP/1.1\r\n\r\n",
+        b"HTTP/1.1 204 No Content\r\n\r\n",
+    );
+    let _ = reveal_all(&transcript);
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+async fn test_session_pair_closes() {
+    let (session_p, session_v) = session_pair();
+
+    let (driver_p, handle_p) = session_p.split();
+    let (driver_v, handle_v) = session_v.split();
+
+    handle_p.close();
+    handle_v.close();
+
+    let (driver_p, driver_v) = futures::join!(driver_p, driver_v);
+    driver_p.unwrap();
+    driver_v.unwrap();
+}
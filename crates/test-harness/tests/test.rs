@@ -0,0 +1,228 @@
+//! Full protocol runs, which are too slow to run by default.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::{pin::Pin, task::Poll};
+
+use futures::{AsyncRead, AsyncWrite, future::poll_fn};
+use tlsn::{
+    config::prover::ProverConfig,
+    connection::ServerName,
+    prover::{Prover, state},
+    record::EventKind,
+};
+use tlsn_server_fixture_certs::SERVER_DOMAIN;
+use tlsn_test_harness::{
+    HarnessConfig, Io, fixture_server, reveal_all, run, run_verifier, session_pair,
+    tls_client_config, tls_commit_config, verifier_config,
+};
+use tokio_util::compat::TokioAsyncReadCompatExt;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+#[ignore]
+async fn test_reveal_all() {
+    let config = HarnessConfig::default();
+
+    let output = run(&config, fixture_server, reveal_all).await;
+
+    let ServerName::Dns(server_name) = output.verifier_output.server_name.unwrap();
+    assert_eq!(server_name.as_str(), SERVER_DOMAIN);
+
+    let partial_transcript = output.verifier_output.transcript.unwrap();
+    assert!(partial_transcript.is_complete());
+    assert_eq!(partial_transcript.sent_unsafe(), output.transcript.sent());
+    assert_eq!(
+        partial_transcript.received_unsafe(),
+        output.transcript.received()
+    );
+    assert!(output.prover_output.transcript_commitments.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+#[ignore]
+async fn test_server_closes_first() {
+    let config = HarnessConfig {
+        close_before_read: false,
+        ..Default::default()
+    };
+
+    let output = run(&config, fixture_server, reveal_all).await;
+
+    let partial_transcript = output.verifier_output.transcript.unwrap();
+    assert!(partial_transcript.is_complete());
+    assert!(!output.transcript.received().is_empty());
+    assert_eq!(
+        partial_transcript.received_unsafe(),
+        output.transcript.received()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+#[ignore]
+async fn test_record() {
+    let config = HarnessConfig {
+        record: true,
+        ..Default::default()
+    };
+
+    let output = run(&config, fixture_server, reveal_all).await;
+
+    let prover = output.prover_recording.unwrap();
+    let verifier = output.verifier_recording.unwrap();
+
+    // Each side read what the other wrote, on the same streams.
+    assert!(!prover.streams().is_empty());
+    assert_eq!(prover.streams(), verifier.streams());
+    for id in prover.streams() {
+        assert_eq!(
+            prover.data(id, EventKind::Write),
+            verifier.data(id, EventKind::Read)
+        );
+        assert_eq!(
+            prover.data(id, EventKind::Read),
+            verifier.data(id, EventKind::Write)
+        );
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+#[ignore]
+async fn test_sans_io() {
+    let config = HarnessConfig::default();
+    let (mut session_p, mut session_v) = session_pair();
+
+    let prover = session_p
+        .new_prover(ProverConfig::builder().build().unwrap())
+        .unwrap();
+    let verifier = session_v.new_verifier(verifier_config()).unwrap();
+
+    let (driver_p, handle_p) = session_p.split();
+    let (driver_v, handle_v) = session_v.split();
+
+    let (client_socket, server_socket) = tokio::io::duplex(1 << 17);
+
+    let prover = async {
+        let mut prover = prover
+            .commit(tls_commit_config(&config))
+            .await
+            .unwrap()
+            .connect_sans_io(tls_client_config())
+            .unwrap();
+
+        let response = drive(&mut prover, client_socket.compat(), &config.request)
+            .await
+            .unwrap();
+
+        let mut prover = prover.finish().unwrap();
+        assert_eq!(prover.transcript().sent(), config.request.as_slice());
+        assert_eq!(prover.transcript().received(), response.as_slice());
+
+        let prove_config = reveal_all(prover.transcript());
+        prover.prove(&prove_config).await.unwrap();
+        prover.close().await.unwrap();
+
+        response
+    };
+
+    let protocol = async {
+        let (response, _, verifier_output) = futures::join!(
+            prover,
+            fixture_server(server_socket.compat()),
+            run_verifier(verifier)
+        );
+
+        handle_p.close();
+        handle_v.close();
+
+        (response, verifier_output)
+    };
+
+    let (driver_p, driver_v, (response, verifier_output)) =
+        futures::join!(driver_p, driver_v, protocol);
+    driver_p.unwrap();
+    driver_v.unwrap();
+
+    assert!(response.starts_with(b"HTTP/1.1 200 OK"));
+    let partial_transcript = verifier_output.transcript.unwrap();
+    assert!(partial_transcript.is_complete());
+    assert_eq!(partial_transcript.received_unsafe(), response.as_slice());
+}
+
+/// Drives the prover from its own loop, the way an embedder would: sends
+/// `request` to the server and returns the response once the server closed
+/// the connection and the prover committed the transcript.
+async fn drive(
+    prover: &mut Prover<state::Engine>,
+    mut socket: Io,
+    mut request: &[u8],
+) -> tlsn::Result<Vec<u8>> {
+    let mut buf = vec![0u8; 1 << 14];
+    let mut tls_in = Vec::new();
+    let mut tls_out = Vec::new();
+    let mut response = Vec::new();
+    let mut server_closed = false;
+
+    poll_fn(|cx| -> Poll<tlsn::Result<()>> {
+        loop {
+            let done = prover.poll(cx)?.is_ready();
+            let mut progress = false;
+
+            // Application -> prover.
+            if !request.is_empty() && prover.wants_write() {
+                let write = prover.write(request)?;
+                request = &request[write..];
+                progress |= write > 0;
+            }
+
+            // Prover -> application.
+            while prover.wants_read() {
+                let read = prover.read(&mut buf)?;
+                if read == 0 {
+                    break;
+                }
+                response.extend_from_slice(&buf[..read]);
+                progress = true;
+            }
+
+            // Prover -> server.
+            if tls_out.is_empty() && prover.wants_write_tls() {
+                let write = prover.write_tls(&mut buf)?;
+                tls_out.extend_from_slice(&buf[..write]);
+            }
+            if !tls_out.is_empty()
+                && let Poll::Ready(write) = Pin::new(&mut socket).poll_write(cx, &tls_out)?
+            {
+                tls_out.drain(..write);
+                progress = true;
+            }
+
+            // Server -> prover.
+            if tls_in.is_empty()
+                && !server_closed
+                && let Poll::Ready(read) = Pin::new(&mut socket).poll_read(cx, &mut buf)?
+            {
+                if read == 0 {
+                    server_closed = true;
+                    prover.server_close();
+                } else {
+                    tls_in.extend_from_slice(&buf[..read]);
+                }
+                progress = true;
+            }
+            if !tls_in.is_empty() && prover.wants_read_tls() {
+                let read = prover.read_tls(&tls_in)?;
+                tls_in.drain(..read);
+                progress |= read > 0;
+            }
+
+            if done && !prover.wants_read() {
+                return Poll::Ready(Ok(()));
+            } else if !progress {
+                return Poll::Pending;
+            }
+        }
+    })
+    .await?;
+
+    Ok(response)
+}
@@ -15,7 +15,7 @@ use tls_core::{
     ke::ServerKxDetails,
     key::PublicKey,
     msgs::{
-        enums::{CipherSuite, NamedGroup, ProtocolVersion},
+        enums::{CipherSuite, HandshakeType, NamedGroup, ProtocolVersion},
         handshake::Random,
         message::{OpaqueMessage, PlainMessage},
     },
@@ -34,6 +34,8 @@ pub enum BackendError {
     UnsupportedCiphersuite(CipherSuite),
     #[error("Unsupported curve group: {0:?}")]
     UnsupportedCurveGroup(NamedGroup),
+    #[error("Unsupported key exchange group {0:?}: {1}")]
+    UnsupportedKxGroup(NamedGroup, String),
     #[error("Invalid configuration: {0:?}")]
     InvalidConfig(String),
     #[error("Invalid server public keyshare")]
@@ -66,6 +68,26 @@ pub enum DecryptMode {
     Application,
 }
 
+/// Sender of a handshake message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandshakeOrigin {
+    /// Sent by the client.
+    Client,
+    /// Sent by the server.
+    Server,
+}
+
+/// A handshake message observed by the backend.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandshakeRecord {
+    /// Sender of the message.
+    pub origin: HandshakeOrigin,
+    /// Type of the message.
+    pub typ: HandshakeType,
+    /// Encoded message, including the handshake header.
+    pub payload: Vec<u8>,
+}
+
 /// Core trait which manages crypto operations for the TLS connection such as
 /// key exchange, encryption and decryption.
 #[async_trait]
@@ -84,6 +106,11 @@ pub trait Backend: Send {
     async fn set_decrypt(&mut self, mode: DecryptMode) -> Result<(), BackendError>;
     /// Returns client_random value.
     async fn get_client_random(&mut self) -> Result<Random, BackendError>;
+    /// Returns the key exchange groups supported by the backend.
+    async fn supported_kx_groups(&mut self) -> Result<Vec<NamedGroup>, BackendError>;
+    /// Sets the key exchange group of the client keyshare.
+    /// Throws error if the group is not supported.
+    async fn set_kx_group(&mut self, group: NamedGroup) -> Result<(), BackendError>;
     /// Returns public client keyshare.
     async fn get_client_key_share(&mut self) -> Result<PublicKey, BackendError>;
     /// Sets server random.
@@ -133,4 +160,10 @@ pub trait Backend: Send {
     async fn server_closed(&mut self) -> Result<(), BackendError> {
         Ok(())
     }
+    /// Records a handshake message sent or received by the client.
+    async fn push_handshake_message(&mut self, record: HandshakeRecord)
+        -> Result<(), BackendError>;
+    /// Returns the handshake messages recorded so far, in the order they were
+    /// sent or received.
+    async fn handshake_transcript(&mut self) -> Result<Vec<HandshakeRecord>, BackendError>;
 }
@@ -1,4 +1,8 @@
 mod standard;
 
+#[cfg(fuzzing)]
+pub use standard::fuzz;
 pub use standard::RustCryptoBackend;
-pub use tls_backend::{Backend, BackendError, DecryptMode, EncryptMode};
+pub use tls_backend::{
+    Backend, BackendError, DecryptMode, EncryptMode, HandshakeOrigin, HandshakeRecord,
+};
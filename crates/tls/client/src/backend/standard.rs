@@ -1,4 +1,4 @@
-use super::{Backend, BackendError};
+use super::{Backend, BackendError, HandshakeRecord};
 use crate::{DecryptMode, EncryptMode, Error};
 #[allow(deprecated)]
 use aes_gcm::{
@@ -6,12 +6,13 @@ use aes_gcm::{
     Aes128Gcm,
 };
 use async_trait::async_trait;
-use p256::{ecdh::EphemeralSecret, EncodedPoint, PublicKey as ECDHPublicKey};
-use rand::{rng, rngs::OsRng, Rng};
+use chacha20poly1305::ChaCha20Poly1305;
+use hmac::{Hmac, Mac};
+use rand::{rng, Rng};
 
 use digest::Digest;
-use rand06_compat::Rand0_6CompatExt;
-use std::{any::Any, collections::VecDeque, convert::TryInto, mem::take};
+use sha2::Sha256;
+use std::{any::Any, collections::VecDeque, convert::TryInto};
 use tls_core::{
     cert::ServerCertDetails,
     ke::ServerKxDetails,
@@ -26,7 +27,21 @@ use tls_core::{
     suites::{self, SupportedCipherSuite},
 };
 
+#[cfg(fuzzing)]
+pub mod fuzz;
+mod kx;
+
+use kx::KxSecret;
+
+/// Length of the AEAD tag of all implemented cipher suites.
+const TAG_LEN: usize = 16;
+
 /// Implementation of TLS backend using RustCrypto primitives
+///
+/// Supports TLS 1.2 and TLS 1.3 with the AES-128-GCM and ChaCha20-Poly1305
+/// cipher suites. The key exchange groups are secp256r1 and X25519, and in
+/// TLS 1.3 also the X25519MLKEM768 post-quantum hybrid. TLS 1.3 is supported
+/// without client authentication, early data or session resumption.
 pub struct RustCryptoBackend {
     client_random: Option<Random>,
     server_random: Option<Random>,
@@ -34,23 +49,24 @@ pub struct RustCryptoBackend {
     master_secret: Option<[u8; 48]>,
     // extended master secret seed
     ems_seed: Option<Vec<u8>>,
-    ecdh_pubkey: Option<Vec<u8>>,
-    ecdh_secret: Option<EphemeralSecret>,
+    kx_group: NamedGroup,
+    kx_secret: Option<KxSecret>,
+    // key exchange shared secret, input to the TLS 1.3 key schedule
+    shared_secret: Option<Vec<u8>>,
     // session_keys size can vary depending on the ciphersuite
     session_keys: Option<Vec<u8>>,
+    tls13_secrets: Option<Tls13Secrets>,
     protocol_version: Option<ProtocolVersion>,
     cipher_suite: Option<SupportedCipherSuite>,
-    curve: Option<NamedGroup>,
-    implemented_suites: [CipherSuite; 2],
-    encrypter: Option<Encrypter>,
-    decrypter: Option<Decrypter>,
+    implemented_suites: [CipherSuite; 6],
+    encrypter: Option<RecordCipher>,
+    decrypter: Option<RecordCipher>,
 
     write_seq: u64,
     read_seq: u64,
     incoming_encrypted: VecDeque<OpaqueMessage>,
-    incoming_plain: VecDeque<PlainMessage>,
     outgoing_encrypted: VecDeque<OpaqueMessage>,
-    outgoing_plain: VecDeque<PlainMessage>,
+    handshake: Vec<HandshakeRecord>,
 }
 
 impl RustCryptoBackend {
@@ -59,26 +75,30 @@ impl RustCryptoBackend {
         Self {
             client_random: None,
             server_random: None,
-            ecdh_pubkey: None,
-            ecdh_secret: None,
+            kx_group: NamedGroup::secp256r1,
+            kx_secret: None,
+            shared_secret: None,
             master_secret: None,
             ems_seed: None,
             session_keys: None,
+            tls13_secrets: None,
             protocol_version: None,
             cipher_suite: None,
-            curve: Some(NamedGroup::secp256r1),
             implemented_suites: [
                 CipherSuite::TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256,
                 CipherSuite::TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256,
+                CipherSuite::TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256,
+                CipherSuite::TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256,
+                CipherSuite::TLS13_AES_128_GCM_SHA256,
+                CipherSuite::TLS13_CHACHA20_POLY1305_SHA256,
             ],
             encrypter: None,
             decrypter: None,
             write_seq: 0,
             read_seq: 0,
             incoming_encrypted: VecDeque::new(),
-            incoming_plain: VecDeque::new(),
             outgoing_encrypted: VecDeque::new(),
-            outgoing_plain: VecDeque::new(),
+            handshake: Vec::new(),
         }
     }
 
@@ -99,13 +119,14 @@ impl RustCryptoBackend {
     }
 
     /// Expands pre-master secret into session key using TLS 1.2 PRF
-    /// Returns master_secret and session keys
+    /// Returns master_secret and a key block of `key_block_len` bytes
     pub fn key_expansion_tls12(
         &mut self,
         client_random: &[u8; 32],
         server_random: &[u8; 32],
         pms: &[u8],
-    ) -> ([u8; 48], [u8; 40]) {
+        key_block_len: usize,
+    ) -> ([u8; 48], Vec<u8>) {
         // first expand pms into ms
         let mut ms = [0u8; 48];
         prf(
@@ -117,7 +138,7 @@ impl RustCryptoBackend {
         .expect("key length is valid");
 
         // expand ms into session keys
-        let mut session_keys = [0u8; 40];
+        let mut session_keys = vec![0u8; key_block_len];
         prf(
             &mut session_keys,
             &ms,
@@ -129,59 +150,75 @@ impl RustCryptoBackend {
         (ms, session_keys)
     }
 
-    fn set_encrypter(&mut self) -> Result<(), BackendError> {
+    fn aead(&self) -> Result<AeadAlg, BackendError> {
         let cipher_suite = self.cipher_suite.ok_or(BackendError::InvalidState(
-            "can not set enccrypter, ciphersuite not set".to_string(),
+            "ciphersuite not set".to_string(),
         ))?;
 
-        match cipher_suite.suite() {
-            CipherSuite::TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256
-            | CipherSuite::TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256 => {
-                // extract client_write_key and client_write_iv. They may be at different
-                // offsets depending on the cipher suite.
-                let mut write_key = [0u8; 16];
-                let mut write_iv = [0u8; 4];
-                let session_keys = self
-                    .session_keys
-                    .as_ref()
-                    .ok_or(BackendError::InvalidState(
-                        "can not set encrypter, session_keys are not set".to_string(),
-                    ))?;
-                write_key.copy_from_slice(&session_keys[0..16]);
-                write_iv.copy_from_slice(&session_keys[32..36]);
-                self.encrypter = Some(Encrypter::new(write_key, write_iv, cipher_suite.suite()));
-            }
-            suite => return Err(BackendError::UnsupportedCiphersuite(suite)),
-        }
+        AeadAlg::for_suite(cipher_suite.suite())
+    }
+
+    fn protocol_version(&self) -> Result<ProtocolVersion, BackendError> {
+        self.protocol_version.ok_or(BackendError::InvalidState(
+            "Protocol version not set".to_string(),
+        ))
+    }
+
+    fn tls13_secrets(&self) -> Result<&Tls13Secrets, BackendError> {
+        self.tls13_secrets
+            .as_ref()
+            .ok_or(BackendError::InvalidState(
+                "TLS 1.3 handshake secrets are not set".to_string(),
+            ))
+    }
+
+    fn set_encrypter(&mut self) -> Result<(), BackendError> {
+        let aead = self.aead()?;
+        // extract client_write_key and client_write_iv. They are at different
+        // offsets depending on the cipher suite.
+        let (key_len, iv_len) = (aead.key_len(), aead.tls12_iv_len());
+        let session_keys = self
+            .session_keys
+            .as_ref()
+            .ok_or(BackendError::InvalidState(
+                "can not set encrypter, session_keys are not set".to_string(),
+            ))?;
+
+        let key = session_keys[..key_len].to_vec();
+        let iv = session_keys[2 * key_len..2 * key_len + iv_len].to_vec();
+        self.encrypter = Some(RecordCipher::new(aead, ProtocolVersion::TLSv1_2, key, iv));
+
         Ok(())
     }
+
     fn set_decrypter(&mut self) -> Result<(), BackendError> {
-        let cipher_suite = self.cipher_suite.ok_or(BackendError::InvalidState(
-            "can not set decrypter, ciphersuite not set".to_string(),
-        ))?;
+        let aead = self.aead()?;
+        // extract server_write_key and server_write_iv. They are at different
+        // offsets depending on the cipher suite.
+        let (key_len, iv_len) = (aead.key_len(), aead.tls12_iv_len());
+        let session_keys = self
+            .session_keys
+            .as_ref()
+            .ok_or(BackendError::InvalidState(
+                "can not set decrypter, session_keys are not set".to_string(),
+            ))?;
+
+        let key = session_keys[key_len..2 * key_len].to_vec();
+        let iv = session_keys[2 * key_len + iv_len..2 * key_len + 2 * iv_len].to_vec();
+        self.decrypter = Some(RecordCipher::new(aead, ProtocolVersion::TLSv1_2, key, iv));
 
-        match cipher_suite.suite() {
-            CipherSuite::TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256
-            | CipherSuite::TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256 => {
-                // extract server_write_key and server_write_iv. They may be at different
-                // offsets depending on the cipher suite.
-                let mut write_key = [0u8; 16];
-                let mut write_iv = [0u8; 4];
-                let session_keys = self
-                    .session_keys
-                    .as_ref()
-                    .ok_or(BackendError::InvalidState(
-                        "can not set decrypter, session_keys are not set".to_string(),
-                    ))?;
-                write_key.copy_from_slice(&session_keys[16..32]);
-                write_iv.copy_from_slice(&session_keys[36..40]);
-                self.decrypter = Some(Decrypter::new(write_key, write_iv, cipher_suite.suite()));
-            }
-            suite => return Err(BackendError::UnsupportedCiphersuite(suite)),
-        }
         Ok(())
     }
 
+    /// Returns the record cipher for a TLS 1.3 traffic secret.
+    fn tls13_cipher(&self, secret: &[u8; 32]) -> Result<RecordCipher, BackendError> {
+        let aead = self.aead()?;
+        let key = hkdf_expand_label(secret, b"key", &[], aead.key_len());
+        let iv = hkdf_expand_label(secret, b"iv", &[], 12);
+
+        Ok(RecordCipher::new(aead, ProtocolVersion::TLSv1_3, key, iv))
+    }
+
     fn encrypt(&mut self, msg: PlainMessage, seq: u64) -> Result<OpaqueMessage, BackendError> {
         let enc = self
             .encrypter
@@ -190,20 +227,7 @@ impl RustCryptoBackend {
                 "Encrypter not ready".to_string(),
             ))?;
 
-        match enc.cipher_suite {
-            CipherSuite::TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256
-            | CipherSuite::TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256 => match msg.version {
-                ProtocolVersion::TLSv1_2 => {
-                    return enc.encrypt_aes128gcm(&msg, seq, &seq.to_be_bytes());
-                }
-                version => {
-                    return Err(BackendError::UnsupportedProtocolVersion(version));
-                }
-            },
-            suite => {
-                return Err(BackendError::UnsupportedCiphersuite(suite));
-            }
-        }
+        enc.encrypt(&msg, seq)
     }
 
     fn decrypt(&mut self, msg: OpaqueMessage, seq: u64) -> Result<PlainMessage, BackendError> {
@@ -214,20 +238,7 @@ impl RustCryptoBackend {
                 "Decrypter not ready".to_string(),
             ))?;
 
-        match dec.cipher_suite {
-            CipherSuite::TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256
-            | CipherSuite::TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256 => match msg.version {
-                ProtocolVersion::TLSv1_2 => {
-                    return dec.decrypt_aes128gcm(&msg, seq);
-                }
-                version => {
-                    return Err(BackendError::UnsupportedProtocolVersion(version));
-                }
-            },
-            suite => {
-                return Err(BackendError::UnsupportedCiphersuite(suite));
-            }
-        }
+        dec.decrypt(&msg, seq)
     }
 }
 
@@ -235,7 +246,7 @@ impl RustCryptoBackend {
 impl Backend for RustCryptoBackend {
     async fn set_protocol_version(&mut self, version: ProtocolVersion) -> Result<(), BackendError> {
         match version {
-            ProtocolVersion::TLSv1_2 => {
+            ProtocolVersion::TLSv1_2 | ProtocolVersion::TLSv1_3 => {
                 self.protocol_version = Some(version);
                 Ok(())
             }
@@ -270,12 +281,47 @@ impl Backend for RustCryptoBackend {
         Ok(suites::tls12::TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256)
     }
 
-    async fn set_encrypt(&mut self, _mode: EncryptMode) -> Result<(), BackendError> {
-        todo!()
+    async fn set_encrypt(&mut self, mode: EncryptMode) -> Result<(), BackendError> {
+        let secrets = self.tls13_secrets()?;
+        let secret = match mode {
+            EncryptMode::Handshake => secrets.client_handshake,
+            EncryptMode::Application => {
+                secrets
+                    .client_application
+                    .ok_or(BackendError::InvalidState(
+                        "application traffic secrets are not set".to_string(),
+                    ))?
+            }
+            EncryptMode::EarlyData => {
+                return Err(BackendError::InvalidConfig(
+                    "early data is not supported".to_string(),
+                ))
+            }
+        };
+
+        self.encrypter = Some(self.tls13_cipher(&secret)?);
+        self.write_seq = 0;
+
+        Ok(())
     }
 
-    async fn set_decrypt(&mut self, _mode: DecryptMode) -> Result<(), BackendError> {
-        todo!()
+    async fn set_decrypt(&mut self, mode: DecryptMode) -> Result<(), BackendError> {
+        let secrets = self.tls13_secrets()?;
+        let secret = match mode {
+            DecryptMode::Handshake => secrets.server_handshake,
+            DecryptMode::Application => {
+                secrets
+                    .server_application
+                    .ok_or(BackendError::InvalidState(
+                        "application traffic secrets are not set".to_string(),
+                    ))?
+            }
+        };
+
+        self.decrypter = Some(self.tls13_cipher(&secret)?);
+        self.read_seq = 0;
+
+        Ok(())
     }
 
     async fn get_client_random(&mut self) -> Result<Random, BackendError> {
@@ -285,22 +331,26 @@ impl Backend for RustCryptoBackend {
         Ok(r)
     }
 
+    async fn supported_kx_groups(&mut self) -> Result<Vec<NamedGroup>, BackendError> {
+        Ok(kx::KX_GROUPS.to_vec())
+    }
+
+    async fn set_kx_group(&mut self, group: NamedGroup) -> Result<(), BackendError> {
+        if !kx::KX_GROUPS.contains(&group) {
+            return Err(kx::unsupported(group));
+        }
+        self.kx_group = group;
+
+        Ok(())
+    }
+
     async fn get_client_key_share(&mut self) -> Result<PublicKey, BackendError> {
-        // TODO make sure this and other methods are not called twice/out of order
-        // generate our ECDH keypair
-        let sk = EphemeralSecret::random(&mut rng().compat());
-        let pk_bytes = EncodedPoint::from(sk.public_key()).to_bytes().to_vec();
-        self.ecdh_pubkey = Some(pk_bytes.clone());
-        self.ecdh_secret = Some(sk);
-
-        // return our ECDH pubkey
-        let group = self.curve.ok_or(BackendError::InvalidState(
-            "ECDH key curve not set yet".to_string(),
-        ))?;
+        let (secret, key) = KxSecret::generate(self.kx_group)?;
+        self.kx_secret = Some(secret);
 
         Ok(PublicKey {
-            group,
-            key: pk_bytes,
+            group: self.kx_group,
+            key,
         })
     }
 
@@ -311,47 +361,51 @@ impl Backend for RustCryptoBackend {
     }
 
     async fn set_server_key_share(&mut self, key: PublicKey) -> Result<(), BackendError> {
-        // convert raw server ECDH pubkey to an object
-        let server_pk =
-            ECDHPublicKey::from_sec1_bytes(&key.key).map_err(|_| BackendError::InvalidServerKey)?;
-
-        let sk = self.ecdh_secret.as_ref().unwrap();
-        // perform ECDH, obtain PMS (which is the X coordinate of the resulting
-        // EC point). The size of X for 256-bit curves is 32 bytes, for 384-bit
-        // curves it is 48 bytes etc.
-        let x_size = match self.curve.ok_or(BackendError::InvalidState(
-            "ECDH key curve not set yet".to_string(),
-        ))? {
-            NamedGroup::secp256r1 => 32,
-            NamedGroup::secp384r1 => 48,
-            group => return Err(BackendError::UnsupportedCurveGroup(group)),
-        };
+        let secret = self.kx_secret.take().ok_or(BackendError::InvalidState(
+            "Client key share not generated".to_string(),
+        ))?;
 
-        let mut pms = vec![0u8; x_size];
-        let secret = *sk.diffie_hellman(&server_pk).raw_secret_bytes();
-        pms.copy_from_slice(&secret);
+        if key.group != secret.group() {
+            return Err(BackendError::InvalidServerKey);
+        }
 
-        let (client_random, server_random) = match (self.client_random, self.server_random) {
-            (Some(cr), Some(sr)) => (cr.0, sr.0),
-            _ => {
-                return Err(BackendError::InvalidState(
-                    "Client_random and/or server_random not set".to_string(),
-                ))
-            }
-        };
+        if key.group == NamedGroup::X25519MLKEM768
+            && self.protocol_version()? != ProtocolVersion::TLSv1_3
+        {
+            return Err(BackendError::UnsupportedKxGroup(
+                key.group,
+                "hybrid key exchange requires TLS 1.3".to_string(),
+            ));
+        }
 
-        (self.master_secret, self.session_keys) = match self.protocol_version.ok_or(
-            BackendError::InvalidState("Protocol version not set".to_string()),
-        )? {
+        let pms = secret.complete(&key.key)?;
+
+        match self.protocol_version()? {
             ProtocolVersion::TLSv1_2 => {
-                let (ms, ek) = self.key_expansion_tls12(&client_random, &server_random, &pms);
-                (Some(ms), Some(ek.to_vec()))
+                let (client_random, server_random) = match (self.client_random, self.server_random)
+                {
+                    (Some(cr), Some(sr)) => (cr.0, sr.0),
+                    _ => {
+                        return Err(BackendError::InvalidState(
+                            "Client_random and/or server_random not set".to_string(),
+                        ))
+                    }
+                };
+
+                let key_block_len = self.aead()?.tls12_key_block_len();
+                let (ms, ek) =
+                    self.key_expansion_tls12(&client_random, &server_random, &pms, key_block_len);
+                self.master_secret = Some(ms);
+                self.session_keys = Some(ek);
+
+                self.set_encrypter()?;
+                self.set_decrypter()?;
             }
+            // In TLS 1.3 the traffic keys also depend on the handshake hash,
+            // they are derived once it is set.
+            ProtocolVersion::TLSv1_3 => self.shared_secret = Some(pms),
             version => return Err(BackendError::UnsupportedProtocolVersion(version)),
-        };
-
-        self.set_encrypter()?;
-        self.set_decrypter()?;
+        }
 
         Ok(())
     }
@@ -375,36 +429,64 @@ impl Backend for RustCryptoBackend {
         Ok(())
     }
 
-    async fn set_hs_hash_server_hello(&mut self, _hash: Vec<u8>) -> Result<(), BackendError> {
+    async fn set_hs_hash_server_hello(&mut self, hash: Vec<u8>) -> Result<(), BackendError> {
+        if self.protocol_version()? != ProtocolVersion::TLSv1_3 {
+            return Ok(());
+        }
+
+        let shared_secret = self
+            .shared_secret
+            .as_ref()
+            .ok_or(BackendError::InvalidState(
+                "Server key share not set".to_string(),
+            ))?;
+
+        self.tls13_secrets = Some(Tls13Secrets::new(shared_secret, &hash));
+
         Ok(())
     }
 
     async fn get_server_finished_vd(&mut self, hash: Vec<u8>) -> Result<Vec<u8>, BackendError> {
-        let ms = self.master_secret.ok_or(BackendError::InvalidState(
-            "Master secret not set".to_string(),
-        ))?;
-
-        let verify_data = match self.protocol_version.ok_or(BackendError::InvalidState(
-            "Protocol version not set".to_string(),
-        ))? {
-            ProtocolVersion::TLSv1_2 => self.verify_data_sf_tls12(&hash, &ms),
-            _ => unreachable!(),
+        let verify_data = match self.protocol_version()? {
+            ProtocolVersion::TLSv1_2 => {
+                let ms = self.master_secret.ok_or(BackendError::InvalidState(
+                    "Master secret not set".to_string(),
+                ))?;
+                self.verify_data_sf_tls12(&hash, &ms).to_vec()
+            }
+            ProtocolVersion::TLSv1_3 => {
+                verify_data_tls13(&self.tls13_secrets()?.server_handshake, &hash).to_vec()
+            }
+            version => return Err(BackendError::UnsupportedProtocolVersion(version)),
         };
-        Ok(verify_data.to_vec())
+        Ok(verify_data)
     }
 
     async fn get_client_finished_vd(&mut self, hash: Vec<u8>) -> Result<Vec<u8>, BackendError> {
-        let ms = self.master_secret.ok_or(BackendError::InvalidState(
-            "Master secret not set".to_string(),
-        ))?;
+        let verify_data = match self.protocol_version()? {
+            ProtocolVersion::TLSv1_2 => {
+                let ms = self.master_secret.ok_or(BackendError::InvalidState(
+                    "Master secret not set".to_string(),
+                ))?;
+                self.verify_data_cf_tls12(&hash, &ms).to_vec()
+            }
+            ProtocolVersion::TLSv1_3 => {
+                let secrets = self
+                    .tls13_secrets
+                    .as_mut()
+                    .ok_or(BackendError::InvalidState(
+                        "TLS 1.3 handshake secrets are not set".to_string(),
+                    ))?;
 
-        let verify_data = match self.protocol_version.ok_or(BackendError::InvalidState(
-            "Protocol version not set".to_string(),
-        ))? {
-            ProtocolVersion::TLSv1_2 => self.verify_data_cf_tls12(&hash, &ms),
-            _ => unreachable!(),
+                // Without client authentication this is the handshake hash up
+                // to the server Finished, which the application traffic
+                // secrets are derived from.
+                secrets.derive_application(&hash);
+                verify_data_tls13(&secrets.client_handshake, &hash).to_vec()
+            }
+            version => return Err(BackendError::UnsupportedProtocolVersion(version)),
         };
-        Ok(verify_data.to_vec())
+        Ok(verify_data)
     }
 
     async fn prepare_encryption(&mut self) -> Result<(), BackendError> {
@@ -412,7 +494,12 @@ impl Backend for RustCryptoBackend {
     }
 
     async fn push_outgoing(&mut self, msg: PlainMessage) -> Result<(), BackendError> {
-        self.outgoing_plain.push_back(msg);
+        // Encrypt immediately, the traffic keys may change before the next
+        // flush.
+        let seq = self.write_seq;
+        let encrypted = self.encrypt(msg, seq)?;
+        self.outgoing_encrypted.push_back(encrypted);
+        self.write_seq += 1;
         Ok(())
     }
 
@@ -426,7 +513,17 @@ impl Backend for RustCryptoBackend {
     }
 
     async fn next_incoming(&mut self) -> Result<Option<PlainMessage>, BackendError> {
-        Ok(self.incoming_plain.pop_front())
+        // Decrypt lazily, a message may change the traffic keys used for the
+        // messages following it.
+        let Some(incoming) = self.incoming_encrypted.pop_front() else {
+            return Ok(None);
+        };
+
+        let seq = self.read_seq;
+        let decrypted = self.decrypt(incoming, seq)?;
+        self.read_seq += 1;
+
+        Ok(Some(decrypted))
     }
 
     async fn start_traffic(&mut self) -> Result<(), BackendError> {
@@ -434,28 +531,23 @@ impl Backend for RustCryptoBackend {
     }
 
     async fn flush(&mut self) -> Result<(), BackendError> {
-        for incoming in take(&mut self.incoming_encrypted) {
-            let seq = self.read_seq;
-            let decrypted = self.decrypt(incoming, seq)?;
-            self.incoming_plain.push_back(decrypted);
-            self.read_seq += 1;
-        }
+        Ok(())
+    }
 
-        for outgoing in take(&mut self.outgoing_plain) {
-            let seq = self.write_seq;
-            let encrypted = self.encrypt(outgoing, seq)?;
-            self.outgoing_encrypted.push_back(encrypted);
-            self.write_seq += 1;
-        }
+    async fn is_empty(&mut self) -> Result<bool, BackendError> {
+        Ok(self.incoming_encrypted.is_empty() && self.outgoing_encrypted.is_empty())
+    }
 
+    async fn push_handshake_message(
+        &mut self,
+        record: HandshakeRecord,
+    ) -> Result<(), BackendError> {
+        self.handshake.push(record);
         Ok(())
     }
 
-    async fn is_empty(&mut self) -> Result<bool, BackendError> {
-        Ok(self.incoming_plain.is_empty()
-            && self.outgoing_plain.is_empty()
-            && self.incoming_encrypted.is_empty()
-            && self.outgoing_encrypted.is_empty())
+    async fn handshake_transcript(&mut self) -> Result<Vec<HandshakeRecord>, BackendError> {
+        Ok(self.handshake.clone())
     }
 }
 
@@ -473,79 +565,281 @@ fn concat<const O: usize>(left: &[u8], right: &[u8]) -> [u8; O] {
     out
 }
 
-pub struct Encrypter {
-    write_key: [u8; 16],
-    write_iv: [u8; 4],
-    cipher_suite: CipherSuite,
+/// Computes HMAC-SHA256 over the concatenation of `data`.
+fn hmac_sha256(key: &[u8], data: &[&[u8]]) -> [u8; 32] {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    for data in data {
+        mac.update(data);
+    }
+    mac.finalize().into_bytes().into()
 }
 
-impl Encrypter {
-    pub fn new(write_key: [u8; 16], write_iv: [u8; 4], cipher_suite: CipherSuite) -> Self {
+/// HKDF-Extract with SHA256 (RFC 5869).
+fn hkdf_extract(salt: &[u8], ikm: &[u8]) -> [u8; 32] {
+    hmac_sha256(salt, &[ikm])
+}
+
+/// HKDF-Expand-Label with SHA256 (RFC 8446, section 7.1).
+fn hkdf_expand_label(secret: &[u8; 32], label: &[u8], context: &[u8], len: usize) -> Vec<u8> {
+    let mut info = Vec::with_capacity(4 + 6 + label.len() + context.len());
+    info.extend_from_slice(&(len as u16).to_be_bytes());
+    info.push((6 + label.len()) as u8);
+    info.extend_from_slice(b"tls13 ");
+    info.extend_from_slice(label);
+    info.push(context.len() as u8);
+    info.extend_from_slice(context);
+
+    let mut okm = Vec::with_capacity(len);
+    let mut block: Vec<u8> = Vec::new();
+    let mut counter = 1u8;
+    while okm.len() < len {
+        block = hmac_sha256(secret, &[&block, &info, &[counter]]).to_vec();
+        okm.extend_from_slice(&block);
+        counter += 1;
+    }
+    okm.truncate(len);
+
+    okm
+}
+
+/// Derive-Secret with SHA256 (RFC 8446, section 7.1).
+fn derive_secret(secret: &[u8; 32], label: &[u8], hash: &[u8]) -> [u8; 32] {
+    hkdf_expand_label(secret, label, hash, 32)
+        .try_into()
+        .expect("output is 32 bytes")
+}
+
+/// Computes TLS 1.3 Finished verify_data from a handshake traffic secret.
+fn verify_data_tls13(secret: &[u8; 32], hash: &[u8]) -> [u8; 32] {
+    let finished_key = hkdf_expand_label(secret, b"finished", &[], 32);
+    hmac_sha256(&finished_key, &[hash])
+}
+
+/// TLS 1.3 traffic secrets.
+struct Tls13Secrets {
+    handshake_secret: [u8; 32],
+    client_handshake: [u8; 32],
+    server_handshake: [u8; 32],
+    client_application: Option<[u8; 32]>,
+    server_application: Option<[u8; 32]>,
+}
+
+impl Tls13Secrets {
+    /// Runs the key schedule up to the handshake traffic secrets.
+    ///
+    /// # Arguments
+    ///
+    /// * `shared_secret` - The ECDHE shared secret.
+    /// * `hash` - The handshake hash up to the ServerHello.
+    fn new(shared_secret: &[u8], hash: &[u8]) -> Self {
+        let empty_hash = Sha256::digest(b"");
+        let early_secret = hkdf_extract(&[0u8; 32], &[0u8; 32]);
+        let salt = derive_secret(&early_secret, b"derived", &empty_hash);
+        let handshake_secret = hkdf_extract(&salt, shared_secret);
+
         Self {
-            write_key,
-            write_iv,
-            cipher_suite,
+            handshake_secret,
+            client_handshake: derive_secret(&handshake_secret, b"c hs traffic", hash),
+            server_handshake: derive_secret(&handshake_secret, b"s hs traffic", hash),
+            client_application: None,
+            server_application: None,
         }
     }
 
-    /// Encrypt with AES128GCM using TLS-specific AAD.
-    fn encrypt_aes128gcm(
-        &self,
-        m: &PlainMessage,
-        seq: u64,
-        explicit_nonce: &[u8; 8],
-    ) -> Result<OpaqueMessage, BackendError> {
-        let mut aad = [0u8; 13];
-        aad[..8].copy_from_slice(&seq.to_be_bytes());
-        aad[8] = m.typ.get_u8();
-        aad[9..11].copy_from_slice(&m.version.get_u16().to_be_bytes());
-        aad[11..13].copy_from_slice(&(m.payload.0.len() as u16).to_be_bytes());
-        let payload = Payload {
-            msg: &m.payload.0,
-            aad: &aad,
-        };
+    /// Derives the application traffic secrets.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The handshake hash up to the server Finished.
+    fn derive_application(&mut self, hash: &[u8]) {
+        let empty_hash = Sha256::digest(b"");
+        let salt = derive_secret(&self.handshake_secret, b"derived", &empty_hash);
+        let master_secret = hkdf_extract(&salt, &[0u8; 32]);
 
-        let mut nonce = [0u8; 12];
-        nonce[..4].copy_from_slice(&self.write_iv);
-        nonce[4..].copy_from_slice(explicit_nonce);
-        #[allow(deprecated)]
-        let nonce = GenericArray::from_slice(&nonce);
-        let cipher = Aes128Gcm::new_from_slice(&self.write_key).unwrap();
-        // ciphertext will have the MAC appended
-        let ciphertext = cipher
-            .encrypt(nonce, payload)
-            .map_err(|e| BackendError::EncryptionError(e.to_string()))?;
-
-        // prepend the explicit nonce
-        let mut nonce_ct_mac = vec![0u8; 0];
-        nonce_ct_mac.extend(explicit_nonce.iter());
-        nonce_ct_mac.extend(ciphertext.iter());
-        let om = OpaqueMessage {
-            typ: m.typ,
-            version: m.version,
-            payload: TLSPayload::new(nonce_ct_mac),
-        };
+        self.client_application = Some(derive_secret(&master_secret, b"c ap traffic", hash));
+        self.server_application = Some(derive_secret(&master_secret, b"s ap traffic", hash));
+    }
+}
+
+/// AEAD algorithm of a cipher suite.
+#[derive(Debug, Clone, Copy)]
+enum AeadAlg {
+    Aes128Gcm,
+    ChaCha20Poly1305,
+}
 
-        Ok(om)
+impl AeadAlg {
+    fn for_suite(suite: CipherSuite) -> Result<Self, BackendError> {
+        match suite {
+            CipherSuite::TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256
+            | CipherSuite::TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256
+            | CipherSuite::TLS13_AES_128_GCM_SHA256 => Ok(AeadAlg::Aes128Gcm),
+            CipherSuite::TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256
+            | CipherSuite::TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256
+            | CipherSuite::TLS13_CHACHA20_POLY1305_SHA256 => Ok(AeadAlg::ChaCha20Poly1305),
+            suite => Err(BackendError::UnsupportedCiphersuite(suite)),
+        }
+    }
+
+    fn key_len(&self) -> usize {
+        match self {
+            AeadAlg::Aes128Gcm => 16,
+            AeadAlg::ChaCha20Poly1305 => 32,
+        }
+    }
+
+    /// Length of the implicit part of the nonce in TLS 1.2.
+    fn tls12_iv_len(&self) -> usize {
+        match self {
+            AeadAlg::Aes128Gcm => 4,
+            AeadAlg::ChaCha20Poly1305 => 12,
+        }
+    }
+
+    /// Length of the TLS 1.2 key block, there are no MAC keys for AEAD suites.
+    fn tls12_key_block_len(&self) -> usize {
+        2 * (self.key_len() + self.tls12_iv_len())
+    }
+
+    /// Length of the explicit nonce prepended to TLS 1.2 records.
+    fn tls12_explicit_nonce_len(&self) -> usize {
+        match self {
+            AeadAlg::Aes128Gcm => 8,
+            AeadAlg::ChaCha20Poly1305 => 0,
+        }
+    }
+
+    #[allow(deprecated)]
+    fn encrypt(&self, key: &[u8], nonce: &[u8; 12], payload: Payload) -> Result<Vec<u8>, String> {
+        let nonce = GenericArray::from_slice(nonce);
+        match self {
+            AeadAlg::Aes128Gcm => Aes128Gcm::new_from_slice(key)
+                .expect("key length is valid")
+                .encrypt(nonce, payload),
+            AeadAlg::ChaCha20Poly1305 => ChaCha20Poly1305::new_from_slice(key)
+                .expect("key length is valid")
+                .encrypt(nonce, payload),
+        }
+        .map_err(|e| e.to_string())
+    }
+
+    #[allow(deprecated)]
+    fn decrypt(&self, key: &[u8], nonce: &[u8; 12], payload: Payload) -> Result<Vec<u8>, String> {
+        let nonce = GenericArray::from_slice(nonce);
+        match self {
+            AeadAlg::Aes128Gcm => Aes128Gcm::new_from_slice(key)
+                .expect("key length is valid")
+                .decrypt(nonce, payload),
+            AeadAlg::ChaCha20Poly1305 => ChaCha20Poly1305::new_from_slice(key)
+                .expect("key length is valid")
+                .decrypt(nonce, payload),
+        }
+        .map_err(|e| e.to_string())
     }
 }
 
-pub struct Decrypter {
-    write_key: [u8; 16],
-    write_iv: [u8; 4],
-    cipher_suite: CipherSuite,
+/// Record protection for one direction of the connection.
+struct RecordCipher {
+    aead: AeadAlg,
+    version: ProtocolVersion,
+    write_key: Vec<u8>,
+    write_iv: Vec<u8>,
 }
 
-impl Decrypter {
-    pub fn new(write_key: [u8; 16], write_iv: [u8; 4], cipher_suite: CipherSuite) -> Self {
+impl RecordCipher {
+    fn new(aead: AeadAlg, version: ProtocolVersion, write_key: Vec<u8>, write_iv: Vec<u8>) -> Self {
         Self {
+            aead,
+            version,
             write_key,
             write_iv,
-            cipher_suite,
         }
     }
 
-    fn decrypt_aes128gcm(&self, m: &OpaqueMessage, seq: u64) -> Result<PlainMessage, BackendError> {
+    /// Returns the nonce for a record.
+    fn nonce(&self, seq: u64) -> [u8; 12] {
+        let mut nonce = [0u8; 12];
+        if self.explicit_nonce_len() == 8 {
+            // TLS 1.2 AES-GCM: implicit salt followed by the explicit nonce,
+            // which is the sequence number.
+            nonce[..4].copy_from_slice(&self.write_iv);
+            nonce[4..].copy_from_slice(&seq.to_be_bytes());
+        } else {
+            // Otherwise the IV XORed with the padded sequence number.
+            nonce.copy_from_slice(&self.write_iv);
+            for (n, s) in nonce[4..].iter_mut().zip(seq.to_be_bytes()) {
+                *n ^= s;
+            }
+        }
+        nonce
+    }
+
+    fn explicit_nonce_len(&self) -> usize {
+        match self.version {
+            ProtocolVersion::TLSv1_2 => self.aead.tls12_explicit_nonce_len(),
+            _ => 0,
+        }
+    }
+
+    /// Encrypt a record using TLS-specific AAD.
+    fn encrypt(&self, m: &PlainMessage, seq: u64) -> Result<OpaqueMessage, BackendError> {
+        let nonce = self.nonce(seq);
+
+        let (typ, version, ciphertext) = if self.version == ProtocolVersion::TLSv1_3 {
+            // The content type is encrypted, records are sent as TLS 1.2
+            // application data.
+            let mut inner = Vec::with_capacity(m.payload.0.len() + 1);
+            inner.extend_from_slice(&m.payload.0);
+            inner.push(m.typ.get_u8());
+
+            let aad = tls13_aad(inner.len() + TAG_LEN);
+            let ciphertext = self
+                .aead
+                .encrypt(
+                    &self.write_key,
+                    &nonce,
+                    Payload {
+                        msg: &inner,
+                        aad: &aad,
+                    },
+                )
+                .map_err(BackendError::EncryptionError)?;
+
+            (
+                ContentType::ApplicationData,
+                ProtocolVersion::TLSv1_2,
+                ciphertext,
+            )
+        } else {
+            let aad = tls12_aad(seq, m.typ, m.version, m.payload.0.len());
+            // ciphertext will have the MAC appended
+            let ciphertext = self
+                .aead
+                .encrypt(
+                    &self.write_key,
+                    &nonce,
+                    Payload {
+                        msg: &m.payload.0,
+                        aad: &aad,
+                    },
+                )
+                .map_err(BackendError::EncryptionError)?;
+
+            // prepend the explicit nonce
+            let mut nonce_ct_mac = nonce[12 - self.explicit_nonce_len()..].to_vec();
+            nonce_ct_mac.extend(ciphertext);
+
+            (m.typ, m.version, nonce_ct_mac)
+        };
+
+        Ok(OpaqueMessage {
+            typ,
+            version,
+            payload: TLSPayload::new(ciphertext),
+        })
+    }
+
+    fn decrypt(&self, m: &OpaqueMessage, seq: u64) -> Result<PlainMessage, BackendError> {
         // TODO tls-client shouldnt call decrypt with CCS
         if m.typ == ContentType::ChangeCipherSpec {
             return Ok(PlainMessage {
@@ -554,32 +848,171 @@ impl Decrypter {
                 payload: TLSPayload(m.payload.0.clone()),
             });
         }
-        let mut aad = [0u8; 13];
-        aad[..8].copy_from_slice(&seq.to_be_bytes());
-        aad[8] = m.typ.get_u8();
-        aad[9..11].copy_from_slice(&m.version.get_u16().to_be_bytes());
-        // 8-byte explicit nonce and 16-byte MAC are not counted towards
-        // plaintext size.
-        aad[11..13].copy_from_slice(&((m.payload.0.len() - 24) as u16).to_be_bytes());
-        let aes_payload = Payload {
-            msg: &m.payload.0[8..],
-            aad: &aad,
-        };
 
-        let cipher = Aes128Gcm::new_from_slice(&self.write_key).unwrap();
-        let mut nonce = [0u8; 12];
-        nonce[..4].copy_from_slice(&self.write_iv);
-        nonce[4..].copy_from_slice(&m.payload.0[0..8]);
-        #[allow(deprecated)]
-        let nonce = GenericArray::from_slice(&nonce);
-        let plaintext = cipher
-            .decrypt(nonce, aes_payload)
-            .map_err(|e| BackendError::DecryptionError(e.to_string()))?;
-
-        Ok(PlainMessage {
-            typ: m.typ,
-            version: m.version,
-            payload: TLSPayload(plaintext),
-        })
+        let explicit_nonce_len = self.explicit_nonce_len();
+        let payload = &m.payload.0;
+        if payload.len() < explicit_nonce_len + TAG_LEN {
+            return Err(BackendError::DecryptionError(
+                "record is too short".to_string(),
+            ));
+        }
+
+        let mut nonce = self.nonce(seq);
+        // Use the explicit nonce chosen by the peer.
+        nonce[12 - explicit_nonce_len..].copy_from_slice(&payload[..explicit_nonce_len]);
+
+        if self.version == ProtocolVersion::TLSv1_3 {
+            let aad = tls13_aad(payload.len());
+            let mut inner = self
+                .aead
+                .decrypt(
+                    &self.write_key,
+                    &nonce,
+                    Payload {
+                        msg: payload,
+                        aad: &aad,
+                    },
+                )
+                .map_err(BackendError::DecryptionError)?;
+
+            // Strip the padding, the content type is the last non-zero byte.
+            let typ_pos =
+                inner
+                    .iter()
+                    .rposition(|&b| b != 0)
+                    .ok_or(BackendError::DecryptionError(
+                        "record has no content type".to_string(),
+                    ))?;
+            let typ = ContentType::from(inner[typ_pos]);
+            inner.truncate(typ_pos);
+
+            Ok(PlainMessage {
+                typ,
+                version: ProtocolVersion::TLSv1_3,
+                payload: TLSPayload(inner),
+            })
+        } else {
+            // The explicit nonce and MAC are not counted towards plaintext
+            // size.
+            let aad = tls12_aad(
+                seq,
+                m.typ,
+                m.version,
+                payload.len() - explicit_nonce_len - TAG_LEN,
+            );
+            let plaintext = self
+                .aead
+                .decrypt(
+                    &self.write_key,
+                    &nonce,
+                    Payload {
+                        msg: &payload[explicit_nonce_len..],
+                        aad: &aad,
+                    },
+                )
+                .map_err(BackendError::DecryptionError)?;
+
+            Ok(PlainMessage {
+                typ: m.typ,
+                version: m.version,
+                payload: TLSPayload(plaintext),
+            })
+        }
+    }
+}
+
+/// Returns the TLS 1.2 additional data of a record.
+fn tls12_aad(seq: u64, typ: ContentType, version: ProtocolVersion, len: usize) -> [u8; 13] {
+    let mut aad = [0u8; 13];
+    aad[..8].copy_from_slice(&seq.to_be_bytes());
+    aad[8] = typ.get_u8();
+    aad[9..11].copy_from_slice(&version.get_u16().to_be_bytes());
+    aad[11..13].copy_from_slice(&(len as u16).to_be_bytes());
+    aad
+}
+
+/// Returns the TLS 1.3 additional data of a record, which is its header.
+fn tls13_aad(len: usize) -> [u8; 5] {
+    let mut aad = [0u8; 5];
+    aad[0] = ContentType::ApplicationData.get_u8();
+    aad[1..3].copy_from_slice(&ProtocolVersion::TLSv1_2.get_u16().to_be_bytes());
+    aad[3..5].copy_from_slice(&(len as u16).to_be_bytes());
+    aad
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test vectors from RFC 8448, section 3.
+    fn from_hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_tls13_key_schedule() {
+        let shared_secret =
+            from_hex("8bd4054fb55b9d63fdfbacf9f04b9f0d35e6d63f537563efd46272900f89492d");
+        let hash_server_hello =
+            from_hex("860c06edc07858ee8e78f0e7428c58edd6b43f2ca3e6e95f02ed063cf0e1cad8");
+
+        let secrets = Tls13Secrets::new(&shared_secret, &hash_server_hello);
+
+        assert_eq!(
+            secrets.handshake_secret.to_vec(),
+            from_hex("1dc826e93606aa6fdc0aadc12f741b01046aa6b99f691ed221a9f0ca043fbeac")
+        );
+        assert_eq!(
+            secrets.client_handshake.to_vec(),
+            from_hex("b3eddb126e067f35a780b3abf45e2d8f3b1a950738f52e9600746a0e27a55a21")
+        );
+        assert_eq!(
+            secrets.server_handshake.to_vec(),
+            from_hex("b67b7d690cc16c4e75e54213cb2d37b4e9c912bcded9105d42befd59d391ad38")
+        );
+
+        let cipher = RustCryptoBackend {
+            cipher_suite: Some(suites::tls13::TLS13_AES_128_GCM_SHA256),
+            ..RustCryptoBackend::new()
+        }
+        .tls13_cipher(&secrets.server_handshake)
+        .unwrap();
+
+        assert_eq!(
+            cipher.write_key,
+            from_hex("3fce516009c21727d0f2e4e86ee403bc")
+        );
+        assert_eq!(cipher.write_iv, from_hex("5d313eb2671276ee13000b30"));
+    }
+
+    #[test]
+    fn test_record_roundtrip() {
+        for version in [ProtocolVersion::TLSv1_2, ProtocolVersion::TLSv1_3] {
+            for aead in [AeadAlg::Aes128Gcm, AeadAlg::ChaCha20Poly1305] {
+                let iv_len = if version == ProtocolVersion::TLSv1_2 {
+                    aead.tls12_iv_len()
+                } else {
+                    12
+                };
+                let cipher =
+                    RecordCipher::new(aead, version, vec![1u8; aead.key_len()], vec![2u8; iv_len]);
+
+                let msg = PlainMessage {
+                    typ: ContentType::Handshake,
+                    version: ProtocolVersion::TLSv1_2,
+                    payload: TLSPayload::new(b"hello".to_vec()),
+                };
+
+                let encrypted = cipher.encrypt(&msg, 7).unwrap();
+                let decrypted = cipher.decrypt(&encrypted, 7).unwrap();
+
+                assert_eq!(decrypted.typ, msg.typ);
+                assert_eq!(decrypted.payload.0, msg.payload.0);
+                assert!(cipher.decrypt(&encrypted, 8).is_err());
+            }
+        }
     }
 }
@@ -0,0 +1,180 @@
+//! Fuzzing entry points of [`RustCryptoBackend`].
+//!
+//! The entry points feed arbitrary bytes, as they could be sent by a hostile
+//! server, into the incoming path of the backend with every implemented
+//! protocol version and AEAD. They panic if the backend panics, or if it
+//! returns more plaintext than it received ciphertext.
+//!
+//! Only available with `--cfg fuzzing`, which is set by `cargo fuzz`.
+
+use std::io::Read;
+
+use futures::executor::block_on;
+use tls_core::msgs::{
+    base::Payload as TLSPayload,
+    deframer::MessageDeframer,
+    enums::{ContentType, ProtocolVersion},
+    message::{OpaqueMessage, PlainMessage},
+};
+
+use super::{tls13_aad, AeadAlg, Payload, RecordCipher, RustCryptoBackend, TAG_LEN};
+use crate::backend::Backend;
+
+/// Maximum length of a record plaintext.
+const MAX_PLAINTEXT_LEN: usize = 1 << 14;
+
+/// Byte of which the server write key of the entry points consists.
+pub const SERVER_WRITE_KEY_BYTE: u8 = 42;
+
+/// Decrypts incoming records as received from the server.
+pub fn decrypt_incoming(data: &[u8]) {
+    let msgs = deframe(data);
+    for cipher in ciphers() {
+        let mut backend = backend(cipher);
+
+        block_on(async {
+            for msg in msgs.iter().cloned() {
+                let len = msg.payload.0.len();
+                backend.push_incoming(msg).await.unwrap();
+
+                match backend.next_incoming().await {
+                    Ok(Some(msg)) => assert!(msg.payload.0.len() <= len),
+                    Ok(None) => unreachable!("message was pushed"),
+                    // The connection is aborted.
+                    Err(_) => break,
+                }
+            }
+        });
+    }
+}
+
+/// Seals the plaintexts contained in the data with the server write key and
+/// decrypts them as incoming records.
+///
+/// The data is a sequence of plaintexts, each prefixed by its length as a
+/// 2-byte big-endian integer. For TLS 1.3 the plaintexts are the inner
+/// plaintexts including the content type and the padding.
+pub fn decrypt_sealed(data: &[u8]) {
+    let plaintexts = split_plaintexts(data);
+    for cipher in ciphers() {
+        let version = cipher.version;
+        let sealed: Vec<_> = plaintexts
+            .iter()
+            .enumerate()
+            .map(|(seq, plaintext)| seal(&cipher, plaintext, seq as u64))
+            .collect();
+        let mut backend = backend(cipher);
+
+        block_on(async {
+            for (plaintext, msg) in plaintexts.iter().zip(sealed) {
+                backend.push_incoming(msg).await.unwrap();
+                let result = backend.next_incoming().await;
+
+                if version == ProtocolVersion::TLSv1_3 {
+                    match plaintext.iter().rposition(|&b| b != 0) {
+                        Some(typ_pos) => {
+                            let msg = result.unwrap().unwrap();
+                            assert_eq!(msg.typ, ContentType::from(plaintext[typ_pos]));
+                            assert_eq!(msg.payload.0, plaintext[..typ_pos]);
+                        }
+                        None => {
+                            assert!(result.is_err());
+                            break;
+                        }
+                    }
+                } else {
+                    assert_eq!(&result.unwrap().unwrap().payload.0, plaintext);
+                }
+            }
+        });
+    }
+}
+
+/// Returns the record ciphers of all implemented protocol versions and
+/// AEADs.
+fn ciphers() -> Vec<RecordCipher> {
+    let mut ciphers = Vec::new();
+    for aead in [AeadAlg::Aes128Gcm, AeadAlg::ChaCha20Poly1305] {
+        let key = vec![SERVER_WRITE_KEY_BYTE; aead.key_len()];
+        ciphers.push(RecordCipher::new(
+            aead,
+            ProtocolVersion::TLSv1_2,
+            key.clone(),
+            vec![0u8; aead.tls12_iv_len()],
+        ));
+        ciphers.push(RecordCipher::new(
+            aead,
+            ProtocolVersion::TLSv1_3,
+            key,
+            vec![0u8; 12],
+        ));
+    }
+    ciphers
+}
+
+/// Returns a backend which decrypts with the cipher.
+fn backend(cipher: RecordCipher) -> RustCryptoBackend {
+    let mut backend = RustCryptoBackend::new();
+    backend.decrypter = Some(cipher);
+    backend
+}
+
+/// Deframes the data into messages, stopping at the first invalid frame.
+fn deframe(mut data: &[u8]) -> Vec<OpaqueMessage> {
+    let mut deframer = MessageDeframer::new();
+    while !deframer.desynced {
+        match deframer.read(&mut data as &mut dyn Read) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+    }
+    deframer.frames.into()
+}
+
+/// Splits the data into length-prefixed plaintexts.
+fn split_plaintexts(mut data: &[u8]) -> Vec<Vec<u8>> {
+    let mut plaintexts = Vec::new();
+    while let [hi, lo, rest @ ..] = data {
+        let len = (u16::from_be_bytes([*hi, *lo]) as usize)
+            .min(MAX_PLAINTEXT_LEN)
+            .min(rest.len());
+        plaintexts.push(rest[..len].to_vec());
+        data = &rest[len..];
+    }
+    plaintexts
+}
+
+/// Seals a plaintext as the server would.
+fn seal(cipher: &RecordCipher, plaintext: &[u8], seq: u64) -> OpaqueMessage {
+    if cipher.version == ProtocolVersion::TLSv1_3 {
+        let aad = tls13_aad(plaintext.len() + TAG_LEN);
+        let ciphertext = cipher
+            .aead
+            .encrypt(
+                &cipher.write_key,
+                &cipher.nonce(seq),
+                Payload {
+                    msg: plaintext,
+                    aad: &aad,
+                },
+            )
+            .unwrap();
+
+        OpaqueMessage {
+            typ: ContentType::ApplicationData,
+            version: ProtocolVersion::TLSv1_2,
+            payload: TLSPayload::new(ciphertext),
+        }
+    } else {
+        cipher
+            .encrypt(
+                &PlainMessage {
+                    typ: ContentType::ApplicationData,
+                    version: ProtocolVersion::TLSv1_2,
+                    payload: TLSPayload::new(plaintext.to_vec()),
+                },
+                seq,
+            )
+            .unwrap()
+    }
+}
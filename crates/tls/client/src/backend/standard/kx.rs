@@ -0,0 +1,201 @@
+//! Key exchange of the [`RustCryptoBackend`](super::RustCryptoBackend).
+
+use ml_kem::{kem::Decapsulate, Ciphertext, EncodedSizeUser, KemCore, MlKem768};
+use p256::{ecdh::EphemeralSecret as P256Secret, EncodedPoint, PublicKey as P256PublicKey};
+use rand::rng;
+use rand06_compat::Rand0_6CompatExt;
+use tls_core::msgs::enums::NamedGroup;
+use x25519_dalek::{EphemeralSecret as X25519Secret, PublicKey as X25519PublicKey};
+
+use crate::backend::BackendError;
+
+/// Key exchange groups implemented by the backend.
+pub(super) const KX_GROUPS: [NamedGroup; 3] = [
+    NamedGroup::X25519MLKEM768,
+    NamedGroup::X25519,
+    NamedGroup::secp256r1,
+];
+
+/// Length of an X25519 public key.
+const X25519_LEN: usize = 32;
+/// Length of an ML-KEM-768 ciphertext.
+const MLKEM768_CIPHERTEXT_LEN: usize = 1088;
+
+/// Returns the error for a group which is not implemented by the backend.
+pub(super) fn unsupported(group: NamedGroup) -> BackendError {
+    BackendError::UnsupportedKxGroup(
+        group,
+        "not implemented by the RustCrypto backend".to_string(),
+    )
+}
+
+/// Client secret of an in-progress key exchange.
+pub(super) enum KxSecret {
+    /// ECDHE over secp256r1.
+    Secp256r1(P256Secret),
+    /// ECDHE over curve25519.
+    X25519(X25519Secret),
+    /// Hybrid of ML-KEM-768 (formerly Kyber) and X25519, see
+    /// draft-ietf-tls-ecdhe-mlkem.
+    X25519MlKem768 {
+        mlkem: <MlKem768 as KemCore>::DecapsulationKey,
+        x25519: X25519Secret,
+    },
+}
+
+impl KxSecret {
+    /// Generates a secret for the given group, returning it along with the
+    /// client key share.
+    pub(super) fn generate(group: NamedGroup) -> Result<(Self, Vec<u8>), BackendError> {
+        let mut rng = rng().compat();
+        match group {
+            NamedGroup::secp256r1 => {
+                let secret = P256Secret::random(&mut rng);
+                let key = EncodedPoint::from(secret.public_key()).to_bytes().to_vec();
+
+                Ok((Self::Secp256r1(secret), key))
+            }
+            NamedGroup::X25519 => {
+                let secret = X25519Secret::random_from_rng(&mut rng);
+                let key = X25519PublicKey::from(&secret).as_bytes().to_vec();
+
+                Ok((Self::X25519(secret), key))
+            }
+            NamedGroup::X25519MLKEM768 => {
+                let (mlkem, encapsulation_key) = MlKem768::generate(&mut rng);
+                let x25519 = X25519Secret::random_from_rng(&mut rng);
+
+                // The ML-KEM share comes first.
+                let mut key = encapsulation_key.as_bytes().to_vec();
+                key.extend_from_slice(X25519PublicKey::from(&x25519).as_bytes());
+
+                Ok((Self::X25519MlKem768 { mlkem, x25519 }, key))
+            }
+            group => Err(unsupported(group)),
+        }
+    }
+
+    /// Returns the group of the secret.
+    pub(super) fn group(&self) -> NamedGroup {
+        match self {
+            Self::Secp256r1(_) => NamedGroup::secp256r1,
+            Self::X25519(_) => NamedGroup::X25519,
+            Self::X25519MlKem768 { .. } => NamedGroup::X25519MLKEM768,
+        }
+    }
+
+    /// Completes the key exchange with the server key share, returning the
+    /// shared secret.
+    pub(super) fn complete(self, server_key: &[u8]) -> Result<Vec<u8>, BackendError> {
+        match self {
+            Self::Secp256r1(secret) => {
+                let server_key = P256PublicKey::from_sec1_bytes(server_key)
+                    .map_err(|_| BackendError::InvalidServerKey)?;
+
+                // The shared secret is the X coordinate of the resulting EC point.
+                Ok(secret
+                    .diffie_hellman(&server_key)
+                    .raw_secret_bytes()
+                    .to_vec())
+            }
+            Self::X25519(secret) => x25519(secret, server_key),
+            Self::X25519MlKem768 {
+                mlkem,
+                x25519: secret,
+            } => {
+                if server_key.len() != MLKEM768_CIPHERTEXT_LEN + X25519_LEN {
+                    return Err(BackendError::InvalidServerKey);
+                }
+
+                let (ciphertext, server_key) = server_key.split_at(MLKEM768_CIPHERTEXT_LEN);
+                let ciphertext = Ciphertext::<MlKem768>::try_from(ciphertext)
+                    .map_err(|_| BackendError::InvalidServerKey)?;
+                let mlkem_secret = mlkem
+                    .decapsulate(&ciphertext)
+                    .map_err(|_| BackendError::InvalidServerKey)?;
+
+                let mut shared_secret = mlkem_secret.to_vec();
+                shared_secret.extend_from_slice(&x25519(secret, server_key)?);
+
+                Ok(shared_secret)
+            }
+        }
+    }
+}
+
+/// Computes the X25519 shared secret.
+fn x25519(secret: X25519Secret, server_key: &[u8]) -> Result<Vec<u8>, BackendError> {
+    let server_key: [u8; X25519_LEN] = server_key
+        .try_into()
+        .map_err(|_| BackendError::InvalidServerKey)?;
+
+    let shared_secret = secret.diffie_hellman(&X25519PublicKey::from(server_key));
+    // Reject low order points, which result in an all-zero shared secret
+    // (RFC 8446, section 7.4.2).
+    if !shared_secret.was_contributory() {
+        return Err(BackendError::InvalidServerKey);
+    }
+
+    Ok(shared_secret.as_bytes().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use ml_kem::{kem::Encapsulate, Encoded};
+
+    use super::*;
+
+    type EncapsulationKey = <MlKem768 as KemCore>::EncapsulationKey;
+
+    #[test]
+    fn test_x25519() {
+        let (secret, client_key) = KxSecret::generate(NamedGroup::X25519).unwrap();
+
+        let server_secret = X25519Secret::random_from_rng(rng().compat());
+        let server_key = X25519PublicKey::from(&server_secret);
+        let client_key: [u8; X25519_LEN] = client_key.try_into().unwrap();
+        let expected = server_secret.diffie_hellman(&X25519PublicKey::from(client_key));
+
+        assert_eq!(
+            secret.complete(server_key.as_bytes()).unwrap(),
+            expected.as_bytes()
+        );
+
+        let (secret, _) = KxSecret::generate(NamedGroup::X25519).unwrap();
+        assert!(secret.complete(&[0u8; X25519_LEN]).is_err());
+    }
+
+    #[test]
+    fn test_x25519_mlkem768() {
+        let (secret, client_key) = KxSecret::generate(NamedGroup::X25519MLKEM768).unwrap();
+        assert_eq!(secret.group(), NamedGroup::X25519MLKEM768);
+
+        let (mlkem_key, x25519_key) = client_key.split_at(client_key.len() - X25519_LEN);
+        let encapsulation_key = EncapsulationKey::from_bytes(
+            &Encoded::<EncapsulationKey>::try_from(mlkem_key).unwrap(),
+        );
+        let (ciphertext, mlkem_secret) =
+            encapsulation_key.encapsulate(&mut rng().compat()).unwrap();
+
+        let server_secret = X25519Secret::random_from_rng(rng().compat());
+        let server_key = X25519PublicKey::from(&server_secret);
+        let x25519_key: [u8; X25519_LEN] = x25519_key.try_into().unwrap();
+        let x25519_secret = server_secret.diffie_hellman(&X25519PublicKey::from(x25519_key));
+
+        let mut server_share = ciphertext.to_vec();
+        server_share.extend_from_slice(server_key.as_bytes());
+
+        let mut expected = mlkem_secret.to_vec();
+        expected.extend_from_slice(x25519_secret.as_bytes());
+
+        assert_eq!(secret.complete(&server_share).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_unsupported_group() {
+        assert!(matches!(
+            KxSecret::generate(NamedGroup::secp384r1),
+            Err(BackendError::UnsupportedKxGroup(NamedGroup::secp384r1, _))
+        ));
+    }
+}
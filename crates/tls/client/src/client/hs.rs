@@ -16,7 +16,7 @@ use tls_core::{
         codec::{Codec, Reader},
         enums::{
             AlertDescription, CipherSuite, Compression, ContentType, ECPointFormat, ExtensionType,
-            HandshakeType, PSKKeyExchangeMode, ProtocolVersion,
+            HandshakeType, NamedGroup, PSKKeyExchangeMode, ProtocolVersion,
         },
         handshake::{
             CertificateStatusRequest, ClientExtension, ClientHelloPayload, ClientSessionTicket,
@@ -56,6 +56,9 @@ pub(super) async fn start_handshake(
 
     let support_tls13 = config.supports_version(ProtocolVersion::TLSv1_3);
     let key_share = if support_tls13 {
+        // We send a key share for the most preferred group only.
+        let group = offered_kx_groups(&config, cx).await?[0];
+        cx.common.backend.set_kx_group(group).await?;
         Some(cx.common.backend.get_client_key_share().await?)
     } else {
         None
@@ -109,6 +112,36 @@ pub(super) async fn start_handshake(
     .await
 }
 
+/// Returns the key exchange groups offered to the server, in order of
+/// preference.
+///
+/// These are the configured groups which are supported by the backend.
+pub(super) async fn offered_kx_groups(
+    config: &ClientConfig,
+    cx: &mut ClientContext<'_>,
+) -> Result<Vec<NamedGroup>, Error> {
+    let supported = cx.common.backend.supported_kx_groups().await?;
+    let support_tls13 = config.supports_version(ProtocolVersion::TLSv1_3);
+
+    let groups: Vec<_> = config
+        .kx_groups
+        .iter()
+        .map(|skxg| skxg.name)
+        .filter(|group| supported.contains(group))
+        // Hybrid groups are only defined for TLS 1.3.
+        .filter(|group| support_tls13 || *group != NamedGroup::X25519MLKEM768)
+        .collect();
+
+    if groups.is_empty() {
+        return Err(Error::General(format!(
+            "none of the configured key exchange groups are supported by the backend, which \
+             supports {supported:?}"
+        )));
+    }
+
+    Ok(groups)
+}
+
 struct ExpectServerHello {
     config: Arc<ClientConfig>,
     server_name: ServerName,
@@ -161,7 +194,7 @@ async fn emit_client_hello_for_retry(
     let mut exts = vec![
         ClientExtension::SupportedVersions(supported_versions),
         ClientExtension::ECPointFormats(ECPointFormatList::supported()),
-        ClientExtension::NamedGroups(config.kx_groups.iter().map(|skxg| skxg.name).collect()),
+        ClientExtension::NamedGroups(offered_kx_groups(&config, cx).await?),
         ClientExtension::SignatureAlgorithms(config.verifier.supported_verify_schemes()),
         //ClientExtension::ExtendedMasterSecretRequest,
         ClientExtension::CertificateStatusRequest(CertificateStatusRequest::build_ocsp()),
@@ -599,11 +632,19 @@ impl ExpectServerHelloOrHelloRetryRequest {
 
         let key_share = match req_group {
             Some(group) if group != offered_key_share.group => {
-                // For now we do not support changing group after starting hs
-                return Err(cx
-                    .common
-                    .illegal_param("server requested hrr with bad group")
-                    .await?);
+                // The server may only request a group we offered.
+                if !offered_kx_groups(&self.next.config, cx)
+                    .await?
+                    .contains(&group)
+                {
+                    return Err(cx
+                        .common
+                        .illegal_param("server requested hrr with bad group")
+                        .await?);
+                }
+
+                cx.common.backend.set_kx_group(group).await?;
+                cx.common.backend.get_client_key_share().await?
             }
             _ => offered_key_share,
         };
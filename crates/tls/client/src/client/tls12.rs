@@ -25,7 +25,7 @@ use tls_core::{
         base::{Payload, PayloadU8},
         ccs::ChangeCipherSpecPayload,
         codec::Codec,
-        enums::{AlertDescription, ContentType, HandshakeType, ProtocolVersion},
+        enums::{AlertDescription, ContentType, HandshakeType, NamedGroup, ProtocolVersion},
         handshake::{
             CertificatePayload, DecomposedSignatureScheme, DigitallySignedStruct,
             HandshakeMessagePayload, HandshakePayload, NewSessionTicketPayload, SCTList,
@@ -689,7 +689,10 @@ impl State<ClientConnectionData> for ExpectServerDone {
             now,
         ) {
             Ok(cert_verified) => cert_verified,
-            Err(e) => return Err(hs::send_cert_error_alert(cx.common, Error::CoreError(e)).await?),
+            Err(e) => {
+                cx.common.rejected_certificates = Some(st.server_cert.cert_chain().to_vec());
+                return Err(hs::send_cert_error_alert(cx.common, Error::CoreError(e)).await?);
+            }
         };
 
         // 3.
@@ -747,12 +750,26 @@ impl State<ClientConnectionData> for ExpectServerDone {
                 }
             };
 
+        // The server may choose any of the offered groups.
+        let group = ecdh_params.curve_params.named_group;
+        if !hs::offered_kx_groups(&st.config, cx)
+            .await?
+            .contains(&group)
+            || group == NamedGroup::X25519MLKEM768
+        {
+            return Err(Error::PeerMisbehavedError(
+                "peer chose an unsupported group".to_string(),
+            ));
+        }
+
+        cx.common.backend.set_kx_group(group).await?;
         let key_share = cx.common.backend.get_client_key_share().await?;
-        if key_share.group != ecdh_params.curve_params.named_group {
+        if key_share.group != group {
             return Err(Error::PeerMisbehavedError(
                 "peer chose an unsupported group".to_string(),
             ));
         }
+        cx.common.kx_group = Some(group);
 
         // 5b.
         let mut transcript = st.transcript;
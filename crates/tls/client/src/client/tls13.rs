@@ -82,6 +82,7 @@ pub(super) async fn handle_server_hello(
     if our_key_share.group != their_key_share.group {
         return Err(cx.common.illegal_param("wrong group for key share").await?);
     }
+    cx.common.kx_group = Some(their_key_share.group);
 
     cx.common
         .backend
@@ -522,7 +523,10 @@ impl State<ClientConnectionData> for ExpectCertificateVerify {
             now,
         ) {
             Ok(cert_verified) => cert_verified,
-            Err(e) => return Err(hs::send_cert_error_alert(cx.common, Error::CoreError(e)).await?),
+            Err(e) => {
+                cx.common.rejected_certificates = Some(self.server_cert.cert_chain().to_vec());
+                return Err(hs::send_cert_error_alert(cx.common, Error::CoreError(e)).await?);
+            }
         };
 
         // 2. Verify their signature on the handshake.
@@ -16,13 +16,16 @@ use std::{
     io, mem,
     ops::{Deref, DerefMut},
 };
-use tls_backend::BackendNotify;
+use tls_backend::{BackendNotify, HandshakeOrigin, HandshakeRecord};
 use tls_core::{
     msgs::{
         alert::AlertMessagePayload,
         base::Payload,
+        codec::Codec,
         deframer::MessageDeframer,
-        enums::{AlertDescription, AlertLevel, ContentType, HandshakeType, ProtocolVersion},
+        enums::{
+            AlertDescription, AlertLevel, ContentType, HandshakeType, NamedGroup, ProtocolVersion,
+        },
         fragmenter::MessageFragmenter,
         handshake::Random,
         hsjoiner::HandshakeJoiner,
@@ -502,6 +505,9 @@ impl ConnectionCommon {
     ) -> Result<Box<dyn State<ClientConnectionData>>, Error> {
         self.common_state.aligned_handshake = self.handshake_joiner.is_empty();
         while let Some(msg) = self.handshake_joiner.frames.pop_front() {
+            self.common_state
+                .record_handshake_message(HandshakeOrigin::Server, &msg)
+                .await?;
             state = self
                 .common_state
                 .process_main_protocol(msg, state, &mut self.data)
@@ -618,6 +624,17 @@ impl DerefMut for ConnectionCommon {
     }
 }
 
+/// Parameters negotiated in a completed handshake.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HandshakeSummary {
+    /// The protocol version.
+    pub protocol_version: ProtocolVersion,
+    /// The cipher suite.
+    pub cipher_suite: SupportedCipherSuite,
+    /// The key exchange group.
+    pub kx_group: NamedGroup,
+}
+
 /// Connection state common to both client and server connections.
 pub struct CommonState {
     pub(crate) negotiated_version: Option<ProtocolVersion>,
@@ -625,6 +642,7 @@ pub struct CommonState {
     pub(crate) record_layer: record_layer::RecordLayer,
     pub(crate) backend: Box<dyn Backend>,
     pub(crate) suite: Option<SupportedCipherSuite>,
+    pub(crate) kx_group: Option<NamedGroup>,
     pub(crate) alpn_protocol: Option<Vec<u8>>,
     aligned_handshake: bool,
     pub(crate) may_send_application_data: bool,
@@ -637,6 +655,7 @@ pub struct CommonState {
     has_seen_eof: bool,
     received_middlebox_ccs: u8,
     pub(crate) peer_certificates: Option<Vec<tls_core::key::Certificate>>,
+    pub(crate) rejected_certificates: Option<Vec<tls_core::key::Certificate>>,
     message_fragmenter: MessageFragmenter,
     received_plaintext: ChunkVecBuffer,
     sendable_plaintext: ChunkVecBuffer,
@@ -658,6 +677,7 @@ impl CommonState {
             record_layer: record_layer::RecordLayer::new(),
             backend,
             suite: None,
+            kx_group: None,
             alpn_protocol: None,
             aligned_handshake: true,
             may_send_application_data: false,
@@ -668,6 +688,7 @@ impl CommonState {
             has_seen_eof: false,
             received_middlebox_ccs: 0,
             peer_certificates: None,
+            rejected_certificates: None,
             message_fragmenter: MessageFragmenter::new(max_fragment_size)
                 .map_err(|_| Error::BadMaxFragmentSize)?,
             received_plaintext: ChunkVecBuffer::new(Some(0)),
@@ -725,6 +746,16 @@ impl CommonState {
         self.peer_certificates.as_deref()
     }
 
+    /// Retrieves the certificate chain presented by the server if it failed
+    /// verification, in the same order as
+    /// [`peer_certificates`](Self::peer_certificates).
+    ///
+    /// This allows diagnosing why the handshake failed, e.g. because a
+    /// middlebox re-signed the connection with its own certificate.
+    pub fn rejected_certificates(&self) -> Option<&[tls_core::key::Certificate]> {
+        self.rejected_certificates.as_deref()
+    }
+
     /// Retrieves the protocol agreed with the peer via ALPN.
     ///
     /// A return value of `None` after handshake completion
@@ -748,6 +779,28 @@ impl CommonState {
         self.negotiated_version
     }
 
+    /// Retrieves the key exchange group agreed with the peer.
+    ///
+    /// This returns `None` until the group is agreed.
+    pub fn negotiated_kx_group(&self) -> Option<NamedGroup> {
+        self.kx_group
+    }
+
+    /// Retrieves the parameters negotiated in the handshake.
+    ///
+    /// This returns `None` until the handshake is complete.
+    pub fn handshake_summary(&self) -> Option<HandshakeSummary> {
+        if self.is_handshaking() {
+            return None;
+        }
+
+        Some(HandshakeSummary {
+            protocol_version: self.negotiated_version?,
+            cipher_suite: self.suite?,
+            kx_group: self.kx_group?,
+        })
+    }
+
     pub(crate) fn is_tls13(&self) -> bool {
         matches!(self.negotiated_version, Some(ProtocolVersion::TLSv1_3))
     }
@@ -758,16 +811,20 @@ impl CommonState {
         mut state: Box<dyn State<ClientConnectionData>>,
         data: &mut ClientConnectionData,
     ) -> Result<Box<dyn State<ClientConnectionData>>, Error> {
-        // For TLS1.2, outside of the handshake, send rejection alerts for
-        // renegotiation requests.  These can occur any time.
+        // For TLS1.2, outside of the handshake, reject renegotiation requests.
+        // These can occur any time.
+        //
+        // Rather than replying with a no_renegotiation warning and carrying on,
+        // the connection is aborted without sending anything. Any record sent
+        // after the handshake must be application data or a closing alert, so
+        // that the connection transcript can be attested to.
         if self.may_receive_application_data && !self.is_tls13() {
             let reject_ty = match self.side {
                 Side::Client => HandshakeType::HelloRequest,
             };
             if msg.is_handshake_type(reject_ty) {
-                self.send_warning_alert(AlertDescription::NoRenegotiation)
-                    .await?;
-                return Ok(state);
+                warn!("Rejecting renegotiation request");
+                return Err(Error::RenegotiationRequested);
             }
         }
 
@@ -1002,6 +1059,9 @@ impl CommonState {
 
     /// Send a raw TLS message, fragmenting it if needed.
     pub(crate) async fn send_msg(&mut self, m: Message, must_encrypt: bool) -> Result<(), Error> {
+        self.record_handshake_message(HandshakeOrigin::Client, &m)
+            .await?;
+
         if !must_encrypt {
             let mut to_send = VecDeque::new();
             self.message_fragmenter.fragment(m.into(), &mut to_send);
@@ -1014,13 +1074,27 @@ impl CommonState {
         }
     }
 
-    pub(crate) fn take_received_plaintext(&mut self, bytes: Payload) {
-        self.received_plaintext.append(bytes.0);
+    /// Records a handshake message with the backend.
+    async fn record_handshake_message(
+        &mut self,
+        origin: HandshakeOrigin,
+        m: &Message,
+    ) -> Result<(), Error> {
+        if let MessagePayload::Handshake(hs) = &m.payload {
+            self.backend
+                .push_handshake_message(HandshakeRecord {
+                    origin,
+                    typ: hs.typ,
+                    payload: hs.get_encoding(),
+                })
+                .await?;
+        }
+
+        Ok(())
     }
 
-    async fn send_warning_alert(&mut self, desc: AlertDescription) -> Result<(), Error> {
-        warn!("Sending warning alert {:?}", desc);
-        self.send_warning_alert_no_log(desc).await
+    pub(crate) fn take_received_plaintext(&mut self, bytes: Payload) {
+        self.received_plaintext.append(bytes.0);
     }
 
     async fn process_alert(&mut self, alert: &AlertMessagePayload) -> Result<(), Error> {
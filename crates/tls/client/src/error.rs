@@ -66,6 +66,10 @@ pub enum Error {
     /// We received a fatal alert.  This means the peer is unhappy.
     AlertReceived(AlertDescription),
 
+    /// The peer requested a renegotiation after the handshake, which is
+    /// not supported.
+    RenegotiationRequested,
+
     /// We received an invalidly encoded certificate from the peer.
     InvalidCertificateEncoding,
 
@@ -146,6 +150,7 @@ impl fmt::Display for Error {
             Self::PeerIncompatibleError(ref why) => write!(f, "peer is incompatible: {}", why),
             Self::PeerMisbehavedError(ref why) => write!(f, "peer misbehaved: {}", why),
             Self::AlertReceived(ref alert) => write!(f, "received fatal alert: {:?}", alert),
+            Self::RenegotiationRequested => write!(f, "peer requested renegotiation"),
             Self::InvalidCertificateEncoding => {
                 write!(f, "invalid peer certificate encoding")
             }
@@ -230,6 +235,7 @@ mod tests {
             Error::PeerIncompatibleError("no tls1.2".to_string()),
             Error::PeerMisbehavedError("inconsistent something".to_string()),
             Error::AlertReceived(AlertDescription::ExportRestriction),
+            Error::RenegotiationRequested,
             Error::InvalidCertificateEncoding,
             Error::InvalidCertificateSignatureType,
             Error::InvalidCertificateSignature,
@@ -24,7 +24,7 @@ impl KeyExchange {
     pub(crate) fn start(skxg: &'static SupportedKxGroup) -> Option<Self> {
         let rng = ring::rand::SystemRandom::new();
         let ours =
-            ring::agreement::EphemeralPrivateKey::generate(skxg.agreement_algorithm, &rng).ok()?;
+            ring::agreement::EphemeralPrivateKey::generate(skxg.agreement_algorithm?, &rng).ok()?;
 
         let pubkey = ours.compute_public_key().ok()?;
 
@@ -45,7 +45,12 @@ impl KeyExchange {
     /// The shared secret is passed into the closure passed down in `f`, and the result of calling
     /// `f` is returned to the caller.
     pub(crate) fn complete<T>(self, peer: &[u8], f: impl FnOnce(&[u8]) -> T) -> Result<T, Error> {
-        let peer_key = ring::agreement::UnparsedPublicKey::new(self.skxg.agreement_algorithm, peer);
+        let peer_key = ring::agreement::UnparsedPublicKey::new(
+            self.skxg
+                .agreement_algorithm
+                .expect("key exchange was started"),
+            peer,
+        );
         ring::agreement::agree_ephemeral(self.privkey, &peer_key, f)
             .map_err(|_| Error::PeerMisbehavedError("key agreement failed".to_string()))
     }
@@ -60,26 +65,37 @@ pub struct SupportedKxGroup {
     /// The IANA "TLS Supported Groups" name of the group
     pub name: NamedGroup,
 
-    /// The corresponding ring agreement::Algorithm
-    agreement_algorithm: &'static ring::agreement::Algorithm,
+    /// The corresponding ring agreement::Algorithm, if ring implements the
+    /// group
+    agreement_algorithm: Option<&'static ring::agreement::Algorithm>,
 }
 
 /// Ephemeral ECDH on curve25519 (see RFC7748)
 pub static X25519: SupportedKxGroup = SupportedKxGroup {
     name: NamedGroup::X25519,
-    agreement_algorithm: &ring::agreement::X25519,
+    agreement_algorithm: Some(&ring::agreement::X25519),
 };
 
 /// Ephemeral ECDH on secp256r1 (aka NIST-P256)
 pub static SECP256R1: SupportedKxGroup = SupportedKxGroup {
     name: NamedGroup::secp256r1,
-    agreement_algorithm: &ring::agreement::ECDH_P256,
+    agreement_algorithm: Some(&ring::agreement::ECDH_P256),
 };
 
 /// Ephemeral ECDH on secp384r1 (aka NIST-P384)
 pub static SECP384R1: SupportedKxGroup = SupportedKxGroup {
     name: NamedGroup::secp384r1,
-    agreement_algorithm: &ring::agreement::ECDH_P384,
+    agreement_algorithm: Some(&ring::agreement::ECDH_P384),
+};
+
+/// Post-quantum hybrid of ML-KEM-768 (formerly Kyber) and X25519 (see
+/// draft-ietf-tls-ecdhe-mlkem)
+///
+/// Only supported in TLS 1.3, and only by backends which implement it, such
+/// as the [`RustCryptoBackend`](crate::RustCryptoBackend).
+pub static X25519MLKEM768: SupportedKxGroup = SupportedKxGroup {
+    name: NamedGroup::X25519MLKEM768,
+    agreement_algorithm: None,
 };
 
 /// A list of all the key exchange groups supported by rustls.
@@ -9,6 +9,8 @@
 //! * ECDSA, Ed25519 or RSA server authentication by clients.
 //! * ECDSA, Ed25519 or RSA server authentication by servers.
 //! * Forward secrecy using ECDHE; with curve25519, nistp256 or nistp384 curves.
+//! * Post-quantum hybrid key exchange using X25519MLKEM768 in TLS1.3, if supported by the
+//!   backend.
 //! * AES128-GCM and AES256-GCM bulk encryption, with safe nonces.
 //! * ChaCha20-Poly1305 bulk encryption ([RFC7905](https://tools.ietf.org/html/rfc7905)).
 //! * ALPN support.
@@ -328,18 +330,23 @@ pub mod internal {
 pub use crate::{
     anchors::RootCertStore,
     builder::{ConfigBuilder, WantsCipherSuites, WantsKxGroups, WantsVerifier, WantsVersions},
-    conn::{CommonState, ConnectionCommon, IoState, Reader, SideData},
+    conn::{CommonState, ConnectionCommon, HandshakeSummary, IoState, Reader, SideData},
     error::Error,
     key_log::{KeyLog, NoKeyLog},
     key_log_file::KeyLogFile,
     kx::{SupportedKxGroup, ALL_KX_GROUPS},
 };
-pub use backend::{Backend, BackendError, DecryptMode, EncryptMode, RustCryptoBackend};
+#[cfg(fuzzing)]
+pub use backend::fuzz;
+pub use backend::{
+    Backend, BackendError, DecryptMode, EncryptMode, HandshakeOrigin, HandshakeRecord,
+    RustCryptoBackend,
+};
 pub use cipher::{MessageDecrypter, MessageEncrypter};
 pub use tls_core::{
     key::{Certificate, PrivateKey},
     msgs::{
-        enums::{CipherSuite, ProtocolVersion, SignatureScheme},
+        enums::{CipherSuite, NamedGroup, ProtocolVersion, SignatureScheme},
         handshake::DistinguishedNames,
     },
     suites::{SupportedCipherSuite, ALL_CIPHER_SUITES},
@@ -380,7 +387,7 @@ pub mod version {
 ///
 /// ALL_KX_GROUPS is provided as an array of all of these values.
 pub mod kx_group {
-    pub use crate::kx::{SECP256R1, SECP384R1, X25519};
+    pub use crate::kx::{SECP256R1, SECP384R1, X25519, X25519MLKEM768};
 }
 
 /// Message signing interfaces and implementations.
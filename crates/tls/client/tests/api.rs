@@ -16,7 +16,8 @@ use std::{
 
 use tls_client::{
     client::ResolvesClientCert, sign, CipherSuite, ClientConfig, ClientConnection, Error, KeyLog,
-    ProtocolVersion, RustCryptoBackend, SignatureScheme, SupportedCipherSuite, ALL_CIPHER_SUITES,
+    NamedGroup, ProtocolVersion, RustCryptoBackend, SignatureScheme, SupportedCipherSuite,
+    ALL_CIPHER_SUITES,
 };
 
 use rustls::{
@@ -1687,6 +1688,112 @@ async fn negotiated_ciphersuite_client() {
     }
 }
 
+/// Suites implemented by the RustCrypto backend which are not enabled by
+/// default.
+static RUST_CRYPTO_CIPHERSUITES: &[(
+    &tls_client::SupportedProtocolVersion,
+    KeyType,
+    SupportedCipherSuite,
+)] = &[
+    (
+        &tls_client::version::TLS13,
+        KeyType::Rsa,
+        tls_core::suites::TLS13_AES_128_GCM_SHA256,
+    ),
+    (
+        &tls_client::version::TLS13,
+        KeyType::Ecdsa,
+        tls_core::suites::TLS13_CHACHA20_POLY1305_SHA256,
+    ),
+    #[cfg(feature = "tls12")]
+    (
+        &tls_client::version::TLS12,
+        KeyType::Ecdsa,
+        tls_core::suites::TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256,
+    ),
+    #[cfg(feature = "tls12")]
+    (
+        &tls_client::version::TLS12,
+        KeyType::Rsa,
+        tls_core::suites::TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256,
+    ),
+];
+
+#[tokio::test]
+async fn negotiated_ciphersuite_rust_crypto_backend() {
+    for item in RUST_CRYPTO_CIPHERSUITES.iter() {
+        let (version, kt, scs) = *item;
+        let client_config = finish_client_config(
+            kt,
+            ClientConfig::builder()
+                .with_cipher_suites(&[scs])
+                .with_safe_default_kx_groups()
+                .with_protocol_versions(&[version])
+                .unwrap(),
+        );
+
+        do_suite_test(client_config, make_server_config(kt), scs, version.version).await;
+    }
+}
+
+#[tokio::test]
+async fn tls13_data_exchange_rust_crypto_backend() {
+    let client_config = finish_client_config(
+        KeyType::Rsa,
+        ClientConfig::builder()
+            .with_cipher_suites(&[tls_core::suites::TLS13_AES_128_GCM_SHA256])
+            .with_safe_default_kx_groups()
+            .with_protocol_versions(&[&tls_client::version::TLS13])
+            .unwrap(),
+    );
+    let (mut client, mut server) =
+        make_pair_for_configs(client_config, make_server_config(KeyType::Rsa)).await;
+    assert_eq!(12, server.writer().write(b"from-server!").unwrap());
+    assert_eq!(12, client.write_plaintext(b"from-client!").unwrap());
+
+    do_handshake(&mut client, &mut server).await;
+
+    receive(&mut server, &mut client);
+    client.process_new_packets().await.unwrap();
+    send(&mut client, &mut server);
+    server.process_new_packets().unwrap();
+
+    check_read(&mut client.reader(), b"from-server!");
+    check_read(&mut server.reader(), b"from-client!");
+}
+
+#[tokio::test]
+async fn handshake_transcript_rust_crypto_backend() {
+    use tls_client::HandshakeOrigin::{Client, Server};
+    use tls_core::msgs::enums::HandshakeType;
+
+    let (mut client, mut server) = make_pair(KeyType::Rsa).await;
+    do_handshake(&mut client, &mut server).await;
+
+    let transcript = client.backend_mut().handshake_transcript().await.unwrap();
+    let messages = transcript
+        .iter()
+        .map(|record| (record.origin, record.typ))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        messages,
+        vec![
+            (Client, HandshakeType::ClientHello),
+            (Server, HandshakeType::ServerHello),
+            (Server, HandshakeType::Certificate),
+            (Server, HandshakeType::ServerKeyExchange),
+            (Server, HandshakeType::ServerHelloDone),
+            (Client, HandshakeType::ClientKeyExchange),
+            (Client, HandshakeType::Finished),
+            (Server, HandshakeType::Finished),
+        ]
+    );
+    assert!(transcript
+        .iter()
+        .all(|record| record.payload[0] == record.typ.get_u8()));
+}
+
 #[derive(Debug, PartialEq)]
 struct KeyLogItem {
     label: String,
@@ -2366,6 +2473,30 @@ async fn test_client_config_keyshare_mismatch() {
         .is_err());
 }
 
+#[tokio::test]
+async fn test_client_config_keyshare_x25519() {
+    for version in tls_client::ALL_VERSIONS {
+        let client_config = make_client_config_with_kx_groups_and_versions(
+            KeyType::Rsa,
+            &[
+                &tls_client::kx_group::X25519,
+                &tls_client::kx_group::X25519MLKEM768,
+            ],
+            &[version],
+        );
+        let server_config =
+            make_server_config_with_kx_groups(KeyType::Rsa, &[&rustls::kx_group::X25519]);
+        let (mut client, mut server) = make_pair_for_configs(client_config, server_config).await;
+
+        assert_eq!(client.handshake_summary(), None);
+        do_handshake(&mut client, &mut server).await;
+
+        let summary = client.handshake_summary().unwrap();
+        assert_eq!(summary.protocol_version, version.version);
+        assert_eq!(summary.kx_group, NamedGroup::X25519);
+    }
+}
+
 #[ignore = "needs to be fixed"]
 #[tokio::test]
 async fn test_client_sends_helloretryrequest() {
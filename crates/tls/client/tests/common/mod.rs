@@ -478,6 +478,19 @@ pub fn make_client_config_with_versions(
     finish_client_config(kt, builder)
 }
 
+pub fn make_client_config_with_kx_groups_and_versions(
+    kt: KeyType,
+    kx_groups: &[&'static tls_client::SupportedKxGroup],
+    versions: &[&'static tls_client::SupportedProtocolVersion],
+) -> ClientConfig {
+    let builder = ClientConfig::builder()
+        .with_safe_default_cipher_suites()
+        .with_kx_groups(kx_groups)
+        .with_protocol_versions(versions)
+        .unwrap();
+    finish_client_config(kt, builder)
+}
+
 pub fn make_client_config_with_auth(kt: KeyType) -> ClientConfig {
     finish_client_config_with_creds(kt, ClientConfig::builder().with_safe_defaults())
 }
@@ -0,0 +1,130 @@
+//! Capabilities exchanged by the parties.
+
+use serde::{Deserialize, Serialize};
+use tls_core::suites::{DEFAULT_CIPHER_SUITES, SupportedCipherSuite};
+use tlsn_core::{hash::HashAlgId, transcript::TranscriptCommitmentKind};
+
+use crate::{Error, Result};
+
+/// Capabilities of a party, exchanged before the commitment protocol.
+///
+/// Both parties send their capabilities and continue with the set they have
+/// in common, see [`Prover::capabilities`](crate::prover::Prover::capabilities)
+/// and [`Verifier::capabilities`](crate::verifier::Verifier::capabilities).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Capabilities {
+    /// Supported TLS cipher suites, as IANA identifiers.
+    pub cipher_suites: Vec<u16>,
+    /// Supported transcript commitment kinds.
+    pub commitment_kinds: Vec<TranscriptCommitmentKind>,
+    /// Maximum number of bytes the prover may send, if limited.
+    pub max_sent_data: Option<usize>,
+    /// Maximum number of bytes the prover may receive, if limited.
+    pub max_recv_data: Option<usize>,
+}
+
+impl Capabilities {
+    /// Returns the capabilities of this party with the given limits.
+    pub(crate) fn new(max_sent_data: Option<usize>, max_recv_data: Option<usize>) -> Self {
+        Self {
+            cipher_suites: DEFAULT_CIPHER_SUITES
+                .iter()
+                .map(|suite| suite.suite().get_u16())
+                .collect(),
+            commitment_kinds: [HashAlgId::SHA256, HashAlgId::BLAKE3, HashAlgId::KECCAK256]
+                .into_iter()
+                .map(|alg| TranscriptCommitmentKind::Hash { alg })
+                .collect(),
+            max_sent_data,
+            max_recv_data,
+        }
+    }
+
+    /// Returns the capabilities both parties have in common.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the parties have no cipher suite or commitment kind
+    /// in common.
+    pub(crate) fn agree(&self, peer: &Self) -> Result<Self> {
+        let cipher_suites: Vec<_> = self
+            .cipher_suites
+            .iter()
+            .filter(|suite| peer.cipher_suites.contains(suite))
+            .copied()
+            .collect();
+        if cipher_suites.is_empty() {
+            return Err(Error::incompatible_peer().with_msg("peer has no cipher suite in common"));
+        }
+
+        let commitment_kinds: Vec<_> = self
+            .commitment_kinds
+            .iter()
+            .filter(|kind| peer.commitment_kinds.contains(kind))
+            .copied()
+            .collect();
+        if commitment_kinds.is_empty() {
+            return Err(
+                Error::incompatible_peer().with_msg("peer has no commitment kind in common")
+            );
+        }
+
+        Ok(Self {
+            cipher_suites,
+            commitment_kinds,
+            max_sent_data: min_limit(self.max_sent_data, peer.max_sent_data),
+            max_recv_data: min_limit(self.max_recv_data, peer.max_recv_data),
+        })
+    }
+
+    /// Returns the supported cipher suites of the TLS client.
+    pub(crate) fn supported_cipher_suites(&self) -> Vec<SupportedCipherSuite> {
+        DEFAULT_CIPHER_SUITES
+            .iter()
+            .filter(|suite| self.cipher_suites.contains(&suite.suite().get_u16()))
+            .copied()
+            .collect()
+    }
+}
+
+fn min_limit(a: Option<usize>, b: Option<usize>) -> Option<usize> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_agree() {
+        let prover = Capabilities::new(None, None);
+        let verifier = Capabilities::new(Some(1 << 12), None);
+
+        let agreed = prover.agree(&verifier).unwrap();
+        assert_eq!(agreed, verifier.agree(&prover).unwrap());
+        assert_eq!(agreed.cipher_suites, prover.cipher_suites);
+        assert_eq!(agreed.commitment_kinds, prover.commitment_kinds);
+        assert_eq!(agreed.max_sent_data, Some(1 << 12));
+        assert_eq!(agreed.max_recv_data, None);
+        assert_eq!(
+            agreed.supported_cipher_suites().len(),
+            DEFAULT_CIPHER_SUITES.len()
+        );
+
+        let mut peer = verifier.clone();
+        peer.commitment_kinds = vec![TranscriptCommitmentKind::Hash {
+            alg: HashAlgId::KECCAK256,
+        }];
+        assert_eq!(
+            prover.agree(&peer).unwrap().commitment_kinds,
+            peer.commitment_kinds
+        );
+
+        peer.cipher_suites = vec![0x1301];
+        assert!(prover.agree(&peer).unwrap_err().is_incompatible_peer());
+    }
+}
@@ -0,0 +1,183 @@
+//! Diagnostics of failed TLS handshakes.
+
+use std::fmt;
+
+use tlsn_core::{
+    hash::{HashAlgorithm, Sha256},
+    webpki::CertificateDer,
+};
+
+/// Markers of TLS inspection products, by vendor.
+///
+/// These products re-sign connections with a certificate issued by their own
+/// CA, whose name contains the marker. A marker in the name of a CA in a
+/// rejected chain is a strong hint that the connection is being intercepted.
+const MIDDLEBOX_MARKERS: &[(&str, &str)] = &[
+    ("Zscaler", "Zscaler"),
+    ("Fortinet", "Fortinet FortiGate"),
+    ("FortiGate", "Fortinet FortiGate"),
+    ("Palo Alto Networks", "Palo Alto Networks"),
+    ("Blue Coat", "Blue Coat ProxySG"),
+    ("Cisco Umbrella", "Cisco Umbrella"),
+    ("Netskope", "Netskope"),
+    ("Forcepoint", "Forcepoint"),
+    ("Check Point", "Check Point"),
+    ("Sophos", "Sophos"),
+    ("Barracuda", "Barracuda"),
+    ("Kaspersky", "Kaspersky"),
+    ("Avast", "Avast"),
+    ("Bitdefender", "Bitdefender"),
+    ("mitmproxy", "mitmproxy"),
+    ("Charles Proxy", "Charles Proxy"),
+    ("DO_NOT_TRUST_Fiddler", "Fiddler"),
+    ("PortSwigger", "Burp Suite"),
+];
+
+/// Diagnostics of a TLS handshake in which the server's certificate chain was
+/// rejected.
+///
+/// Available with [`Error::handshake_diagnostics`](crate::Error::handshake_diagnostics),
+/// this allows applications to explain the failure to users, most notably
+/// when a corporate proxy or anti-virus product intercepts the connection.
+#[derive(Debug, Clone)]
+pub struct HandshakeDiagnostics {
+    chain: Vec<CertificateDer>,
+    fingerprints: Vec<[u8; 32]>,
+    middlebox: Option<&'static str>,
+}
+
+impl HandshakeDiagnostics {
+    pub(crate) fn new(chain: Vec<CertificateDer>) -> Self {
+        let fingerprints = chain
+            .iter()
+            .map(|cert| {
+                Sha256::default()
+                    .hash(&cert.0)
+                    .as_bytes()
+                    .try_into()
+                    .expect("SHA-256 hash is 32 bytes")
+            })
+            .collect();
+
+        let names = ca_names(&chain);
+        let middlebox = MIDDLEBOX_MARKERS
+            .iter()
+            .find(|(marker, _)| {
+                names.iter().any(|name| {
+                    name.windows(marker.len())
+                        .any(|window| window == marker.as_bytes())
+                })
+            })
+            .map(|(_, vendor)| *vendor);
+
+        Self {
+            chain,
+            fingerprints,
+            middlebox,
+        }
+    }
+
+    /// Returns the certificate chain presented by the server, starting with
+    /// the end-entity certificate.
+    pub fn chain(&self) -> &[CertificateDer] {
+        &self.chain
+    }
+
+    /// Returns the SHA-256 fingerprints of the certificates in the chain.
+    pub fn fingerprints(&self) -> &[[u8; 32]] {
+        &self.fingerprints
+    }
+
+    /// Returns the vendor of the TLS inspection product which likely issued
+    /// the chain, if it matches a known vendor.
+    pub fn middlebox(&self) -> Option<&str> {
+        self.middlebox
+    }
+}
+
+/// Returns the DER-encoded names of the CAs in the chain, i.e. the issuer of
+/// each certificate and the subject of each certificate but the end-entity.
+///
+/// Certificates which fail to parse are skipped.
+fn ca_names(chain: &[CertificateDer]) -> Vec<Vec<u8>> {
+    let mut names = Vec::new();
+    for (i, cert) in chain.iter().enumerate() {
+        let der = rustls_pki_types::CertificateDer::from(cert.0.as_slice());
+        let Ok(cert) = webpki::EndEntityCert::try_from(&der) else {
+            continue;
+        };
+
+        names.push(cert.issuer().to_vec());
+        if i > 0 {
+            names.push(cert.subject().to_vec());
+        }
+    }
+
+    names
+}
+
+impl fmt::Display for HandshakeDiagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "server presented {} certificate(s) which failed verification",
+            self.chain.len()
+        )?;
+
+        if let Some(vendor) = self.middlebox {
+            write!(
+                f,
+                ", the connection appears to be intercepted by {vendor} TLS inspection"
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tlsn_server_fixture_certs::SERVER_CERT_DER;
+
+    const ZSCALER_CA: &[u8] = include_bytes!("../tests/fixtures/middlebox/zscaler_ca.der");
+    const ZSCALER_LEAF: &[u8] = include_bytes!("../tests/fixtures/middlebox/zscaler_leaf.der");
+    /// End-entity certificate issued by the fixture CA, with "Zscaler" in its
+    /// subject.
+    const SUBJECT_LEAF: &[u8] = include_bytes!("../tests/fixtures/middlebox/subject_leaf.der");
+
+    fn diagnostics(chain: &[&[u8]]) -> HandshakeDiagnostics {
+        HandshakeDiagnostics::new(
+            chain
+                .iter()
+                .map(|cert| CertificateDer(cert.to_vec()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        let diagnostics = diagnostics(&[SERVER_CERT_DER]);
+        assert_eq!(diagnostics.fingerprints().len(), 1);
+        assert_eq!(diagnostics.middlebox(), None);
+
+        // The interception CA is only named as the issuer of the end-entity.
+        let diagnostics = diagnostics(&[ZSCALER_LEAF]);
+        assert_eq!(diagnostics.middlebox(), Some("Zscaler"));
+        assert!(diagnostics.to_string().contains("Zscaler"));
+
+        let diagnostics = diagnostics(&[SERVER_CERT_DER, ZSCALER_CA]);
+        assert_eq!(diagnostics.middlebox(), Some("Zscaler"));
+    }
+
+    #[test]
+    fn test_diagnostics_ignores_end_entity_subject() {
+        assert_eq!(diagnostics(&[SUBJECT_LEAF]).middlebox(), None);
+
+        // Markers outside of the names are ignored, as is data which is not a
+        // certificate.
+        let mut cert = SERVER_CERT_DER.to_vec();
+        cert.extend_from_slice(b"Zscaler Root CA");
+        assert_eq!(diagnostics(&[&cert]).middlebox(), None);
+    }
+}
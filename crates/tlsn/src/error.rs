@@ -1,5 +1,7 @@
 use std::fmt::Display;
 
+use crate::{HandshakeDiagnostics, session::PeerDisconnected};
+
 /// TLSNotary error.
 ///
 /// Errors are categorized by kind:
@@ -10,6 +12,20 @@ use std::fmt::Display;
 ///   error in the library.
 /// - **Config** ([`is_config`](Self::is_config)): invalid configuration
 ///   provided by the user.
+/// - **TLS** ([`is_tls`](Self::is_tls)): the TLS connection with the server
+///   failed, see [`tls_error`](Self::tls_error). If the server's certificate
+///   chain was rejected, [`handshake_diagnostics`](Self::handshake_diagnostics)
+///   describes the presented chain.
+/// - **Incompatible peer** ([`is_incompatible_peer`](Self::is_incompatible_peer)):
+///   the remote party runs an incompatible protocol version.
+/// - **Sent budget exceeded**
+///   ([`is_sent_budget_exceeded`](Self::is_sent_budget_exceeded)): the
+///   application attempted to send more data than configured with
+///   `max_sent_data`.
+/// - **Peer disconnected**
+///   ([`is_peer_disconnected`](Self::is_peer_disconnected)): the remote party
+///   stopped responding to keep-alive pings, see
+///   [`SessionConfig`](crate::config::session::SessionConfig).
 ///
 /// The [`msg`](Self::msg) method returns additional context if available, such
 /// as a rejection message provided by a verifier.
@@ -18,6 +34,7 @@ pub struct Error {
     kind: ErrorKind,
     msg: Option<String>,
     source: Option<Box<dyn std::error::Error + Send + Sync>>,
+    diagnostics: Option<Box<HandshakeDiagnostics>>,
 }
 
 impl Error {
@@ -26,6 +43,7 @@ impl Error {
             kind: ErrorKind::Io,
             msg: None,
             source: None,
+            diagnostics: None,
         }
     }
 
@@ -34,6 +52,7 @@ impl Error {
             kind: ErrorKind::Internal,
             msg: None,
             source: None,
+            diagnostics: None,
         }
     }
 
@@ -42,6 +61,7 @@ impl Error {
             kind: ErrorKind::User,
             msg: None,
             source: None,
+            diagnostics: None,
         }
     }
 
@@ -50,6 +70,43 @@ impl Error {
             kind: ErrorKind::Config,
             msg: None,
             source: None,
+            diagnostics: None,
+        }
+    }
+
+    pub(crate) fn tls(err: TlsError) -> Self {
+        Self {
+            kind: ErrorKind::Tls(err),
+            msg: None,
+            source: None,
+            diagnostics: None,
+        }
+    }
+
+    pub(crate) fn incompatible_peer() -> Self {
+        Self {
+            kind: ErrorKind::IncompatiblePeer,
+            msg: None,
+            source: None,
+            diagnostics: None,
+        }
+    }
+
+    pub(crate) fn sent_budget_exceeded() -> Self {
+        Self {
+            kind: ErrorKind::SentBudgetExceeded,
+            msg: None,
+            source: None,
+            diagnostics: None,
+        }
+    }
+
+    pub(crate) fn peer_disconnected() -> Self {
+        Self {
+            kind: ErrorKind::PeerDisconnected,
+            msg: None,
+            source: None,
+            diagnostics: None,
         }
     }
 
@@ -66,6 +123,11 @@ impl Error {
         self
     }
 
+    pub(crate) fn with_diagnostics(mut self, diagnostics: HandshakeDiagnostics) -> Self {
+        self.diagnostics = Some(Box::new(diagnostics));
+        self
+    }
+
     /// Returns `true` if the error was user created.
     pub fn is_user(&self) -> bool {
         self.kind.is_user()
@@ -86,6 +148,43 @@ impl Error {
         self.kind.is_config()
     }
 
+    /// Returns `true` if the TLS connection with the server failed.
+    pub fn is_tls(&self) -> bool {
+        self.kind.is_tls()
+    }
+
+    /// Returns `true` if the remote party runs an incompatible protocol
+    /// version.
+    pub fn is_incompatible_peer(&self) -> bool {
+        self.kind.is_incompatible_peer()
+    }
+
+    /// Returns `true` if the application attempted to send more data than
+    /// the sent budget allows.
+    pub fn is_sent_budget_exceeded(&self) -> bool {
+        self.kind.is_sent_budget_exceeded()
+    }
+
+    /// Returns `true` if the remote party was detected to be disconnected.
+    pub fn is_peer_disconnected(&self) -> bool {
+        self.kind.is_peer_disconnected()
+    }
+
+    /// Returns the reason the TLS connection with the server failed, if
+    /// available.
+    pub fn tls_error(&self) -> Option<TlsError> {
+        match self.kind {
+            ErrorKind::Tls(err) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// Returns diagnostics of the TLS handshake if the server's certificate
+    /// chain was rejected.
+    pub fn handshake_diagnostics(&self) -> Option<&HandshakeDiagnostics> {
+        self.diagnostics.as_deref()
+    }
+
     /// Returns the error message if available.
     pub fn msg(&self) -> Option<&str> {
         self.msg.as_deref()
@@ -99,6 +198,10 @@ impl Display for Error {
             ErrorKind::Io => write!(f, "io error")?,
             ErrorKind::Internal => write!(f, "internal error")?,
             ErrorKind::Config => write!(f, "config error")?,
+            ErrorKind::Tls(err) => write!(f, "tls error: {err}")?,
+            ErrorKind::IncompatiblePeer => write!(f, "incompatible peer")?,
+            ErrorKind::SentBudgetExceeded => write!(f, "sent budget exceeded")?,
+            ErrorKind::PeerDisconnected => write!(f, "peer disconnected")?,
         }
 
         if let Some(msg) = &self.msg {
@@ -109,40 +212,95 @@ impl Display for Error {
             write!(f, " caused by: {source}")?;
         }
 
+        if let Some(diagnostics) = &self.diagnostics {
+            write!(f, " ({diagnostics})")?;
+        }
+
         Ok(())
     }
 }
 
 impl From<std::io::Error> for Error {
     fn from(e: std::io::Error) -> Self {
+        if is_peer_disconnected(&e) {
+            return Self::peer_disconnected().with_source(e);
+        }
+
         Self::io().with_source(e)
     }
 }
 
 impl From<mpz_common::ContextError> for Error {
     fn from(e: mpz_common::ContextError) -> Self {
+        if is_peer_disconnected(&e) {
+            return Self::peer_disconnected().with_source(e);
+        }
+
         Self::internal().with_msg("context error").with_source(e)
     }
 }
 
 impl From<mpc_tls::MpcTlsError> for Error {
     fn from(e: mpc_tls::MpcTlsError) -> Self {
+        if is_peer_disconnected(&e) {
+            return Self::peer_disconnected().with_source(e);
+        }
+
         Self::internal().with_msg("mpc-tls error").with_source(e)
     }
 }
 
+impl From<tls_client::Error> for Error {
+    fn from(e: tls_client::Error) -> Self {
+        match e {
+            tls_client::Error::AlertReceived(desc) => Self::tls(TlsError::alert(desc)),
+            tls_client::Error::RenegotiationRequested => Self::tls(TlsError::Renegotiation),
+            tls_client::Error::NoCertificatesPresented
+            | tls_client::Error::CoreError(
+                tls_core::Error::InvalidCertificateEncoding
+                | tls_core::Error::InvalidCertificateSignatureType
+                | tls_core::Error::InvalidCertificateSignature
+                | tls_core::Error::InvalidCertificateData(_),
+            ) => Self::tls(TlsError::Certificate).with_source(e),
+            e => Self::internal().with_source(e),
+        }
+    }
+}
+
 impl From<tlsn_mux::ConnectionError> for Error {
     fn from(e: tlsn_mux::ConnectionError) -> Self {
         Self::io().with_msg("mux connection error").with_source(e)
     }
 }
 
+/// Returns `true` if a channel operation failed because the peer was detected
+/// to be disconnected.
+fn is_peer_disconnected(err: &(dyn std::error::Error + 'static)) -> bool {
+    let mut source = Some(err);
+    while let Some(err) = source {
+        if err.is::<PeerDisconnected>()
+            || err
+                .downcast_ref::<std::io::Error>()
+                .and_then(|err| err.get_ref())
+                .is_some_and(|err| err.is::<PeerDisconnected>())
+        {
+            return true;
+        }
+        source = err.source();
+    }
+    false
+}
+
 #[derive(Debug)]
 enum ErrorKind {
     User,
     Io,
     Internal,
     Config,
+    Tls(TlsError),
+    IncompatiblePeer,
+    SentBudgetExceeded,
+    PeerDisconnected,
 }
 
 impl ErrorKind {
@@ -161,4 +319,192 @@ impl ErrorKind {
     fn is_config(&self) -> bool {
         matches!(self, ErrorKind::Config)
     }
+
+    fn is_tls(&self) -> bool {
+        matches!(self, ErrorKind::Tls(_))
+    }
+
+    fn is_incompatible_peer(&self) -> bool {
+        matches!(self, ErrorKind::IncompatiblePeer)
+    }
+
+    fn is_sent_budget_exceeded(&self) -> bool {
+        matches!(self, ErrorKind::SentBudgetExceeded)
+    }
+
+    fn is_peer_disconnected(&self) -> bool {
+        matches!(self, ErrorKind::PeerDisconnected)
+    }
+}
+
+/// Reason the TLS connection with the server failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TlsError {
+    /// The server sent a fatal alert.
+    Alert {
+        /// Category of the alert.
+        kind: AlertKind,
+        /// Alert description code.
+        code: u8,
+    },
+    /// The server requested a renegotiation.
+    ///
+    /// Renegotiation is always rejected, as the session keys are only known
+    /// to the prover and verifier jointly.
+    Renegotiation,
+    /// The server's certificate chain failed verification.
+    Certificate,
+    /// The session ended before the server sent `close_notify`.
+    ///
+    /// An attacker on the network can cut a connection short, so the data
+    /// received so far can not be notarized as a partial session.
+    Truncated,
+}
+
+impl TlsError {
+    pub(crate) fn alert(desc: tls_core::msgs::enums::AlertDescription) -> Self {
+        TlsError::Alert {
+            kind: AlertKind::from_description(desc),
+            code: desc.get_u8(),
+        }
+    }
+}
+
+impl Display for TlsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TlsError::Alert { kind, code } => write!(f, "received {kind} alert ({code})"),
+            TlsError::Renegotiation => write!(f, "server requested renegotiation"),
+            TlsError::Certificate => write!(f, "server certificate was rejected"),
+            TlsError::Truncated => write!(f, "connection was truncated"),
+        }
+    }
+}
+
+/// Category of a TLS alert.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AlertKind {
+    /// The connection was closed, e.g. `close_notify` or `user_canceled`.
+    Closure,
+    /// The handshake parameters could not be agreed on, e.g.
+    /// `handshake_failure` or `protocol_version`.
+    Handshake,
+    /// The certificates were rejected, e.g. `bad_certificate` or
+    /// `unknown_ca`.
+    Certificate,
+    /// A record could not be authenticated, e.g. `bad_record_mac`.
+    Decrypt,
+    /// A message was unexpected or malformed, e.g. `unexpected_message` or
+    /// `decode_error`.
+    Protocol,
+    /// The server failed for reasons unrelated to the protocol, e.g.
+    /// `internal_error`.
+    Internal,
+    /// Any other alert.
+    Other,
+}
+
+impl AlertKind {
+    fn from_description(desc: tls_core::msgs::enums::AlertDescription) -> Self {
+        use tls_core::msgs::enums::AlertDescription::*;
+
+        match desc {
+            CloseNotify | UserCanceled => AlertKind::Closure,
+            HandshakeFailure
+            | ProtocolVersion
+            | InsufficientSecurity
+            | InappropriateFallback
+            | NoRenegotiation
+            | MissingExtension
+            | UnsupportedExtension
+            | UnrecognisedName
+            | NoApplicationProtocol
+            | UnknownPSKIdentity
+            | ExportRestriction => AlertKind::Handshake,
+            NoCertificate
+            | BadCertificate
+            | UnsupportedCertificate
+            | CertificateRevoked
+            | CertificateExpired
+            | CertificateUnknown
+            | UnknownCA
+            | AccessDenied
+            | CertificateUnobtainable
+            | BadCertificateStatusResponse
+            | BadCertificateHashValue
+            | CertificateRequired => AlertKind::Certificate,
+            BadRecordMac | DecryptionFailed | DecryptError => AlertKind::Decrypt,
+            UnexpectedMessage | RecordOverflow | DecompressionFailure | IllegalParameter
+            | DecodeError => AlertKind::Protocol,
+            InternalError => AlertKind::Internal,
+            _ => AlertKind::Other,
+        }
+    }
+}
+
+impl Display for AlertKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            AlertKind::Closure => "closure",
+            AlertKind::Handshake => "handshake",
+            AlertKind::Certificate => "certificate",
+            AlertKind::Decrypt => "decrypt",
+            AlertKind::Protocol => "protocol",
+            AlertKind::Internal => "internal",
+            AlertKind::Other => "unknown",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tls_core::msgs::enums::AlertDescription;
+
+    #[test]
+    fn test_tls_error() {
+        let err = Error::from(tls_client::Error::AlertReceived(
+            AlertDescription::HandshakeFailure,
+        ));
+        assert!(err.is_tls());
+        assert_eq!(
+            err.tls_error(),
+            Some(TlsError::Alert {
+                kind: AlertKind::Handshake,
+                code: 0x28,
+            })
+        );
+
+        // Alerts after the handshake are returned with the prover output.
+        assert_eq!(
+            TlsError::alert(AlertDescription::InternalError),
+            TlsError::Alert {
+                kind: AlertKind::Internal,
+                code: 0x50,
+            }
+        );
+
+        let err = Error::from(tls_client::Error::RenegotiationRequested);
+        assert_eq!(err.tls_error(), Some(TlsError::Renegotiation));
+
+        let err = Error::from(tls_client::Error::CoreError(
+            tls_core::Error::InvalidCertificateData("unknown issuer".into()),
+        ));
+        assert_eq!(err.tls_error(), Some(TlsError::Certificate));
+
+        let err = Error::from(tls_client::Error::DecryptError);
+        assert!(err.is_internal());
+        assert_eq!(err.tls_error(), None);
+    }
+
+    #[test]
+    fn test_peer_disconnected_error() {
+        let err = Error::from(std::io::Error::from(PeerDisconnected));
+        assert!(err.is_peer_disconnected());
+
+        let err = Error::from(std::io::Error::other("connection reset"));
+        assert!(err.is_io());
+    }
 }
@@ -37,13 +37,26 @@
 //!    exchanges data to obtain a commitment to the TLS transcript.
 //! 5. (Optional) Perform selective disclosure: the prover provably reveals
 //!    selected data to the verifier.
+//!
+//! # WebAssembly
+//!
+//! The crate builds for `wasm32-unknown-unknown` with the `web` feature, which
+//! spawns worker threads with [`web-spawn`](https://docs.rs/web-spawn) and
+//! sources randomness from the browser. The target must be built with atomics
+//! and `--cfg getrandom_backend="wasm_js"`; see `crates/wasm/.cargo/config.toml`
+//! for the full set of flags. The `tlsn-wasm` crate is a complete example of
+//! driving a prover from the browser over a WebSocket.
 
 #![deny(missing_docs, unreachable_pub, unused_must_use)]
 #![deny(clippy::all)]
 #![forbid(unsafe_code)]
 
+mod capabilities;
+mod diagnostics;
 mod error;
 pub(crate) mod ghash;
+#[cfg(test)]
+mod malicious;
 pub(crate) mod map;
 pub(crate) mod mpz;
 pub(crate) mod msg;
@@ -53,9 +66,11 @@ pub(crate) mod tag;
 pub(crate) mod transcript_internal;
 pub mod verifier;
 
-pub use error::Error;
+pub use capabilities::Capabilities;
+pub use diagnostics::HandshakeDiagnostics;
+pub use error::{AlertKind, Error, TlsError};
 pub use rangeset;
-pub use session::{Session, SessionDriver, SessionHandle};
+pub use session::{Session, SessionDriver, SessionHandle, record};
 pub use tlsn_attestation as attestation;
 pub use tlsn_core::{config, connection, hash, transcript, webpki};
 
@@ -66,11 +81,40 @@ use std::sync::LazyLock;
 
 use semver::Version;
 
+use crate::msg::HelloMsg;
+
+/// Version of the protocol.
+///
+/// Peers must run the same protocol version to interoperate, regardless of
+/// their crate versions. It is bumped whenever the messages or the MPC
+/// protocols change.
+pub const PROTOCOL_VERSION: u16 = 1;
+
 // Package version.
 pub(crate) static VERSION: LazyLock<Version> = LazyLock::new(|| {
     Version::parse(env!("CARGO_PKG_VERSION")).expect("cargo pkg version should be a valid semver")
 });
 
+/// Returns the crate version.
+///
+/// The version is sent to the peer for diagnostics, compatibility is decided
+/// by [`PROTOCOL_VERSION`].
+pub fn version() -> &'static Version {
+    &VERSION
+}
+
+/// Checks that the peer's protocol version is compatible with ours.
+pub(crate) fn check_peer_version(peer: &HelloMsg) -> Result<()> {
+    if peer.protocol_version != PROTOCOL_VERSION {
+        return Err(Error::incompatible_peer().with_msg(format!(
+            "peer protocol version does not match: {} != {PROTOCOL_VERSION} (peer version {})",
+            peer.protocol_version, peer.version
+        )));
+    }
+
+    Ok(())
+}
+
 /// The party's role in the TLSN protocol.
 ///
 /// A Notary is classified as a Verifier.
@@ -0,0 +1,233 @@
+//! Malicious peers for testing that honest parties abort on protocol
+//! deviations.
+//!
+//! The peers speak the protocol messages directly over a session context and
+//! deviate from the protocol according to a script. Deviations inside the
+//! MPC protocols themselves, e.g. in oblivious transfer, are tested in the
+//! crates which implement them.
+
+use mpz_common::Context;
+use semver::Version;
+use serio::{SinkExt, stream::IoStreamExt};
+use tokio_util::compat::TokioAsyncReadCompatExt;
+
+use tlsn_core::{
+    config::{
+        prover::ProverConfig,
+        tls_commit::{TlsCommitConfig, mpc::MpcTlsConfig},
+        verifier::VerifierConfig,
+    },
+    webpki::RootCertStore,
+};
+
+use crate::{
+    Capabilities, Error, Session, SessionHandle,
+    msg::{HelloMsg, Response, TlsCommitRequestMsg},
+};
+
+/// A scripted deviation from the protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Deviation {
+    /// Sends an incompatible protocol version.
+    WrongVersion,
+    /// Supports none of the cipher suites of the honest party.
+    NoCommonSuite,
+    /// Sends a malformed message in place of the commitment request or
+    /// response.
+    Malformed,
+    /// Rejects the commitment request, running another release of the same
+    /// protocol version.
+    Reject,
+    /// Drops the connection after the version exchange.
+    EarlyClose,
+}
+
+impl Deviation {
+    fn hello(&self) -> HelloMsg {
+        let mut hello = HelloMsg::new(Capabilities::new(None, None));
+        match self {
+            Self::WrongVersion => hello.protocol_version = crate::PROTOCOL_VERSION + 1,
+            Self::NoCommonSuite => hello.capabilities.cipher_suites.clear(),
+            Self::Reject => hello.version = Version::new(99, 0, 0),
+            _ => {}
+        }
+        hello
+    }
+}
+
+/// A prover which deviates from the protocol.
+struct MaliciousProver(Deviation);
+
+impl MaliciousProver {
+    async fn run(&self, ctx: &mut Context) -> Result<(), std::io::Error> {
+        ctx.io_mut().send(self.0.hello()).await?;
+        let _: HelloMsg = ctx.io_mut().expect_next().await?;
+
+        match self.0 {
+            Deviation::Malformed => ctx.io_mut().send(0xffu8).await,
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A notary which deviates from the protocol.
+struct MaliciousNotary(Deviation);
+
+impl MaliciousNotary {
+    async fn run(&self, ctx: &mut Context) -> Result<(), std::io::Error> {
+        let _: HelloMsg = ctx.io_mut().expect_next().await?;
+        ctx.io_mut().send(self.0.hello()).await?;
+
+        if matches!(self.0, Deviation::WrongVersion | Deviation::NoCommonSuite) {
+            return Ok(());
+        }
+
+        let _: TlsCommitRequestMsg = ctx.io_mut().expect_next().await?;
+
+        match self.0 {
+            Deviation::Malformed => ctx.io_mut().send(0xffu8).await,
+            Deviation::Reject => ctx.io_mut().send(Response::err(Some("rejected"))).await,
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A peer which deviates from the protocol.
+enum Peer {
+    Prover(MaliciousProver),
+    Notary(MaliciousNotary),
+}
+
+impl Peer {
+    fn deviation(&self) -> Deviation {
+        match self {
+            Self::Prover(prover) => prover.0,
+            Self::Notary(notary) => notary.0,
+        }
+    }
+
+    async fn run(&self, ctx: &mut Context) -> Result<(), std::io::Error> {
+        match self {
+            Self::Prover(prover) => prover.run(ctx).await,
+            Self::Notary(notary) => notary.run(ctx).await,
+        }
+    }
+}
+
+/// Spawns the peer and the session drivers, returning the session handle of
+/// the honest party.
+fn spawn(peer: Peer) -> SessionHandle {
+    let (socket_0, socket_1) = tokio::io::duplex(1 << 16);
+    let (driver_h, handle_h) = Session::new(socket_0.compat()).split();
+    let mut session_m = Session::new(socket_1.compat());
+
+    let mut ctx = session_m.new_context().unwrap();
+    let (driver_m, _handle_m) = session_m.split();
+
+    tokio::spawn(driver_h);
+    let driver_m = tokio::spawn(driver_m);
+    tokio::spawn(async move {
+        let _ = peer.run(&mut ctx).await;
+
+        if peer.deviation() == Deviation::EarlyClose {
+            driver_m.abort();
+        }
+    });
+
+    handle_h
+}
+
+async fn verifier_against(deviation: Deviation) -> Error {
+    let mut handle = spawn(Peer::Prover(MaliciousProver(deviation)));
+
+    let verifier = handle
+        .new_verifier(
+            VerifierConfig::builder()
+                .root_store(RootCertStore { roots: vec![] })
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+
+    verifier
+        .commit()
+        .await
+        .err()
+        .expect("verifier should abort")
+}
+
+async fn prover_against(deviation: Deviation) -> Error {
+    let mut handle = spawn(Peer::Notary(MaliciousNotary(deviation)));
+
+    let prover = handle
+        .new_prover(ProverConfig::builder().build().unwrap())
+        .unwrap();
+    let config = TlsCommitConfig::builder()
+        .protocol(
+            MpcTlsConfig::builder()
+                .max_sent_data(1 << 12)
+                .max_recv_data(1 << 14)
+                .build()
+                .unwrap(),
+        )
+        .build()
+        .unwrap();
+
+    prover
+        .commit(config)
+        .await
+        .err()
+        .expect("prover should abort")
+}
+
+#[tokio::test]
+async fn test_verifier_wrong_version() {
+    assert!(
+        verifier_against(Deviation::WrongVersion)
+            .await
+            .is_incompatible_peer()
+    );
+}
+
+#[tokio::test]
+async fn test_verifier_malformed_request() {
+    assert!(verifier_against(Deviation::Malformed).await.is_io());
+}
+
+#[tokio::test]
+async fn test_verifier_early_close() {
+    assert!(verifier_against(Deviation::EarlyClose).await.is_io());
+}
+
+#[tokio::test]
+async fn test_prover_wrong_version() {
+    assert!(
+        prover_against(Deviation::WrongVersion)
+            .await
+            .is_incompatible_peer()
+    );
+}
+
+#[tokio::test]
+async fn test_prover_no_common_suite() {
+    assert!(
+        prover_against(Deviation::NoCommonSuite)
+            .await
+            .is_incompatible_peer()
+    );
+}
+
+#[tokio::test]
+async fn test_prover_malformed_response() {
+    assert!(prover_against(Deviation::Malformed).await.is_io());
+}
+
+#[tokio::test]
+async fn test_prover_rejected() {
+    assert!(prover_against(Deviation::Reject).await.is_user());
+}
+
+#[tokio::test]
+async fn test_prover_early_close() {
+    assert!(prover_against(Deviation::EarlyClose).await.is_io());
+}
@@ -15,7 +15,10 @@ use mpz_ot::{
 use mpz_zk::{Prover, Verifier};
 #[cfg(not(tlsn_insecure))]
 use rand::Rng;
-use tlsn_core::config::tls_commit::mpc::{MpcTlsConfig, NetworkSetting};
+use tlsn_core::{
+    clock::Clock,
+    config::tls_commit::mpc::{MpcTlsConfig, NetworkSetting},
+};
 use tlsn_deap::Deap;
 use tokio::sync::Mutex;
 
@@ -48,7 +51,11 @@ pub(crate) struct ProverDeps {
     pub(crate) mpc_tls: MpcTlsLeader,
 }
 
-pub(crate) fn build_prover_deps(config: MpcTlsConfig, ctx: Context) -> ProverDeps {
+pub(crate) fn build_prover_deps(
+    config: MpcTlsConfig,
+    clock: Arc<dyn Clock>,
+    ctx: Context,
+) -> ProverDeps {
     let mut rng = rand::rng();
     let delta = Delta::new(Block::random(&mut rng));
 
@@ -84,7 +91,7 @@ pub(crate) fn build_prover_deps(config: MpcTlsConfig, ctx: Context) -> ProverDep
 
     let vm = Arc::new(Mutex::new(Deap::new(tlsn_deap::Role::Leader, mpc, zk)));
     let mpc_tls = MpcTlsLeader::new(
-        build_mpc_tls_config(config),
+        build_mpc_tls_config(config, clock),
         ctx,
         vm.clone(),
         (rcot_send.clone(), rcot_send.clone(), rcot_send),
@@ -99,7 +106,11 @@ pub(crate) struct VerifierDeps {
     pub(crate) mpc_tls: MpcTlsFollower,
 }
 
-pub(crate) fn build_verifier_deps(config: MpcTlsConfig, ctx: Context) -> VerifierDeps {
+pub(crate) fn build_verifier_deps(
+    config: MpcTlsConfig,
+    clock: Arc<dyn Clock>,
+    ctx: Context,
+) -> VerifierDeps {
     let mut rng = rand::rng();
 
     let delta = Delta::random(&mut rng);
@@ -135,7 +146,7 @@ pub(crate) fn build_verifier_deps(config: MpcTlsConfig, ctx: Context) -> Verifie
 
     let vm = Arc::new(Mutex::new(Deap::new(tlsn_deap::Role::Follower, mpc, zk)));
     let mpc_tls = MpcTlsFollower::new(
-        build_mpc_tls_config(config),
+        build_mpc_tls_config(config, clock),
         ctx,
         vm.clone(),
         rcot_send,
@@ -145,10 +156,11 @@ pub(crate) fn build_verifier_deps(config: MpcTlsConfig, ctx: Context) -> Verifie
     VerifierDeps { vm, mpc_tls }
 }
 
-fn build_mpc_tls_config(config: MpcTlsConfig) -> mpc_tls::Config {
+fn build_mpc_tls_config(config: MpcTlsConfig, clock: Arc<dyn Clock>) -> mpc_tls::Config {
     let mut builder = mpc_tls::Config::builder();
 
     builder
+        .clock(clock)
         .defer_decryption(config.defer_decryption_from_start())
         .max_sent(config.max_sent_data())
         .max_recv_online(config.max_recv_data_online())
@@ -1,3 +1,9 @@
+//! Messages of the protocol.
+//!
+//! The messages are not versioned individually. Both parties send a
+//! [`HelloMsg`] first and abort unless the protocol versions match, so every
+//! later message is only read by a party running the same protocol version.
+
 use std::fmt;
 
 use semver::Version;
@@ -9,10 +15,34 @@ use tlsn_core::{
     transcript::PartialTranscript,
 };
 
+use crate::Capabilities;
+
+/// First message sent by both parties.
+///
+/// The format of this message must remain stable across versions so that
+/// parties running incompatible versions fail with a clear error instead of
+/// a deserialization failure. Fields may only be appended.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct HelloMsg {
+    /// Crate version, for diagnostics.
+    pub(crate) version: Version,
+    pub(crate) protocol_version: u16,
+    pub(crate) capabilities: Capabilities,
+}
+
+impl HelloMsg {
+    pub(crate) fn new(capabilities: Capabilities) -> Self {
+        Self {
+            version: crate::VERSION.clone(),
+            protocol_version: crate::PROTOCOL_VERSION,
+            capabilities,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub(crate) struct TlsCommitRequestMsg {
     pub(crate) request: TlsCommitRequest,
-    pub(crate) version: Version,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -4,20 +4,22 @@ mod client;
 mod conn;
 mod control;
 mod future;
+#[cfg(feature = "insecure-local-backend")]
+pub mod local;
 mod prove;
 pub mod state;
 
 pub use conn::TlsConnection;
-pub use control::ProverControl;
+pub use control::{ProverControl, SessionPhase, SessionStats};
 pub use future::ProverFuture;
 pub use tlsn_core::ProverOutput;
 
 use crate::{
-    Error, Result,
+    Capabilities, Error, Result, TlsError,
     mpz::{ProverDeps, build_prover_deps, translate_keys},
-    msg::{ProveRequestMsg, Response, TlsCommitRequestMsg},
+    msg::{HelloMsg, ProveRequestMsg, Response, TlsCommitRequestMsg},
     prover::{
-        client::{MpcTlsClient, TlsOutput},
+        client::{Budget, MpcTlsClient, TlsOutput},
         state::ConnectedProj,
     },
 };
@@ -36,13 +38,11 @@ use tlsn_core::{
         tls_commit::{TlsCommitConfig, TlsCommitProtocolConfig},
     },
     connection::{HandshakeData, ServerName},
-    transcript::{TlsTranscript, Transcript},
+    transcript::{Direction, TlsTranscript, TranscriptStorage},
 };
-use tracing::{Span, debug, info_span, instrument};
+use tracing::{Span, debug, info_span, instrument, warn};
 use webpki::anchor_from_trusted_cert;
 
-const BUF_CAP: usize = 16 * 1024 * 1024;
-
 /// A prover instance.
 #[derive(Debug)]
 pub struct Prover<T: state::ProverState = state::Initialized> {
@@ -87,11 +87,31 @@ impl Prover<state::Initialized> {
             .take()
             .ok_or_else(|| Error::internal().with_msg("commitment protocol context was dropped"))?;
 
+        // Exchanges versions and capabilities with the verifier for
+        // compatibility check.
+        let capabilities = Capabilities::new(None, None);
+        ctx.io_mut()
+            .send(HelloMsg::new(capabilities.clone()))
+            .await
+            .map_err(|e| {
+                Error::io()
+                    .with_msg("commitment protocol failed to send hello")
+                    .with_source(e)
+            })?;
+
+        let peer: HelloMsg = ctx.io_mut().expect_next().await.map_err(|e| {
+            Error::io()
+                .with_msg("commitment protocol failed to receive hello")
+                .with_source(e)
+        })?;
+
+        crate::check_peer_version(&peer)?;
+        let capabilities = capabilities.agree(&peer.capabilities)?;
+
         // Sends protocol configuration to verifier for compatibility check.
         ctx.io_mut()
             .send(TlsCommitRequestMsg {
                 request: config.to_request(),
-                version: crate::VERSION.clone(),
             })
             .await
             .map_err(|e| {
@@ -119,7 +139,12 @@ impl Prover<state::Initialized> {
             unreachable!("only MPC TLS is supported");
         };
 
-        let ProverDeps { vm, mut mpc_tls } = build_prover_deps(mpc_tls_config, ctx);
+        let budget = Budget::new(
+            mpc_tls_config.max_sent_data(),
+            mpc_tls_config.max_recv_data(),
+        );
+        let ProverDeps { vm, mut mpc_tls } =
+            build_prover_deps(mpc_tls_config, self.config.clock().clone(), ctx);
 
         // Allocate resources for MPC-TLS in the VM.
         let mut keys = mpc_tls.alloc().map_err(|e| {
@@ -145,12 +170,23 @@ impl Prover<state::Initialized> {
             config: self.config,
             span: self.span,
             ctx: None,
-            state: state::CommitAccepted { mpc_tls, keys, vm },
+            state: state::CommitAccepted {
+                mpc_tls,
+                keys,
+                vm,
+                budget,
+                capabilities,
+            },
         })
     }
 }
 
 impl Prover<state::CommitAccepted> {
+    /// Returns the capabilities agreed with the verifier.
+    pub fn capabilities(&self) -> &Capabilities {
+        &self.state.capabilities
+    }
+
     /// Connects to the server using the provided socket.
     ///
     /// Returns a handle to the TLS connection, a future which returns the
@@ -167,8 +203,95 @@ impl Prover<state::CommitAccepted> {
         config: TlsClientConfig,
         socket: S,
     ) -> Result<(TlsConnection, ProverFuture<S>)> {
+        let budget = self.state.budget.clone();
+        let Prover {
+            ctx,
+            config: prover_config,
+            span,
+            state: engine,
+        } = self.connect_sans_io(config)?;
+
+        let (client_io, tlsn_conn) = futures_plex::duplex(prover_config.app_buffer_size());
+        let (client_to_server, server_to_client) =
+            futures_plex::duplex(prover_config.tls_buffer_size());
+
+        let prover = Prover {
+            ctx,
+            config: prover_config,
+            span,
+            state: state::Connected {
+                engine,
+                client_io,
+                server_socket: socket,
+                client_to_server,
+                server_to_client,
+                client_closed: false,
+                server_closed: false,
+            },
+        };
+
+        let conn = TlsConnection::new(tlsn_conn, budget);
+        let fut = ProverFuture {
+            prover: Some(prover),
+        };
+
+        Ok((conn, fut))
+    }
+
+    /// Connects to the server through a tunnel, e.g. a SOCKS or HTTP CONNECT
+    /// proxy, or an outer TLS session.
+    ///
+    /// `open` is called with the name of the server configured in `config` and
+    /// must return a stream which carries bytes to and from that server. The
+    /// inner TLS session always uses this name for SNI and certificate
+    /// verification, regardless of which host the tunnel itself connects to.
+    ///
+    /// See [`connect`](Self::connect) for details on the returned values.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The TLS client configuration.
+    /// * `open` - Opens the tunnel to the named server.
+    #[instrument(parent = &self.span, level = "debug", skip_all, err)]
+    pub async fn connect_via<S, F, Fut>(
+        self,
+        config: TlsClientConfig,
+        open: F,
+    ) -> Result<(TlsConnection, ProverFuture<S>)>
+    where
+        S: AsyncWrite + AsyncRead + Send + Unpin + 'static,
+        F: FnOnce(ServerName) -> Fut,
+        Fut: Future<Output = std::io::Result<S>>,
+    {
+        let socket = open(config.server_name().clone()).await.map_err(|e| {
+            Error::io()
+                .with_msg("failed to open tunnel to the server")
+                .with_source(e)
+        })?;
+
+        self.connect(config, socket)
+    }
+
+    /// Starts the TLS connection without performing any IO.
+    ///
+    /// The returned prover does not own a socket. Instead, the caller is
+    /// responsible for moving TLS data between the prover and the server, and
+    /// application data between the prover and the application. This allows
+    /// embedding the prover into a custom event loop.
+    ///
+    /// See [`connect`](Self::connect) for a version which drives the IO.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The TLS client configuration.
+    #[instrument(parent = &self.span, level = "debug", skip_all, err)]
+    pub fn connect_sans_io(self, config: TlsClientConfig) -> Result<Prover<state::Engine>> {
         let state::CommitAccepted {
-            mpc_tls, keys, vm, ..
+            mpc_tls,
+            keys,
+            vm,
+            budget,
+            capabilities,
         } = self.state;
 
         let decrypt = mpc_tls.is_decrypting();
@@ -178,44 +301,19 @@ impl Prover<state::CommitAccepted> {
         let server_name =
             TlsServerName::try_from(server_name.as_ref()).expect("name was validated");
 
-        let root_store = tls_client::RootCertStore {
-            roots: config
-                .root_store()
-                .roots
-                .iter()
-                .map(|cert| {
-                    let der = CertificateDer::from_slice(&cert.0);
-                    anchor_from_trusted_cert(&der)
-                        .map(|anchor| anchor.to_owned())
-                        .map_err(|e| {
-                            Error::config()
-                                .with_msg("failed to parse root certificate")
-                                .with_source(e)
-                        })
-                })
-                .collect::<Result<Vec<_>, _>>()?,
-        };
-
-        let rustls_config = tls_client::ClientConfig::builder()
-            .with_safe_defaults()
-            .with_root_certificates(root_store);
-
-        let rustls_config = if let Some((cert, key)) = config.client_auth() {
-            rustls_config
-                .with_single_cert(
-                    cert.iter()
-                        .map(|cert| tls_client::Certificate(cert.0.clone()))
-                        .collect(),
-                    tls_client::PrivateKey(key.0.clone()),
-                )
+        // Only the cipher suites which both parties support are offered.
+        let rustls_config = client_config(
+            &config,
+            tls_client::ClientConfig::builder()
+                .with_cipher_suites(&capabilities.supported_cipher_suites())
+                .with_safe_default_kx_groups()
+                .with_safe_default_protocol_versions()
                 .map_err(|e| {
                     Error::config()
-                        .with_msg("failed to configure client authentication")
+                        .with_msg("failed to configure protocol versions")
                         .with_source(e)
-                })?
-        } else {
-            rustls_config.with_no_client_auth()
-        };
+                })?,
+        )?;
 
         let client = ClientConnection::new(
             Arc::new(rustls_config),
@@ -237,34 +335,196 @@ impl Prover<state::CommitAccepted> {
             mpc_ctrl,
             client,
             decrypt,
+            budget,
         );
 
-        let (client_io, tlsn_conn) = futures_plex::duplex(BUF_CAP);
-        let (client_to_server, server_to_client) = futures_plex::duplex(BUF_CAP);
-
-        let prover = Prover {
+        Ok(Prover {
             ctx: self.ctx,
             config: self.config,
             span: self.span,
-            state: state::Connected {
+            state: state::Engine {
                 server_name: config.server_name().clone(),
                 tls_client: Box::new(mpc_tls),
-                client_io,
                 output: None,
-                server_socket: socket,
-                client_to_server,
-                server_to_client,
-                client_closed: false,
-                server_closed: false,
             },
-        };
+        })
+    }
+}
 
-        let conn = TlsConnection::new(tlsn_conn);
-        let fut = ProverFuture {
-            prover: Some(prover),
-        };
+/// Builds the configuration of the TLS client, with the root certificates and
+/// client authentication of `config`.
+pub(crate) fn client_config(
+    config: &TlsClientConfig,
+    builder: tls_client::ConfigBuilder<tls_client::WantsVerifier>,
+) -> Result<tls_client::ClientConfig> {
+    let root_store = tls_client::RootCertStore {
+        roots: config
+            .root_store()
+            .roots
+            .iter()
+            .map(|cert| {
+                let der = CertificateDer::from_slice(&cert.0);
+                anchor_from_trusted_cert(&der)
+                    .map(|anchor| anchor.to_owned())
+                    .map_err(|e| {
+                        Error::config()
+                            .with_msg("failed to parse root certificate")
+                            .with_source(e)
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+    };
+
+    let builder = builder.with_root_certificates(root_store);
+
+    if let Some((cert, key)) = config.client_auth() {
+        builder
+            .with_single_cert(
+                cert.iter()
+                    .map(|cert| tls_client::Certificate(cert.0.clone()))
+                    .collect(),
+                tls_client::PrivateKey(key.0.clone()),
+            )
+            .map_err(|e| {
+                Error::config()
+                    .with_msg("failed to configure client authentication")
+                    .with_source(e)
+            })
+    } else {
+        Ok(builder.with_no_client_auth())
+    }
+}
 
-        Ok((conn, fut))
+impl Prover<state::Engine> {
+    /// Returns `true` if the prover wants to read TLS data from the server.
+    pub fn wants_read_tls(&self) -> bool {
+        self.state.tls_client.wants_read_tls()
+    }
+
+    /// Returns `true` if the prover wants to write TLS data to the server.
+    pub fn wants_write_tls(&self) -> bool {
+        self.state.tls_client.wants_write_tls()
+    }
+
+    /// Reads TLS data received from the server.
+    ///
+    /// Returns the number of bytes consumed from `buf`.
+    pub fn read_tls(&mut self, buf: &[u8]) -> Result<usize> {
+        self.state.tls_client.read_tls(buf)
+    }
+
+    /// Writes TLS data which must be sent to the server into `buf`.
+    ///
+    /// Returns the number of bytes written.
+    pub fn write_tls(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.state.tls_client.write_tls(buf)
+    }
+
+    /// Returns `true` if the prover has plaintext from the server to be read.
+    pub fn wants_read(&self) -> bool {
+        self.state.tls_client.wants_read()
+    }
+
+    /// Returns `true` if the prover can accept plaintext to send to the
+    /// server.
+    pub fn wants_write(&self) -> bool {
+        self.state.tls_client.wants_write()
+    }
+
+    /// Reads plaintext received from the server into `buf`.
+    ///
+    /// Returns the number of bytes written to `buf`.
+    pub fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.state.tls_client.read(buf)
+    }
+
+    /// Writes plaintext to be sent to the server.
+    ///
+    /// Returns the number of bytes consumed from `buf`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error without consuming any data if `buf` does not fit into
+    /// the remaining sent budget, see [`Error::is_sent_budget_exceeded`].
+    pub fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.state.tls_client.write(buf)
+    }
+
+    /// Closes the connection from the client side.
+    pub fn client_close(&mut self) {
+        self.state.tls_client.client_close()
+    }
+
+    /// Signals that the server has closed the connection.
+    pub fn server_close(&mut self) {
+        self.state.tls_client.server_close()
+    }
+
+    /// Returns a controller for the prover for advanced functionality.
+    pub fn control(&self) -> ProverControl {
+        ProverControl {
+            decrypt_state: self.state.tls_client.decrypt(),
+            stats: self.state.tls_client.stats(),
+            abort: self.state.tls_client.abort(),
+        }
+    }
+
+    /// Polls the prover to make progress.
+    ///
+    /// Returns ready once the connection has been closed and the TLS
+    /// transcript has been committed, after which the prover can be
+    /// [finished](Self::finish).
+    pub fn poll(&mut self, cx: &mut std::task::Context<'_>) -> Poll<Result<()>> {
+        self.state.poll(cx)
+    }
+
+    /// Finishes the connection, returning the committed prover.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection is not yet closed, see
+    /// [`poll`](Self::poll).
+    pub fn finish(self) -> Result<Prover<state::Committed>> {
+        let TlsOutput {
+            ctx,
+            vm,
+            keys,
+            tls_transcript,
+            transcript,
+            recv_budget_exceeded,
+            aborted,
+            alert,
+        } = self
+            .state
+            .output
+            .ok_or(Error::internal().with_msg("prover has not yet closed the connection"))?;
+
+        Ok(Prover {
+            config: self.config,
+            span: self.span,
+            ctx: Some(ctx),
+            state: state::Committed {
+                vm,
+                server_name: self.state.server_name,
+                keys,
+                tls_transcript,
+                transcript,
+                recv_budget_exceeded,
+                aborted,
+                alert,
+            },
+        })
+    }
+}
+
+impl state::Engine {
+    pub(crate) fn poll(&mut self, cx: &mut std::task::Context<'_>) -> Poll<Result<()>> {
+        if self.output.is_none() {
+            let output = ready!(self.tls_client.poll(cx))?;
+            self.output = Some(output);
+        }
+
+        Poll::Ready(Ok(()))
     }
 }
 
@@ -280,18 +540,20 @@ where
     ) -> Poll<Self::Output> {
         let mut state = Pin::new(&mut self.state).project();
 
-        if state.output.is_none()
-            && let Poll::Ready(output) = state.tls_client.poll(cx)?
-        {
-            *state.output = Some(output);
-        }
+        let _ = state.engine.poll(cx)?;
 
         Self::io_client_conn(&mut state, cx)?;
         Self::io_client_server(&mut state, cx)?;
 
-        if *state.server_closed && state.output.is_some() {
+        if let Some(aborted) = state.engine.output.as_ref().map(|output| output.aborted)
+            && (*state.server_closed || aborted)
+        {
             ready!(state.client_io.poll_close(cx))?;
-            ready!(state.server_socket.poll_close(cx))?;
+            match ready!(state.server_socket.poll_close(cx)) {
+                // The connection of an aborted session may be broken.
+                Err(err) if aborted => debug!("failed to close server connection: {err}"),
+                result => result?,
+            }
 
             return Poll::Ready(Ok(()));
         }
@@ -305,31 +567,13 @@ where
     S: AsyncRead + AsyncWrite + Send + Unpin + 'static,
 {
     fn finish(self) -> Result<Prover<state::Committed>, Error> {
-        let TlsOutput {
-            ctx,
-            vm,
-            keys,
-            tls_transcript,
-            transcript,
-        } = self
-            .state
-            .output
-            .ok_or(Error::internal().with_msg("prover has not yet closed the connection"))?;
-
-        let prover = Prover {
+        Prover {
             config: self.config,
             span: self.span,
-            ctx: Some(ctx),
-            state: state::Committed {
-                vm,
-                server_name: self.state.server_name,
-                keys,
-                tls_transcript,
-                transcript,
-            },
-        };
-
-        Ok(prover)
+            ctx: self.ctx,
+            state: self.state.engine,
+        }
+        .finish()
     }
 
     fn io_client_conn(
@@ -342,15 +586,15 @@ where
             && let Poll::Ready(buf) = simplex.poll_get(cx)?
         {
             if !buf.is_empty() {
-                if state.tls_client.wants_write() {
-                    let write = state.tls_client.write(buf)?;
+                if state.engine.tls_client.wants_write() {
+                    let write = state.engine.tls_client.write(buf)?;
                     if write > 0 {
                         simplex.advance(write);
                     }
                 }
             } else if !*state.client_closed && !*state.server_closed {
                 *state.client_closed = true;
-                state.tls_client.client_close();
+                state.engine.tls_client.client_close();
             }
         }
 
@@ -358,9 +602,9 @@ where
         // Always poll to register wakers, then check wants_read()
         if let Poll::Ready(mut simplex) = state.client_io.as_mut().poll_lock_write(cx)
             && let Poll::Ready(buf) = simplex.poll_mut(cx)?
-            && state.tls_client.wants_read()
+            && state.engine.tls_client.wants_read()
         {
-            let read = state.tls_client.read(buf)?;
+            let read = state.engine.tls_client.read(buf)?;
             if read > 0 {
                 simplex.advance_mut(read);
             }
@@ -373,23 +617,33 @@ where
         cx: &mut std::task::Context<'_>,
     ) -> Result<(), Error> {
         // server_socket -> buf
-        if let Poll::Ready(write) = state
+        match state
             .server_to_client
-            .poll_write_from(cx, state.server_socket.as_mut())?
-            && write == 0
-            && !*state.server_closed
+            .poll_write_from(cx, state.server_socket.as_mut())
         {
-            *state.server_closed = true;
-            state.tls_client.server_close();
+            Poll::Ready(Ok(0)) if !*state.server_closed => {
+                *state.server_closed = true;
+                state.engine.tls_client.server_close();
+            }
+            // A failed connection is only tolerated once the server sent
+            // close_notify, see `ProverControl::abort`.
+            Poll::Ready(Err(err)) if !*state.server_closed => {
+                let abort = state.engine.tls_client.abort();
+                if !abort.is_requested() {
+                    warn!("connection to the server failed: {err}");
+                    abort.request();
+                }
+            }
+            _ => {}
         }
 
         // buf -> tls_client
         // Always poll to register wakers, then check wants_read_tls()
         if let Poll::Ready(mut simplex) = state.client_to_server.as_mut().poll_lock_read(cx)
             && let Poll::Ready(buf) = simplex.poll_get(cx)?
-            && state.tls_client.wants_read_tls()
+            && state.engine.tls_client.wants_read_tls()
         {
-            let read = state.tls_client.read_tls(buf)?;
+            let read = state.engine.tls_client.read_tls(buf)?;
             if read > 0 {
                 simplex.advance(read);
             }
@@ -399,9 +653,9 @@ where
         // Always poll to register wakers, then check wants_write_tls()
         if let Poll::Ready(mut simplex) = state.client_to_server.as_mut().poll_lock_write(cx)
             && let Poll::Ready(buf) = simplex.poll_mut(cx)?
-            && state.tls_client.wants_write_tls()
+            && state.engine.tls_client.wants_write_tls()
         {
-            let write = state.tls_client.write_tls(buf)?;
+            let write = state.engine.tls_client.write_tls(buf)?;
             if write > 0 {
                 simplex.advance_mut(write);
             }
@@ -424,13 +678,111 @@ where
 }
 
 impl Prover<state::Committed> {
+    /// Moves the transcript into the provided storage.
+    ///
+    /// By default the transcript is kept in memory, this allows embedders to
+    /// control where the plaintext lives between the connection and proving.
+    /// The records of the [TLS transcript](Self::tls_transcript) do not hold
+    /// the application data plaintext, so once moved the in-memory copy is
+    /// dropped. The plaintext is still held in memory while the connection is
+    /// open.
+    ///
+    /// # Arguments
+    ///
+    /// * `storage` - An empty transcript storage.
+    pub fn with_transcript_storage<S: TranscriptStorage>(
+        self,
+        mut storage: S,
+    ) -> Result<Prover<state::Committed<S>>> {
+        let state::Committed {
+            vm,
+            server_name,
+            keys,
+            tls_transcript,
+            transcript,
+            recv_budget_exceeded,
+            aborted,
+            alert,
+        } = self.state;
+
+        if storage.len_of_direction(Direction::Sent) != 0
+            || storage.len_of_direction(Direction::Received) != 0
+        {
+            return Err(Error::config().with_msg("transcript storage must be empty"));
+        }
+
+        for direction in [Direction::Sent, Direction::Received] {
+            let data = transcript
+                .read_all(direction)
+                .expect("in-memory transcript is readable");
+            storage.append(direction, &data).map_err(|e| {
+                Error::io()
+                    .with_msg("failed to write transcript to storage")
+                    .with_source(e)
+            })?;
+        }
+
+        Ok(Prover {
+            config: self.config,
+            span: self.span,
+            ctx: self.ctx,
+            state: state::Committed {
+                vm,
+                server_name,
+                keys,
+                tls_transcript,
+                transcript: storage,
+                recv_budget_exceeded,
+                aborted,
+                alert,
+            },
+        })
+    }
+}
+
+impl<T: TranscriptStorage> Prover<state::Committed<T>> {
     /// Returns the TLS transcript.
+    ///
+    /// The application data records only hold the ciphertext, their plaintext
+    /// was moved into [`transcript`](Self::transcript). Calling
+    /// [`TlsTranscript::to_transcript`] on them returns an error.
     pub fn tls_transcript(&self) -> &TlsTranscript {
         &self.state.tls_transcript
     }
 
-    /// Returns the transcript.
-    pub fn transcript(&self) -> &Transcript {
+    /// Returns `true` if the prover closed the connection because the next
+    /// record from the server would have exceeded the receive budget.
+    ///
+    /// In that case the transcript ends with the last record which fit into
+    /// the budget.
+    pub fn recv_budget_exceeded(&self) -> bool {
+        self.state.recv_budget_exceeded
+    }
+
+    /// Returns `true` if the session was [aborted](ProverControl::abort)
+    /// after the server sent `close_notify`, including when the connection to
+    /// the server failed afterwards.
+    ///
+    /// In that case the transcript ends with the data the server sent before
+    /// `close_notify`, so it is not truncated. The verifier can not observe
+    /// the abort, but it attests whether the server ended the connection with
+    /// `close_notify` in
+    /// [`ConnectionInfo::truncated`](crate::connection::ConnectionInfo::truncated).
+    pub fn aborted(&self) -> bool {
+        self.state.aborted
+    }
+
+    /// Returns the fatal alert the server sent after the handshake, if any.
+    ///
+    /// The alert ends the connection, and the transcript ends with the data
+    /// the server sent before it. The alert is always a
+    /// [`TlsError::Alert`].
+    pub fn alert(&self) -> Option<TlsError> {
+        self.state.alert
+    }
+
+    /// Returns the transcript storage.
+    pub fn transcript(&self) -> &T {
         &self.state.transcript
     }
 
@@ -473,7 +825,13 @@ impl Prover<state::Committed> {
 
         let partial_transcript = config
             .reveal()
-            .map(|(sent, recv)| transcript.to_partial(sent.clone(), recv.clone()));
+            .map(|(sent, recv)| transcript.read_partial(sent.clone(), recv.clone()))
+            .transpose()
+            .map_err(|e| {
+                Error::io()
+                    .with_msg("failed to read transcript from storage")
+                    .with_source(e)
+            })?;
 
         let msg = ProveRequestMsg {
             request: config.to_request(),
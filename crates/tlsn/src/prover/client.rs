@@ -1,13 +1,18 @@
 //! Provides a TLS client.
 
-use crate::mpz::ProverZk;
+use crate::{
+    Error, TlsError,
+    mpz::ProverZk,
+    prover::control::{SessionPhase, SessionStats},
+};
+use futures::task::AtomicWaker;
 use mpc_tls::SessionKeys;
 use std::{
     sync::{
         Arc,
-        atomic::{AtomicBool, Ordering},
+        atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering},
     },
-    task::{Context, Poll},
+    task::{Context, Poll, Waker},
 };
 use tlsn_core::transcript::{TlsTranscript, Transcript};
 
@@ -52,6 +57,12 @@ pub(crate) trait TlsClient {
     /// Returns the inner decryption state.
     fn decrypt(&self) -> Arc<DecryptState>;
 
+    /// Returns the live session statistics.
+    fn stats(&self) -> Arc<StatsState>;
+
+    /// Returns the abort state.
+    fn abort(&self) -> Arc<AbortState>;
+
     /// Polls the client to make progress.
     fn poll(&mut self, cx: &mut Context) -> Poll<Result<TlsOutput, Self::Error>>;
 }
@@ -72,6 +83,202 @@ impl DecryptState {
     }
 }
 
+/// Abort state, shared with [`ProverControl`](crate::prover::ProverControl).
+#[derive(Debug, Default)]
+pub(crate) struct AbortState {
+    requested: AtomicBool,
+    waker: AtomicWaker,
+}
+
+impl AbortState {
+    /// Requests the session to be aborted.
+    pub(crate) fn request(&self) {
+        self.requested.store(true, Ordering::Release);
+        self.waker.wake();
+    }
+
+    pub(crate) fn is_requested(&self) -> bool {
+        self.requested.load(Ordering::Acquire)
+    }
+
+    /// Registers the waker of the task which handles the abort.
+    pub(crate) fn register(&self, waker: &Waker) {
+        self.waker.register(waker);
+    }
+}
+
+/// Checks whether a session can be aborted.
+///
+/// A session can only be cut short once the server has sent `close_notify`.
+/// Before that, the data received so far may have been truncated by an
+/// attacker.
+pub(crate) fn check_abort(handshaking: bool, received_close_notify: bool) -> Result<(), Error> {
+    if handshaking {
+        Err(Error::io().with_msg("session ended during the handshake"))
+    } else if !received_close_notify {
+        Err(Error::tls(TlsError::Truncated)
+            .with_msg("session ended before the server sent close_notify"))
+    } else {
+        Ok(())
+    }
+}
+
+/// Live session statistics, shared with [`ProverControl`](crate::prover::ProverControl).
+#[derive(Debug, Default)]
+pub(crate) struct StatsState {
+    sent: AtomicUsize,
+    recv: AtomicUsize,
+    recv_records: AtomicUsize,
+    phase: AtomicU8,
+}
+
+impl StatsState {
+    /// Records that `len` plaintext bytes were sent.
+    pub(crate) fn sent(&self, len: usize) {
+        self.sent.fetch_add(len, Ordering::Relaxed);
+    }
+
+    /// Records that `len` plaintext bytes were received.
+    pub(crate) fn received(&self, len: usize) {
+        self.recv.fetch_add(len, Ordering::Relaxed);
+    }
+
+    /// Sets the number of application data records received.
+    pub(crate) fn set_recv_records(&self, records: usize) {
+        self.recv_records.store(records, Ordering::Relaxed);
+    }
+
+    /// Sets the current phase of the session.
+    pub(crate) fn set_phase(&self, phase: SessionPhase) {
+        self.phase.store(phase as u8, Ordering::Relaxed);
+    }
+
+    /// Returns a snapshot of the statistics.
+    pub(crate) fn snapshot(&self) -> SessionStats {
+        SessionStats {
+            sent_bytes: self.sent.load(Ordering::Relaxed),
+            recv_bytes: self.recv.load(Ordering::Relaxed),
+            recv_records: self.recv_records.load(Ordering::Relaxed),
+            phase: SessionPhase::from_u8(self.phase.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+/// TLS record header length.
+const RECORD_HEADER_LEN: usize = 5;
+/// Overhead of an AES-GCM encrypted record, i.e. the explicit nonce and tag.
+const RECORD_OVERHEAD: usize = 8 + 16;
+/// Application data content type.
+const APPLICATION_DATA: u8 = 23;
+
+/// Tracks the application data sent and received against the configured
+/// budgets.
+///
+/// The MPC-TLS record layer fails once its limits are exceeded, so the
+/// budgets are enforced before any data reaches it.
+#[derive(Debug, Clone)]
+pub(crate) struct Budget {
+    max_sent: usize,
+    max_recv: usize,
+    sent: usize,
+    recv: usize,
+    /// Number of application data records received.
+    recv_records: usize,
+    /// Header of the incoming record which is being read.
+    header: Vec<u8>,
+    /// Remaining payload length of the incoming record which is being read.
+    remaining: usize,
+}
+
+impl Budget {
+    pub(crate) fn new(max_sent: usize, max_recv: usize) -> Self {
+        Self {
+            max_sent,
+            max_recv,
+            sent: 0,
+            recv: 0,
+            recv_records: 0,
+            header: Vec::with_capacity(RECORD_HEADER_LEN),
+            remaining: 0,
+        }
+    }
+
+    /// Checks that `len` more bytes can be sent.
+    pub(crate) fn check_sent(&self, len: usize) -> Result<(), Error> {
+        if self.sent + len > self.max_sent {
+            return Err(Error::sent_budget_exceeded().with_msg(format!(
+                "attempted to send {len} bytes with {} of {} bytes already sent",
+                self.sent, self.max_sent
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Records that `len` bytes were sent.
+    pub(crate) fn sent(&mut self, len: usize) {
+        self.sent += len;
+    }
+
+    /// Returns the number of application data records received.
+    pub(crate) fn recv_records(&self) -> usize {
+        self.recv_records
+    }
+
+    /// Returns the length of the prefix of the incoming TLS data `buf` which
+    /// can be processed without exceeding the receive budget, and whether the
+    /// record after it would exceed the budget.
+    pub(crate) fn recv_limit(&self, buf: &[u8]) -> (usize, bool) {
+        self.clone().scan(buf)
+    }
+
+    /// Records that the incoming TLS data `buf` was processed.
+    ///
+    /// `buf` must not be longer than the limit returned by
+    /// [`recv_limit`](Self::recv_limit).
+    pub(crate) fn received(&mut self, buf: &[u8]) {
+        let (len, _) = self.scan(buf);
+        debug_assert_eq!(len, buf.len(), "receive budget was exceeded");
+    }
+
+    fn scan(&mut self, buf: &[u8]) -> (usize, bool) {
+        let mut pos = 0;
+        while pos < buf.len() {
+            if self.remaining > 0 {
+                let n = self.remaining.min(buf.len() - pos);
+                self.remaining -= n;
+                pos += n;
+                continue;
+            }
+
+            let start = pos;
+            let n = (RECORD_HEADER_LEN - self.header.len()).min(buf.len() - pos);
+            self.header.extend_from_slice(&buf[pos..pos + n]);
+            pos += n;
+
+            if self.header.len() < RECORD_HEADER_LEN {
+                break;
+            }
+
+            let len = u16::from_be_bytes([self.header[3], self.header[4]]) as usize;
+            if self.header[0] == APPLICATION_DATA {
+                let data_len = len.saturating_sub(RECORD_OVERHEAD);
+                if self.recv + data_len > self.max_recv {
+                    self.header.truncate(self.header.len() - n);
+                    return (start, true);
+                }
+                self.recv += data_len;
+                self.recv_records += 1;
+            }
+
+            self.header.clear();
+            self.remaining = len;
+        }
+
+        (pos, false)
+    }
+}
+
 /// Output of a TLS session.
 pub(crate) struct TlsOutput {
     pub(crate) ctx: mpz_common::Context,
@@ -79,4 +286,110 @@ pub(crate) struct TlsOutput {
     pub(crate) keys: SessionKeys,
     pub(crate) tls_transcript: TlsTranscript,
     pub(crate) transcript: Transcript,
+    /// Whether the connection was closed because the receive budget would
+    /// have been exceeded.
+    pub(crate) recv_budget_exceeded: bool,
+    /// Whether the session was aborted after the server sent close_notify.
+    pub(crate) aborted: bool,
+    /// Fatal alert the server sent after the handshake.
+    pub(crate) alert: Option<TlsError>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(typ: u8, data_len: usize) -> Vec<u8> {
+        let len = (data_len + RECORD_OVERHEAD) as u16;
+        let mut record = vec![typ, 3, 3];
+        record.extend_from_slice(&len.to_be_bytes());
+        record.resize(RECORD_HEADER_LEN + len as usize, 0);
+        record
+    }
+
+    #[test]
+    fn test_sent_budget() {
+        let mut budget = Budget::new(10, 0);
+
+        budget.check_sent(10).unwrap();
+        budget.sent(6);
+
+        assert!(budget.check_sent(4).is_ok());
+        assert!(budget.check_sent(5).unwrap_err().is_sent_budget_exceeded());
+    }
+
+    #[test]
+    fn test_check_abort() {
+        assert!(check_abort(false, true).is_ok());
+
+        let err = check_abort(false, false).unwrap_err();
+        assert_eq!(err.tls_error(), Some(TlsError::Truncated));
+
+        let err = check_abort(true, false).unwrap_err();
+        assert!(err.is_io());
+        assert_eq!(err.tls_error(), None);
+    }
+
+    #[test]
+    fn test_abort_state() {
+        let abort = AbortState::default();
+        assert!(!abort.is_requested());
+
+        struct Woken(AtomicBool);
+
+        impl futures::task::ArcWake for Woken {
+            fn wake_by_ref(arc_self: &Arc<Self>) {
+                arc_self.0.store(true, Ordering::Relaxed);
+            }
+        }
+
+        let woken = Arc::new(Woken(AtomicBool::new(false)));
+        abort.register(&futures::task::waker(woken.clone()));
+        abort.request();
+
+        assert!(abort.is_requested());
+        assert!(woken.0.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn test_recv_budget() {
+        let mut budget = Budget::new(0, 100);
+
+        // Handshake records do not count towards the budget.
+        let mut data = record(22, 200);
+        data.extend(record(APPLICATION_DATA, 60));
+        let boundary = data.len();
+        data.extend(record(APPLICATION_DATA, 60));
+
+        assert_eq!(budget.recv_limit(&data), (boundary, true));
+
+        // Process the data in small chunks.
+        for chunk in data[..boundary].chunks(3) {
+            assert_eq!(budget.recv_limit(chunk), (chunk.len(), false));
+            budget.received(chunk);
+        }
+
+        assert_eq!(budget.recv_limit(&data[boundary..]), (0, true));
+        assert_eq!(budget.recv_records(), 1);
+        assert_eq!(
+            budget.recv_limit(&record(APPLICATION_DATA, 40)),
+            (69, false)
+        );
+    }
+
+    #[test]
+    fn test_recv_budget_empty_records() {
+        let mut budget = Budget::new(0, 10);
+
+        // Zero-length records do not count towards the budget.
+        let mut data = record(APPLICATION_DATA, 0);
+        data.extend(record(APPLICATION_DATA, 10));
+        data.extend(record(APPLICATION_DATA, 0));
+
+        assert_eq!(budget.recv_limit(&data), (data.len(), false));
+        budget.received(&data);
+
+        assert_eq!(budget.recv_records(), 3);
+        assert_eq!(budget.recv_limit(&record(APPLICATION_DATA, 1)), (0, true));
+    }
 }
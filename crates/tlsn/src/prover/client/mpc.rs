@@ -1,9 +1,13 @@
 //! Implementation of an MPC-TLS client.
 
 use crate::{
-    error::Error as TlsnError,
+    HandshakeDiagnostics,
+    error::{Error as TlsnError, TlsError},
     mpz::{ProverMpc, ProverZk},
-    prover::client::{DecryptState, TlsClient, TlsOutput},
+    prover::{
+        SessionPhase,
+        client::{AbortState, Budget, DecryptState, StatsState, TlsClient, TlsOutput, check_abort},
+    },
     tag::verify_tags,
 };
 use futures::{Future, FutureExt};
@@ -15,8 +19,9 @@ use std::{
     sync::{Arc, atomic::AtomicBool},
     task::Poll,
 };
-use tls_client::ClientConnection;
-use tlsn_core::transcript::TlsTranscript;
+use tls_client::{ClientConnection, Error as TlsClientError};
+use tls_core::msgs::enums::AlertDescription;
+use tlsn_core::{transcript::TlsTranscript, webpki::CertificateDer};
 use tlsn_deap::Deap;
 use tokio::sync::Mutex;
 use tracing::{Span, debug, instrument, trace, warn};
@@ -30,8 +35,13 @@ type FinalizeFuture =
 pub(crate) struct MpcTlsClient {
     state: State,
     decrypt: Arc<DecryptState>,
+    stats: Arc<StatsState>,
+    abort: Arc<AbortState>,
+    budget: Budget,
+    recv_budget_exceeded: bool,
     client_wants_close: bool,
     server_closed: bool,
+    aborted: bool,
 }
 
 enum State {
@@ -76,6 +86,7 @@ impl MpcTlsClient {
         mpc_ctrl: LeaderCtrl,
         tls: ClientConnection,
         decrypt: bool,
+        budget: Budget,
     ) -> Self {
         let inner = InnerState {
             span,
@@ -86,6 +97,7 @@ impl MpcTlsClient {
             client_closed: false,
             mpc_stopped: false,
             decrypt,
+            alert: None,
         };
 
         let decrypt = DecryptState {
@@ -94,8 +106,13 @@ impl MpcTlsClient {
 
         Self {
             decrypt: Arc::new(decrypt),
+            stats: Arc::new(StatsState::default()),
+            abort: Arc::new(AbortState::default()),
+            budget,
+            recv_budget_exceeded: false,
             client_wants_close: false,
             server_closed: false,
+            aborted: false,
             state: State::Start {
                 mpc: Box::into_pin(mpc),
                 inner: Box::new(inner),
@@ -128,7 +145,9 @@ impl TlsClient for MpcTlsClient {
     type Error = TlsnError;
 
     fn wants_read_tls(&self) -> bool {
-        if let Some(client) = self.inner_client() {
+        if self.recv_budget_exceeded {
+            false
+        } else if let Some(client) = self.inner_client() {
             client.wants_read()
         } else {
             false
@@ -143,14 +162,31 @@ impl TlsClient for MpcTlsClient {
         }
     }
 
-    fn read_tls(&mut self, mut buf: &[u8]) -> Result<usize, Self::Error> {
-        if let Some(client) = self.inner_client_mut()
+    fn read_tls(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if self.recv_budget_exceeded {
+            return Ok(0);
+        }
+
+        // Only pass on whole records which fit into the receive budget.
+        let (limit, exceeded) = self.budget.recv_limit(buf);
+        if exceeded {
+            warn!("receive budget would be exceeded, closing connection");
+            self.recv_budget_exceeded = true;
+            self.client_wants_close = true;
+        }
+
+        let buf = &buf[..limit];
+        let read = if let Some(client) = self.inner_client_mut()
             && client.wants_read()
         {
-            client.read_tls(&mut buf).map_err(TlsnError::from)
+            client.read_tls(&mut &buf[..]).map_err(TlsnError::from)?
         } else {
-            Ok(0)
-        }
+            0
+        };
+        self.budget.received(&buf[..read]);
+        self.stats.set_recv_records(self.budget.recv_records());
+
+        Ok(read)
     }
 
     fn write_tls(&mut self, mut buf: &mut [u8]) -> Result<usize, Self::Error> {
@@ -183,22 +219,31 @@ impl TlsClient for MpcTlsClient {
         if let Some(client) = self.inner_client_mut()
             && !client.plaintext_is_empty()
         {
-            client.read_plaintext(buf).map_err(TlsnError::from)
+            let read = client.read_plaintext(buf).map_err(TlsnError::from)?;
+            self.stats.received(read);
+
+            Ok(read)
         } else {
             Ok(0)
         }
     }
 
     fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
-        if let Some(client) = self.inner_client_mut()
+        self.budget.check_sent(buf.len())?;
+
+        let written = if let Some(client) = self.inner_client_mut()
             && !client.sendable_plaintext_is_full()
         {
             client
                 .write_plaintext(buf)
-                .map_err(|err| TlsnError::internal().with_source(err))
+                .map_err(|err| TlsnError::internal().with_source(err))?
         } else {
-            Ok(0)
-        }
+            0
+        };
+        self.budget.sent(written);
+        self.stats.sent(written);
+
+        Ok(written)
     }
 
     fn client_close(&mut self) {
@@ -213,7 +258,17 @@ impl TlsClient for MpcTlsClient {
         self.decrypt.clone()
     }
 
+    fn stats(&self) -> Arc<StatsState> {
+        self.stats.clone()
+    }
+
+    fn abort(&self) -> Arc<AbortState> {
+        self.abort.clone()
+    }
+
     fn poll(&mut self, cx: &mut std::task::Context) -> Poll<Result<TlsOutput, Self::Error>> {
+        self.abort.register(cx.waker());
+
         match std::mem::replace(&mut self.state, State::Error) {
             State::Start { mpc, inner } => {
                 trace!("inner client is starting");
@@ -225,10 +280,28 @@ impl TlsClient for MpcTlsClient {
             }
             State::Active { mpc, inner } => {
                 trace!("inner client is active");
+                self.stats.set_phase(if inner.tls.is_handshaking() {
+                    SessionPhase::Handshake
+                } else {
+                    SessionPhase::Active
+                });
                 let decrypt = self.decrypt.is_decrypting();
 
+                if self.abort.is_requested() && !self.aborted && !self.server_closed {
+                    if let Err(err) = check_abort(
+                        inner.tls.is_handshaking(),
+                        inner.tls.received_close_notify(),
+                    ) {
+                        return Poll::Ready(Err(err));
+                    }
+
+                    debug!("aborting session after close_notify");
+                    self.aborted = true;
+                    self.server_closed = true;
+                }
+
                 if !inner.tls.is_handshaking() {
-                    if self.server_closed {
+                    if self.server_closed || inner.alert.is_some() {
                         self.state = State::CloseBusy {
                             mpc,
                             fut: Box::pin(inner.server_close()),
@@ -277,6 +350,7 @@ impl TlsClient for MpcTlsClient {
             }
             State::MpcStop { mpc, inner } => {
                 trace!("inner client is stopping mpc");
+                self.stats.set_phase(SessionPhase::Closing);
                 self.state = State::CloseBusy {
                     mpc,
                     fut: Box::pin(inner.stop()),
@@ -285,6 +359,7 @@ impl TlsClient for MpcTlsClient {
             }
             State::CloseBusy { mut mpc, mut fut } => {
                 trace!("inner client is busy closing");
+                self.stats.set_phase(SessionPhase::Closing);
                 match (mpc.poll_unpin(cx)?, fut.poll_unpin(cx)?) {
                     (Poll::Ready((ctx, transcript)), Poll::Ready(inner)) => {
                         self.state = State::Finalizing {
@@ -332,11 +407,16 @@ impl TlsClient for MpcTlsClient {
             }
             State::Finalizing { mut fut } => match fut.poll_unpin(cx) {
                 Poll::Ready(output) => {
-                    let (inner, ctx, tls_transcript) = output?;
-                    let InnerState { vm, keys, .. } = inner;
-
+                    let (inner, ctx, mut tls_transcript) = output?;
+                    self.stats.set_phase(SessionPhase::Finished);
+                    let InnerState {
+                        vm, keys, alert, ..
+                    } = inner;
+
+                    // The plaintext is only kept in the transcript, which can be
+                    // moved into a storage of the embedder.
                     let transcript = tls_transcript
-                        .to_transcript()
+                        .take_transcript()
                         .expect("transcript is complete");
 
                     let (_, vm) = Arc::into_inner(vm)
@@ -350,12 +430,16 @@ impl TlsClient for MpcTlsClient {
                         keys,
                         tls_transcript,
                         transcript,
+                        recv_budget_exceeded: self.recv_budget_exceeded,
+                        aborted: self.aborted,
+                        alert: alert.map(TlsError::alert),
                     };
 
                     self.state = State::Finished;
                     Poll::Ready(Ok(output))
                 }
                 Poll::Pending => {
+                    self.stats.set_phase(SessionPhase::Finalizing);
                     self.state = State::Finalizing { fut };
                     Poll::Pending
                 }
@@ -379,24 +463,54 @@ struct InnerState {
     decrypt: bool,
     client_closed: bool,
     mpc_stopped: bool,
+    /// Alert received from the server after the handshake.
+    alert: Option<AlertDescription>,
 }
 
 impl InnerState {
     #[instrument(parent = &self.span, level = "debug", skip_all, err)]
     async fn start(mut self: Box<Self>) -> Result<Box<Self>, TlsnError> {
-        self.tls
-            .start()
-            .await
-            .map_err(|err| TlsnError::internal().with_source(err))?;
+        self.tls.start().await.map_err(TlsnError::from)?;
         Ok(self)
     }
 
+    /// Processes new TLS messages.
+    ///
+    /// A fatal alert after the handshake ends the connection like a close by
+    /// the server, so that the application data authenticated before the
+    /// alert is kept. The alert is returned with the output of the prover,
+    /// see [`Prover::alert`](crate::prover::Prover::alert). Once an alert is
+    /// received the connection is not processed any further.
+    async fn process_new_packets(&mut self) -> Result<(), TlsnError> {
+        if self.alert.is_some() {
+            return Ok(());
+        }
+
+        match self.tls.process_new_packets().await {
+            Ok(_) => Ok(()),
+            Err(TlsClientError::AlertReceived(desc)) if !self.tls.is_handshaking() => {
+                warn!("server sent alert after handshake: {desc:?}");
+                self.alert = Some(desc);
+                Ok(())
+            }
+            Err(err) => {
+                let mut err = TlsnError::from(err);
+                if let Some(chain) = self.tls.rejected_certificates() {
+                    err = err.with_diagnostics(HandshakeDiagnostics::new(
+                        chain
+                            .iter()
+                            .map(|cert| CertificateDer(cert.0.clone()))
+                            .collect(),
+                    ));
+                }
+                Err(err)
+            }
+        }
+    }
+
     #[instrument(parent = &self.span, level = "trace", skip_all, err)]
     async fn run(mut self: Box<Self>) -> Result<Box<Self>, TlsnError> {
-        self.tls
-            .process_new_packets()
-            .await
-            .map_err(|err| TlsnError::internal().with_source(err))?;
+        self.process_new_packets().await?;
         Ok(self)
     }
 
@@ -412,12 +526,9 @@ impl InnerState {
 
     #[instrument(parent = &self.span, level = "debug", skip_all, err)]
     async fn client_close(mut self: Box<Self>) -> Result<Box<Self>, TlsnError> {
-        self.tls
-            .process_new_packets()
-            .await
-            .map_err(|err| TlsnError::internal().with_source(err))?;
+        self.process_new_packets().await?;
 
-        if !self.client_closed {
+        if !self.client_closed && self.alert.is_none() {
             debug!("sending close notify");
             if let Err(e) = self.tls.send_close_notify().await {
                 warn!("failed to send close_notify to server: {}", e);
@@ -430,10 +541,7 @@ impl InnerState {
 
     #[instrument(parent = &self.span, level = "debug", skip_all, err)]
     async fn server_close(mut self: Box<Self>) -> Result<Box<Self>, TlsnError> {
-        self.tls
-            .process_new_packets()
-            .await
-            .map_err(|err| TlsnError::internal().with_source(err))?;
+        self.process_new_packets().await?;
         self.tls
             .server_closed()
             .await
@@ -446,10 +554,7 @@ impl InnerState {
     #[instrument(parent = &self.span, level = "debug", skip_all, err)]
     async fn stop(mut self: Box<Self>) -> Result<Box<Self>, TlsnError> {
         if !self.mpc_stopped {
-            self.tls
-                .process_new_packets()
-                .await
-                .map_err(|err| TlsnError::internal().with_source(err))?;
+            self.process_new_packets().await?;
 
             if self.tls.plaintext_is_empty()
                 && self
@@ -1,6 +1,8 @@
+use crate::prover::client::Budget;
 use futures::{AsyncRead, AsyncWrite};
 use futures_plex::DuplexStream;
 use std::{
+    io::IoSlice,
     pin::Pin,
     task::{Context, Poll},
 };
@@ -10,6 +12,14 @@ use std::{
 /// This type implements [`AsyncRead`] and [`AsyncWrite`] and can be used to
 /// communicate with a server using TLS.
 ///
+/// Writes which do not fit into the remaining sent budget (`max_sent_data`)
+/// fail with an [`std::io::ErrorKind::InvalidInput`] error wrapping an
+/// [`Error`](crate::Error) for which
+/// [`is_sent_budget_exceeded`](crate::Error::is_sent_budget_exceeded) is
+/// `true`. No data
+/// of a failed write is sent. Vectored writes send as many whole buffers as
+/// fit into the budget, and only fail if the first non-empty buffer does not.
+///
 /// # Note
 ///
 /// This connection is closed on a best-effort basis if this is dropped. To
@@ -18,11 +28,12 @@ use std::{
 /// connection.
 pub struct TlsConnection {
     duplex: DuplexStream,
+    budget: Budget,
 }
 
 impl TlsConnection {
-    pub(crate) fn new(duplex: DuplexStream) -> Self {
-        Self { duplex }
+    pub(crate) fn new(duplex: DuplexStream, budget: Budget) -> Self {
+        Self { duplex, budget }
     }
 }
 
@@ -43,7 +54,50 @@ impl AsyncWrite for TlsConnection {
         cx: &mut Context<'_>,
         buf: &[u8],
     ) -> Poll<std::io::Result<usize>> {
-        Pin::new(&mut self.duplex).poll_write(cx, buf)
+        if let Err(err) = self.budget.check_sent(buf.len()) {
+            return Poll::Ready(Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                err,
+            )));
+        }
+
+        let written = std::task::ready!(Pin::new(&mut self.duplex).poll_write(cx, buf))?;
+        self.budget.sent(written);
+
+        Poll::Ready(Ok(written))
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<std::io::Result<usize>> {
+        // Writes the buffers which fit into the budget.
+        let mut len = 0;
+        let mut count = 0;
+        for buf in bufs {
+            if self.budget.check_sent(len + buf.len()).is_err() {
+                break;
+            }
+            len += buf.len();
+            count += 1;
+        }
+
+        if len == 0
+            && let Some(buf) = bufs[count..].iter().find(|buf| !buf.is_empty())
+            && let Err(err) = self.budget.check_sent(buf.len())
+        {
+            return Poll::Ready(Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                err,
+            )));
+        }
+
+        let written =
+            std::task::ready!(Pin::new(&mut self.duplex).poll_write_vectored(cx, &bufs[..count]))?;
+        self.budget.sent(written);
+
+        Poll::Ready(Ok(written))
     }
 
     fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
@@ -54,3 +108,39 @@ impl AsyncWrite for TlsConnection {
         Pin::new(&mut self.duplex).poll_close(cx)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use futures::{AsyncReadExt, AsyncWriteExt, executor::block_on};
+
+    use super::*;
+
+    #[test]
+    fn test_write_vectored_budget() {
+        let (duplex, mut peer) = futures_plex::duplex(1 << 10);
+        let mut conn = TlsConnection::new(duplex, Budget::new(10, 0));
+
+        block_on(async {
+            // Only the buffers which fit are written.
+            let bufs = [
+                IoSlice::new(b"abcd"),
+                IoSlice::new(b"efgh"),
+                IoSlice::new(b"ijkl"),
+            ];
+            let written = conn.write_vectored(&bufs).await.unwrap();
+            assert_eq!(written, 8);
+
+            // Fails if the first non-empty buffer does not fit.
+            let bufs = [IoSlice::new(b""), IoSlice::new(b"ijkl")];
+            let err = conn.write_vectored(&bufs).await.unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+            conn.write_all(b"ij").await.unwrap();
+            conn.close().await.unwrap();
+
+            let mut sent = Vec::new();
+            peer.read_to_end(&mut sent).await.unwrap();
+            assert_eq!(sent, b"abcdefghij");
+        });
+    }
+}
@@ -1,12 +1,15 @@
-use crate::prover::client::DecryptState;
+use crate::prover::client::{AbortState, DecryptState, StatsState};
 use std::sync::Arc;
 
 /// A controller for the prover.
 ///
-/// Can be used to control the decryption of server traffic.
+/// Can be used to control the decryption of server traffic, to observe the
+/// progress of the session and to abort it.
 #[derive(Clone, Debug)]
 pub struct ProverControl {
     pub(crate) decrypt_state: Arc<DecryptState>,
+    pub(crate) stats: Arc<StatsState>,
+    pub(crate) abort: Arc<AbortState>,
 }
 
 impl ProverControl {
@@ -23,4 +26,69 @@ impl ProverControl {
     pub fn enable_decryption(&self, enable: bool) {
         self.decrypt_state.enable_decryption(enable)
     }
+
+    /// Returns a snapshot of the session statistics.
+    ///
+    /// This can be polled while the connection is active, e.g. to display the
+    /// progress of a long download.
+    pub fn stats(&self) -> SessionStats {
+        self.stats.snapshot()
+    }
+
+    /// Aborts the session, without waiting for the server to close the
+    /// connection.
+    ///
+    /// This is useful if the server misbehaves after it sent `close_notify`,
+    /// e.g. by keeping the connection open. The transcript received so far is
+    /// then committed as usual, and
+    /// [`Prover::aborted`](crate::prover::Prover::aborted) returns `true`.
+    ///
+    /// If the server has not sent `close_notify` yet, the prover fails with
+    /// [`TlsError::Truncated`](crate::TlsError::Truncated) instead, as the
+    /// transcript could have been truncated by an attacker.
+    pub fn abort(&self) {
+        self.abort.request()
+    }
+}
+
+/// Statistics of an MPC-TLS session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionStats {
+    /// Number of plaintext bytes sent to the server.
+    pub sent_bytes: usize,
+    /// Number of plaintext bytes received from the server and read by the
+    /// application.
+    pub recv_bytes: usize,
+    /// Number of application data records received from the server.
+    pub recv_records: usize,
+    /// Current phase of the session.
+    pub phase: SessionPhase,
+}
+
+/// Phase of an MPC-TLS session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum SessionPhase {
+    /// The TLS handshake is in progress.
+    Handshake = 0,
+    /// The connection is established and application data is exchanged.
+    Active = 1,
+    /// The connection is being closed.
+    Closing = 2,
+    /// The transcript is being committed.
+    Finalizing = 3,
+    /// The session is complete.
+    Finished = 4,
+}
+
+impl SessionPhase {
+    pub(crate) fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::Handshake,
+            1 => Self::Active,
+            2 => Self::Closing,
+            3 => Self::Finalizing,
+            _ => Self::Finished,
+        }
+    }
 }
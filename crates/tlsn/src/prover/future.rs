@@ -16,15 +16,19 @@ pub struct ProverFuture<S> {
 impl<S> ProverFuture<S> {
     /// Returns a controller for the prover for advanced functionality.
     pub fn control(&self) -> ProverControl {
-        let decrypt_state = self
+        let tls_client = &self
             .prover
             .as_ref()
             .expect("prover should be available")
             .state
-            .tls_client
-            .decrypt();
+            .engine
+            .tls_client;
 
-        ProverControl { decrypt_state }
+        ProverControl {
+            decrypt_state: tls_client.decrypt(),
+            stats: tls_client.stats(),
+            abort: tls_client.abort(),
+        }
     }
 }
 
@@ -0,0 +1,277 @@
+//! Insecure local backend, see
+//! [`ProverConfig::insecure_local_backend`](tlsn_core::config::prover::ProverConfig::insecure_local_backend).
+
+use std::{pin::Pin, sync::Arc};
+
+use futures::{
+    AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, Future, FutureExt, pin_mut, select,
+};
+use futures_plex::DuplexStream;
+use tls_client::{
+    ClientConnection, RustCryptoBackend, ServerName as TlsServerName, SupportedCipherSuite,
+};
+use tls_core::suites;
+use tlsn_core::{
+    config::{prover::ProverConfig, tls::TlsClientConfig},
+    connection::ServerName,
+    transcript::Transcript,
+};
+use tracing::debug;
+
+pub use tls_client::{CipherSuite, ProtocolVersion};
+
+use crate::{
+    Error, Result,
+    prover::{TlsConnection, client::Budget, client_config},
+};
+
+/// Cipher suites supported by the local backend, in order of preference.
+static CIPHER_SUITES: &[SupportedCipherSuite] = &[
+    suites::TLS13_AES_128_GCM_SHA256,
+    suites::TLS13_CHACHA20_POLY1305_SHA256,
+    suites::TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256,
+    suites::TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256,
+    suites::TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256,
+    suites::TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256,
+];
+
+/// Size of the buffers for reading from the server and the application.
+const BUF_LEN: usize = 1 << 14;
+
+/// Future which runs the connection of a [`LocalProver`].
+pub type LocalProverFuture = Pin<Box<dyn Future<Output = Result<LocalOutput>> + Send>>;
+
+/// Output of a [`LocalProver`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct LocalOutput {
+    /// Transcript of the application data.
+    pub transcript: Transcript,
+    /// Negotiated protocol version.
+    pub protocol_version: ProtocolVersion,
+    /// Negotiated cipher suite.
+    pub cipher_suite: CipherSuite,
+}
+
+/// A prover which runs the TLS connection on its own with the RustCrypto
+/// backend, without a verifier.
+///
+/// **This is insecure**: nothing about the connection is committed or
+/// attested, the prover only returns the plaintext it exchanged with the
+/// server. It is meant for integration tests and dry runs, see
+/// [`ProverConfig::insecure_local_backend`].
+#[derive(Debug)]
+pub struct LocalProver {
+    config: ProverConfig,
+    cipher_suites: Vec<SupportedCipherSuite>,
+}
+
+impl LocalProver {
+    /// Creates a new local prover.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configuration is not for the insecure local
+    /// backend.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration for the prover.
+    pub fn new(config: ProverConfig) -> Result<Self> {
+        if !config.is_insecure_local_backend() {
+            return Err(Error::config()
+                .with_msg("prover is not configured with the insecure local backend"));
+        }
+
+        Ok(Self {
+            config,
+            cipher_suites: CIPHER_SUITES.to_vec(),
+        })
+    }
+
+    /// Restricts the cipher suites offered to the server, defaults to all the
+    /// suites supported by the backend.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the suites is not supported.
+    pub fn cipher_suites(mut self, cipher_suites: &[CipherSuite]) -> Result<Self> {
+        self.cipher_suites = cipher_suites
+            .iter()
+            .map(|suite| {
+                CIPHER_SUITES
+                    .iter()
+                    .find(|supported| supported.suite() == *suite)
+                    .copied()
+                    .ok_or_else(|| {
+                        Error::config().with_msg(format!(
+                            "cipher suite is not supported by the local backend: {suite:?}"
+                        ))
+                    })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(self)
+    }
+
+    /// Connects to the server using the provided socket.
+    ///
+    /// Returns a handle to the TLS connection and a future which runs the
+    /// connection, returning the transcript once it is closed.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The TLS client configuration.
+    /// * `socket` - The socket to the server.
+    pub fn connect<S>(
+        self,
+        config: TlsClientConfig,
+        socket: S,
+    ) -> Result<(TlsConnection, LocalProverFuture)>
+    where
+        S: AsyncRead + AsyncWrite + Send + Unpin + 'static,
+    {
+        let rustls_config = client_config(
+            &config,
+            tls_client::ClientConfig::builder()
+                .with_cipher_suites(&self.cipher_suites)
+                .with_safe_default_kx_groups()
+                .with_protocol_versions(&[&tls_client::version::TLS13, &tls_client::version::TLS12])
+                .map_err(|e| {
+                    Error::config()
+                        .with_msg("failed to configure protocol versions")
+                        .with_source(e)
+                })?,
+        )?;
+
+        let ServerName::Dns(server_name) = config.server_name();
+        let server_name =
+            TlsServerName::try_from(server_name.as_ref()).expect("name was validated");
+
+        let client = ClientConnection::new(
+            Arc::new(rustls_config),
+            Box::new(RustCryptoBackend::new()),
+            server_name,
+        )
+        .map_err(|e| {
+            Error::config()
+                .with_msg("failed to create tls client connection")
+                .with_source(e)
+        })?;
+
+        let (client_io, tlsn_conn) = futures_plex::duplex(self.config.app_buffer_size());
+
+        // The local backend has no limits on the data sent.
+        let conn = TlsConnection::new(tlsn_conn, Budget::new(usize::MAX, usize::MAX));
+        let fut = Box::pin(run(client, socket, client_io));
+
+        Ok((conn, fut))
+    }
+}
+
+/// Event of the IO loop.
+enum Event {
+    Server(std::io::Result<usize>),
+    App(std::io::Result<usize>),
+}
+
+/// Moves data between the server, the TLS client and the application until
+/// the server closes the connection.
+async fn run<S>(
+    mut tls: ClientConnection,
+    mut socket: S,
+    mut app: DuplexStream,
+) -> Result<LocalOutput>
+where
+    S: AsyncRead + AsyncWrite + Send + Unpin,
+{
+    tls.start().await?;
+
+    let mut sent = Vec::new();
+    let mut recv = Vec::new();
+    let mut server_buf = vec![0u8; BUF_LEN];
+    let mut app_buf = vec![0u8; BUF_LEN];
+    let mut client_closed = false;
+    let mut server_closed = false;
+
+    loop {
+        tls.process_new_packets().await?;
+
+        while tls.wants_write() {
+            let mut buf = Vec::new();
+            tls.write_tls(&mut buf)?;
+            socket.write_all(&buf).await?;
+        }
+        socket.flush().await?;
+
+        while !tls.plaintext_is_empty() {
+            let read = tls.read_plaintext(&mut app_buf)?;
+            recv.extend_from_slice(&app_buf[..read]);
+            app.write_all(&app_buf[..read]).await?;
+        }
+
+        if server_closed || tls.received_close_notify() {
+            break;
+        }
+
+        let event = {
+            let server_read = socket.read(&mut server_buf).fuse();
+            let app_read = async {
+                if client_closed {
+                    futures::future::pending().await
+                } else {
+                    app.read(&mut app_buf).await
+                }
+            }
+            .fuse();
+            pin_mut!(server_read, app_read);
+
+            select! {
+                read = server_read => Event::Server(read),
+                read = app_read => Event::App(read),
+            }
+        };
+
+        match event {
+            Event::Server(read) => match read? {
+                0 => {
+                    debug!("server closed the connection");
+                    tls.server_closed().await?;
+                    server_closed = true;
+                }
+                read => {
+                    let mut data = &server_buf[..read];
+                    while !data.is_empty() {
+                        tls.read_tls(&mut data)?;
+                        tls.process_new_packets().await?;
+                    }
+                }
+            },
+            Event::App(read) => match read? {
+                0 => {
+                    debug!("sending close notify");
+                    tls.send_close_notify().await?;
+                    client_closed = true;
+                }
+                read => {
+                    sent.extend_from_slice(&app_buf[..read]);
+                    tls.write_all_plaintext(&app_buf[..read])?;
+                }
+            },
+        }
+    }
+
+    app.close().await?;
+
+    let (Some(protocol_version), Some(cipher_suite)) =
+        (tls.protocol_version(), tls.negotiated_cipher_suite())
+    else {
+        return Err(Error::io().with_msg("connection closed during the handshake"));
+    };
+
+    Ok(LocalOutput {
+        transcript: Transcript::new(sent, recv),
+        protocol_version,
+        cipher_suite: cipher_suite.suite(),
+    })
+}
@@ -7,7 +7,7 @@ use tlsn_core::{
     ProverOutput,
     config::prove::ProveConfig,
     transcript::{
-        ContentType, Direction, TlsTranscript, Transcript, TranscriptCommitment, TranscriptSecret,
+        Direction, TlsTranscript, TranscriptCommitment, TranscriptSecret, TranscriptStorage,
     },
 };
 
@@ -16,11 +16,11 @@ use crate::{
     transcript_internal::{TranscriptRefs, auth::prove_plaintext, commit::hash::prove_hash},
 };
 
-pub(crate) async fn prove<T: Vm<Binary> + Send + Sync>(
+pub(crate) async fn prove<T: Vm<Binary> + Send + Sync, S: TranscriptStorage>(
     ctx: &mut Context,
     vm: &mut T,
     keys: &SessionKeys,
-    transcript: &Transcript,
+    transcript: &S,
     tls_transcript: &TlsTranscript,
     config: &ProveConfig,
 ) -> Result<ProverOutput> {
@@ -40,16 +40,23 @@ pub(crate) async fn prove<T: Vm<Binary> + Send + Sync>(
             });
     }
 
+    let read = move |direction| {
+        transcript.read_all(direction).map_err(|e| {
+            Error::io()
+                .with_msg("failed to read transcript from storage")
+                .with_source(e)
+        })
+    };
+    let sent = read(Direction::Sent)?;
+    let received = read(Direction::Received)?;
+
     let transcript_refs = TranscriptRefs {
         sent: prove_plaintext(
             vm,
             keys.client_write_key,
             keys.client_write_iv,
-            transcript.sent(),
-            tls_transcript
-                .sent()
-                .iter()
-                .filter(|record| record.typ == ContentType::ApplicationData),
+            &sent,
+            tls_transcript.application_data(Direction::Sent),
             &reveal_sent,
             &commit_sent,
         )
@@ -62,11 +69,8 @@ pub(crate) async fn prove<T: Vm<Binary> + Send + Sync>(
             vm,
             keys.server_write_key,
             keys.server_write_iv,
-            transcript.received(),
-            tls_transcript
-                .recv()
-                .iter()
-                .filter(|record| record.typ == ContentType::ApplicationData),
+            &received,
+            tls_transcript.application_data(Direction::Received),
             &reveal_recv,
             &commit_recv,
         )
@@ -87,6 +91,7 @@ pub(crate) async fn prove<T: Vm<Binary> + Send + Sync>(
                 commit_config
                     .iter_hash()
                     .map(|((dir, idx), alg)| (*dir, idx.clone(), *alg)),
+                &mut commit_config.blinder_rng(),
             )
             .map_err(|e| {
                 Error::internal()
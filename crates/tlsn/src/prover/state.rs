@@ -12,9 +12,9 @@ use tlsn_deap::Deap;
 use tokio::sync::Mutex;
 
 use crate::{
-    Error,
+    Capabilities, Error, TlsError,
     mpz::{ProverMpc, ProverZk},
-    prover::client::{TlsClient, TlsOutput},
+    prover::client::{Budget, TlsClient, TlsOutput},
 };
 
 /// Entry state
@@ -28,19 +28,28 @@ pub struct CommitAccepted {
     pub(crate) mpc_tls: MpcTlsLeader,
     pub(crate) keys: SessionKeys,
     pub(crate) vm: Arc<Mutex<Deap<ProverMpc, ProverZk>>>,
+    pub(crate) budget: Budget,
+    pub(crate) capabilities: Capabilities,
 }
 
 opaque_debug::implement!(CommitAccepted);
 
+/// State during the MPC-TLS connection when IO is driven by the caller.
+pub struct Engine {
+    pub(crate) server_name: ServerName,
+    pub(crate) tls_client: Box<dyn TlsClient<Error = Error> + Send>,
+    pub(crate) output: Option<TlsOutput>,
+}
+
+opaque_debug::implement!(Engine);
+
 pin_project_lite::pin_project! {
     /// State during the MPC-TLS connection.
     #[project = ConnectedProj]
     pub struct Connected<S> {
-        pub(crate) server_name: ServerName,
-        pub(crate) tls_client: Box<dyn TlsClient<Error = Error> + Send>,
+        pub(crate) engine: Engine,
         #[pin]
         pub(crate) client_io: DuplexStream,
-        pub(crate) output: Option<TlsOutput>,
         #[pin]
         pub(crate) server_socket: S,
         #[pin]
@@ -55,28 +64,36 @@ pin_project_lite::pin_project! {
 opaque_debug::implement!(Connected<S>);
 
 /// State after the TLS transcript has been committed.
-pub struct Committed {
+///
+/// The plaintext of the transcript is kept in the storage `T`, see
+/// [`Prover::with_transcript_storage`](crate::prover::Prover::with_transcript_storage).
+pub struct Committed<T = Transcript> {
     pub(crate) vm: ProverZk,
     pub(crate) server_name: ServerName,
     pub(crate) keys: SessionKeys,
     pub(crate) tls_transcript: TlsTranscript,
-    pub(crate) transcript: Transcript,
+    pub(crate) transcript: T,
+    pub(crate) recv_budget_exceeded: bool,
+    pub(crate) aborted: bool,
+    pub(crate) alert: Option<TlsError>,
 }
 
-opaque_debug::implement!(Committed);
+opaque_debug::implement!(Committed<T>);
 
 #[allow(missing_docs)]
 pub trait ProverState: sealed::Sealed {}
 
 impl ProverState for Initialized {}
 impl ProverState for CommitAccepted {}
+impl ProverState for Engine {}
 impl<S> ProverState for Connected<S> {}
-impl ProverState for Committed {}
+impl<T> ProverState for Committed<T> {}
 
 mod sealed {
     pub trait Sealed {}
     impl Sealed for super::Initialized {}
     impl Sealed for super::CommitAccepted {}
+    impl Sealed for super::Engine {}
     impl<S> Sealed for super::Connected<S> {}
-    impl Sealed for super::Committed {}
+    impl<T> Sealed for super::Committed<T> {}
 }
@@ -1,3 +1,6 @@
+mod keep_alive;
+pub mod record;
+
 use std::{
     future::Future,
     pin::Pin,
@@ -10,9 +13,13 @@ use std::{
 
 use futures::{AsyncRead, AsyncWrite};
 use mpz_common::{ThreadId, context::Multithread, io::Io, mux::Mux};
-use tlsn_core::config::{prover::ProverConfig, verifier::VerifierConfig};
+use tlsn_core::config::{prover::ProverConfig, session::SessionConfig, verifier::VerifierConfig};
 use tlsn_mux::{Connection, Handle};
 
+pub(crate) use keep_alive::PeerDisconnected;
+use keep_alive::{GuardedStream, KEEP_ALIVE_STREAM_ID, KeepAlive, Liveness, MonitoredIo};
+use record::{RecordedStream, Recorder, Recording};
+
 use crate::{
     Error, Result,
     prover::{Prover, state as prover_state},
@@ -37,9 +44,25 @@ const MAX_CONCURRENCY: usize = 8;
 /// match on both sides. For example, if the prover side calls `new_prover`
 /// then `new_verifier`, the verifier side must call `new_verifier` then
 /// `new_prover`.
+///
+/// With keep-alive enabled in the [`SessionConfig`], a peer which stops
+/// responding is detected and the session fails with an error for which
+/// [`Error::is_peer_disconnected`] returns `true`, as do the pending
+/// operations of provers and verifiers.
+///
+/// With recording enabled in the [`SessionConfig`], the messages of the
+/// session are recorded and can be retrieved with
+/// [`recording`](Self::recording), see the [`record`] module.
+///
+/// The session does not limit the size of the messages received on its
+/// streams beyond what the framing of the underlying channel allows, so a
+/// malicious peer can force large allocations. The limits of the
+/// [`VerifierConfig`] only bound the transcript the prover may request.
 #[must_use = "session must be polled continuously to make progress, including during closing."]
 pub struct Session<Io> {
-    conn: Option<Connection<Io>>,
+    conn: Option<Connection<MonitoredIo<Io>>>,
+    keep_alive: Option<KeepAlive>,
+    recorder: Option<Recorder>,
     mt: Multithread,
 }
 
@@ -49,18 +72,49 @@ where
 {
     /// Creates a new session.
     pub fn new(io: Io) -> Self {
+        Self::with_config(io, SessionConfig::default())
+    }
+
+    /// Creates a new session with the given configuration.
+    pub fn with_config(io: Io, config: SessionConfig) -> Self {
         let mut mux_config = tlsn_mux::Config::default();
 
-        mux_config.set_max_num_streams(36);
+        // One more stream carries the keep-alive pings.
+        let max_num_streams = 36 + usize::from(config.keep_alive_interval().is_some());
+        mux_config.set_max_num_streams(max_num_streams);
         mux_config.set_keep_alive(true);
         mux_config.set_close_sync(true);
 
-        let conn = tlsn_mux::Connection::new(io, mux_config);
+        let liveness = Liveness::default();
+        let conn = tlsn_mux::Connection::new(MonitoredIo::new(io, liveness.clone()), mux_config);
         let handle = conn.handle().expect("handle should be available");
-        let mt = build_mt_context(MuxHandle { handle });
+
+        let keep_alive = config.keep_alive_interval().zip(config.peer_timeout()).map(
+            |(interval, peer_timeout)| {
+                let stream = handle
+                    .new_stream(KEEP_ALIVE_STREAM_ID)
+                    .expect("keep-alive stream should be available");
+
+                KeepAlive::new(
+                    mpz_common::io::Io::from_io(stream),
+                    interval,
+                    peer_timeout,
+                    liveness.clone(),
+                )
+            },
+        );
+
+        let recorder = config.record().then(Recorder::default);
+        let mt = build_mt_context(MuxHandle {
+            handle,
+            liveness,
+            recorder: recorder.clone(),
+        });
 
         Self {
             conn: Some(conn),
+            keep_alive,
+            recorder,
             mt,
         }
     }
@@ -70,6 +124,8 @@ where
         &mut self,
         config: ProverConfig,
     ) -> Result<Prover<prover_state::Initialized>> {
+        check_prover_config(&config)?;
+
         let ctx = self.mt.new_context().map_err(|e| {
             Error::internal()
                 .with_msg("failed to create new prover")
@@ -93,6 +149,23 @@ where
         Ok(Verifier::new(ctx, config))
     }
 
+    /// Creates a new context which is not bound to a protocol participant.
+    ///
+    /// This is a hook for driving scripted peers in tests.
+    #[cfg(test)]
+    pub(crate) fn new_context(&mut self) -> Result<mpz_common::Context> {
+        self.mt.new_context().map_err(|e| {
+            Error::internal()
+                .with_msg("failed to create new context")
+                .with_source(e)
+        })
+    }
+
+    /// Returns the recording of the session so far, if recording is enabled.
+    pub fn recording(&self) -> Option<Recording> {
+        self.recorder.as_ref().map(Recorder::snapshot)
+    }
+
     /// Returns `true` if the session is closed.
     pub fn is_closed(&self) -> bool {
         self.conn
@@ -109,6 +182,10 @@ where
         if let Some(conn) = self.conn.as_mut() {
             conn.close()
         }
+
+        if let Some(keep_alive) = self.keep_alive.as_mut() {
+            keep_alive.close();
+        }
     }
 
     /// Attempts to take the IO, returning an error if it is not available.
@@ -123,18 +200,25 @@ where
                 Err(Error::io()
                     .with_msg("failed to take the session io, session was not completed yet"))
             }
-            Ok(conn) => Ok(conn),
+            Ok(conn) => Ok(conn.into_inner()),
         }
     }
 
     /// Polls the session.
     pub fn poll(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        if self.conn.is_none() {
+            return Poll::Ready(Err(Error::io().with_msg(
+                "failed to poll the session connection because it has been taken",
+            )));
+        }
+
+        if let Poll::Ready(err) = poll_keep_alive(&mut self.keep_alive, &mut self.conn, cx) {
+            return Poll::Ready(Err(err));
+        }
+
         self.conn
             .as_mut()
-            .ok_or_else(|| {
-                Error::io()
-                    .with_msg("failed to poll the session connection because it has been taken")
-            })?
+            .expect("connection is available")
             .poll(cx)
             .map_err(|e| {
                 Error::io()
@@ -154,11 +238,13 @@ where
         (
             SessionDriver {
                 conn: self.conn,
+                keep_alive: self.keep_alive,
                 should_close: should_close.clone(),
                 waker: waker.clone(),
             },
             SessionHandle {
                 mt: self.mt,
+                recorder: self.recorder,
                 should_close,
                 waker,
             },
@@ -183,7 +269,8 @@ where
 /// IO when the session closes.
 #[must_use = "driver must be polled to make progress"]
 pub struct SessionDriver<Io> {
-    conn: Option<Connection<Io>>,
+    conn: Option<Connection<MonitoredIo<Io>>>,
+    keep_alive: Option<KeepAlive>,
     should_close: Arc<AtomicBool>,
     waker: Arc<Mutex<Option<Waker>>>,
 }
@@ -200,15 +287,23 @@ where
             *waker_guard = Some(cx.waker().clone());
         }
 
-        let conn = self
-            .conn
-            .as_mut()
-            .ok_or_else(|| Error::io().with_msg("session driver already completed"))?;
+        if self.conn.is_none() {
+            return Poll::Ready(Err(Error::io().with_msg("session driver already completed")));
+        }
 
         if self.should_close.load(Ordering::Acquire) {
-            conn.close();
+            self.conn.as_mut().expect("connection is available").close();
+            if let Some(keep_alive) = self.keep_alive.as_mut() {
+                keep_alive.close();
+            }
+        }
+
+        if let Poll::Ready(err) = poll_keep_alive(&mut self.keep_alive, &mut self.conn, cx) {
+            return Poll::Ready(Err(err));
         }
 
+        let conn = self.conn.as_mut().expect("connection is available");
+
         match conn.poll(cx) {
             Poll::Ready(Ok(())) => {}
             Poll::Ready(Err(e)) => {
@@ -222,6 +317,7 @@ where
         let conn = self.conn.take().unwrap();
         Poll::Ready(
             conn.try_into_io()
+                .map(MonitoredIo::into_inner)
                 .map_err(|_| Error::io().with_msg("failed to take session io")),
         )
     }
@@ -243,6 +339,7 @@ where
 /// Used to create provers/verifiers and control the session lifecycle.
 pub struct SessionHandle {
     mt: Multithread,
+    recorder: Option<Recorder>,
     should_close: Arc<AtomicBool>,
     waker: Arc<Mutex<Option<Waker>>>,
 }
@@ -253,6 +350,8 @@ impl SessionHandle {
         &mut self,
         config: ProverConfig,
     ) -> Result<Prover<prover_state::Initialized>> {
+        check_prover_config(&config)?;
+
         let ctx = self.mt.new_context().map_err(|e| {
             Error::internal()
                 .with_msg("failed to create new prover")
@@ -276,6 +375,11 @@ impl SessionHandle {
         Ok(Verifier::new(ctx, config))
     }
 
+    /// Returns the recording of the session so far, if recording is enabled.
+    pub fn recording(&self) -> Option<Recording> {
+        self.recorder.as_ref().map(Recorder::snapshot)
+    }
+
     /// Signals the session to close.
     ///
     /// The driver must continue to be polled until it completes.
@@ -287,9 +391,30 @@ impl SessionHandle {
     }
 }
 
+/// Polls the keep-alive of a connection.
+///
+/// If the peer is detected to be disconnected the connection is dropped, so
+/// that pending operations on its streams fail promptly.
+fn poll_keep_alive<Io>(
+    keep_alive: &mut Option<KeepAlive>,
+    conn: &mut Option<Connection<MonitoredIo<Io>>>,
+    cx: &mut Context<'_>,
+) -> Poll<Error> {
+    let Some(keep_alive) = keep_alive.as_mut() else {
+        return Poll::Pending;
+    };
+
+    let err = std::task::ready!(keep_alive.poll(cx));
+    conn.take();
+
+    Poll::Ready(Error::peer_disconnected().with_source(err))
+}
+
 /// Multiplexer controller providing streams.
 struct MuxHandle {
     handle: Handle,
+    liveness: Liveness,
+    recorder: Option<Recorder>,
 }
 
 impl std::fmt::Debug for MuxHandle {
@@ -304,7 +429,13 @@ impl Mux for MuxHandle {
             .handle
             .new_stream(id.as_ref())
             .map_err(std::io::Error::other)?;
-        let io = Io::from_io(stream);
+        let stream = GuardedStream::new(stream, self.liveness.clone());
+        let io = match &self.recorder {
+            Some(recorder) => {
+                Io::from_io(RecordedStream::new(stream, id.as_ref(), recorder.clone()))
+            }
+            None => Io::from_io(stream),
+        };
 
         Ok(io)
     }
@@ -324,3 +455,14 @@ fn build_mt_context(mux: MuxHandle) -> Multithread {
 
     builder.build().unwrap()
 }
+
+/// Checks that the prover configuration can be used with a session.
+fn check_prover_config(config: &ProverConfig) -> Result<()> {
+    if config.is_insecure_local_backend() {
+        return Err(Error::config().with_msg(
+            "prover is configured with the insecure local backend, which runs without a session",
+        ));
+    }
+
+    Ok(())
+}
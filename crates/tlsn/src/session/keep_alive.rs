@@ -0,0 +1,363 @@
+//! Keep-alive pings and dead-peer detection.
+//!
+//! Both parties exchange a ping over a dedicated mux stream at the configured
+//! interval. Any data received from the peer, including MPC traffic, shows
+//! that it is alive. If nothing is received within the peer timeout, the peer
+//! is considered disconnected and all pending channel operations fail with
+//! [`PeerDisconnected`].
+
+use std::{
+    io,
+    pin::Pin,
+    sync::{
+        Arc, Mutex, Weak,
+        atomic::{AtomicBool, Ordering},
+    },
+    task::{Context, Poll, ready},
+    time::Duration,
+};
+
+use futures::{
+    AsyncRead, AsyncWrite, FutureExt, future::BoxFuture, pin_mut, select, task::AtomicWaker,
+};
+use futures_timer::Delay;
+use mpz_common::io::Io;
+use serde::{Deserialize, Serialize};
+use serio::{SinkExt, stream::IoStreamExt};
+
+/// Identifier of the mux stream which carries the pings.
+pub(crate) const KEEP_ALIVE_STREAM_ID: &[u8] = b"tlsn/keep-alive";
+
+/// Error of channel operations after the peer was detected to be
+/// disconnected.
+#[derive(Debug, Clone, Copy, thiserror::Error)]
+#[error("peer stopped responding to keep-alive pings")]
+pub(crate) struct PeerDisconnected;
+
+impl From<PeerDisconnected> for io::Error {
+    fn from(err: PeerDisconnected) -> Self {
+        io::Error::new(io::ErrorKind::ConnectionAborted, err)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Ping;
+
+/// Liveness of the peer, shared by the connection and its streams.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Liveness {
+    /// Set when data is received from the peer.
+    activity: Arc<AtomicBool>,
+    /// Set when the peer is detected to be disconnected.
+    disconnected: Arc<AtomicBool>,
+    /// Wakers of the streams, to fail their pending operations.
+    wakers: Arc<Mutex<Vec<Weak<AtomicWaker>>>>,
+}
+
+impl Liveness {
+    /// Returns `true` if data was received since the last call.
+    fn take_activity(&self) -> bool {
+        self.activity.swap(false, Ordering::AcqRel)
+    }
+
+    fn is_disconnected(&self) -> bool {
+        self.disconnected.load(Ordering::Acquire)
+    }
+
+    /// Marks the peer as disconnected and wakes all streams.
+    fn set_disconnected(&self) {
+        self.disconnected.store(true, Ordering::Release);
+        for waker in self.wakers.lock().unwrap().drain(..) {
+            if let Some(waker) = waker.upgrade() {
+                waker.wake();
+            }
+        }
+    }
+
+    fn register(&self) -> Arc<AtomicWaker> {
+        let waker = Arc::new(AtomicWaker::new());
+        let mut wakers = self.wakers.lock().unwrap();
+        wakers.retain(|waker| waker.strong_count() > 0);
+        wakers.push(Arc::downgrade(&waker));
+        waker
+    }
+}
+
+/// Connection IO which records activity of the peer.
+pub(crate) struct MonitoredIo<Io> {
+    io: Io,
+    liveness: Liveness,
+}
+
+impl<Io> MonitoredIo<Io> {
+    pub(crate) fn new(io: Io, liveness: Liveness) -> Self {
+        Self { io, liveness }
+    }
+
+    pub(crate) fn into_inner(self) -> Io {
+        self.io
+    }
+}
+
+impl<Io> AsyncRead for MonitoredIo<Io>
+where
+    Io: AsyncRead + Unpin,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let n = ready!(Pin::new(&mut self.io).poll_read(cx, buf))?;
+        if n > 0 {
+            self.liveness.activity.store(true, Ordering::Release);
+        }
+
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl<Io> AsyncWrite for MonitoredIo<Io>
+where
+    Io: AsyncWrite + Unpin,
+{
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.io).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.io).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.io).poll_close(cx)
+    }
+}
+
+/// Mux stream which fails once the peer is detected to be disconnected.
+pub(crate) struct GuardedStream<S> {
+    stream: S,
+    liveness: Liveness,
+    waker: Arc<AtomicWaker>,
+}
+
+impl<S> GuardedStream<S> {
+    pub(crate) fn new(stream: S, liveness: Liveness) -> Self {
+        let waker = liveness.register();
+        Self {
+            stream,
+            liveness,
+            waker,
+        }
+    }
+
+    /// Registers the task and fails if the peer is disconnected.
+    fn check(&self, cx: &mut Context<'_>) -> io::Result<()> {
+        self.waker.register(cx.waker());
+        if self.liveness.is_disconnected() {
+            return Err(PeerDisconnected.into());
+        }
+
+        Ok(())
+    }
+}
+
+impl<S> AsyncRead for GuardedStream<S>
+where
+    S: AsyncRead + Unpin,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        self.check(cx)?;
+        Pin::new(&mut self.stream).poll_read(cx, buf)
+    }
+}
+
+impl<S> AsyncWrite for GuardedStream<S>
+where
+    S: AsyncWrite + Unpin,
+{
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.check(cx)?;
+        Pin::new(&mut self.stream).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.check(cx)?;
+        Pin::new(&mut self.stream).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.check(cx)?;
+        Pin::new(&mut self.stream).poll_close(cx)
+    }
+}
+
+/// Sends keep-alive pings and detects a dead peer.
+pub(crate) struct KeepAlive {
+    liveness: Liveness,
+    peer_timeout: Duration,
+    state: State,
+}
+
+enum State {
+    /// Exchanging pings with the peer.
+    Active(BoxFuture<'static, Result<(), PeerDisconnected>>),
+    /// The session is closing, the pings are stopped so that the stream does
+    /// not hold the connection open.
+    Closing(Delay),
+    Done,
+}
+
+impl KeepAlive {
+    /// Creates a new keep-alive which pings over the provided stream.
+    pub(crate) fn new(
+        io: Io,
+        interval: Duration,
+        peer_timeout: Duration,
+        liveness: Liveness,
+    ) -> Self {
+        let ping = ping(io, interval, peer_timeout, liveness.clone()).boxed();
+
+        Self {
+            liveness,
+            peer_timeout,
+            state: State::Active(ping),
+        }
+    }
+
+    /// Stops sending pings, the peer is still expected to respond until the
+    /// session is closed.
+    pub(crate) fn close(&mut self) {
+        if let State::Active(_) = self.state {
+            self.state = State::Closing(Delay::new(self.peer_timeout));
+        }
+    }
+
+    /// Polls the keep-alive, returning an error if the peer is detected to be
+    /// disconnected.
+    pub(crate) fn poll(&mut self, cx: &mut Context<'_>) -> Poll<PeerDisconnected> {
+        let result = match &mut self.state {
+            State::Active(ping) => ready!(ping.poll_unpin(cx)),
+            State::Closing(deadline) => loop {
+                ready!(deadline.poll_unpin(cx));
+                if !self.liveness.take_activity() {
+                    break Err(PeerDisconnected);
+                }
+                deadline.reset(self.peer_timeout);
+            },
+            State::Done => return Poll::Pending,
+        };
+
+        self.state = State::Done;
+        match result {
+            Ok(()) => Poll::Pending,
+            Err(err) => {
+                self.liveness.set_disconnected();
+                Poll::Ready(err)
+            }
+        }
+    }
+}
+
+/// Exchanges pings with the peer until it stops responding.
+///
+/// Returns `Ok` if the stream fails, in which case the connection reports the
+/// error.
+async fn ping(
+    mut io: Io,
+    interval: Duration,
+    peer_timeout: Duration,
+    liveness: Liveness,
+) -> Result<(), PeerDisconnected> {
+    loop {
+        if io.send(Ping).await.is_err() {
+            return Ok(());
+        }
+
+        let pong = io.expect_next::<Ping>().fuse();
+        pin_mut!(pong);
+        loop {
+            select! {
+                pong = pong => match pong {
+                    Ok(_) => break,
+                    Err(_) => return Ok(()),
+                },
+                _ = Delay::new(peer_timeout).fuse() => {
+                    if !liveness.take_activity() {
+                        return Err(PeerDisconnected);
+                    }
+                }
+            }
+        }
+
+        Delay::new(interval).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::future::poll_fn;
+    use tlsn_core::config::session::SessionConfig;
+    use tokio_util::compat::TokioAsyncReadCompatExt;
+
+    use crate::Session;
+
+    use super::*;
+
+    fn config() -> SessionConfig {
+        SessionConfig::builder()
+            .keep_alive_interval(Duration::from_millis(20))
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_dead_peer() {
+        // The peer never responds, but keeps the connection open.
+        let (io, _peer) = tokio::io::duplex(1 << 16);
+        let mut session = Session::with_config(io.compat(), config());
+
+        let err = tokio::time::timeout(Duration::from_secs(5), poll_fn(|cx| session.poll(cx)))
+            .await
+            .expect("dead peer should be detected")
+            .unwrap_err();
+
+        assert!(err.is_peer_disconnected());
+    }
+
+    #[tokio::test]
+    async fn test_idle_peer() {
+        let (io_0, io_1) = tokio::io::duplex(1 << 16);
+        let mut session_0 = Session::with_config(io_0.compat(), config());
+        let mut session_1 = Session::with_config(io_1.compat(), config());
+
+        // Idle for several peer timeouts.
+        let idle = tokio::time::sleep(Duration::from_millis(300));
+        tokio::select! {
+            res = poll_fn(|cx| session_0.poll(cx)) => panic!("session closed: {res:?}"),
+            res = poll_fn(|cx| session_1.poll(cx)) => panic!("session closed: {res:?}"),
+            _ = idle => {}
+        }
+
+        session_0.close();
+        session_1.close();
+        let (res_0, res_1) = tokio::join!(
+            poll_fn(|cx| session_0.poll(cx)),
+            poll_fn(|cx| session_1.poll(cx))
+        );
+        res_0.unwrap();
+        res_1.unwrap();
+    }
+}
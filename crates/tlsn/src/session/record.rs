@@ -0,0 +1,246 @@
+//! Recording of session messages.
+//!
+//! With recording enabled in the [`SessionConfig`](crate::config::session::SessionConfig),
+//! a session records the data read and written on each of its streams, keyed
+//! by the stream id and numbered per stream. Both the prover and the verifier
+//! can record their side, and comparing the two recordings of a failed run
+//! shows which stream and message it failed on.
+//!
+//! A recording is only for inspection, it can not be used to replay a run:
+//! the MPC protocols sample fresh randomness, so a rerun sends different
+//! messages. The keep-alive pings are not recorded.
+
+use std::{
+    collections::BTreeSet,
+    io,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, ready},
+};
+
+use futures::{AsyncRead, AsyncWrite};
+use serde::{Deserialize, Serialize};
+
+/// Kind of a recorded event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EventKind {
+    /// Data was read from the peer.
+    Read,
+    /// Data was written to the peer.
+    Write,
+}
+
+/// A recorded event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Event {
+    /// Id of the stream.
+    pub stream: Vec<u8>,
+    /// Sequence number of the event within the stream and direction.
+    pub seq: u64,
+    /// Kind of the event.
+    pub kind: EventKind,
+    /// Data which was transferred.
+    pub data: Vec<u8>,
+}
+
+/// A recording of the messages of a session.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Recording {
+    events: Vec<Event>,
+}
+
+impl Recording {
+    /// Returns the recorded events in the order they occurred.
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    /// Returns the ids of the recorded streams.
+    pub fn streams(&self) -> BTreeSet<&[u8]> {
+        self.events
+            .iter()
+            .map(|event| event.stream.as_slice())
+            .collect()
+    }
+
+    /// Returns the events of a stream.
+    pub fn stream<'a>(&'a self, id: &'a [u8]) -> impl Iterator<Item = &'a Event> + 'a {
+        self.events.iter().filter(move |event| event.stream == id)
+    }
+
+    /// Returns all data of a stream in the given direction.
+    pub fn data(&self, id: &[u8], kind: EventKind) -> Vec<u8> {
+        self.stream(id)
+            .filter(|event| event.kind == kind)
+            .flat_map(|event| event.data.iter().copied())
+            .collect()
+    }
+
+    fn push(&mut self, stream: &[u8], kind: EventKind, data: &[u8]) {
+        let seq = self
+            .stream(stream)
+            .filter(|event| event.kind == kind)
+            .count() as u64;
+
+        self.events.push(Event {
+            stream: stream.to_vec(),
+            seq,
+            kind,
+            data: data.to_vec(),
+        });
+    }
+}
+
+/// Shared recording of a session, to which its streams record.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Recorder(Arc<Mutex<Recording>>);
+
+impl Recorder {
+    /// Returns a copy of the recording so far.
+    pub(crate) fn snapshot(&self) -> Recording {
+        self.0.lock().unwrap().clone()
+    }
+
+    fn push(&self, stream: &[u8], kind: EventKind, data: &[u8]) {
+        self.0.lock().unwrap().push(stream, kind, data);
+    }
+}
+
+/// Stream which records the data it transfers.
+pub(crate) struct RecordedStream<S> {
+    stream: S,
+    id: Vec<u8>,
+    recorder: Recorder,
+}
+
+impl<S> RecordedStream<S> {
+    pub(crate) fn new(stream: S, id: &[u8], recorder: Recorder) -> Self {
+        Self {
+            stream,
+            id: id.to_vec(),
+            recorder,
+        }
+    }
+}
+
+impl<S> AsyncRead for RecordedStream<S>
+where
+    S: AsyncRead + Unpin,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let n = ready!(Pin::new(&mut self.stream).poll_read(cx, buf))?;
+        if n > 0 {
+            self.recorder.push(&self.id, EventKind::Read, &buf[..n]);
+        }
+
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl<S> AsyncWrite for RecordedStream<S>
+where
+    S: AsyncWrite + Unpin,
+{
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let n = ready!(Pin::new(&mut self.stream).poll_write(cx, buf))?;
+        if n > 0 {
+            self.recorder.push(&self.id, EventKind::Write, &buf[..n]);
+        }
+
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_close(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serio::{SinkExt, stream::IoStreamExt};
+    use tlsn_core::config::session::SessionConfig;
+    use tokio_util::compat::TokioAsyncReadCompatExt;
+
+    use crate::Session;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_record_session() {
+        let config = SessionConfig::builder().record(true).build().unwrap();
+        let (io_0, io_1) = tokio::io::duplex(1 << 16);
+        let mut session_0 = Session::with_config(io_0.compat(), config.clone());
+        let mut session_1 = Session::with_config(io_1.compat(), config);
+
+        let mut ctx_0 = session_0.new_context().unwrap();
+        let mut ctx_1 = session_1.new_context().unwrap();
+        let (driver_0, handle_0) = session_0.split();
+        let (driver_1, handle_1) = session_1.split();
+
+        let messages = async {
+            ctx_0.io_mut().send(1u32).await.unwrap();
+            ctx_0.io_mut().send(2u32).await.unwrap();
+            let a: u32 = ctx_1.io_mut().expect_next().await.unwrap();
+            let b: u32 = ctx_1.io_mut().expect_next().await.unwrap();
+            assert_eq!((a, b), (1, 2));
+
+            handle_0.close();
+            handle_1.close();
+        };
+
+        let (driver_0, driver_1, _) = tokio::join!(driver_0, driver_1, messages);
+        driver_0.unwrap();
+        driver_1.unwrap();
+
+        let recording_0 = handle_0.recording().unwrap();
+        let recording_1 = handle_1.recording().unwrap();
+
+        // What one side wrote on a stream is what the other side read on it.
+        let streams = recording_0.streams();
+        assert!(!streams.is_empty());
+        assert_eq!(streams, recording_1.streams());
+        for id in streams {
+            assert_eq!(
+                recording_0.data(id, EventKind::Write),
+                recording_1.data(id, EventKind::Read)
+            );
+            assert_eq!(
+                recording_0.data(id, EventKind::Read),
+                recording_1.data(id, EventKind::Write)
+            );
+
+            let writes = recording_0
+                .stream(id)
+                .filter(|event| event.kind == EventKind::Write);
+            for (seq, event) in writes.enumerate() {
+                assert_eq!(event.seq, seq as u64);
+            }
+        }
+        assert!(
+            recording_0
+                .events()
+                .iter()
+                .any(|event| event.kind == EventKind::Write)
+        );
+    }
+
+    #[test]
+    fn test_recording_disabled() {
+        let (io, _) = tokio::io::duplex(1 << 16);
+        let session = Session::new(io.compat());
+
+        assert!(session.recording().is_none());
+    }
+}
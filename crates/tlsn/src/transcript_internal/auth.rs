@@ -525,9 +525,15 @@ mod tests {
     #[case::single_block_full([16], [0..16])]
     #[case::single_block_partial([16], [2..14])]
     #[case::partial_block_full([15], [0..15])]
+    #[case::single_byte_record([1], [0..1])]
+    #[case::partial_block_single_byte([15], [14..15])]
+    #[case::single_block_last_byte([16], [15..16])]
+    #[case::block_boundary([17], [15..17])]
     #[case::out_of_bounds([16], [0..17])]
     #[case::multiple_records_full([128, 63, 33, 15, 4], [0..243])]
     #[case::multiple_records_partial([128, 63, 33, 15, 4], [1..15, 16..17, 18..19, 126..130, 224..225, 242..243])]
+    #[case::multiple_records_with_empty_full([0, 128, 0, 63, 0], [0..191])]
+    #[case::multiple_records_with_empty_partial([128, 0, 0, 63], [120..136, 190..191])]
     #[tokio::test]
     async fn test_alloc_keystream(
         #[case] record_lens: impl IntoIterator<Item = usize>,
@@ -585,6 +591,9 @@ mod tests {
     #[rstest]
     #[case::single_record_empty([0])]
     #[case::single_record([32])]
+    #[case::single_byte_record([1])]
+    #[case::partial_block_record([15])]
+    #[case::single_block_record([16])]
     #[case::multiple_records([128, 63, 33, 15, 4])]
     #[case::multiple_records_with_empty([128, 63, 33, 0, 15, 4])]
     fn test_verify_plaintext_with_key(
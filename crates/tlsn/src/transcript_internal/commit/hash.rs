@@ -9,6 +9,7 @@ use mpz_memory_core::{
     binary::{Binary, U8},
 };
 use mpz_vm_core::{Vm, VmError, prelude::*};
+use rand::Rng;
 use rangeset::set::RangeSet;
 use tlsn_core::{
     hash::{Blinder, Hash, HashAlgId, TypedHash},
@@ -57,18 +58,19 @@ impl HashCommitFuture {
     }
 }
 
-/// Prove plaintext hash commitments.
+/// Prove plaintext hash commitments, generating the blinders with `rng`.
 pub(crate) fn prove_hash(
     vm: &mut dyn Vm<Binary>,
     refs: &TranscriptRefs,
     idxs: impl IntoIterator<Item = (Direction, RangeSet<usize>, HashAlgId)>,
+    rng: &mut impl Rng,
 ) -> Result<(HashCommitFuture, Vec<PlaintextHashSecret>), HashCommitError> {
     let mut futs = Vec::new();
     let mut secrets = Vec::new();
     for (direction, idx, alg, hash_ref, blinder_ref) in
         hash_commit_inner(vm, Role::Prover, refs, idxs)?
     {
-        let blinder: Blinder = rand::random();
+        let blinder: Blinder = rng.random();
 
         vm.assign(blinder_ref, blinder.as_bytes().to_vec())?;
         vm.commit(blinder_ref)?;
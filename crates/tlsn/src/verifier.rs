@@ -9,9 +9,9 @@ use mpz_common::Context;
 pub use tlsn_core::{VerifierOutput, webpki::ServerCertVerifier};
 
 use crate::{
-    Error, Result,
+    Capabilities, Error, Result,
     mpz::{VerifierDeps, build_verifier_deps, translate_keys},
-    msg::{ProveRequestMsg, Response, TlsCommitRequestMsg},
+    msg::{HelloMsg, ProveRequestMsg, Response, TlsCommitRequestMsg},
     tag::verify_tags,
 };
 use mpz_vm_core::prelude::*;
@@ -25,6 +25,7 @@ use tlsn_core::{
     connection::{ConnectionInfo, ServerName},
     transcript::TlsTranscript,
 };
+use tlsn_deap::DeapError;
 
 use tracing::{Span, debug, info, info_span, instrument};
 
@@ -68,25 +69,42 @@ impl Verifier<state::Initialized> {
             .take()
             .ok_or_else(|| Error::internal().with_msg("commitment protocol context was dropped"))?;
 
-        // Receives protocol configuration from prover to perform compatibility check.
-        let TlsCommitRequestMsg { request, version } =
-            ctx.io_mut().expect_next().await.map_err(|e| {
+        // Exchanges versions and capabilities with the prover for
+        // compatibility check.
+        let peer: HelloMsg = ctx.io_mut().expect_next().await.map_err(|e| {
+            Error::io()
+                .with_msg("commitment protocol failed to receive hello")
+                .with_source(e)
+        })?;
+
+        let capabilities =
+            Capabilities::new(self.config.max_sent_data(), self.config.max_recv_data());
+        ctx.io_mut()
+            .send(HelloMsg::new(capabilities.clone()))
+            .await
+            .map_err(|e| {
                 Error::io()
-                    .with_msg("commitment protocol failed to receive request")
+                    .with_msg("commitment protocol failed to send hello")
                     .with_source(e)
             })?;
 
-        if version != *crate::VERSION {
-            let msg = format!(
-                "prover version does not match with verifier: {version} != {}",
-                *crate::VERSION
-            );
+        crate::check_peer_version(&peer)?;
+        let capabilities = capabilities.agree(&peer.capabilities)?;
+
+        // Receives protocol configuration from prover to perform compatibility check.
+        let TlsCommitRequestMsg { request } = ctx.io_mut().expect_next().await.map_err(|e| {
+            Error::io()
+                .with_msg("commitment protocol failed to receive request")
+                .with_source(e)
+        })?;
+
+        if let Err(msg) = check_limits(&self.config, &request) {
             ctx.io_mut()
                 .send(Response::err(Some(msg.clone())))
                 .await
                 .map_err(|e| {
                     Error::io()
-                        .with_msg("commitment protocol failed to send version mismatch response")
+                        .with_msg("commitment protocol failed to send limit exceeded response")
                         .with_source(e)
                 })?;
 
@@ -97,7 +115,10 @@ impl Verifier<state::Initialized> {
             config: self.config,
             span: self.span,
             ctx: Some(ctx),
-            state: state::CommitStart { request },
+            state: state::CommitStart {
+                request,
+                capabilities,
+            },
         })
     }
 }
@@ -108,6 +129,11 @@ impl Verifier<state::CommitStart> {
         &self.state.request
     }
 
+    /// Returns the capabilities agreed with the prover.
+    pub fn capabilities(&self) -> &Capabilities {
+        &self.state.capabilities
+    }
+
     /// Accepts the proposed protocol configuration.
     #[instrument(parent = &self.span, level = "info", skip_all, err)]
     pub async fn accept(mut self) -> Result<Verifier<state::CommitAccepted>> {
@@ -115,7 +141,7 @@ impl Verifier<state::CommitStart> {
             .ctx
             .take()
             .ok_or_else(|| Error::internal().with_msg("commitment protocol context was dropped"))?;
-        let state::CommitStart { request } = self.state;
+        let state::CommitStart { request, .. } = self.state;
 
         ctx.io_mut().send(Response::ok()).await.map_err(|e| {
             Error::io()
@@ -127,7 +153,8 @@ impl Verifier<state::CommitStart> {
             unreachable!("only MPC TLS is supported");
         };
 
-        let VerifierDeps { vm, mut mpc_tls } = build_verifier_deps(mpc_tls_config, ctx);
+        let VerifierDeps { vm, mut mpc_tls } =
+            build_verifier_deps(mpc_tls_config, self.config.clock().clone(), ctx);
 
         // Allocate resources for MPC-TLS in the VM.
         let mut keys = mpc_tls.alloc().map_err(|e| {
@@ -197,9 +224,14 @@ impl Verifier<state::CommitAccepted> {
             debug!("finalizing mpc");
 
             vm.finalize(&mut ctx).await.map_err(|e| {
-                Error::internal()
-                    .with_msg("mpc finalization failed")
-                    .with_source(e)
+                let msg = if DeapError::from_vm_error(&e).is_some_and(DeapError::is_equality_check)
+                {
+                    "prover used inconsistent inputs during mpc"
+                } else {
+                    "mpc finalization failed"
+                };
+
+                Error::internal().with_msg(msg).with_source(e)
             })?;
 
             debug!("mpc finalized");
@@ -397,3 +429,78 @@ impl Verifier<state::Verify> {
         })
     }
 }
+
+/// Checks that the commitment request is within the limits of the verifier.
+fn check_limits(config: &VerifierConfig, request: &TlsCommitRequest) -> Result<(), String> {
+    let TlsCommitProtocolConfig::Mpc(mpc_tls_config) = request.protocol() else {
+        unreachable!("only MPC TLS is supported");
+    };
+
+    if let Some(max_sent_data) = config.max_sent_data()
+        && mpc_tls_config.max_sent_data() > max_sent_data
+    {
+        return Err(format!(
+            "requested max sent data exceeds verifier limit: {} > {max_sent_data}",
+            mpc_tls_config.max_sent_data()
+        ));
+    }
+
+    if let Some(max_recv_data) = config.max_recv_data()
+        && mpc_tls_config.max_recv_data() > max_recv_data
+    {
+        return Err(format!(
+            "requested max received data exceeds verifier limit: {} > {max_recv_data}",
+            mpc_tls_config.max_recv_data()
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use tlsn_core::{
+        config::tls_commit::{TlsCommitConfig, mpc::MpcTlsConfig},
+        webpki::RootCertStore,
+    };
+
+    use super::*;
+
+    fn request(max_sent_data: usize, max_recv_data: usize) -> TlsCommitRequest {
+        TlsCommitConfig::builder()
+            .protocol(
+                MpcTlsConfig::builder()
+                    .max_sent_data(max_sent_data)
+                    .max_recv_data(max_recv_data)
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap()
+            .to_request()
+    }
+
+    #[test]
+    fn test_check_limits() {
+        let config = VerifierConfig::builder()
+            .root_store(RootCertStore { roots: vec![] })
+            .max_sent_data(1 << 12)
+            .max_recv_data(1 << 14)
+            .build()
+            .unwrap();
+
+        assert!(check_limits(&config, &request(1 << 12, 1 << 14)).is_ok());
+        assert!(check_limits(&config, &request((1 << 12) + 1, 1 << 14)).is_err());
+        assert!(check_limits(&config, &request(1 << 12, (1 << 14) + 1)).is_err());
+    }
+
+    #[test]
+    fn test_check_limits_unbounded() {
+        let config = VerifierConfig::builder()
+            .root_store(RootCertStore { roots: vec![] })
+            .build()
+            .unwrap();
+
+        assert!(check_limits(&config, &request(1 << 20, 1 << 24)).is_ok());
+    }
+}
@@ -11,7 +11,10 @@ use tlsn_core::{
 use tlsn_deap::Deap;
 use tokio::sync::Mutex;
 
-use crate::mpz::{VerifierMpc, VerifierZk};
+use crate::{
+    Capabilities,
+    mpz::{VerifierMpc, VerifierZk},
+};
 
 /// TLS Verifier state.
 pub trait VerifierState: sealed::Sealed {}
@@ -24,6 +27,7 @@ opaque_debug::implement!(Initialized);
 /// State after receiving protocol configuration from the prover.
 pub struct CommitStart {
     pub(crate) request: TlsCommitRequest,
+    pub(crate) capabilities: Capabilities,
 }
 
 opaque_debug::implement!(CommitStart);
@@ -7,9 +7,7 @@ use tlsn_core::{
     VerifierOutput,
     config::prove::ProveRequest,
     connection::{HandshakeData, ServerName},
-    transcript::{
-        ContentType, Direction, PartialTranscript, Record, TlsTranscript, TranscriptCommitment,
-    },
+    transcript::{Direction, PartialTranscript, Record, TlsTranscript, TranscriptCommitment},
     webpki::ServerCertVerifier,
 };
 
@@ -29,8 +27,8 @@ pub(crate) async fn verify<T: Vm<Binary> + Send + Sync>(
     handshake: Option<(ServerName, HandshakeData)>,
     transcript: Option<PartialTranscript>,
 ) -> Result<VerifierOutput> {
-    let ciphertext_sent = collect_ciphertext(tls_transcript.sent());
-    let ciphertext_recv = collect_ciphertext(tls_transcript.recv());
+    let ciphertext_sent = collect_ciphertext(tls_transcript.application_data(Direction::Sent));
+    let ciphertext_recv = collect_ciphertext(tls_transcript.application_data(Direction::Received));
 
     let transcript = if let Some((auth_sent, auth_recv)) = request.reveal() {
         let Some(transcript) = transcript else {
@@ -97,10 +95,7 @@ pub(crate) async fn verify<T: Vm<Binary> + Send + Sync>(
         keys.client_write_iv,
         transcript.sent_unsafe(),
         &ciphertext_sent,
-        tls_transcript
-            .sent()
-            .iter()
-            .filter(|record| record.typ == ContentType::ApplicationData),
+        tls_transcript.application_data(Direction::Sent),
         transcript.sent_authed(),
         &commit_sent,
     )
@@ -115,10 +110,7 @@ pub(crate) async fn verify<T: Vm<Binary> + Send + Sync>(
         keys.server_write_iv,
         transcript.received_unsafe(),
         &ciphertext_recv,
-        tls_transcript
-            .recv()
-            .iter()
-            .filter(|record| record.typ == ContentType::ApplicationData),
+        tls_transcript.application_data(Direction::Received),
         transcript.received_authed(),
         &commit_recv,
     )
@@ -183,11 +175,8 @@ pub(crate) async fn verify<T: Vm<Binary> + Send + Sync>(
 
 fn collect_ciphertext<'a>(records: impl IntoIterator<Item = &'a Record>) -> Vec<u8> {
     let mut ciphertext = Vec::new();
-    records
-        .into_iter()
-        .filter(|record| record.typ == ContentType::ApplicationData)
-        .for_each(|record| {
-            ciphertext.extend_from_slice(&record.ciphertext);
-        });
+    records.into_iter().for_each(|record| {
+        ciphertext.extend_from_slice(&record.ciphertext);
+    });
     ciphertext
 }
@@ -110,6 +110,7 @@ pub struct ConnectionInfo {
     time: u64,
     version: TlsVersion,
     transcript_length: TranscriptLength,
+    truncated: bool,
 }
 
 impl From<tlsn::connection::ConnectionInfo> for ConnectionInfo {
@@ -118,6 +119,7 @@ impl From<tlsn::connection::ConnectionInfo> for ConnectionInfo {
             time: value.time,
             version: value.version.into(),
             transcript_length: value.transcript_length.into(),
+            truncated: value.truncated,
         }
     }
 }
@@ -170,6 +170,7 @@ impl JsVerifier {
                 sent: sent as u32,
                 received: received as u32,
             },
+            truncated: verifier.tls_transcript().is_truncated(),
         };
 
         let (output, verifier) = verifier